@@ -0,0 +1,43 @@
+use directories::ProjectDirs;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use tracing::error;
+
+/// Get the app data directory used for local state that isn't part of settings
+pub fn get_app_data_dir() -> Option<PathBuf> {
+    let proj_dirs = ProjectDirs::from("com", "borderzone", "bzmm")?;
+    let data_dir = proj_dirs.data_dir();
+    if let Err(e) = fs::create_dir_all(data_dir) {
+        error!("Failed to create app data directory: {}", e);
+        return None;
+    }
+    Some(data_dir.to_path_buf())
+}
+
+/// Load a JSON file from the app data directory, falling back to the default when it doesn't exist yet
+pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> Result<T, String> {
+    let path = get_app_data_dir()
+        .ok_or_else(|| "Could not determine app data path".to_string())?
+        .join(file_name);
+
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", file_name, e))
+}
+
+/// Save a value as JSON in the app data directory
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> Result<(), String> {
+    let path = get_app_data_dir()
+        .ok_or_else(|| "Could not determine app data path".to_string())?
+        .join(file_name);
+
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", file_name, e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", file_name, e))
+}
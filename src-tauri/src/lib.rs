@@ -1,22 +1,60 @@
+mod app_data;
+mod logging;
 mod mods;
+mod secrets;
 mod settings;
+mod state_db;
+mod tray;
 
 use mods::{
     delete_mod, disable_mod, download_mod, enable_mod, get_downloaded_mods, get_mods,
-    handlers::get_enabled_mods, queue_download, update_mod,
+    handlers::get_enabled_mods, queue_download, update_mod, verify_mod, reapply_enabled_mods,
+    get_presets, save_preset, delete_preset, apply_preset, rename_profile, export_mod_list, import_mod_list,
+    get_patch_conflicts, list_backups, restore_backup, get_mod_locks, force_unlock_mod,
+    force_disable_mod, search_mods, cleanup_deprecated_mods, migrate_deprecated_mod,
+    open_mod_folder, get_storage_usage, cleanup_orphaned_files,
+    migrate_download_path, get_favorite_mods, set_mod_favorite, get_hidden_mods, hide_mod, unhide_mod,
+    get_mod_note, set_mod_note, set_mod_variant, get_mod_changelog, get_queue_status, set_queue_paused,
+    get_download_history, clear_download_history, check_updates, get_all_updates, verify_all_downloads, download_from_url,
+    export_diagnostics, set_mods_enabled, get_mod_categories, get_category_mods,
+    validate_repo, run_self_check, clear_xml_cache, get_repo_snapshots, diff_repo_versions,
+    get_mod_image, get_asset_cache_usage, purge_asset_cache, get_cache_usage,
+};
+use logging::{get_log_level, get_recent_logs, set_log_level};
+use settings::{
+    clone_profile, delete_profile, get_active_profile, get_app_version, get_linked_profiles, get_settings,
+    set_active_profile, take_settings_warning, update_profile, update_settings, validate_dcs_path,
 };
-use settings::{delete_profile, get_app_version, get_settings, update_profile, update_settings};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    logging::init();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
+        .setup(|app| {
+            settings::register_app_handle(app.handle().clone());
+            logging::register_app_handle(app.handle().clone());
+            tray::setup_tray(app.handle())?;
+            mods::watcher::start_download_watcher(app.handle().clone());
+            mods::auto_refresh::start_auto_refresh(app.handle().clone());
+            mods::prefetch::start_prefetch();
+            Ok(())
+        })
+        .on_window_event(tray::handle_window_event)
         .invoke_handler(tauri::generate_handler![
             get_settings,
+            take_settings_warning,
             update_settings,
             update_profile,
             delete_profile,
+            clone_profile,
+            rename_profile,
+            get_linked_profiles,
+            get_active_profile,
+            set_active_profile,
+            validate_dcs_path,
             get_mods,
             get_downloaded_mods,
             get_enabled_mods,
@@ -26,6 +64,60 @@ pub fn run() {
             disable_mod,
             update_mod,
             delete_mod,
+            verify_mod,
+            reapply_enabled_mods,
+            get_presets,
+            save_preset,
+            delete_preset,
+            apply_preset,
+            export_mod_list,
+            import_mod_list,
+            get_patch_conflicts,
+            list_backups,
+            restore_backup,
+            get_mod_locks,
+            force_unlock_mod,
+            force_disable_mod,
+            search_mods,
+            cleanup_deprecated_mods,
+            migrate_deprecated_mod,
+            open_mod_folder,
+            get_storage_usage,
+            cleanup_orphaned_files,
+            migrate_download_path,
+            get_favorite_mods,
+            set_mod_favorite,
+            get_hidden_mods,
+            hide_mod,
+            unhide_mod,
+            get_mod_note,
+            set_mod_note,
+            set_mod_variant,
+            get_mod_changelog,
+            get_queue_status,
+            set_queue_paused,
+            get_download_history,
+            clear_download_history,
+            check_updates,
+            get_all_updates,
+            verify_all_downloads,
+            download_from_url,
+            export_diagnostics,
+            set_mods_enabled,
+            get_mod_categories,
+            get_category_mods,
+            validate_repo,
+            run_self_check,
+            clear_xml_cache,
+            get_repo_snapshots,
+            diff_repo_versions,
+            get_mod_image,
+            get_asset_cache_usage,
+            purge_asset_cache,
+            get_cache_usage,
+            get_recent_logs,
+            get_log_level,
+            set_log_level,
             get_app_version
         ])
         .run(tauri::generate_context!())
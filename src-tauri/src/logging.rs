@@ -0,0 +1,326 @@
+use crate::app_data;
+use crate::settings::Settings;
+use serde::Serialize;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt::{self as std_fmt, Write as _};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{info, Event, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// Environment variable with filter directives, such as `info,bzmm::mods::downloader=debug`.
+/// Wins over the `log_level` setting
+const LOG_FILTER_ENV: &str = "BZMM_LOG";
+
+const DEFAULT_FILTER: &str = "info";
+
+const LOG_FILE_PREFIX: &str = "bzmm";
+
+/// Daily log files kept before the oldest is deleted
+const LOG_FILES_KEPT: usize = 7;
+
+/// Entries kept in memory for the Logs panel, the oldest are dropped first
+const RECENT_LOGS_KEPT: usize = 1000;
+
+/// Keeps the background writer flushing to the log file for as long as the app runs
+static LOG_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+/// Swaps the filter of every log destination while the app runs
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Where `log-event` is sent, set once the app is running
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+static RECENT_LOGS: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+thread_local! {
+    /// Set while a `log-event` is sent, so anything Tauri logs while sending it isn't sent too
+    static EMITTING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A log event as the Logs panel shows it
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub level: String,
+    /// Module the event was logged from, such as `bzmm::mods::downloader`
+    pub target: String,
+    /// Spans the event happened in, outermost first, such as `download{file=a.zip}`
+    pub spans: Vec<String>,
+    pub message: String,
+}
+
+/// Collects an event's message, followed by its other fields as `name=value`
+#[derive(Default)]
+struct FieldText {
+    message: String,
+    fields: String,
+}
+
+impl FieldText {
+    fn push(&mut self, field: &Field, value: std_fmt::Arguments) {
+        if field.name() == "message" {
+            let _ = self.message.write_fmt(value);
+        } else {
+            if !self.fields.is_empty() {
+                self.fields.push(' ');
+            }
+            let _ = write!(self.fields, "{}={}", field.name(), value);
+        }
+    }
+
+    fn into_text(self) -> String {
+        match (self.message.is_empty(), self.fields.is_empty()) {
+            (_, true) => self.message,
+            (true, false) => self.fields,
+            (false, false) => format!("{} {}", self.message, self.fields),
+        }
+    }
+}
+
+impl Visit for FieldText {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std_fmt::Debug) {
+        self.push(field, format_args!("{:?}", value));
+    }
+}
+
+/// The fields of a span, kept in its extensions to name it in the entries logged inside it
+struct SpanFields(String);
+
+/// Keeps recent events for `get_recent_logs` and sends each one to the frontend as a
+/// `log-event`
+struct FrontendLayer;
+
+impl<S> Layer<S> for FrontendLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = FieldText::default();
+        attrs.record(&mut fields);
+        span.extensions_mut().insert(SpanFields(fields.into_text()));
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if EMITTING.get() {
+            return;
+        }
+        let mut text = FieldText::default();
+        event.record(&mut text);
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .map(|span| match span.extensions().get::<SpanFields>() {
+                        Some(SpanFields(fields)) if !fields.is_empty() => format!("{}{{{}}}", span.name(), fields),
+                        _ => span.name().to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+        let entry = LogEntry {
+            timestamp,
+            level: event.metadata().level().to_string(),
+            target: event.metadata().target().to_string(),
+            spans,
+            message: text.into_text(),
+        };
+
+        {
+            let mut recent = RECENT_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+            if recent.len() >= RECENT_LOGS_KEPT {
+                recent.pop_front();
+            }
+            recent.push_back(entry.clone());
+        }
+        if let Some(app) = APP_HANDLE.get() {
+            EMITTING.set(true);
+            let _ = app.emit("log-event", &entry);
+            EMITTING.set(false);
+        }
+    }
+}
+
+/// Where the log files go, inside the app data directory
+pub fn log_dir() -> Option<PathBuf> {
+    Some(app_data::get_app_data_dir()?.join("logs"))
+}
+
+/// Send log events to a daily rotating file in the app data directory, and to the console in
+/// debug builds, and keep the recent ones for the Logs panel. The level comes from `BZMM_LOG`,
+/// else the `log_level` setting, per module if need be. Without a log directory the app logs to
+/// the console only
+pub fn init() {
+    let from_env = filter_from_env();
+    let (filter, handle) = reload::Layer::new(from_env.clone().unwrap_or_else(|| EnvFilter::new(DEFAULT_FILTER)));
+    let console = cfg!(debug_assertions).then(|| fmt::layer().with_writer(std::io::stdout));
+
+    let appender = log_dir().and_then(|dir| {
+        let built = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(LOG_FILES_KEPT)
+            .build(&dir);
+        match built {
+            Ok(appender) => Some(appender),
+            Err(e) => {
+                eprintln!("Failed to open log files in {}: {}", dir.display(), e);
+                None
+            }
+        }
+    });
+    let file = appender.map(|appender| {
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        let _ = LOG_GUARD.set(guard);
+        fmt::layer().with_writer(writer).with_ansi(false)
+    });
+
+    let subscriber = tracing_subscriber::registry().with(filter).with(console).with(file).with(FrontendLayer);
+    if let Err(e) = subscriber.try_init() {
+        return eprintln!("Failed to set up logging: {}", e);
+    }
+    let _ = FILTER_HANDLE.set(handle);
+
+    // Settings are read once logging is up, so what loading them reports isn't lost
+    if from_env.is_none() {
+        if let Err(e) = Settings::load().and_then(|settings| set_log_filter(&settings.log_level)) {
+            eprintln!("Failed to apply the log level setting: {}", e);
+        }
+    }
+}
+
+fn filter_from_env() -> Option<EnvFilter> {
+    std::env::var(LOG_FILTER_ENV).ok().and_then(|directives| parse_filter(&directives).ok())
+}
+
+/// Whether `BZMM_LOG` sets the level, the `log_level` setting is then left alone
+pub fn level_set_by_env() -> bool {
+    filter_from_env().is_some()
+}
+
+/// Filter directives such as `debug` or `info,bzmm::mods::downloader=trace`, empty for the
+/// default level
+pub fn parse_filter(directives: &str) -> Result<EnvFilter, String> {
+    let directives = directives.trim();
+    if directives.is_empty() {
+        return Ok(EnvFilter::new(DEFAULT_FILTER));
+    }
+    EnvFilter::try_new(directives).map_err(|e| format!("Invalid log level \"{}\": {}", directives, e))
+}
+
+/// Change which events are logged from now on, without a restart
+pub fn set_log_filter(directives: &str) -> Result<(), String> {
+    let filter = parse_filter(directives)?;
+    let handle = FILTER_HANDLE.get().ok_or_else(|| "Logging is not set up".to_string())?;
+    handle
+        .reload(filter)
+        .map_err(|e| format!("Failed to change the log level: {}", e))?;
+    info!("Log level set to {}", current_log_filter());
+    Ok(())
+}
+
+fn current_log_filter() -> String {
+    FILTER_HANDLE
+        .get()
+        .and_then(|handle| handle.with_current(|filter| filter.to_string()).ok())
+        .unwrap_or_default()
+}
+
+/// Start sending log events to the frontend, those logged before are in `get_recent_logs`
+pub fn register_app_handle(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// The newest log files, newest first, for diagnostics
+pub fn recent_log_files(count: usize) -> Vec<PathBuf> {
+    let Some(Ok(entries)) = log_dir().map(fs::read_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX) && name.ends_with(".log"))
+        })
+        .collect();
+    // Named by date, so the name orders them
+    files.sort();
+    files.into_iter().rev().take(count).collect()
+}
+
+/// The most recent log entries, oldest first, the Logs panel follows `log-event` from there
+#[tauri::command]
+pub async fn get_recent_logs(limit: Option<usize>) -> Result<Vec<LogEntry>, String> {
+    let recent = RECENT_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+    let skip = limit.map_or(0, |limit| recent.len().saturating_sub(limit));
+    Ok(recent.iter().skip(skip).cloned().collect())
+}
+
+/// The filter directives events are logged with right now
+#[tauri::command]
+pub async fn get_log_level() -> Result<String, String> {
+    Ok(current_log_filter())
+}
+
+/// Change the log level until the app closes, the `log_level` setting is left as it is. An empty
+/// level goes back to the one in the settings
+#[tauri::command]
+pub async fn set_log_level(level: String) -> Result<String, String> {
+    let level = if level.trim().is_empty() { Settings::load()?.log_level } else { level };
+    set_log_filter(&level)?;
+    Ok(current_log_filter())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::{info, info_span};
+
+    #[test]
+    fn test_frontend_layer_records_spans_and_fields() {
+        let subscriber = tracing_subscriber::registry().with(FrontendLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = info_span!("download", file = "a.zip").entered();
+            info!(attempt = 2, "Retrying from mirror {}", "eu");
+        });
+
+        let recent = RECENT_LOGS.lock().unwrap();
+        let entry = recent.back().unwrap();
+        assert_eq!(entry.level, "INFO");
+        assert_eq!(entry.spans, ["download{file=a.zip}"]);
+        assert_eq!(entry.message, "Retrying from mirror eu attempt=2");
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(parse_filter("").unwrap().to_string(), DEFAULT_FILTER);
+        assert_eq!(parse_filter(" debug ").unwrap().to_string(), "debug");
+        assert!(parse_filter("info,bzmm::mods::downloader=trace").is_ok());
+        assert!(parse_filter("info,=loud").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Disk cache for content repositories link to besides the mods themselves: changelogs, icons
+//! and screenshots. It counts towards the cache size limit, the files used least recently go first
+use super::cache_limit::enforce_cache_limit;
+use super::downloader::ModDownloader;
+use super::xml_cache::XmlCache;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{error, warn};
+
+/// Where changelogs were cached before this cache, removed on purge
+const LEGACY_CHANGELOG_DIR: &str = "changelogs";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Changelog,
+    Icon,
+    Screenshot,
+}
+
+impl AssetKind {
+    const ALL: [AssetKind; 3] = [AssetKind::Changelog, AssetKind::Icon, AssetKind::Screenshot];
+
+    fn prefix(self) -> &'static str {
+        match self {
+            AssetKind::Changelog => "changelog",
+            AssetKind::Icon => "icon",
+            AssetKind::Screenshot => "screenshot",
+        }
+    }
+
+    fn of_file(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        Self::ALL.into_iter().find(|kind| name.starts_with(&format!("{}_", kind.prefix())))
+    }
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct AssetCacheUsage {
+    pub files: usize,
+    pub bytes: u64,
+    pub bytes_by_kind: BTreeMap<AssetKind, u64>,
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    let dir = XmlCache::get_cache_dir()?.parent()?.join("assets");
+    if let Err(e) = fs::create_dir_all(&dir) {
+        error!("Failed to create asset cache directory: {}", e);
+        return None;
+    }
+    Some(dir)
+}
+
+/// Cache file for an asset, keyed by its URL and, for content that changes with the mod, the
+/// version it belongs to
+fn asset_path(dir: &Path, kind: AssetKind, url: &str, version: Option<&str>) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    (url, version).hash(&mut hasher);
+    dir.join(format!("{}_{}", kind.prefix(), hasher.finish()))
+}
+
+/// Cached files with their size and when they were last used, least recently used first
+fn cached_files(dir: &Path) -> Vec<(SystemTime, u64, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok().filter(|meta| meta.is_file())?;
+            Some((meta.modified().ok()?, meta.len(), entry.path()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Read a cached asset, marking it as just used
+fn read_cached(path: &Path) -> Option<Vec<u8>> {
+    let bytes = fs::read(path).ok()?;
+    let touched = fs::File::options().write(true).open(path).and_then(|file| file.set_modified(SystemTime::now()));
+    if let Err(e) = touched {
+        warn!("Failed to mark {} as used: {}", path.display(), e);
+    }
+    Some(bytes)
+}
+
+/// An asset from the cache, downloaded and cached first when it isn't there or `refresh` is set.
+/// Without a cache directory it is downloaded every time
+pub async fn cached_asset(
+    kind: AssetKind,
+    url: &str,
+    version: Option<&str>,
+    refresh: bool,
+) -> Result<Vec<u8>, String> {
+    let path = cache_dir().map(|dir| asset_path(&dir, kind, url, version));
+    if !refresh {
+        if let Some(bytes) = path.as_deref().and_then(read_cached) {
+            return Ok(bytes);
+        }
+    }
+
+    let bytes = ModDownloader::new().fetch_bytes(url).await.map_err(|e| e.to_string())?;
+    if let Some(path) = path {
+        match fs::write(&path, &bytes) {
+            Ok(()) => enforce_cache_limit(),
+            Err(e) => warn!("Failed to cache {}: {}", url, e),
+        }
+    }
+    Ok(bytes)
+}
+
+fn usage(dir: &Path) -> AssetCacheUsage {
+    let mut usage = AssetCacheUsage::default();
+    for (_, size, path) in cached_files(dir) {
+        usage.files += 1;
+        usage.bytes += size;
+        if let Some(kind) = AssetKind::of_file(&path) {
+            *usage.bytes_by_kind.entry(kind).or_default() += size;
+        }
+    }
+    usage
+}
+
+/// Mod images such as icons and screenshots, through the cache
+#[tauri::command]
+pub async fn get_mod_image(url: String, kind: AssetKind) -> Result<tauri::ipc::Response, String> {
+    if kind == AssetKind::Changelog {
+        return Err("Changelogs are fetched with get_mod_changelog".to_string());
+    }
+    cached_asset(kind, &url, None, false).await.map(tauri::ipc::Response::new)
+}
+
+/// How much the cache of changelogs, icons and screenshots holds
+#[tauri::command]
+pub async fn get_asset_cache_usage() -> Result<AssetCacheUsage, String> {
+    let dir = cache_dir().ok_or_else(|| "Could not determine cache path".to_string())?;
+    Ok(usage(&dir))
+}
+
+/// Empty the asset cache, or with `kind` only the assets of that kind. Returns what is left
+#[tauri::command]
+pub async fn purge_asset_cache(kind: Option<AssetKind>) -> Result<AssetCacheUsage, String> {
+    let dir = cache_dir().ok_or_else(|| "Could not determine cache path".to_string())?;
+    for (_, _, path) in cached_files(&dir) {
+        if kind.is_none() || AssetKind::of_file(&path) == kind {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+    }
+    if kind.is_none_or(|kind| kind == AssetKind::Changelog) {
+        let legacy = dir.with_file_name(LEGACY_CHANGELOG_DIR);
+        if legacy.is_dir() {
+            fs::remove_dir_all(&legacy).map_err(|e| format!("Failed to remove {}: {}", legacy.display(), e))?;
+        }
+    }
+    Ok(usage(&dir))
+}
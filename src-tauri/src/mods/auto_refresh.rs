@@ -0,0 +1,93 @@
+use super::downloader::ModDownloader;
+use super::handlers::pending_updates;
+use super::migration::adopt_renamed_mod_dirs;
+use super::types::ModUpdate;
+use super::xml_cache::update_cache_path_in_settings;
+use crate::settings::Settings;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+/// How often the interval setting is looked at, so changing it takes effect without a restart
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Payload of the `mod-updates-checked` event
+#[derive(Debug, Serialize, Clone)]
+pub struct ProfileUpdates {
+    pub profile_index: usize,
+    pub profile_name: String,
+    pub updates: Vec<ModUpdate>,
+}
+
+/// Fetch every profile's repository once and emit `mod-updates-checked` for each profile
+async fn refresh_repos(app_handle: &AppHandle) {
+    let Ok(mut settings) = Settings::load() else {
+        return;
+    };
+    let urls: BTreeSet<String> = settings
+        .profiles
+        .iter()
+        .map(|profile| profile.repo_url.trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+        .collect();
+
+    for url in urls {
+        let (mods_file, cache_path) = match ModDownloader::new().fetch_and_parse_mods(&url).await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                warn!("Background refresh of {} failed: {}", url, e);
+                continue;
+            }
+        };
+        if let Some(path) = cache_path {
+            if let Err(e) = update_cache_path_in_settings(&mut settings, &url, &path) {
+                warn!("Failed to update cache path in settings: {}", e);
+            }
+        }
+
+        // Profiles sharing a repository can still download to different places
+        for (index, profile) in settings.profiles.iter().enumerate() {
+            if profile.repo_url.trim_end_matches('/') != url {
+                continue;
+            }
+            let download_path = settings.profile_download_path(profile);
+            adopt_renamed_mod_dirs(&mods_file, &download_path, &url);
+            match pending_updates(&mods_file, &download_path, &url) {
+                Ok(updates) => {
+                    let payload = ProfileUpdates {
+                        profile_index: index,
+                        profile_name: profile.name.clone(),
+                        updates,
+                    };
+                    if let Err(e) = app_handle.emit("mod-updates-checked", &payload) {
+                        error!("Failed to emit mod-updates-checked event: {}", e);
+                    }
+                }
+                Err(e) => warn!("Update check for profile {} failed: {}", profile.name, e),
+            }
+        }
+    }
+}
+
+/// Re-check the repositories for mod updates every `auto_refresh_minutes`, counted from app
+/// start since the mods page loads them then. Does nothing while the setting is 0
+pub fn start_auto_refresh(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_refresh = Instant::now();
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            // Reloaded every time, the interval can change while running
+            let minutes = Settings::load()
+                .map(|settings| settings.auto_refresh_minutes)
+                .unwrap_or(0);
+            if minutes == 0 || last_refresh.elapsed() < Duration::from_secs(minutes * 60) {
+                continue;
+            }
+            info!("Refreshing repositories in the background");
+            refresh_repos(&app_handle).await;
+            last_refresh = Instant::now();
+        }
+    });
+}
@@ -0,0 +1,64 @@
+use super::mod_enablement::{BackupStore, TargetDirs, TargetRoot};
+use super::mod_management::ModResult;
+use crate::settings::Settings;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// A DCS file a pristine copy is held for
+#[derive(Debug, Serialize)]
+pub struct BackupEntry {
+    pub file: String,
+    pub target: TargetRoot,
+}
+
+/// Backup stores for every target root the profile has set up
+fn profile_backup_stores(profile_name: &str) -> Result<Vec<(TargetRoot, BackupStore)>, String> {
+    let settings = Settings::load()?;
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+    let targets = TargetDirs::from_profile(profile);
+
+    let mut stores = Vec::new();
+    for target in [TargetRoot::SavedGames, TargetRoot::Install] {
+        if let Ok(dir) = targets.get(target) {
+            let store = BackupStore::new(profile_name, target, dir).map_err(|e| e.to_string())?;
+            stores.push((target, store));
+        }
+    }
+    Ok(stores)
+}
+
+/// DCS files backed up for a profile, the active one when no name is given
+#[tauri::command]
+pub async fn list_backups(profile_name: Option<String>) -> Result<Vec<BackupEntry>, String> {
+    let profile_name = Settings::load()?.profile_or_active(profile_name)?;
+    let mut entries = Vec::new();
+    for (target, store) in profile_backup_stores(&profile_name)? {
+        entries.extend(store.list_backed_up_files().into_iter().map(|file| BackupEntry {
+            file: file.display().to_string(),
+            target,
+        }));
+    }
+    Ok(entries)
+}
+
+/// Put the pristine copy of a DCS file back, dropping every patch and replacement made by mods.
+/// Mods still enabled can put their changes back with reapply_enabled_mods.
+#[tauri::command]
+pub async fn restore_backup(profile_name: Option<String>, file: String) -> Result<ModResult, String> {
+    let profile_name = Settings::load()?.profile_or_active(profile_name)?;
+    let file = PathBuf::from(file);
+    for (_, store) in profile_backup_stores(&profile_name)? {
+        if store.has_backup(&file) {
+            store.restore_file(&file).await.map_err(|e| e.to_string())?;
+            return Ok(ModResult {
+                success: true,
+                message: None,
+            });
+        }
+    }
+    Err(format!("No backup held for {}", file.display()))
+}
@@ -0,0 +1,204 @@
+//! Keeps the cache directory within the size set in the settings: repository files, their
+//! earlier copies and the asset cache. The files used least recently are removed first, the
+//! repository files of configured profiles are kept so mods still list offline
+use super::xml_cache::XmlCache;
+use crate::settings::Settings;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use tracing::{error, info};
+
+/// Directories of the cache that are pruned, other files in the cache directory are left alone
+const PRUNED_DIRS: [&str; 3] = ["xml_cache", "assets", "changelogs"];
+
+/// Set while the cache is pruned in the background
+static PRUNING: AtomicBool = AtomicBool::new(false);
+/// Set when something was added to the cache since the last prune started
+static PRUNE_AGAIN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize, Default)]
+pub struct CacheUsage {
+    pub files: usize,
+    pub bytes: u64,
+    /// 0 when the cache isn't limited
+    pub limit_bytes: u64,
+    /// Current copies of repository files
+    pub xml_bytes: u64,
+    /// Earlier copies of repository files
+    pub history_bytes: u64,
+    /// Changelogs, icons and screenshots
+    pub asset_bytes: u64,
+}
+
+fn limit_bytes(cache_size_mb: u64) -> u64 {
+    cache_size_mb.saturating_mul(1024 * 1024)
+}
+
+fn cache_root() -> Option<PathBuf> {
+    Some(XmlCache::get_cache_dir()?.parent()?.to_path_buf())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<(SystemTime, u64, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            collect_files(&entry.path(), files);
+        } else if let Ok(modified) = meta.modified() {
+            files.push((modified, meta.len(), entry.path()));
+        }
+    }
+}
+
+/// Files in the pruned parts of the cache, least recently used first. Reading an asset marks it
+/// as used, a repository file counts as used when it was fetched
+fn cached_files(root: &Path) -> Vec<(SystemTime, u64, PathBuf)> {
+    let mut files = Vec::new();
+    for dir in PRUNED_DIRS {
+        collect_files(&root.join(dir), &mut files);
+    }
+    files.sort();
+    files
+}
+
+/// Remove the least recently used files until the cache fits in `limit` bytes, except those
+/// `keep` lists, which is only worked out when the cache is too large. Returns the number of
+/// files removed
+fn prune(root: &Path, limit: u64, keep: impl FnOnce() -> HashSet<PathBuf>) -> io::Result<usize> {
+    let files = cached_files(root);
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= limit {
+        return Ok(0);
+    }
+    let keep = keep();
+    let mut removed = 0;
+    for (_, size, path) in files {
+        if total <= limit {
+            break;
+        }
+        if keep.contains(&path) {
+            continue;
+        }
+        fs::remove_file(&path)?;
+        total -= size;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// Repository files the profiles list their mods from, along with the files they include
+fn files_in_use(settings: &Settings) -> HashSet<PathBuf> {
+    settings
+        .profiles
+        .iter()
+        .flat_map(|profile| XmlCache::repo_cache_files(profile.repo_url.trim_end_matches('/')))
+        .collect()
+}
+
+/// Prune the cache down to the size limit in the settings, after something was added to it. The
+/// cache is walked on a background thread, calls made while it runs add up to one more prune
+/// after it. Failures are only logged, an oversized cache does no harm
+pub fn enforce_cache_limit() {
+    PRUNE_AGAIN.store(true, Ordering::SeqCst);
+    if PRUNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let spawned = std::thread::Builder::new().name("cache-prune".to_string()).spawn(|| loop {
+        while PRUNE_AGAIN.swap(false, Ordering::SeqCst) {
+            prune_to_limit();
+        }
+        PRUNING.store(false, Ordering::SeqCst);
+        // A call between the last prune and clearing PRUNING found it still set and returned
+        if !PRUNE_AGAIN.load(Ordering::SeqCst) || PRUNING.swap(true, Ordering::SeqCst) {
+            break;
+        }
+    });
+    if let Err(e) = spawned {
+        PRUNING.store(false, Ordering::SeqCst);
+        error!("Failed to start pruning the cache: {}", e);
+    }
+}
+
+fn prune_to_limit() {
+    let settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => return error!("Failed to load settings for the cache size limit: {}", e),
+    };
+    let Some(root) = cache_root() else {
+        return;
+    };
+    if settings.cache_size_mb == 0 {
+        return;
+    }
+    match prune(&root, limit_bytes(settings.cache_size_mb), || files_in_use(&settings)) {
+        Ok(0) => {}
+        Ok(removed) => info!("Removed {} cached files to stay within {} MB", removed, settings.cache_size_mb),
+        Err(e) => error!("Failed to prune the cache: {}", e),
+    }
+}
+
+/// How much the cache holds, against the size limit in the settings
+#[tauri::command]
+pub async fn get_cache_usage() -> Result<CacheUsage, String> {
+    let settings = Settings::load()?;
+    let root = cache_root().ok_or_else(|| "Could not determine cache path".to_string())?;
+    let history = root.join("xml_cache").join("history");
+    let xml = root.join("xml_cache");
+
+    let mut usage = CacheUsage {
+        limit_bytes: limit_bytes(settings.cache_size_mb),
+        ..Default::default()
+    };
+    for (_, size, path) in cached_files(&root) {
+        usage.files += 1;
+        usage.bytes += size;
+        if path.starts_with(&history) {
+            usage.history_bytes += size;
+        } else if path.starts_with(&xml) {
+            usage.xml_bytes += size;
+        } else {
+            usage.asset_bytes += size;
+        }
+    }
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_prune_least_recently_used() {
+        let root = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let files = [
+            ("xml_cache/repo_1.xml", 40),
+            ("xml_cache/history/repo_1/100.xml", 30),
+            ("assets/icon_1", 20),
+            ("assets/screenshot_2", 10),
+            ("webview/data", 50),
+        ];
+        for (name, age_secs) in files {
+            let path = root.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, [0u8; 100]).unwrap();
+            let file = fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - Duration::from_secs(age_secs)).unwrap();
+        }
+        let keep = || HashSet::from([root.path().join("xml_cache/repo_1.xml")]);
+
+        assert_eq!(prune(root.path(), 250, keep).unwrap(), 2);
+        let left: Vec<_> = cached_files(root.path()).into_iter().map(|(_, _, path)| path).collect();
+        assert_eq!(left, [root.path().join("xml_cache/repo_1.xml"), root.path().join("assets/screenshot_2")]);
+        assert!(root.path().join("webview/data").exists());
+    }
+}
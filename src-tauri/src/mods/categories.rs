@@ -0,0 +1,113 @@
+use super::handlers::{fetch_mods, get_cached_mods};
+use super::mod_utils::local_mod_dir;
+use super::sorting::{sort_mods, ModSort};
+use super::types::{Category, ModsResult};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a built listing is reused by the category commands, so opening one category after
+/// another doesn't build the whole listing each time. Changes show up once it runs out, or when
+/// `get_mods` builds the listing again
+const LISTING_REUSED_FOR: Duration = Duration::from_secs(30);
+
+/// A listing with the repository it was built from and when
+struct BuiltListing {
+    repo_url: String,
+    built_at: Instant,
+    listing: ModsResult,
+}
+
+/// Listing last built for each profile index
+static LISTINGS: Mutex<Option<HashMap<usize, BuiltListing>>> = Mutex::new(None);
+
+/// A category without its mods, for listing huge repositories before loading any of them
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CategorySummary {
+    pub name: String,
+    pub sort_order: i32,
+    pub mod_count: usize,
+    pub downloaded: usize,
+    pub updatable: usize,
+}
+
+/// Keep a listing just built for a profile, for the category commands to reuse
+pub fn remember_listing(profile_index: usize, repo_url: &str, listing: &ModsResult) {
+    let mut listings = LISTINGS.lock().unwrap_or_else(|e| e.into_inner());
+    listings
+        .get_or_insert_with(HashMap::new)
+        .insert(
+            profile_index,
+            BuiltListing { repo_url: repo_url.to_string(), built_at: Instant::now(), listing: listing.clone() },
+        );
+}
+
+/// The listing built last for a profile, if it is recent and still for the profile's repository
+fn reused_listing(profile_index: usize, repo_url: &str) -> Option<ModsResult> {
+    let listings = LISTINGS.lock().unwrap_or_else(|e| e.into_inner());
+    listings
+        .as_ref()?
+        .get(&profile_index)
+        .filter(|built| built.repo_url == repo_url && built.built_at.elapsed() < LISTING_REUSED_FOR)
+        .map(|built| built.listing.clone())
+}
+
+/// The listing `get_mods` would return: the one built last when it is recent, else from the
+/// cache when there is one
+async fn load_listing(profile_index: usize) -> Result<ModsResult, String> {
+    let repo_url = Settings::load()?
+        .profiles
+        .get(profile_index)
+        .map(|profile| profile.repo_url.trim_end_matches('/').to_string())
+        .ok_or_else(|| "Profile index out of bounds".to_string())?;
+    if let Some(listing) = reused_listing(profile_index, &repo_url) {
+        return Ok(listing);
+    }
+    match get_cached_mods(profile_index)? {
+        Some(cached) => Ok(cached),
+        None => fetch_mods(profile_index).await,
+    }
+}
+
+/// Category names and counts of a profile's listing, without the mods themselves
+#[tauri::command]
+pub async fn get_mod_categories(profile_index: usize) -> Result<Vec<CategorySummary>, String> {
+    let listing = load_listing(profile_index).await?;
+    let settings = Settings::load()?;
+
+    Ok(listing
+        .categories
+        .iter()
+        .map(|cat| CategorySummary {
+            name: cat.name.clone(),
+            sort_order: cat.sort_order,
+            mod_count: cat.mods.len(),
+            downloaded: cat
+                .mods
+                .iter()
+                .filter(|m| local_mod_dir(&settings, m.source.as_deref(), &m.name).is_some())
+                .count(),
+            updatable: cat.mods.iter().filter(|m| m.new_version.is_some()).count(),
+        })
+        .collect())
+}
+
+/// The mods of a single category, loaded on demand
+#[tauri::command]
+pub async fn get_category_mods(
+    profile_index: usize,
+    category: String,
+    sort: Option<ModSort>,
+) -> Result<Category, String> {
+    let mut listing = load_listing(profile_index).await?;
+    listing.categories.retain(|cat| cat.name == category);
+    if let Some(sort) = sort {
+        sort_mods(&mut listing, sort, &Settings::load()?);
+    }
+    listing
+        .categories
+        .pop()
+        .ok_or_else(|| format!("Category '{}' not found", category))
+}
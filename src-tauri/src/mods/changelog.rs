@@ -0,0 +1,32 @@
+use super::asset_cache::{cached_asset, AssetKind};
+use super::handlers::{fetch_mods, get_cached_mods};
+
+/// Get the changelog of a repository mod, downloading it once per repository version.
+/// Returns None when the repository doesn't provide one for the mod.
+#[tauri::command]
+pub async fn get_mod_changelog(
+    profile_index: usize,
+    mod_name: String,
+    refresh: Option<bool>,
+) -> Result<Option<String>, String> {
+    let result = match get_cached_mods(profile_index)? {
+        Some(cached) => cached,
+        None => fetch_mods(profile_index).await?,
+    };
+    let mod_entry = result
+        .categories
+        .into_iter()
+        .flat_map(|cat| cat.mods)
+        .find(|m| m.name == mod_name)
+        .ok_or_else(|| format!("Mod '{}' not found in the repository", mod_name))?;
+    let Some(url) = mod_entry.changelog_url.filter(|url| !url.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    // The changelog of the version an update would install
+    let version = mod_entry.new_version.unwrap_or(mod_entry.version);
+    let bytes = cached_asset(AssetKind::Changelog, &url, Some(&version), refresh.unwrap_or(false))
+        .await
+        .map_err(|e| format!("Failed to download changelog for {}: {}", mod_name, e))?;
+    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+}
@@ -0,0 +1,176 @@
+use super::types::Category;
+use super::versions::compare_versions;
+use super::xml_cache::XmlCache;
+use crate::settings::{DcsVariant, Profile, Settings};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+/// DCS version of a profile's installation, read from the `autoupdate.cfg` the updater keeps
+/// in the install directory
+pub fn detect_dcs_version(profile: &Profile) -> Option<String> {
+    if profile.install_path.is_empty() {
+        return None;
+    }
+    let content = fs::read_to_string(Path::new(&profile.install_path).join("autoupdate.cfg")).ok()?;
+    let config: serde_json::Value = serde_json::from_str(&content).ok()?;
+    config.get("version")?.as_str().map(|version| version.trim().to_string())
+}
+
+/// The first segments of a version, so an upper bound of 2.9 takes in every 2.9.x release
+fn truncate_segments(version: &str, count: usize) -> String {
+    version.split('.').take(count).collect::<Vec<_>>().join(".")
+}
+
+/// Whether a DCS version falls within a mod's `dcs_version`, either a minimum like "2.9" or an
+/// inclusive range like "2.8-2.9". Ranges that can't be read don't rule anything out
+pub fn is_dcs_version_compatible(range: &str, dcs_version: &str) -> bool {
+    let (min, max) = match range.split_once('-') {
+        Some((min, max)) => (min.trim(), max.trim()),
+        None => (range.trim(), ""),
+    };
+    if !min.is_empty() && compare_versions(dcs_version, min) == Ordering::Less {
+        return false;
+    }
+    if !max.is_empty() {
+        let segments = max.split('.').count();
+        return compare_versions(&truncate_segments(dcs_version, segments), max) != Ordering::Greater;
+    }
+    true
+}
+
+/// A variant from how repositories, folder names and updater branches spell it, such as
+/// "OpenBeta", "DCS.openbeta" or "dcs_server"
+fn parse_dcs_variant(name: &str) -> Option<DcsVariant> {
+    let name = name.to_lowercase();
+    if name.contains("server") {
+        Some(DcsVariant::DedicatedServer)
+    } else if name.contains("beta") {
+        Some(DcsVariant::OpenBeta)
+    } else if name.starts_with("dcs") || name.contains("stable") || name.contains("release") {
+        Some(DcsVariant::Stable)
+    } else {
+        None
+    }
+}
+
+/// Variant of a DCS folder, from the branch in an install's `autoupdate.cfg` or else the folder
+/// name, like "DCS.openbeta" in Saved Games or "DCS World OpenBeta Server" for an install
+pub fn dcs_dir_variant(dir: &Path) -> Option<DcsVariant> {
+    let branch = fs::read_to_string(dir.join("autoupdate.cfg"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|config| config.get("branch")?.as_str().map(str::to_string));
+    branch
+        .as_deref()
+        .and_then(parse_dcs_variant)
+        .or_else(|| parse_dcs_variant(&dir.file_name()?.to_string_lossy()))
+}
+
+/// Variant of a profile's DCS, looking at the install directory before Saved Games
+pub fn detect_dcs_variant(profile: &Profile) -> Option<DcsVariant> {
+    [&profile.install_path, &profile.dcs_path]
+        .into_iter()
+        .filter(|path| !path.trim().is_empty())
+        .find_map(|path| dcs_dir_variant(Path::new(path.trim())))
+}
+
+/// The variant set on a profile, or the one its paths point to
+pub fn profile_dcs_variant(profile: &Profile) -> Option<DcsVariant> {
+    profile.dcs_variant.or_else(|| detect_dcs_variant(profile))
+}
+
+/// Whether a mod's `dcs_variants` list takes in a variant. Lists without a single recognized
+/// variant don't rule anything out
+pub fn is_dcs_variant_compatible(variants: &str, variant: DcsVariant) -> bool {
+    let listed: Vec<DcsVariant> = variants.split(',').filter_map(parse_dcs_variant).collect();
+    listed.is_empty() || listed.contains(&variant)
+}
+
+/// Flag the mods of a listing that weren't made for the profile's DCS version or variant
+pub fn mark_dcs_incompatible(
+    categories: &mut [Category],
+    dcs_version: Option<&str>,
+    dcs_variant: Option<DcsVariant>,
+) {
+    for mod_entry in categories.iter_mut().flat_map(|cat| cat.mods.iter_mut()) {
+        let wrong_version = dcs_version.is_some_and(|dcs_version| {
+            mod_entry
+                .dcs_version
+                .as_deref()
+                .is_some_and(|range| !is_dcs_version_compatible(range, dcs_version))
+        });
+        let wrong_variant = dcs_variant.is_some_and(|dcs_variant| {
+            mod_entry
+                .dcs_variants
+                .as_deref()
+                .is_some_and(|variants| !is_dcs_variant_compatible(variants, dcs_variant))
+        });
+        mod_entry.dcs_incompatible = wrong_version || wrong_variant;
+    }
+}
+
+/// A warning for the user when a repository mod wasn't made for the profile's DCS version or
+/// variant
+pub fn dcs_compatibility_note(profile_name: &str, mod_name: &str) -> Option<String> {
+    let settings = Settings::load().ok()?;
+    let profile = settings.profiles.iter().find(|p| p.name == profile_name)?;
+    let mods_file = XmlCache::load_for_repo(&profile.repo_url)?;
+    let mod_entry = mods_file
+        .categories
+        .iter()
+        .flat_map(|cat| cat.mods.iter())
+        .find(|m| m.name == mod_name)?;
+
+    let mut notes = Vec::new();
+    if let (Some(range), Some(dcs_version)) = (&mod_entry.dcs_version, detect_dcs_version(profile)) {
+        if !is_dcs_version_compatible(range, &dcs_version) {
+            notes.push(format!(
+                "{} is made for DCS {}, this installation is {}, it may not work",
+                mod_name, range, dcs_version
+            ));
+        }
+    }
+    if let (Some(variants), Some(dcs_variant)) = (&mod_entry.dcs_variants, profile_dcs_variant(profile)) {
+        if !is_dcs_variant_compatible(variants, dcs_variant) {
+            notes.push(format!(
+                "{} is made for DCS {}, not {}, it may not work",
+                mod_name,
+                variants,
+                dcs_variant.label()
+            ));
+        }
+    }
+    (!notes.is_empty()).then(|| notes.join(". "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dcs_version_ranges() {
+        assert!(is_dcs_version_compatible("2.9", "2.9.3.51704"));
+        assert!(!is_dcs_version_compatible("2.9", "2.8.8.43704"));
+        assert!(is_dcs_version_compatible("2.8-2.9", "2.9.3.51704"));
+        assert!(!is_dcs_version_compatible("2.8-2.9", "2.10.0.1"));
+        assert!(is_dcs_version_compatible("-2.8.8", "2.8.8.43704"));
+        assert!(!is_dcs_version_compatible("2.9.2-2.9.4", "2.9.1.48111"));
+    }
+
+    #[test]
+    fn test_dcs_variants() {
+        assert_eq!(dcs_dir_variant(Path::new("Saved Games/DCS")), Some(DcsVariant::Stable));
+        assert_eq!(dcs_dir_variant(Path::new("Saved Games/DCS.openbeta")), Some(DcsVariant::OpenBeta));
+        assert_eq!(
+            dcs_dir_variant(Path::new("Saved Games/DCS.openbeta_server")),
+            Some(DcsVariant::DedicatedServer)
+        );
+        assert_eq!(dcs_dir_variant(Path::new("Games/Eagle Dynamics")), None);
+
+        assert!(is_dcs_variant_compatible("Stable, OpenBeta", DcsVariant::OpenBeta));
+        assert!(!is_dcs_variant_compatible("Stable, OpenBeta", DcsVariant::DedicatedServer));
+        assert!(is_dcs_variant_compatible("Dedicated Server", DcsVariant::DedicatedServer));
+        assert!(is_dcs_variant_compatible("anything", DcsVariant::Stable));
+    }
+}
@@ -0,0 +1,123 @@
+//! Mods extracted from byte-identical archives, e.g. from a repository and its mirror, share
+//! their files through hardlinks instead of taking the disk space twice. Nothing writes into a
+//! downloaded mod's files in place, updates and removals replace whole directories, so a shared
+//! file never changes under the other copy
+use crate::state_db::with_db;
+use rusqlite::params;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// SHA-256 of a downloaded archive, identifying its content whatever repository it came from
+pub fn hash_archive(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Whether two files have the same content, compared in full since the other copy may have been
+/// changed since it was extracted
+fn same_content(a: &Path, b: &Path) -> io::Result<bool> {
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+    let (mut a, mut b) = (io::BufReader::new(fs::File::open(a)?), io::BufReader::new(fs::File::open(b)?));
+    let (mut buf_a, mut buf_b) = (vec![0u8; 64 * 1024], vec![0u8; 64 * 1024]);
+    loop {
+        let read = a.read(&mut buf_a)?;
+        if read == 0 {
+            return Ok(true);
+        }
+        b.read_exact(&mut buf_b[..read])?;
+        if buf_a[..read] != buf_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+/// Replace each file under `dir` with a hardlink to the identical file at the same place under
+/// `original`, returning the bytes freed. Files that differ or are missing there are left alone
+fn link_identical_files(original: &Path, dir: &Path) -> io::Result<u64> {
+    let mut freed = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let counterpart = original.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if counterpart.is_dir() {
+                freed += link_identical_files(&counterpart, &path)?;
+            }
+            continue;
+        }
+        if !file_type.is_file() || !counterpart.is_file() || !same_content(&counterpart, &path)? {
+            continue;
+        }
+        // Linked next to the file first, so it is never missing if linking fails
+        let link = path.with_file_name(format!("{}.bzmm-link", entry.file_name().to_string_lossy()));
+        let _ = fs::remove_file(&link);
+        fs::hard_link(&counterpart, &link)?;
+        fs::rename(&link, &path)?;
+        freed += entry.metadata()?.len();
+    }
+    Ok(freed)
+}
+
+/// Remember which archive a mod was extracted from, and link its files to an earlier extraction
+/// of the same archive. Linking fails quietly across drives, the mod is complete either way
+pub fn share_identical_mod(mod_dir: &Path, archive_hash: &str) -> Result<(), String> {
+    let path = mod_dir.to_string_lossy().to_string();
+    let others: Vec<String> = with_db(|db| {
+        db.execute(
+            "INSERT OR REPLACE INTO mod_archives (path, archive_hash) VALUES (?1, ?2)",
+            params![path, archive_hash],
+        )?;
+        let mut stmt = db.prepare("SELECT path FROM mod_archives WHERE archive_hash = ?1 AND path != ?2")?;
+        let others = stmt.query_map(params![archive_hash, path], |row| row.get(0))?.collect();
+        others
+    })?;
+
+    let Some(original) = others.iter().map(Path::new).find(|other| other.is_dir()) else {
+        return Ok(());
+    };
+    match link_identical_files(original, mod_dir) {
+        Ok(freed) => info!(
+            "{} shares {} bytes with the identical download at {}",
+            mod_dir.display(),
+            freed,
+            original.display()
+        ),
+        Err(e) => warn!("Not sharing files of {} with {}: {}", mod_dir.display(), original.display(), e),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_identical_files() {
+        let root = tempfile::tempdir().unwrap();
+        let (original, copy) = (root.path().join("a/ModA"), root.path().join("b/ModA"));
+        for dir in [&original, &copy] {
+            fs::create_dir_all(dir.join("ModA")).unwrap();
+            fs::write(dir.join("ModA/texture.dds"), [7u8; 1000]).unwrap();
+        }
+        fs::write(original.join("VERSION.txt"), "1.0").unwrap();
+        fs::write(copy.join("VERSION.txt"), "1.1").unwrap();
+
+        assert_eq!(link_identical_files(&original, &copy).unwrap(), 1000);
+        assert_eq!(fs::read_to_string(copy.join("VERSION.txt")).unwrap(), "1.1");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let inode = |path: &Path| fs::metadata(path).unwrap().ino();
+            assert_eq!(inode(&original.join("ModA/texture.dds")), inode(&copy.join("ModA/texture.dds")));
+            assert_ne!(inode(&original.join("VERSION.txt")), inode(&copy.join("VERSION.txt")));
+        }
+    }
+}
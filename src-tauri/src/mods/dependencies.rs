@@ -0,0 +1,217 @@
+use super::download_queue::{get_queue, DownloadTarget};
+use super::mod_download::is_mod_successfully_downloaded;
+use super::mod_management::enable_single_mod;
+use super::mod_utils::{get_mod_version, get_repo_download_dir};
+use super::types::{Mod, ModRequirement, ModsFile};
+use super::versions::compare_versions;
+use super::xml_cache::XmlCache;
+use crate::settings::Settings;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tracing::info;
+
+/// Requirements of a mod, resolved against its repository
+#[derive(Debug, Default)]
+pub struct ResolvedRequirements<'a> {
+    /// Every mod needed directly or through another requirement, dependencies before the mods
+    /// that need them
+    pub mods: Vec<&'a Mod>,
+    /// Required names the repository doesn't list
+    pub unlisted: Vec<String>,
+}
+
+fn find_mod<'a>(mods_file: &'a ModsFile, name: &str) -> Option<&'a Mod> {
+    mods_file
+        .categories
+        .iter()
+        .flat_map(|cat| cat.mods.iter())
+        .find(|m| m.name == name || m.display_name.as_deref() == Some(name))
+}
+
+fn visit<'a>(
+    mods_file: &'a ModsFile,
+    mod_entry: &'a Mod,
+    seen: &mut HashSet<String>,
+    resolved: &mut ResolvedRequirements<'a>,
+) {
+    for requirement in &mod_entry.requires {
+        if !seen.insert(requirement.name.clone()) {
+            continue;
+        }
+        match find_mod(mods_file, &requirement.name) {
+            Some(required) => {
+                visit(mods_file, required, seen, resolved);
+                resolved.mods.push(required);
+            }
+            None => resolved.unlisted.push(requirement.name.clone()),
+        }
+    }
+}
+
+/// Walk the requirements of a mod, tolerating cycles in the repository
+pub fn resolve_requirements<'a>(mods_file: &'a ModsFile, mod_name: &str) -> ResolvedRequirements<'a> {
+    let mut resolved = ResolvedRequirements::default();
+    if let Some(mod_entry) = find_mod(mods_file, mod_name) {
+        let mut seen = HashSet::from([mod_name.to_string()]);
+        visit(mods_file, mod_entry, &mut seen, &mut resolved);
+    }
+    resolved
+}
+
+/// Where a required mod is installed, the repository's copy first, then the sideloaded one
+fn installed_dir(settings: &Settings, repo_dir: &Path, mod_name: &str) -> Option<PathBuf> {
+    if is_mod_successfully_downloaded(repo_dir, mod_name) {
+        return Some(repo_dir.join(mod_name));
+    }
+    let sideloaded = Path::new(&settings.sideload_path).join(mod_name);
+    (!settings.sideload_path.is_empty() && sideloaded.is_dir()).then_some(sideloaded)
+}
+
+/// Queue a required mod that isn't installed, unless it is already on its way. Returns whether
+/// the mod will be downloaded
+async fn queue_requirement(
+    app_handle: &AppHandle,
+    repo_url: &str,
+    required: &Mod,
+    target: DownloadTarget,
+) -> bool {
+    let Some(url) = required.url.clone() else {
+        return false;
+    };
+    let filename = format!("{}.zip", required.name);
+    let queue = get_queue();
+    if !queue.is_pending(&filename).await {
+        info!("Queuing required mod {}", required.name);
+        queue
+            .add_download(app_handle.clone(), url, filename, repo_url.to_string(), required.size, target)
+            .await;
+    }
+    true
+}
+
+/// Queue downloads for the requirements of a mod that aren't installed yet. Returns the names queued
+pub async fn queue_missing_requirements(
+    app_handle: &AppHandle,
+    repo_url: &str,
+    mod_name: &str,
+    target: DownloadTarget,
+) -> Vec<String> {
+    let (Ok(settings), Some(mods_file)) = (Settings::load(), XmlCache::load_for_repo(repo_url)) else {
+        return Vec::new();
+    };
+    let download_path = settings.repo_download_path(repo_url, target.profile.as_deref());
+    let repo_dir = get_repo_download_dir(&download_path, repo_url.trim_end_matches('/'));
+
+    let mut queued = Vec::new();
+    for required in resolve_requirements(&mods_file, mod_name).mods {
+        if installed_dir(&settings, &repo_dir, &required.name).is_none()
+            && queue_requirement(app_handle, repo_url, required, target.clone()).await
+        {
+            queued.push(required.name.clone());
+        }
+    }
+    queued
+}
+
+/// Enable the installed requirements of a mod for a profile and queue the missing ones, which
+/// get enabled when their download finishes. Returns a note for the user about anything that
+/// couldn't be enabled right away
+pub async fn enable_requirements(
+    app_handle: &AppHandle,
+    profile_name: &str,
+    mod_name: &str,
+) -> Result<Option<String>, String> {
+    let settings = Settings::load()?;
+    let Some(profile) = settings.profiles.iter().find(|p| p.name == profile_name) else {
+        return Ok(None);
+    };
+    let Some(mods_file) = XmlCache::load_for_repo(&profile.repo_url) else {
+        return Ok(None);
+    };
+    let repo_url = profile.repo_url.trim_end_matches('/');
+    let repo_dir = get_repo_download_dir(&settings.profile_download_path(profile), repo_url);
+
+    let resolved = resolve_requirements(&mods_file, mod_name);
+    // Every <requires> along the way, the highest minimum version asked for a mod wins
+    let needs: Vec<&ModRequirement> = find_mod(&mods_file, mod_name)
+        .into_iter()
+        .chain(resolved.mods.iter().copied())
+        .flat_map(|m| m.requires.iter())
+        .collect();
+    let mut queued = Vec::new();
+    let mut missing = resolved.unlisted.clone();
+    let mut outdated = Vec::new();
+
+    for required in &resolved.mods {
+        let Some(dir) = installed_dir(&settings, &repo_dir, &required.name) else {
+            if queue_requirement(app_handle, repo_url, required, DownloadTarget::enabling(profile_name.to_string())).await {
+                queued.push(required.name.clone());
+            } else {
+                missing.push(required.name.clone());
+            }
+            continue;
+        };
+
+        let min_version = needs
+            .iter()
+            .filter(|r| r.name == required.name)
+            .filter_map(|r| r.min_version.as_deref())
+            .max_by(|a, b| compare_versions(a, b));
+        if let (Some(min_version), Ok(version)) = (min_version, get_mod_version(&dir)) {
+            if compare_versions(&version, min_version) == Ordering::Less {
+                outdated.push(format!("{} {} (needs {})", required.name, version, min_version));
+            }
+        }
+
+        info!("Enabling {} required by {}", required.name, mod_name);
+        enable_single_mod(
+            app_handle.clone(),
+            required.name.clone(),
+            profile_name.to_string(),
+            None,
+        )
+        .await
+        .map_err(|e| format!("Failed to enable required mod '{}': {}", required.name, e))?;
+    }
+
+    let mut notes = Vec::new();
+    if !queued.is_empty() {
+        notes.push(format!("Downloading required mods: {}", queued.join(", ")));
+    }
+    if !missing.is_empty() {
+        notes.push(format!("Required mods not available from the repository: {}", missing.join(", ")));
+    }
+    if !outdated.is_empty() {
+        notes.push(format!("Required mods older than needed: {}", outdated.join(", ")));
+    }
+    Ok((!notes.is_empty()).then(|| notes.join(". ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mods::parser::ModParser;
+
+    #[test]
+    fn test_requirements_resolve_dependencies_first() {
+        let xml = r#"<mods>
+            <category name="Aircraft" sort_order="1">
+                <mod name="Viper Liveries" version="1.0">Liveries
+                    <requires version="2.0">Viper Textures</requires>
+                    <requires>Missing Pack</requires>
+                </mod>
+                <mod name="Viper Textures" version="2.1"><requires>Common Assets</requires></mod>
+                <mod name="Common Assets" version="1.0"><requires>Viper Textures</requires></mod>
+            </category>
+        </mods>"#;
+        let mods_file = ModParser::parse_mod_list(xml).unwrap();
+        assert_eq!(mods_file.categories[0].mods[0].description, "Liveries");
+
+        let resolved = resolve_requirements(&mods_file, "Viper Liveries");
+        let names: Vec<&str> = resolved.mods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["Common Assets", "Viper Textures"]);
+        assert_eq!(resolved.unlisted, ["Missing Pack"]);
+    }
+}
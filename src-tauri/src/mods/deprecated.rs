@@ -1,11 +1,20 @@
+use super::download_queue::{get_queue, DownloadTarget};
+use super::mod_download::is_mod_successfully_downloaded;
+use super::mod_list::load_repo_mods;
+use super::mod_management::{disable_mod, enable_mod, ModOperationOutcome, ModResult};
+use super::mod_utils::{dir_size, get_repo_download_dir, get_repo_source, is_mod_enabled};
 use super::types::{Category, Mod, ModError};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 use std::collections::HashSet;
+use tauri::AppHandle;
+use tracing::{error, info};
 
 // Similar to sideload.rs, but for detecting deprecated mods
 pub fn read_mod_metadata(mod_dir: &Path) -> Result<Mod, ModError> {
-    println!("Reading metadata for deprecated mod: {:?}", mod_dir);
+    info!("Reading metadata for deprecated mod: {:?}", mod_dir);
     let name = mod_dir
         .file_name()
         .and_then(|n| n.to_str())
@@ -24,7 +33,7 @@ pub fn read_mod_metadata(mod_dir: &Path) -> Result<Mod, ModError> {
         .trim()
         .to_string();
 
-    println!("Found deprecated mod: {} ({})", name, version);
+    info!("Found deprecated mod: {} ({})", name, version);
     Ok(Mod::new_deprecated(name, version, description))
 }
 
@@ -34,12 +43,12 @@ pub fn scan_for_deprecated_mods(
     xml_specific_path: &Path,
     active_mod_names: &HashSet<String>,
 ) -> Result<Category, ModError> {
-    println!(
+    info!(
         "Scanning for deprecated mods within specific path: {}",
         xml_specific_path.display()
     );
     if !xml_specific_path.exists() || !xml_specific_path.is_dir() {
-        println!("XML-specific directory does not exist or is not a directory.");
+        info!("XML-specific directory does not exist or is not a directory.");
         // Not an error, just means no mods downloaded for this source yet.
         return Ok(Category::new_deprecated(Vec::new()));
     }
@@ -57,16 +66,195 @@ pub fn scan_for_deprecated_mods(
                 if !active_mod_names.contains(mod_name) {
                     match read_mod_metadata(&path) {
                         Ok(mod_info) => {
-                            println!("Successfully read metadata for deprecated mod: {:?}", path);
+                            info!("Successfully read metadata for deprecated mod: {:?}", path);
                             deprecated_mods.push(mod_info);
                         }
-                        Err(e) => eprintln!("Failed to read metadata for deprecated mod {:?}: {}", path, e),
+                        Err(e) => error!("Failed to read metadata for deprecated mod {:?}: {}", path, e),
                     }
                 }
             }
         }
     }
 
-    println!("Found {} deprecated mods", deprecated_mods.len());
+    info!("Found {} deprecated mods", deprecated_mods.len());
     Ok(Category::new_deprecated(deprecated_mods))
 }
+
+/// Take the mods the repository marks as deprecated out of its categories. The downloaded ones
+/// are returned to join the deprecated mods found locally, the others aren't offered anymore
+pub fn take_repo_deprecated(categories: &mut [Category], repo_dir: &Path) -> Vec<Mod> {
+    let mut downloaded = Vec::new();
+    for category in categories.iter_mut() {
+        let (deprecated, kept): (Vec<Mod>, Vec<Mod>) =
+            std::mem::take(&mut category.mods).into_iter().partition(|m| m.deprecated);
+        category.mods = kept;
+        downloaded.extend(deprecated.into_iter().filter(|m| repo_dir.join(&m.name).is_dir()));
+    }
+    downloaded
+}
+
+/// Result of deleting every deprecated mod of a repository
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct DeprecatedCleanupResult {
+    pub mods: Vec<ModOperationOutcome>,
+    /// Disk space freed by the mods that were deleted, in bytes
+    pub bytes_reclaimed: u64,
+}
+
+/// Delete every deprecated mod downloaded from a profile's repository, disabling it first in
+/// each profile using that repository
+#[tauri::command]
+pub async fn cleanup_deprecated_mods(
+    app_handle: AppHandle,
+    profile_name: String,
+) -> Result<DeprecatedCleanupResult, String> {
+    let settings = Settings::load()?;
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+    let repo_url = profile.repo_url.trim_end_matches('/');
+
+    // Without the repository listing every downloaded mod would look deprecated, so fail instead
+    let repo_mods = load_repo_mods(repo_url).await?;
+    let active_mod_names: HashSet<String> = repo_mods
+        .categories
+        .iter()
+        .flat_map(|cat| cat.mods.iter().filter(|m| !m.deprecated).map(|m| m.name.clone()))
+        .collect();
+
+    let repo_dir = get_repo_download_dir(&settings.profile_download_path(profile), repo_url);
+    let deprecated = scan_for_deprecated_mods(&repo_dir, &active_mod_names).map_err(|e| e.to_string())?;
+    let repo_profiles: Vec<&str> = settings
+        .profiles
+        .iter()
+        .filter(|p| p.repo_url.trim_end_matches('/') == repo_url)
+        .map(|p| p.name.as_str())
+        .collect();
+
+    let mut result = DeprecatedCleanupResult::default();
+    for mod_entry in deprecated.mods {
+        let mod_dir = repo_dir.join(&mod_entry.name);
+        info!("Cleaning up deprecated mod: {}", mod_entry.name);
+
+        let mut disable_error = None;
+        for name in &repo_profiles {
+            match is_mod_enabled(&mod_dir, name) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    disable_error = Some(e);
+                    break;
+                }
+            }
+            let source = Some(get_repo_source(repo_url));
+            if let Err(e) = disable_mod(app_handle.clone(), mod_entry.name.clone(), name.to_string(), source).await {
+                disable_error = Some(format!("Failed to disable for profile '{}': {}", name, e));
+                break;
+            }
+        }
+        if let Some(message) = disable_error {
+            result.mods.push(ModOperationOutcome {
+                mod_name: mod_entry.name,
+                success: false,
+                message: Some(message),
+            });
+            continue;
+        }
+
+        let size = dir_size(&mod_dir);
+        let outcome = match tokio::fs::remove_dir_all(&mod_dir).await {
+            Ok(()) => {
+                result.bytes_reclaimed += size;
+                ModOperationOutcome {
+                    mod_name: mod_entry.name,
+                    success: true,
+                    message: None,
+                }
+            }
+            Err(e) => ModOperationOutcome {
+                mod_name: mod_entry.name,
+                success: false,
+                message: Some(e.to_string()),
+            },
+        };
+        result.mods.push(outcome);
+    }
+
+    info!("Deleted deprecated mods, reclaimed {} bytes", result.bytes_reclaimed);
+    Ok(result)
+}
+
+/// Move a profile from a deprecated mod to the one the repository names as its replacement:
+/// the old mod is disabled, and the replacement enabled in its place, after downloading it
+/// when needed. The old copy stays on disk for `cleanup_deprecated_mods`
+#[tauri::command]
+pub async fn migrate_deprecated_mod(
+    app_handle: AppHandle,
+    profile_name: String,
+    mod_name: String,
+) -> Result<ModResult, String> {
+    let settings = Settings::load()?;
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+    let repo_url = profile.repo_url.trim_end_matches('/').to_string();
+
+    let repo_mods = load_repo_mods(&repo_url).await?;
+    let mods: Vec<&Mod> = repo_mods.categories.iter().flat_map(|cat| cat.mods.iter()).collect();
+    let replaced_by = mods
+        .iter()
+        .find(|m| m.name == mod_name)
+        .and_then(|m| m.replaced_by.clone())
+        .ok_or_else(|| format!("The repository names no replacement for '{}'", mod_name))?;
+    let replacement = mods
+        .iter()
+        .find(|m| !m.deprecated && (m.name == replaced_by || m.display_name.as_deref() == Some(replaced_by.as_str())))
+        .ok_or_else(|| format!("Replacement '{}' is not in the repository", replaced_by))?;
+
+    let repo_dir = get_repo_download_dir(&settings.profile_download_path(profile), &repo_url);
+    let source = Some(get_repo_source(&repo_url));
+    let was_enabled = is_mod_enabled(&repo_dir.join(&mod_name), &profile_name)?;
+    info!("Migrating {} to {} for profile {}", mod_name, replacement.name, profile_name);
+    if was_enabled {
+        disable_mod(app_handle.clone(), mod_name.clone(), profile_name.clone(), source.clone()).await?;
+    }
+
+    let message = if is_mod_successfully_downloaded(&repo_dir, &replacement.name) {
+        if was_enabled {
+            return enable_mod(app_handle, replacement.name.clone(), profile_name, source).await;
+        }
+        format!("{} is already downloaded", replacement.name)
+    } else {
+        let url = replacement
+            .url
+            .clone()
+            .ok_or_else(|| format!("Replacement '{}' has no download URL", replacement.name))?;
+        get_queue()
+            .add_download(
+                app_handle,
+                url,
+                format!("{}.zip", replacement.name),
+                repo_url.clone(),
+                replacement.size,
+                DownloadTarget {
+                    profile: Some(profile_name),
+                    enable: was_enabled,
+                },
+            )
+            .await;
+        if was_enabled {
+            format!("Downloading {}, it will be enabled once installed", replacement.name)
+        } else {
+            format!("Downloading {}", replacement.name)
+        }
+    };
+
+    Ok(ModResult {
+        success: true,
+        message: Some(message),
+    })
+}
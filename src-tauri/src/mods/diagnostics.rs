@@ -0,0 +1,174 @@
+use super::download_queue::get_queue;
+use super::enable_lock::{read_lock_status, LockStatus};
+use super::mod_utils::{
+    get_mod_version, get_repo_source, is_mod_enabled, list_mod_dirs, list_source_dirs, SIDELOAD_SOURCE,
+};
+use crate::logging::recent_log_files;
+use crate::settings::Settings;
+use directories::BaseDirs;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+use tracing::info;
+
+#[derive(Debug, Serialize)]
+struct ProfileDiagnostics {
+    name: String,
+    repo_url: String,
+    repo_source: String,
+    dcs_path_exists: bool,
+    install_path_exists: bool,
+    enabled_mods: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ModSnapshot {
+    name: String,
+    source: String,
+    version: Option<String>,
+    enabled_for: Vec<String>,
+    locks: Vec<LockStatus>,
+}
+
+/// Most recent daily log files put in a bundle
+const BUNDLED_LOG_FILES: usize = 3;
+
+/// Replace the user's home directory in a path with `~`, so bundles don't carry user names
+fn sanitize_path(path: &str, home: Option<&Path>) -> String {
+    match home.and_then(|home| Path::new(path).strip_prefix(home).ok()) {
+        Some(relative) => Path::new("~").join(relative).display().to_string(),
+        None => path.to_string(),
+    }
+}
+
+/// Drop credentials and query strings from a repository URL
+fn sanitize_url(url: &str) -> String {
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    format!("{}://{}{}", scheme, host, path)
+}
+
+fn sanitized_settings(settings: &Settings, home: Option<&Path>) -> serde_json::Value {
+    serde_json::json!({
+        "download_path": sanitize_path(&settings.download_path, home),
+        "sideload_path": sanitize_path(&settings.sideload_path, home),
+        "close_to_tray": settings.close_to_tray,
+        "profiles": settings.profiles.iter().map(|p| serde_json::json!({
+            "name": p.name,
+            "dcs_path": sanitize_path(&p.dcs_path, home),
+            "install_path": sanitize_path(&p.install_path, home),
+            "download_path": sanitize_path(&p.download_path, home),
+            "repo_url": sanitize_url(&p.repo_url),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// Every mod in the download and sideload directories with its version, enabled profiles and locks
+fn mod_snapshot(settings: &Settings) -> Result<Vec<ModSnapshot>, String> {
+    let mut sources = list_source_dirs(settings);
+    if !settings.sideload_path.is_empty() {
+        sources.push((SIDELOAD_SOURCE.to_string(), PathBuf::from(&settings.sideload_path)));
+    }
+
+    let mut mods = Vec::new();
+    for (source, dir) in sources {
+        for mod_dir in list_mod_dirs(&dir) {
+            let profiles = settings.profiles.iter().map(|p| p.name.as_str());
+            let mut enabled_for = Vec::new();
+            for name in profiles.clone() {
+                if is_mod_enabled(&mod_dir, name)? {
+                    enabled_for.push(name.to_string());
+                }
+            }
+            mods.push(ModSnapshot {
+                name: mod_dir.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                source: source.clone(),
+                version: get_mod_version(&mod_dir).ok(),
+                enabled_for,
+                locks: profiles.filter_map(|name| read_lock_status(&mod_dir, name)).collect(),
+            });
+        }
+    }
+    Ok(mods)
+}
+
+fn profile_summaries(settings: &Settings, mods: &[ModSnapshot]) -> Vec<ProfileDiagnostics> {
+    settings
+        .profiles
+        .iter()
+        .map(|p| ProfileDiagnostics {
+            name: p.name.clone(),
+            repo_url: sanitize_url(&p.repo_url),
+            repo_source: get_repo_source(p.repo_url.trim_end_matches('/')),
+            dcs_path_exists: Path::new(&p.dcs_path).is_dir(),
+            install_path_exists: !p.install_path.is_empty() && Path::new(&p.install_path).is_dir(),
+            enabled_mods: mods.iter().filter(|m| m.enabled_for.contains(&p.name)).count(),
+        })
+        .collect()
+}
+
+/// Write a zip with sanitized settings, profile summaries, a snapshot of every mod's state and the
+/// download queue, along with the latest log files, for attaching to bug reports
+#[tauri::command]
+pub async fn export_diagnostics(file_path: String) -> Result<String, String> {
+    let settings = Settings::load()?;
+    let base_dirs = BaseDirs::new();
+    let home = base_dirs.as_ref().map(|dirs| dirs.home_dir());
+
+    let mods = mod_snapshot(&settings)?;
+    let system = format!(
+        "bzmm {}\nOS: {} ({})\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let mut files: Vec<(String, String)> = vec![
+        ("system.txt".to_string(), system),
+        ("settings.json".to_string(), to_json(&sanitized_settings(&settings, home))?),
+        ("profiles.json".to_string(), to_json(&profile_summaries(&settings, &mods))?),
+        ("mods.json".to_string(), to_json(&mods)?),
+        ("queue.json".to_string(), to_json(&get_queue().status().await)?),
+    ];
+    files.extend(log_files(home));
+
+    let file = std::fs::File::create(&file_path)
+        .map_err(|e| format!("Failed to create diagnostics file: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    for (name, content) in files {
+        zip.start_file(name.as_str(), FileOptions::default())
+            .map_err(|e| format!("Failed to add {} to diagnostics: {}", name, e))?;
+        zip.write_all(content.as_bytes())
+            .map_err(|e| format!("Failed to write {} to diagnostics: {}", name, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish diagnostics file: {}", e))?;
+
+    info!("Exported diagnostics to {}", file_path);
+    Ok(file_path)
+}
+
+/// The latest log files, named `logs/<file>`, with the home directory replaced as in the paths
+fn log_files(home: Option<&Path>) -> Vec<(String, String)> {
+    recent_log_files(BUNDLED_LOG_FILES)
+        .into_iter()
+        .filter_map(|path| {
+            let bytes = std::fs::read(&path).ok()?;
+            let mut content = String::from_utf8_lossy(&bytes).into_owned();
+            if let Some(home) = home.map(|home| home.display().to_string()) {
+                content = content.replace(&home, "~");
+            }
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            Some((format!("logs/{}", name), content))
+        })
+        .collect()
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize diagnostics: {}", e))
+}
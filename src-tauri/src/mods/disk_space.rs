@@ -0,0 +1,53 @@
+use super::types::ModError;
+use std::path::Path;
+
+/// Space a download needs relative to its size: the zip, plus roughly as much again for the
+/// files it extracts to
+const EXTRACTION_FACTOR: u64 = 2;
+
+/// Bytes available to the current user on the volume holding `path`
+#[cfg(unix)]
+pub fn available_space(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // The field types differ between platforms
+    #[allow(clippy::useless_conversion)]
+    Some(u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize)))
+}
+
+/// Bytes available to the current user on the volume holding `path`
+#[cfg(windows)]
+pub fn available_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available = 0u64;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut())
+    };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn available_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Fail early when the volume holding `dir` can't fit a download of `download_size` bytes and
+/// its extracted files. Unknown sizes and volumes that can't be queried pass
+pub fn ensure_space_for_download(dir: &Path, download_size: u64) -> Result<(), ModError> {
+    if download_size == 0 {
+        return Ok(());
+    }
+    let needed = download_size.saturating_mul(EXTRACTION_FACTOR);
+    match available_space(dir) {
+        Some(available) if available < needed => Err(ModError::InsufficientDiskSpace(needed, available)),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,171 @@
+use super::download_queue::QueuedDownload;
+use crate::state_db::{now_secs, with_db};
+use rusqlite::{params, Connection, Row};
+use serde::Serialize;
+use tracing::error;
+
+/// Downloads kept in the history, older ones are dropped as new ones finish
+const HISTORY_LIMIT: usize = 1000;
+
+/// How a queued download ended
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl DownloadStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            DownloadStatus::Completed => "completed",
+            DownloadStatus::Failed => "failed",
+            DownloadStatus::Cancelled => "cancelled",
+        }
+    }
+
+    fn parse(status: &str) -> Self {
+        match status {
+            "completed" => DownloadStatus::Completed,
+            "cancelled" => DownloadStatus::Cancelled,
+            _ => DownloadStatus::Failed,
+        }
+    }
+}
+
+/// A finished download
+#[derive(Debug, Serialize, Clone)]
+pub struct DownloadRecord {
+    pub id: i64,
+    pub repo_url: String,
+    pub mod_name: String,
+    /// Version downloaded, read back from the mod once it was extracted
+    pub version: Option<String>,
+    pub variant: Option<String>,
+    pub size: Option<u64>,
+    pub status: DownloadStatus,
+    pub error: Option<String>,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    pub finished_at: u64,
+}
+
+impl DownloadRecord {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(DownloadRecord {
+            id: row.get(0)?,
+            repo_url: row.get(1)?,
+            mod_name: row.get(2)?,
+            version: row.get(3)?,
+            variant: row.get(4)?,
+            size: row.get(5)?,
+            status: DownloadStatus::parse(&row.get::<_, String>(6)?),
+            error: row.get(7)?,
+            started_at: row.get(8)?,
+            finished_at: row.get(9)?,
+        })
+    }
+}
+
+fn insert_download(conn: &Connection, record: &DownloadRecord) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO downloads (repo_url, mod_name, version, variant, size, status, error, started_at, finished_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            record.repo_url,
+            record.mod_name,
+            record.version,
+            record.variant,
+            record.size,
+            record.status.as_str(),
+            record.error,
+            record.started_at,
+            record.finished_at,
+        ],
+    )?;
+    conn.execute(
+        "DELETE FROM downloads WHERE id NOT IN (SELECT id FROM downloads ORDER BY id DESC LIMIT ?1)",
+        [HISTORY_LIMIT],
+    )?;
+    Ok(())
+}
+
+fn recent_downloads(conn: &Connection, limit: usize) -> rusqlite::Result<Vec<DownloadRecord>> {
+    let mut select = conn.prepare(
+        "SELECT id, repo_url, mod_name, version, variant, size, status, error, started_at, finished_at
+         FROM downloads ORDER BY id DESC LIMIT ?1",
+    )?;
+    let rows = select.query_map([limit], DownloadRecord::from_row)?;
+    rows.collect()
+}
+
+/// Add a queued download to the history once it has ended
+pub fn record_download(
+    download: &QueuedDownload,
+    version: Option<String>,
+    status: DownloadStatus,
+    error: Option<String>,
+    started_at: u64,
+) {
+    let record = DownloadRecord {
+        id: 0,
+        repo_url: download.repo_url.clone(),
+        mod_name: download.filename.trim_end_matches(".zip").to_string(),
+        version,
+        variant: download.variant.clone(),
+        size: download.size,
+        status,
+        error,
+        started_at,
+        finished_at: now_secs(),
+    };
+    if let Err(e) = with_db(|db| insert_download(db, &record)) {
+        error!("Failed to record download of {}: {}", record.mod_name, e);
+    }
+}
+
+/// Finished downloads, newest first
+#[tauri::command]
+pub async fn get_download_history(limit: Option<usize>) -> Result<Vec<DownloadRecord>, String> {
+    let limit = limit.unwrap_or(HISTORY_LIMIT).min(HISTORY_LIMIT);
+    with_db(|db| recent_downloads(db, limit))
+}
+
+#[tauri::command]
+pub async fn clear_download_history() -> Result<(), String> {
+    with_db(|db| db.execute("DELETE FROM downloads", [])).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_db::migrate;
+
+    #[test]
+    fn test_download_history() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        let record = |mod_name: &str, status| DownloadRecord {
+            id: 0,
+            repo_url: "https://example.com/repo.xml".to_string(),
+            mod_name: mod_name.to_string(),
+            version: None,
+            variant: None,
+            size: Some(1024),
+            status,
+            error: None,
+            started_at: 1,
+            finished_at: 2,
+        };
+        insert_download(&conn, &record("ModA", DownloadStatus::Completed)).unwrap();
+        insert_download(&conn, &record("ModB", DownloadStatus::Cancelled)).unwrap();
+
+        let history = recent_downloads(&conn, 10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].mod_name, "ModB");
+        assert_eq!(history[0].status, DownloadStatus::Cancelled);
+        assert_eq!(history[1].size, Some(1024));
+        assert_eq!(recent_downloads(&conn, 1).unwrap().len(), 1);
+    }
+}
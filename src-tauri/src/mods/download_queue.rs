@@ -1,36 +1,152 @@
 use std::collections::{VecDeque, HashMap};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::{Mutex, Semaphore};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tokio::sync::{watch, Mutex, OwnedSemaphorePermit, Semaphore};
 use tokio_util::sync::CancellationToken;
 use tauri::Emitter;
-
-const MAX_CONCURRENT_DOWNLOADS: usize = 2;
+use super::download_history::{record_download, DownloadStatus};
+use super::downloader::ModDownloader;
+use super::hooks::run_install_hooks;
+use super::installed::record_installed_mod;
+use super::mod_utils::{get_mod_version, get_repo_download_dir, record_mod_variant};
+use super::xml_cache::XmlCache;
+use crate::settings::{Profile, Settings, MAX_CONCURRENT_DOWNLOADS_RANGE};
+use crate::state_db::now_secs;
+use tracing::{error, info, instrument, warn};
 
 #[derive(Clone, Debug)]
 pub struct QueuedDownload {
     pub url: String,
     pub filename: String,
     pub repo_url: String,
+    /// Mirrors to try in turn when downloading from `url` fails
+    pub fallback_urls: Vec<String>,
+    /// Download size in bytes, from the repository or the server
+    pub size: Option<u64>,
+    /// Profile whose download path the mod goes to
+    pub profile: Option<String>,
+    /// Profile to enable the mod for once it has been downloaded and extracted
+    pub enable_profile: Option<String>,
+    /// Variant of the mod `url` downloads, recorded with the local copy
+    pub variant: Option<String>,
+}
+
+/// Which profile a download is for
+#[derive(Clone, Debug, Default)]
+pub struct DownloadTarget {
+    /// Profile whose download path the mod goes to, those of profiles sharing a repository can
+    /// differ. Without one it is the first profile using the repository
+    pub profile: Option<String>,
+    /// Enable the mod for `profile` once it has been downloaded and extracted
+    pub enable: bool,
+}
+
+impl DownloadTarget {
+    /// Download into a profile's download path and enable the mod there
+    pub fn enabling(profile: String) -> Self {
+        DownloadTarget { profile: Some(profile), enable: true }
+    }
+}
+
+/// Snapshot of the queue for status displays
+#[derive(Debug, Serialize, Clone)]
+pub struct QueueStatus {
+    pub paused: bool,
+    /// Filenames of the downloads in progress
+    pub active: Vec<String>,
+    pub queued: usize,
+    /// Bytes left to download, counting only downloads of known size
+    pub remaining_bytes: u64,
+    /// Rough time until the queue is empty, once a download has finished to measure the speed
+    pub eta_seconds: Option<u64>,
+}
+
+/// A download in progress, for estimating how much of it is left
+#[derive(Clone, Copy, Debug)]
+struct ActiveDownload {
+    size: Option<u64>,
+    started: Instant,
 }
 
 #[derive(Clone)]
 pub struct DownloadQueue {
     queue: Arc<Mutex<VecDeque<QueuedDownload>>>,
     semaphore: Arc<Semaphore>,
+    /// Downloads allowed at once, the semaphore's permits once any owed ones are paid back
+    max_concurrent: Arc<std::sync::Mutex<usize>>,
+    /// Permits to take out of circulation when running downloads finish, after the limit was lowered
+    owed_permits: Arc<std::sync::Mutex<usize>>,
     cancel_tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    /// While set, queued downloads wait instead of starting; running ones finish
+    paused: Arc<watch::Sender<bool>>,
+    active: Arc<std::sync::Mutex<HashMap<String, ActiveDownload>>>,
+    /// Average speed of finished downloads of known size, in bytes per second
+    throughput: Arc<std::sync::Mutex<Option<f64>>>,
 }
 
 impl DownloadQueue {
     pub fn new() -> Self {
+        let max_concurrent = Settings::load()
+            .map(|settings| settings.max_concurrent_downloads)
+            .unwrap_or_else(|_| Settings::default().max_concurrent_downloads)
+            .clamp(*MAX_CONCURRENT_DOWNLOADS_RANGE.start(), *MAX_CONCURRENT_DOWNLOADS_RANGE.end());
         Self {
             queue: Arc::new(Mutex::new(VecDeque::new())),
-            semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_DOWNLOADS)),
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent: Arc::new(std::sync::Mutex::new(max_concurrent)),
+            owed_permits: Arc::new(std::sync::Mutex::new(0)),
             cancel_tokens: Arc::new(Mutex::new(HashMap::new())),
+            paused: Arc::new(watch::Sender::new(false)),
+            active: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            throughput: Arc::new(std::sync::Mutex::new(None)),
         }
     }
 
-    pub async fn add_download(&self, app_handle: tauri::AppHandle, url: String, filename: String, repo_url: String) {
-        let download = QueuedDownload { url, filename: filename.clone(), repo_url };
+    pub async fn add_download(
+        &self,
+        app_handle: tauri::AppHandle,
+        url: String,
+        filename: String,
+        repo_url: String,
+        size: Option<u64>,
+        target: DownloadTarget,
+    ) {
+        // The cached listing knows the mod's mirrors, ordered for the preferred region
+        let mod_name = filename.trim_end_matches(".zip");
+        let listed = XmlCache::load_for_repo(&repo_url).and_then(|mods_file| {
+            mods_file.categories.into_iter().flat_map(|cat| cat.mods).find(|m| m.name == mod_name)
+        });
+        let region = Settings::load().map(|s| s.mirror_region).unwrap_or_default();
+        let mut urls = match &listed {
+            Some(listed) if listed.download_urls("").contains(&url) => listed.download_urls(&region),
+            _ => vec![url],
+        };
+        let url = urls.remove(0);
+        // A variant has a download of its own, the mod's size may belong to another one
+        let variant = listed.as_ref().and_then(|m| m.variant_for_url(&url)).cloned();
+        let listed_size = match &variant {
+            Some(variant) => variant.size,
+            None => listed.and_then(|m| m.size),
+        };
+
+        // Without a size from the repository, ask the server so the queue can estimate its time
+        let size = match size.or(listed_size) {
+            Some(size) => Some(size),
+            None => ModDownloader::new().head_content_length(&url).await.ok().flatten(),
+        };
+        let download = QueuedDownload {
+            url,
+            filename: filename.clone(),
+            repo_url,
+            fallback_urls: urls,
+            size,
+            enable_profile: target.profile.clone().filter(|_| target.enable),
+            profile: target.profile,
+            variant: variant.map(|v| v.id),
+        };
         
         // Add to queue
         {
@@ -40,16 +156,108 @@ impl DownloadQueue {
 
         // Emit queued event
         if let Err(e) = app_handle.emit("download-queued", &filename) {
-            eprintln!("Failed to emit download-queued event: {}", e);
+            error!("Failed to emit download-queued event: {}", e);
         }
 
         // Start processing - this spawns a task to avoid Send issues
         let queue_ref = self.clone();
         tokio::spawn(async move {
-            queue_ref.process_one_download(app_handle).await;
+            queue_ref.process_next(app_handle).await;
         });
     }
 
+    /// Change how many downloads run at once. Raising it starts queued downloads straight away,
+    /// lowering it lets the running ones finish
+    pub fn set_max_concurrent(&self, limit: usize) {
+        let mut current = self.max_concurrent.lock().unwrap();
+        let mut owed = self.owed_permits.lock().unwrap();
+        if limit > *current {
+            let extra = limit - *current;
+            let repaid = extra.min(*owed);
+            *owed -= repaid;
+            self.semaphore.add_permits(extra - repaid);
+        } else {
+            let excess = *current - limit;
+            *owed += excess - self.semaphore.forget_permits(excess);
+        }
+        info!("Downloading up to {} mods at once", limit);
+        *current = limit;
+    }
+
+    /// Hand a permit back once its download is done, unless the limit was lowered meanwhile
+    fn release_permit(&self, permit: OwnedSemaphorePermit) {
+        let mut owed = self.owed_permits.lock().unwrap();
+        if *owed > 0 {
+            *owed -= 1;
+            permit.forget();
+        }
+    }
+
+    /// Filenames of the downloads currently in progress
+    pub async fn active_downloads(&self) -> Vec<String> {
+        self.cancel_tokens.lock().await.keys().cloned().collect()
+    }
+
+    /// Whether a download is waiting in the queue or in progress
+    pub async fn is_pending(&self, filename: &str) -> bool {
+        self.queue.lock().await.iter().any(|download| download.filename == filename)
+            || self.cancel_tokens.lock().await.contains_key(filename)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    pub async fn status(&self) -> QueueStatus {
+        let queued_sizes: Vec<Option<u64>> = self.queue.lock().await.iter().map(|d| d.size).collect();
+        let active = self.active_downloads().await;
+        let throughput = *self.throughput.lock().unwrap();
+
+        let mut remaining_bytes: u64 = queued_sizes.iter().flatten().sum();
+        for download in self.active.lock().unwrap().values() {
+            if let Some(size) = download.size {
+                let done = throughput.map_or(0.0, |rate| rate * download.started.elapsed().as_secs_f64());
+                remaining_bytes += size.saturating_sub(done as u64);
+            }
+        }
+        let max_concurrent = *self.max_concurrent.lock().unwrap();
+        let parallel = (active.len() + queued_sizes.len()).clamp(1, max_concurrent) as f64;
+        let eta_seconds = throughput
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| (remaining_bytes as f64 / (rate * parallel)).ceil() as u64);
+
+        QueueStatus {
+            paused: self.is_paused(),
+            active,
+            queued: queued_sizes.len(),
+            remaining_bytes,
+            eta_seconds,
+        }
+    }
+
+    /// Fold a finished download into the average speed, weighted towards recent downloads
+    fn record_throughput(&self, bytes: u64, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if bytes == 0 || seconds <= 0.0 {
+            return;
+        }
+        let rate = bytes as f64 / seconds;
+        let mut throughput = self.throughput.lock().unwrap();
+        *throughput = Some(throughput.map_or(rate, |average| average * 0.7 + rate * 0.3));
+    }
+
+    /// Hold back queued downloads, or let them start again
+    pub fn set_paused(&self, app_handle: &tauri::AppHandle, paused: bool) {
+        if self.paused.send_replace(paused) == paused {
+            return;
+        }
+        info!("Download queue {}", if paused { "paused" } else { "resumed" });
+        let event = if paused { "queue-paused" } else { "queue-resumed" };
+        if let Err(e) = app_handle.emit(event, ()) {
+            error!("Failed to emit {} event: {}", event, e);
+        }
+    }
+
     #[allow(dead_code)]
     pub async fn cancel_download(&self, filename: &str) -> Result<(), String> {
         #[allow(unused_assignments)] // False positive
@@ -70,21 +278,21 @@ impl DownloadQueue {
             if let Some(token) = cancel_tokens.remove(filename) {
                 token.cancel();
                 was_downloading = true;
-                println!("Cancelled ongoing download for: {}", filename);
+                info!("Cancelled ongoing download for: {}", filename);
             }
         }
 
         // Clean up any temporary files
         if was_downloading {
             if let Err(e) = self.cleanup_download_files(filename).await {
-                eprintln!("Warning: Failed to clean up files for {}: {}", filename, e);
+                warn!("Failed to clean up files for {}: {}", filename, e);
             }
         }
 
         if was_queued || was_downloading {
-            println!("Successfully cancelled download for: {}", filename);
+            info!("Successfully cancelled download for: {}", filename);
         } else {
-            println!("No active download found for: {}", filename);
+            info!("No active download found for: {}", filename);
         }
 
         Ok(())
@@ -93,38 +301,42 @@ impl DownloadQueue {
     #[allow(dead_code)]
     async fn cleanup_download_files(&self, filename: &str) -> Result<(), String> {
         use crate::settings;
-        use std::path::PathBuf;
 
         let settings = settings::Settings::load()?;
-        let base_downloads_dir = PathBuf::from(&settings.download_path);
         
         // Try to find and remove any temporary files matching this filename
         let temp_filename = format!("{}.tmp", filename.trim_end_matches(".zip"));
         
-        // Search through all subdirectories for the temp file
-        if let Ok(entries) = std::fs::read_dir(&base_downloads_dir) {
-            for entry in entries.flatten() {
-                if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
-                    let subdir = entry.path();
-                    let temp_path = subdir.join(&temp_filename);
-                    let final_path = subdir.join(filename);
-                    
-                    // Remove temporary file if it exists
-                    if temp_path.exists() {
-                        if let Err(e) = std::fs::remove_file(&temp_path) {
-                            eprintln!("Failed to remove temp file {}: {}", temp_path.display(), e);
-                        } else {
-                            println!("Cleaned up temp file: {}", temp_path.display());
-                        }
+        // Search through all subdirectories of every download path and the staging path for the temp file
+        let mut dirs = settings.download_paths();
+        if !settings.staging_path.is_empty() {
+            dirs.push(std::path::PathBuf::from(&settings.staging_path));
+        }
+        let entries = dirs
+            .into_iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten();
+        for entry in entries.flatten() {
+            if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+                let subdir = entry.path();
+                let temp_path = subdir.join(&temp_filename);
+                let final_path = subdir.join(filename);
+                
+                // Remove temporary file if it exists
+                if temp_path.exists() {
+                    if let Err(e) = std::fs::remove_file(&temp_path) {
+                        error!("Failed to remove temp file {}: {}", temp_path.display(), e);
+                    } else {
+                        info!("Cleaned up temp file: {}", temp_path.display());
                     }
-                    
-                    // Remove final file if it exists (partial download)
-                    if final_path.exists() {
-                        if let Err(e) = std::fs::remove_file(&final_path) {
-                            eprintln!("Failed to remove partial file {}: {}", final_path.display(), e);
-                        } else {
-                            println!("Cleaned up partial file: {}", final_path.display());
-                        }
+                }
+                
+                // Remove final file if it exists (partial download)
+                if final_path.exists() {
+                    if let Err(e) = std::fs::remove_file(&final_path) {
+                        error!("Failed to remove partial file {}: {}", final_path.display(), e);
+                    } else {
+                        info!("Cleaned up partial file: {}", final_path.display());
                     }
                 }
             }
@@ -133,10 +345,19 @@ impl DownloadQueue {
         Ok(())
     }
 
+    /// `process_one_download` behind an explicit type: enabling a downloaded mod can queue its
+    /// requirements, which spawns this again, and the compiler can't see through that cycle
+    fn process_next(&self, app_handle: tauri::AppHandle) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(self.process_one_download(app_handle))
+    }
+
     async fn process_one_download(&self, app_handle: tauri::AppHandle) {
         // Wait for a permit (blocking)
         let permit = self.semaphore.clone().acquire_owned().await.unwrap();
 
+        // Don't start anything new while the queue is paused
+        let _ = self.paused.subscribe().wait_for(|paused| !paused).await;
+
         // Get next download from queue
         let download = {
             let mut queue = self.queue.lock().await;
@@ -144,35 +365,121 @@ impl DownloadQueue {
         };
 
         if let Some(download) = download {
-            // Create cancellation token for this download
-            let cancel_token = CancellationToken::new();
-            {
-                let mut cancel_tokens = self.cancel_tokens.lock().await;
-                cancel_tokens.insert(download.filename.clone(), cancel_token.clone());
-            }
+            self.run_download(app_handle, download).await;
+        }
+
+        self.release_permit(permit);
+    }
+
+    /// Download one queued mod, falling back to its mirrors, then record and enable it. Logged
+    /// under a span naming the file, so the lines of concurrent downloads can be told apart
+    #[instrument(name = "download", skip_all, fields(file = %download.filename, repo = %download.repo_url))]
+    async fn run_download(&self, app_handle: tauri::AppHandle, download: QueuedDownload) {
+        // Create cancellation token for this download
+        let cancel_token = CancellationToken::new();
+        {
+            let mut cancel_tokens = self.cancel_tokens.lock().await;
+            cancel_tokens.insert(download.filename.clone(), cancel_token.clone());
+        }
+        self.active.lock().unwrap().insert(
+            download.filename.clone(),
+            ActiveDownload { size: download.size, started: Instant::now() },
+        );
 
-            // Actually perform the download with cancellation support
-            let result = super::mod_download::download_mod_with_cancellation(
+        // The copy just downloaded is the one to enable, even if a sideloaded mod shares its name
+        let source = super::mod_utils::get_repo_source(&download.repo_url);
+        let started_at = now_secs();
+
+        // Actually perform the download with cancellation support, moving on to the next
+        // mirror when one fails
+        let urls = std::iter::once(download.url.clone()).chain(download.fallback_urls.clone());
+        let mut result = Err("No download URL".to_string());
+        for (attempt, url) in urls.enumerate() {
+            if attempt > 0 {
+                info!("Retrying {} from mirror {}", download.filename, url);
+            }
+            result = super::mod_download::download_mod_with_cancellation(
                 app_handle.clone(),
-                download.url,
+                url,
                 download.filename.clone(),
-                download.repo_url,
+                download.repo_url.clone(),
+                download.size,
+                download.profile.clone(),
                 cancel_token.clone(),
             ).await;
-
-            // Clean up cancellation token after download+extraction completes (success or failure)
-            {
-                let mut cancel_tokens = self.cancel_tokens.lock().await;
-                cancel_tokens.remove(&download.filename);
+            if result.is_ok() || cancel_token.is_cancelled() {
+                break;
             }
+        }
 
-            if let Err(e) = result {
-                eprintln!("Download failed: {}", e);
-            }
+        // Clean up cancellation token after download+extraction completes (success or failure)
+        {
+            let mut cancel_tokens = self.cancel_tokens.lock().await;
+            cancel_tokens.remove(&download.filename);
         }
+        let finished = self.active.lock().unwrap().remove(&download.filename);
+
+        let mod_dir = Settings::load().map(|settings| {
+            let download_path = settings.repo_download_path(&download.repo_url, download.profile.as_deref());
+            get_repo_download_dir(&download_path, &download.repo_url)
+                .join(download.filename.trim_end_matches(".zip"))
+        });
+        let (status, error) = match &result {
+            Ok(()) => (DownloadStatus::Completed, None),
+            Err(_) if cancel_token.is_cancelled() => (DownloadStatus::Cancelled, None),
+            Err(e) => (DownloadStatus::Failed, Some(e.clone())),
+        };
+        let version = match (&result, &mod_dir) {
+            (Ok(()), Ok(mod_dir)) => get_mod_version(mod_dir).ok(),
+            _ => None,
+        };
+        record_download(&download, version, status, error, started_at);
 
-        // Permit is automatically released when dropped
-        drop(permit);
+        match result {
+            Ok(()) => {
+                if let Some(ActiveDownload { size: Some(size), started }) = finished {
+                    self.record_throughput(size, started.elapsed());
+                }
+                if let Some(variant) = &download.variant {
+                    let recorded = mod_dir.clone().and_then(|mod_dir| {
+                        record_mod_variant(&mod_dir, Some(variant)).map_err(|e| e.to_string())
+                    });
+                    if let Err(e) = recorded {
+                        error!("Failed to record variant {} of {}: {}", variant, download.filename, e);
+                    }
+                }
+                if let Ok(mod_dir) = &mod_dir {
+                    if let Err(e) = record_installed_mod(&download.repo_url, mod_dir) {
+                        error!("Failed to record downloaded mod {}: {}", download.filename, e);
+                    }
+                    run_install_hooks(&download.repo_url, mod_dir);
+                }
+                if let Some(profile_name) = download.enable_profile {
+                    let mod_name = download.filename.trim_end_matches(".zip").to_string();
+                    info!("Enabling {} for profile {} after download", mod_name, profile_name);
+                    match super::mod_management::enable_mod(
+                        app_handle.clone(),
+                        mod_name.clone(),
+                        profile_name.clone(),
+                        Some(source),
+                    )
+                    .await
+                    {
+                        Ok(_) => {
+                            let _ = app_handle.emit(
+                                "mod-enabled",
+                                serde_json::json!({
+                                    "mod_name": mod_name,
+                                    "profile_name": profile_name
+                                }),
+                            );
+                        }
+                        Err(e) => error!("Failed to enable {} after download: {}", mod_name, e),
+                    }
+                }
+            }
+            Err(e) => error!("Download failed: {}", e),
+        }
     }
 }
 
@@ -183,35 +490,96 @@ pub fn get_queue() -> &'static DownloadQueue {
     DOWNLOAD_QUEUE.get_or_init(DownloadQueue::new)
 }
 
+/// The profile to enable a download for once it is extracted. With `enable` that is the profile
+/// given or the active one, which has to use the download's repository. Left out, the profile
+/// decides by its auto-enable setting
+fn enable_after_download(
+    settings: &Settings,
+    repo_url: &str,
+    enable: Option<bool>,
+    profile_name: Option<String>,
+) -> Result<Option<String>, String> {
+    let uses_repo = |profile: &Profile| profile.repo_url.trim_end_matches('/') == repo_url.trim_end_matches('/');
+    let find_profile = |name: &str| settings.profiles.iter().find(|p| p.name == name);
+
+    let Some(enable) = enable else {
+        let profile = settings.profile_or_active(profile_name).ok();
+        return Ok(profile
+            .and_then(|name| find_profile(&name))
+            .filter(|profile| profile.auto_enable && uses_repo(profile))
+            .map(|profile| profile.name.clone()));
+    };
+    if !enable {
+        return Ok(None);
+    }
+    let profile_name = settings.profile_or_active(profile_name)?;
+    let profile = find_profile(&profile_name).ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+    if !uses_repo(profile) {
+        return Err(format!("Profile '{}' doesn't use this mod's repository", profile_name));
+    }
+    Ok(Some(profile_name))
+}
+
+/// Queue a mod's download. With `enable` the mod is enabled for `profile_name` (the active profile
+/// without one) once it is extracted, left out it follows that profile's auto-enable setting
 #[tauri::command]
 pub async fn queue_download(
     app_handle: tauri::AppHandle,
     url: String,
     filename: String,
     repo_url: String,
+    size: Option<u64>,
+    enable: Option<bool>,
+    profile_name: Option<String>,
 ) -> Result<(), String> {
-    println!("Queuing download: {} from {} (Repo: {})", filename, url, repo_url);
-    
+    info!("Queuing download: {} from {} (Repo: {})", filename, url, repo_url);
+
+    let settings = Settings::load()?;
+    let enable_profile = enable_after_download(&settings, &repo_url, enable, profile_name.clone())?;
+    let target = DownloadTarget {
+        enable: enable_profile.is_some(),
+        profile: enable_profile.or_else(|| settings.profile_or_active(profile_name).ok()),
+    };
+    let mod_name = filename.trim_end_matches(".zip").to_string();
     let queue = get_queue();
-    queue.add_download(app_handle, url, filename, repo_url).await;
-    
+    queue.add_download(app_handle.clone(), url, filename, repo_url.clone(), size, target.clone()).await;
+
+    // Mods it needs come along, enabling it later would pull them in anyway
+    let required = super::dependencies::queue_missing_requirements(&app_handle, &repo_url, &mod_name, target).await;
+    if !required.is_empty() {
+        info!("Also queued required mods: {}", required.join(", "));
+    }
+
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_queue_status() -> Result<QueueStatus, String> {
+    Ok(get_queue().status().await)
+}
+
+/// Pause or resume starting queued downloads, downloads already running carry on
+#[tauri::command]
+pub async fn set_queue_paused(app_handle: tauri::AppHandle, paused: bool) -> Result<QueueStatus, String> {
+    let queue = get_queue();
+    queue.set_paused(&app_handle, paused);
+    Ok(queue.status().await)
+}
+
 #[allow(dead_code)]
 #[tauri::command]
 pub async fn cancel_download(
     app_handle: tauri::AppHandle,
     filename: String,
 ) -> Result<(), String> {
-    println!("Cancelling download: {}", filename);
+    info!("Cancelling download: {}", filename);
     
     let queue = get_queue();
     queue.cancel_download(&filename).await?;
     
     // Emit cancellation event
     if let Err(e) = app_handle.emit("download-cancelled", &filename) {
-        eprintln!("Failed to emit download-cancelled event: {}", e);
+        error!("Failed to emit download-cancelled event: {}", e);
     }
     
     Ok(())
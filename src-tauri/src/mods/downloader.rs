@@ -1,27 +1,80 @@
+use super::disk_space::ensure_space_for_download;
+use super::includes::fetch_includes;
+use super::installed::record_repo_versions;
+use super::mod_enablement::save_repo_mapping;
+use super::parsed_cache::parse_repo_file;
 use super::parser::ModParser;
 use super::progress::{calculate_progress, DownloadProgress};
+use super::throttle::get_throttle;
 use super::types::{ModError, ModsFile};
+use super::xml_cache::XmlCache;
+use crate::settings::{app_language, Settings};
 use futures_util::StreamExt;
 use reqwest::Client;
+use std::path::{Path, PathBuf};
 use tauri::Emitter;
+use tokio::io::AsyncWriteExt;
 use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
 
 pub struct ModDownloader {
     client: Client,
+    /// Language repository texts are picked in
+    language: String,
 }
 
 impl ModDownloader {
     pub fn new() -> Self {
-        let client = Client::builder()
-            .user_agent("BZMM/1.0")
-            .build()
-            .expect("Failed to create HTTP client");
+        let builder = Client::builder().user_agent("BZMM/1.0");
+        // The proxy is checked when saved, a broken one from a hand-edited file falls back to the system's
+        let proxy = Settings::load().map(|settings| settings.proxy).unwrap_or_default();
+        let builder = match proxy.apply(builder) {
+            Ok(builder) => builder,
+            Err(e) => {
+                warn!("Ignoring proxy settings: {}", e);
+                Client::builder().user_agent("BZMM/1.0")
+            }
+        };
+        let client = builder.build().expect("Failed to create HTTP client");
 
-        Self { client }
+        Self { client, language: app_language() }
     }
 
+    /// Fetch a repository's XML, decompressing `.xml.gz` files and gzip-encoded responses
     pub async fn fetch_mod_list(&self, url: &str) -> Result<String, ModError> {
-        Ok(self.client.get(url).send().await?.text().await?)
+        let body = self.client.get(url).send().await?.error_for_status()?.bytes().await?;
+        ModParser::repo_text(&body)
+    }
+
+    /// Content-Disposition header of a download, if the server sends one for a HEAD request
+    pub async fn content_disposition(&self, url: &str) -> Option<String> {
+        let resp = self.client.head(url).send().await.ok()?;
+        resp.headers()
+            .get(reqwest::header::CONTENT_DISPOSITION)?
+            .to_str()
+            .ok()
+            .map(str::to_string)
+    }
+
+    /// Content-Length the server reports for a HEAD request, if it reports one
+    pub async fn head_content_length(&self, url: &str) -> Result<Option<u64>, ModError> {
+        let resp = self.client.head(url).send().await?;
+        Ok(resp
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|ct_len| ct_len.to_str().ok())
+            .and_then(|ct_len| ct_len.parse().ok()))
+    }
+
+    /// Status a server answers a HEAD request with, to tell whether it can be reached at all
+    pub async fn head_status(&self, url: &str) -> Result<reqwest::StatusCode, ModError> {
+        Ok(self.client.head(url).send().await?.status())
+    }
+
+    /// Fetch a small file such as a changelog or an image into memory, failing on HTTP error
+    /// statuses rather than returning the error page
+    pub async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>, ModError> {
+        Ok(self.client.get(url).send().await?.error_for_status()?.bytes().await?.to_vec())
     }
 
     pub async fn download_mod(
@@ -30,10 +83,11 @@ impl ModDownloader {
         url: &str,
         path: &std::path::Path,
         mod_name: &str,
+        expected_size: Option<u64>,
     ) -> Result<(), ModError> {
         // Function to emit error event
         let emit_error = |e: &ModError| {
-            println!("Download error for {}: {:?}", mod_name, e);
+            warn!("Download error for {}: {:?}", mod_name, e);
             let _ = app_handle.emit(
                 "download-error",
                 serde_json::json!({
@@ -44,9 +98,9 @@ impl ModDownloader {
         };
 
         // Validate URL
-        println!("Download started for {} from URL: '{}'", mod_name, url);
+        info!("Download started for {} from URL: '{}'", mod_name, url);
         if url.is_empty() || !url.starts_with("http") {
-            println!("Invalid URL for {}: '{}'", mod_name, url);
+            warn!("Invalid URL for {}: '{}'", mod_name, url);
             let err = ModError::InvalidUrl(format!("Invalid URL provided: {}", url));
             emit_error(&err);
             return Err(err);
@@ -59,25 +113,28 @@ impl ModDownloader {
             return Err(err);
         }
 
-        // First make a HEAD request to get the content length
-        let resp = match self.client.head(url).send().await {
-            Ok(r) => r,
-            Err(e) => {
-                println!("HEAD request failed for {}: {}", mod_name, e);
-                let err = ModError::RequestError(e);
+        // The repository's size attribute spares a round trip, otherwise ask the server
+        let total_size = match expected_size {
+            Some(size) => size,
+            None => match self.head_content_length(url).await {
+                Ok(size) => size.unwrap_or(0),
+                Err(e) => {
+                    warn!("HEAD request failed for {}: {}", mod_name, e);
+                    emit_error(&e);
+                    return Err(e);
+                }
+            },
+        };
+
+        // Room for the zip and what it extracts to, before writing anything
+        if let Some(dir) = path.parent() {
+            if let Err(err) = ensure_space_for_download(dir, total_size) {
                 emit_error(&err);
                 return Err(err);
             }
-        };
-
-        let total_size = resp
-            .headers()
-            .get(reqwest::header::CONTENT_LENGTH)
-            .and_then(|ct_len| ct_len.to_str().ok())
-            .and_then(|ct_len| ct_len.parse().ok())
-            .unwrap_or(0u64);
+        }
 
-        println!("Starting download of {} bytes for {}", total_size, mod_name);
+        info!("Starting download of {} bytes for {}", total_size, mod_name);
 
         // Now make the actual download request
         let res = match self.client.get(url).send().await {
@@ -89,7 +146,7 @@ impl ModDownloader {
                         .text()
                         .await
                         .unwrap_or_else(|_| format!("HTTP Error: {}", status));
-                    println!("HTTP error for {}: {} - {}", mod_name, status, error_text);
+                    warn!("HTTP error for {}: {} - {}", mod_name, status, error_text);
                     let err = ModError::HttpError(format!(
                         "Server returned error: {} - {}",
                         status, error_text
@@ -100,7 +157,7 @@ impl ModDownloader {
                 r
             }
             Err(e) => {
-                println!("GET request failed for {}: {}", mod_name, e);
+                warn!("GET request failed for {}: {}", mod_name, e);
                 let err = ModError::RequestError(e);
                 emit_error(&err);
                 return Err(err);
@@ -114,7 +171,7 @@ impl ModDownloader {
         let mut file = match tokio::fs::File::create(path).await {
             Ok(f) => f,
             Err(e) => {
-                println!("Failed to create file {}: {}", path.display(), e);
+                warn!("Failed to create file {}: {}", path.display(), e);
                 let err = ModError::IoError(e);
                 emit_error(&err);
                 return Err(err);
@@ -123,12 +180,12 @@ impl ModDownloader {
 
         use tokio::io::AsyncWriteExt;
 
-        println!("Downloading to path: {}", path.display());
+        info!("Downloading to path: {}", path.display());
         while let Some(chunk) = stream.next().await {
             let chunk = match chunk {
                 Ok(c) => c,
                 Err(e) => {
-                    println!("Download stream error for {}: {}", mod_name, e);
+                    warn!("Download stream error for {}: {}", mod_name, e);
                     let err = ModError::RequestError(e);
                     emit_error(&err);
                     return Err(err);
@@ -136,13 +193,15 @@ impl ModDownloader {
             };
 
             if let Err(e) = file.write_all(&chunk).await {
-                println!("Failed to write chunk to file {}: {}", path.display(), e);
+                warn!("Failed to write chunk to file {}: {}", path.display(), e);
                 let err = ModError::IoError(e);
                 emit_error(&err);
                 return Err(err);
             }
 
             downloaded += chunk.len() as u64;
+            debug!("Wrote {} bytes of {}, {}/{} bytes", chunk.len(), mod_name, downloaded, total_size);
+            get_throttle().consume(chunk.len() as u64).await;
             let progress = calculate_progress(downloaded, total_size);
 
             // Get the current percentage as an integer
@@ -169,14 +228,14 @@ impl ModDownloader {
 
         // Ensure file is flushed and closed correctly
         if let Err(e) = file.flush().await {
-            println!("Failed to flush file {}: {}", path.display(), e);
+            warn!("Failed to flush file {}: {}", path.display(), e);
             let err = ModError::IoError(e);
             emit_error(&err);
             return Err(err);
         }
 
         if let Err(e) = file.sync_all().await {
-            println!("Failed to sync file {}: {}", path.display(), e);
+            warn!("Failed to sync file {}: {}", path.display(), e);
             // Log but continue, as this is not critical
         }
 
@@ -204,7 +263,7 @@ impl ModDownloader {
         let metadata = match tokio::fs::metadata(path).await {
             Ok(m) => m,
             Err(e) => {
-                println!("Failed to get metadata for {}: {}", path.display(), e);
+                warn!("Failed to get metadata for {}: {}", path.display(), e);
                 let err = ModError::IoError(e);
                 emit_error(&err);
                 return Err(err);
@@ -212,7 +271,7 @@ impl ModDownloader {
         };
 
         if metadata.len() == 0 {
-            println!("Downloaded file is empty: {}", path.display());
+            warn!("Downloaded file is empty: {}", path.display());
             let err = ModError::IoError(std::io::Error::new(
                 std::io::ErrorKind::UnexpectedEof,
                 "Downloaded file is empty",
@@ -222,7 +281,7 @@ impl ModDownloader {
         }
 
         // Emit completion event
-        println!(
+        info!(
             "Download completed for {} - File size: {} bytes",
             mod_name,
             metadata.len()
@@ -244,11 +303,12 @@ impl ModDownloader {
         url: &str,
         path: &std::path::Path,
         mod_name: &str,
+        expected_size: Option<u64>,
         cancel_token: CancellationToken,
     ) -> Result<(), ModError> {
         // Function to emit error event
         let emit_error = |e: &ModError| {
-            println!("Download error for {}: {:?}", mod_name, e);
+            warn!("Download error for {}: {:?}", mod_name, e);
             let _ = app_handle.emit(
                 "download-error",
                 serde_json::json!({
@@ -267,9 +327,9 @@ impl ModDownloader {
         }
 
         // Validate URL
-        println!("Download started for {} from URL: '{}'", mod_name, url);
+        info!("Download started for {} from URL: '{}'", mod_name, url);
         if url.is_empty() || !url.starts_with("http") {
-            println!("Invalid URL for {}: '{}'", mod_name, url);
+            warn!("Invalid URL for {}: '{}'", mod_name, url);
             let err = ModError::InvalidUrl(format!("Invalid URL provided: {}", url));
             emit_error(&err);
             return Err(err);
@@ -282,25 +342,28 @@ impl ModDownloader {
             return Err(err);
         }
 
-        // First make a HEAD request to get the content length
-        let resp = match self.client.head(url).send().await {
-            Ok(r) => r,
-            Err(e) => {
-                println!("HEAD request failed for {}: {}", mod_name, e);
-                let err = ModError::RequestError(e);
+        // The repository's size attribute spares a round trip, otherwise ask the server
+        let total_size = match expected_size {
+            Some(size) => size,
+            None => match self.head_content_length(url).await {
+                Ok(size) => size.unwrap_or(0),
+                Err(e) => {
+                    warn!("HEAD request failed for {}: {}", mod_name, e);
+                    emit_error(&e);
+                    return Err(e);
+                }
+            },
+        };
+
+        // Room for the zip and what it extracts to, before writing anything
+        if let Some(dir) = path.parent() {
+            if let Err(err) = ensure_space_for_download(dir, total_size) {
                 emit_error(&err);
                 return Err(err);
             }
-        };
-
-        let total_size = resp
-            .headers()
-            .get(reqwest::header::CONTENT_LENGTH)
-            .and_then(|ct_len| ct_len.to_str().ok())
-            .and_then(|ct_len| ct_len.parse().ok())
-            .unwrap_or(0u64);
+        }
 
-        println!("Starting download of {} bytes for {}", total_size, mod_name);
+        info!("Starting download of {} bytes for {}", total_size, mod_name);
 
         // Check if cancelled before main download
         if cancel_token.is_cancelled() {
@@ -320,7 +383,7 @@ impl ModDownloader {
                         .text()
                         .await
                         .unwrap_or_else(|_| format!("HTTP Error: {}", status));
-                    println!("HTTP error for {}: {} - {}", mod_name, status, error_text);
+                    warn!("HTTP error for {}: {} - {}", mod_name, status, error_text);
                     let err = ModError::HttpError(format!(
                         "Server returned error: {} - {}",
                         status, error_text
@@ -331,7 +394,7 @@ impl ModDownloader {
                 r
             }
             Err(e) => {
-                println!("GET request failed for {}: {}", mod_name, e);
+                warn!("GET request failed for {}: {}", mod_name, e);
                 let err = ModError::RequestError(e);
                 emit_error(&err);
                 return Err(err);
@@ -345,7 +408,7 @@ impl ModDownloader {
         let mut file = match tokio::fs::File::create(path).await {
             Ok(f) => f,
             Err(e) => {
-                println!("Failed to create file {}: {}", path.display(), e);
+                warn!("Failed to create file {}: {}", path.display(), e);
                 let err = ModError::IoError(e);
                 emit_error(&err);
                 return Err(err);
@@ -354,7 +417,7 @@ impl ModDownloader {
 
         use tokio::io::AsyncWriteExt;
 
-        println!("Downloading to path: {}", path.display());
+        info!("Downloading to path: {}", path.display());
         while let Some(chunk) = stream.next().await {
             // Check if cancelled during download
             if cancel_token.is_cancelled() {
@@ -367,7 +430,7 @@ impl ModDownloader {
             let chunk = match chunk {
                 Ok(c) => c,
                 Err(e) => {
-                    println!("Download stream error for {}: {}", mod_name, e);
+                    warn!("Download stream error for {}: {}", mod_name, e);
                     let err = ModError::RequestError(e);
                     emit_error(&err);
                     return Err(err);
@@ -375,13 +438,15 @@ impl ModDownloader {
             };
 
             if let Err(e) = file.write_all(&chunk).await {
-                println!("Failed to write chunk to file {}: {}", path.display(), e);
+                warn!("Failed to write chunk to file {}: {}", path.display(), e);
                 let err = ModError::IoError(e);
                 emit_error(&err);
                 return Err(err);
             }
 
             downloaded += chunk.len() as u64;
+            debug!("Wrote {} bytes of {}, {}/{} bytes", chunk.len(), mod_name, downloaded, total_size);
+            get_throttle().consume(chunk.len() as u64).await;
             let progress = calculate_progress(downloaded, total_size);
 
             // Get the current percentage as an integer
@@ -416,14 +481,14 @@ impl ModDownloader {
 
         // Ensure file is flushed and closed correctly
         if let Err(e) = file.flush().await {
-            println!("Failed to flush file {}: {}", path.display(), e);
+            warn!("Failed to flush file {}: {}", path.display(), e);
             let err = ModError::IoError(e);
             emit_error(&err);
             return Err(err);
         }
 
         if let Err(e) = file.sync_all().await {
-            println!("Failed to sync file {}: {}", path.display(), e);
+            warn!("Failed to sync file {}: {}", path.display(), e);
             // Log but continue, as this is not critical
         }
 
@@ -451,7 +516,7 @@ impl ModDownloader {
         let metadata = match tokio::fs::metadata(path).await {
             Ok(m) => m,
             Err(e) => {
-                println!("Failed to get metadata for {}: {}", path.display(), e);
+                warn!("Failed to get metadata for {}: {}", path.display(), e);
                 let err = ModError::IoError(e);
                 emit_error(&err);
                 return Err(err);
@@ -459,7 +524,7 @@ impl ModDownloader {
         };
 
         if metadata.len() == 0 {
-            println!("Downloaded file is empty: {}", path.display());
+            warn!("Downloaded file is empty: {}", path.display());
             let err = ModError::IoError(std::io::Error::new(
                 std::io::ErrorKind::UnexpectedEof,
                 "Downloaded file is empty",
@@ -469,7 +534,7 @@ impl ModDownloader {
         }
 
         // Emit completion event
-        println!(
+        info!(
             "Download completed for {} - File size: {} bytes",
             mod_name,
             metadata.len()
@@ -484,20 +549,65 @@ impl ModDownloader {
     }
 
     pub async fn fetch_and_parse_mods(&self, url: &str) -> Result<(ModsFile, Option<std::path::PathBuf>), ModError> {
-        let xml_content = self.fetch_mod_list(url).await?;
-        let mods_file = ModParser::parse_mod_list(&xml_content)?;
-        
-        // Save the successful XML to cache
-        let cache_path = match super::xml_cache::XmlCache::save_xml(url, &xml_content) {
+        let (mut mods_file, cache_path) = self.fetch_repo_file(url).await?;
+        fetch_includes(self, &mut mods_file, url).await;
+        // Kept for update checks across repositories without fetching them all again
+        if let Err(e) = record_repo_versions(url, &mods_file) {
+            warn!("Failed to record repository versions: {}", e);
+        }
+        // Mods are enabled with the mapping of the repository as it was last fetched
+        if let Err(e) = save_repo_mapping(url, mods_file.mapping.as_ref()) {
+            warn!("Failed to keep the repository's mapping: {}", e);
+        }
+        Ok((mods_file, cache_path))
+    }
+
+    /// Fetch and parse one repository file. It streams to disk and is parsed from there, so a
+    /// large repository is never held in memory whole, and becomes the cached copy once it parses
+    pub async fn fetch_repo_file(&self, url: &str) -> Result<(ModsFile, Option<PathBuf>), ModError> {
+        let partial_path = match XmlCache::partial_path(url) {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("Failed to cache XML: {}", e);
+                let xml_content = self.fetch_mod_list(url).await?;
+                return Ok((ModParser::parse_mod_list_lenient(&xml_content, &self.language)?, None));
+            }
+        };
+
+        let parsed = match self.stream_to_file(url, &partial_path).await {
+            Ok(()) => parse_repo_file(&partial_path, &self.language),
+            Err(e) => Err(e),
+        };
+        let mods_file = match parsed {
+            Ok(mods_file) => mods_file,
+            Err(e) => {
+                // The previous cached copy stays usable
+                let _ = std::fs::remove_file(&partial_path);
+                return Err(e);
+            }
+        };
+
+        let cache_path = match XmlCache::commit_partial(url, &partial_path) {
             Ok(path) => Some(path),
             Err(e) => {
-                println!("Warning: Failed to cache XML: {}", e);
+                warn!("Failed to cache XML: {}", e);
                 None
             }
         };
-        
         Ok((mods_file, cache_path))
     }
+
+    /// Write a response body to a file chunk by chunk
+    async fn stream_to_file(&self, url: &str, path: &Path) -> Result<(), ModError> {
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        let mut file = tokio::fs::File::create(path).await?;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            file.write_all(&chunk?).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
 }
 
 impl Default for ModDownloader {
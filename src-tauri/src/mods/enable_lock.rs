@@ -0,0 +1,90 @@
+use super::mod_utils::{get_enabling_file_path, local_mod_dir};
+use super::types::{Category, ModError};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// An ENABLING lock older than this is considered left behind by a crash, whoever holds it
+pub const STALE_LOCK_AGE: Duration = Duration::from_secs(30 * 60);
+
+/// Contents of an ENABLING file
+#[derive(Debug, Serialize, Deserialize)]
+struct LockInfo {
+    pid: u32,
+    started_at: u64,
+}
+
+/// State of an ENABLING lock found on a mod
+#[derive(Debug, Clone, Serialize)]
+pub struct LockStatus {
+    pub mod_name: String,
+    /// Process that took the lock, None for locks written by older versions
+    pub pid: Option<u32>,
+    pub age_secs: u64,
+    /// True if the enable that took the lock can no longer be running
+    pub stale: bool,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Take the ENABLING lock for a profile, recording this process and the time
+pub fn write_enabling_lock(mod_dir: &Path, profile_name: &str) -> Result<(), ModError> {
+    let info = LockInfo {
+        pid: std::process::id(),
+        started_at: now_secs(),
+    };
+    let content = serde_json::to_string(&info)
+        .map_err(|e| ModError::EnablementError(format!("Failed to serialize lock: {}", e)))?;
+    fs::write(get_enabling_file_path(mod_dir, profile_name), content).map_err(ModError::IoError)
+}
+
+/// Read the ENABLING lock of a mod for a profile, if there is one.
+///
+/// A lock is stale when it was taken by another process (the app only runs once, so that
+/// process crashed or was closed mid-enable) or when it is older than STALE_LOCK_AGE.
+pub fn read_lock_status(mod_dir: &Path, profile_name: &str) -> Option<LockStatus> {
+    let path = get_enabling_file_path(mod_dir, profile_name);
+    let metadata = fs::metadata(&path).ok()?;
+    let info: Option<LockInfo> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+
+    let started_at = match &info {
+        Some(info) => info.started_at,
+        None => metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+    let age_secs = now_secs().saturating_sub(started_at);
+    let pid = info.map(|info| info.pid);
+
+    Some(LockStatus {
+        mod_name: mod_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        pid,
+        age_secs,
+        stale: pid.is_some_and(|pid| pid != std::process::id())
+            || age_secs > STALE_LOCK_AGE.as_secs(),
+    })
+}
+
+/// Attach the ENABLING lock of each downloaded mod of a listing for a profile, so a mod stuck
+/// mid-enable shows as such and can be unlocked
+pub fn attach_enabling_locks(categories: &mut [Category], settings: &Settings, profile_name: &str) {
+    for mod_entry in categories.iter_mut().flat_map(|cat| cat.mods.iter_mut()) {
+        mod_entry.enabling_lock = local_mod_dir(settings, mod_entry.source.as_deref(), &mod_entry.name)
+            .and_then(|mod_dir| read_lock_status(&mod_dir, profile_name));
+    }
+}
@@ -0,0 +1,239 @@
+use super::mod_utils::{get_enabled_file_path, list_mod_dirs, list_source_dirs, SIDELOAD_SOURCE};
+use super::types::ModError;
+use crate::app_data;
+use crate::settings::Settings;
+use crate::state_db::{now_secs, with_db};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tracing::{info, warn};
+
+/// Where the previous version kept enable state, read once into the state database
+const ENABLE_STATE_FILE: &str = "enabled_mods.json";
+
+/// A mod enabled for a profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnableRecord {
+    /// Seconds since the Unix epoch
+    pub enabled_at: u64,
+}
+
+/// Enabled mods by profile name, then by "<source>/<mod name>", as `enabled_mods.json` held them
+type EnableRegistry = BTreeMap<String, BTreeMap<String, EnableRecord>>;
+
+/// Source of a directory holding mods: "sideload" for the sideload directory, otherwise its
+/// repo-hash name
+fn dir_source(settings: &Settings, dir: &Path) -> String {
+    if !settings.sideload_path.is_empty() && dir == Path::new(&settings.sideload_path) {
+        return SIDELOAD_SOURCE.to_string();
+    }
+    dir.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// Source and name a mod folder is recorded under. Kept apart from the folder's location, so the
+/// state survives moving the download directory and downloading a mod again
+fn mod_dir_key(settings: &Settings, mod_dir: &Path) -> (String, String) {
+    let source = dir_source(settings, mod_dir.parent().unwrap_or(mod_dir));
+    let name = mod_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+    (source, name)
+}
+
+/// Build the registry from the `ENABLED-<profile>.txt` markers of earlier versions, taking the
+/// marker's modification time as when the mod was enabled
+fn registry_from_markers(settings: &Settings) -> EnableRegistry {
+    let mut dirs: Vec<PathBuf> = list_source_dirs(settings).into_iter().map(|(_, dir)| dir).collect();
+    if !settings.sideload_path.is_empty() {
+        dirs.push(PathBuf::from(&settings.sideload_path));
+    }
+
+    let mut registry = EnableRegistry::new();
+    for mod_dir in dirs.iter().flat_map(|dir| list_mod_dirs(dir)) {
+        let Ok(entries) = std::fs::read_dir(&mod_dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(profile_name) = file_name.strip_prefix("ENABLED-").and_then(|rest| rest.strip_suffix(".txt")) else {
+                continue;
+            };
+            let enabled_at = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|since_epoch| since_epoch.as_secs())
+                .unwrap_or_else(now_secs);
+            let (source, name) = mod_dir_key(settings, &mod_dir);
+            registry
+                .entry(profile_name.to_string())
+                .or_default()
+                .insert(format!("{}/{}", source, name), EnableRecord { enabled_at });
+        }
+    }
+    registry
+}
+
+/// Fill a new state database with the enable state of earlier versions: `enabled_mods.json`, or
+/// the marker files from before that
+pub(crate) fn import_legacy_state(conn: &Connection) -> rusqlite::Result<()> {
+    let exists = app_data::get_app_data_dir().is_some_and(|dir| dir.join(ENABLE_STATE_FILE).exists());
+    let loaded = if exists {
+        app_data::load_json(ENABLE_STATE_FILE)
+            .inspect_err(|e| warn!("Failed to load enabled mods, reading marker files instead: {}", e))
+            .ok()
+    } else {
+        None
+    };
+    let registry: EnableRegistry = loaded.unwrap_or_else(|| {
+        Settings::load().map(|settings| registry_from_markers(&settings)).unwrap_or_default()
+    });
+
+    let mut insert = conn.prepare(
+        "INSERT OR IGNORE INTO enabled_mods (profile, source, name, enabled_at) VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for (profile_name, mods) in &registry {
+        for (key, record) in mods {
+            let Some((source, name)) = key.split_once('/') else {
+                continue;
+            };
+            insert.execute(params![profile_name, source, name, record.enabled_at])?;
+        }
+    }
+    info!("Imported enable state of {} profiles", registry.len());
+    Ok(())
+}
+
+/// Whether the mod in `mod_dir` is enabled for a profile
+pub fn is_enabled(settings: &Settings, mod_dir: &Path, profile_name: &str) -> Result<bool, String> {
+    let (source, name) = mod_dir_key(settings, mod_dir);
+    with_db(|db| {
+        db.query_row(
+            "SELECT EXISTS (SELECT 1 FROM enabled_mods WHERE profile = ?1 AND source = ?2 AND name = ?3)",
+            params![profile_name, source, name],
+            |row| row.get(0),
+        )
+    })
+}
+
+/// Whether any profile has the mod in `mod_dir` enabled
+pub fn is_enabled_anywhere(settings: &Settings, mod_dir: &Path) -> Result<bool, String> {
+    let (source, name) = mod_dir_key(settings, mod_dir);
+    with_db(|db| {
+        db.query_row(
+            "SELECT EXISTS (SELECT 1 FROM enabled_mods WHERE source = ?1 AND name = ?2)",
+            params![source, name],
+            |row| row.get(0),
+        )
+    })
+}
+
+/// Whether any mod from a source is recorded as enabled, for any profile name including those of
+/// deleted profiles, whose mods stay linked into DCS until they are disabled
+pub fn has_enabled_mods_from(source: &str) -> Result<bool, String> {
+    with_db(|db| {
+        db.query_row(
+            "SELECT EXISTS (SELECT 1 FROM enabled_mods WHERE source = ?1)",
+            [source],
+            |row| row.get(0),
+        )
+    })
+}
+
+/// When the mod in `mod_dir` was enabled for a profile, if it is
+pub fn enabled_at(settings: &Settings, mod_dir: &Path, profile_name: &str) -> Result<Option<u64>, String> {
+    let (source, name) = mod_dir_key(settings, mod_dir);
+    with_db(|db| {
+        db.query_row(
+            "SELECT enabled_at FROM enabled_mods WHERE profile = ?1 AND source = ?2 AND name = ?3",
+            params![profile_name, source, name],
+            |row| row.get(0),
+        )
+        .optional()
+    })
+}
+
+/// Record a mod as enabled or disabled for a profile. The `ENABLED-<profile>.txt` marker is
+/// kept in step while the `enabled_markers` setting is on, and removed on disable either way
+pub fn set_enabled(mod_dir: &Path, profile_name: &str, enabled: bool) -> Result<(), ModError> {
+    let settings = Settings::load().map_err(ModError::SettingsError)?;
+    let (source, name) = mod_dir_key(&settings, mod_dir);
+    with_db(|db| {
+        if enabled {
+            // Re-enabling after an update keeps the original time
+            db.execute(
+                "INSERT OR IGNORE INTO enabled_mods (profile, source, name, enabled_at) VALUES (?1, ?2, ?3, ?4)",
+                params![profile_name, source, name, now_secs()],
+            )
+        } else {
+            db.execute(
+                "DELETE FROM enabled_mods WHERE profile = ?1 AND source = ?2 AND name = ?3",
+                params![profile_name, source, name],
+            )
+        }
+    })
+    .map_err(ModError::EnablementError)?;
+
+    let marker = get_enabled_file_path(mod_dir, profile_name);
+    if enabled && settings.enabled_markers {
+        std::fs::write(&marker, "")?;
+    } else if !enabled && marker.exists() {
+        std::fs::remove_file(&marker)?;
+    }
+    Ok(())
+}
+
+/// Whether mods are recorded as enabled for a profile of this name, such as a deleted one whose
+/// mods are still linked into DCS
+pub fn has_profile_state(profile_name: &str) -> Result<bool, String> {
+    with_db(|db| {
+        db.query_row(
+            "SELECT EXISTS (SELECT 1 FROM enabled_mods WHERE profile = ?1)",
+            [profile_name],
+            |row| row.get(0),
+        )
+    })
+}
+
+/// Move a profile's enabled mods to its new name
+pub fn rename_profile_state(old_name: &str, new_name: &str) -> Result<(), String> {
+    with_db(|db| {
+        db.execute(
+            "UPDATE enabled_mods SET profile = ?2 WHERE profile = ?1",
+            [old_name, new_name],
+        )
+    })
+    .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_from_markers() {
+        let dir = tempfile::tempdir().unwrap();
+        let downloads = dir.path().join("downloads");
+        let sideload = dir.path().join("sideload");
+        let settings = Settings {
+            download_path: downloads.to_string_lossy().to_string(),
+            sideload_path: sideload.to_string_lossy().to_string(),
+            ..Settings::default()
+        };
+        let repo_mod = downloads.join("a1b2c3").join("ModA");
+        let sideloaded = sideload.join("ModB");
+        std::fs::create_dir_all(&repo_mod).unwrap();
+        std::fs::create_dir_all(&sideloaded).unwrap();
+        std::fs::write(get_enabled_file_path(&repo_mod, "Stable"), "").unwrap();
+        std::fs::write(get_enabled_file_path(&repo_mod, "OpenBeta"), "").unwrap();
+        std::fs::write(get_enabled_file_path(&sideloaded, "Stable"), "").unwrap();
+        std::fs::write(repo_mod.join("VERSION.txt"), "1.0").unwrap();
+
+        let registry = registry_from_markers(&settings);
+        let stable: Vec<&String> = registry["Stable"].keys().collect();
+        assert_eq!(stable, ["a1b2c3/ModA", "sideload/ModB"]);
+        assert!(registry["OpenBeta"].contains_key("a1b2c3/ModA"));
+        assert_eq!(registry.len(), 2);
+    }
+}
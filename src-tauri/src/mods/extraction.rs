@@ -6,6 +6,7 @@ use std::path::Path;
 use tauri::Emitter;
 use tokio_util::sync::CancellationToken;
 use zip::ZipArchive;
+use tracing::{debug, info, warn};
 
 #[derive(Clone, Serialize)]
 pub struct ExtractionStatus {
@@ -50,7 +51,7 @@ pub async fn extract_zip(
     extract_dir: &Path,
     mod_name: &str,
 ) -> Result<(), String> {
-    println!(
+    info!(
         "Starting extraction of {} to {}",
         zip_path.display(),
         extract_dir.display()
@@ -98,7 +99,7 @@ pub async fn extract_zip(
         Ok(archive) => archive,
         Err(e) => {
             let error_msg = format!("The ZIP file is corrupted or invalid: {}", e);
-            println!("{}", error_msg);
+            warn!("{}", error_msg);
             let _ = app_handle.emit(
                 "extraction-error",
                 ExtractionError {
@@ -114,7 +115,7 @@ pub async fn extract_zip(
     // Verify the archive is intact by checking for CRC errors
     if let Err(e) = verify_archive(&mut archive) {
         let error_msg = format!("ZIP archive failed verification: {}", e);
-        println!("{}", error_msg);
+        warn!("{}", error_msg);
         let _ = app_handle.emit(
             "extraction-error",
             ExtractionError {
@@ -201,11 +202,12 @@ pub async fn extract_zip(
                 );
                 return Err(error_msg);
             }
+            debug!("Extracted {} ({} bytes)", outpath.display(), file.size());
         }
     }
 
     // Emit extraction completed event
-    println!("Extraction completed for {}", mod_name);
+    info!("Extraction completed for {}", mod_name);
     app_handle
         .emit(
             "extraction-status",
@@ -228,7 +230,7 @@ pub async fn extract_zip_with_cancellation(
     mod_name: &str,
     cancel_token: CancellationToken,
 ) -> Result<(), String> {
-    println!(
+    info!(
         "Starting cancellable extraction of {} to {}",
         zip_path.display(),
         extract_dir.display()
@@ -288,7 +290,7 @@ pub async fn extract_zip_with_cancellation(
         Ok(archive) => archive,
         Err(e) => {
             let error_msg = format!("The ZIP file is corrupted or invalid: {}", e);
-            println!("{}", error_msg);
+            warn!("{}", error_msg);
             let _ = app_handle.emit(
                 "extraction-error",
                 ExtractionError {
@@ -309,7 +311,7 @@ pub async fn extract_zip_with_cancellation(
     // Verify the archive is intact by checking for CRC errors
     if let Err(e) = verify_archive(&mut archive) {
         let error_msg = format!("ZIP archive failed verification: {}", e);
-        println!("{}", error_msg);
+        warn!("{}", error_msg);
         let _ = app_handle.emit(
             "extraction-error",
             ExtractionError {
@@ -402,6 +404,7 @@ pub async fn extract_zip_with_cancellation(
                 );
                 return Err(error_msg);
             }
+            debug!("Extracted {} ({} bytes)", outpath.display(), file.size());
         }
     }
 
@@ -413,7 +416,7 @@ pub async fn extract_zip_with_cancellation(
     }
 
     // Emit extraction completed event
-    println!("Extraction completed for {}", mod_name);
+    info!("Extraction completed for {}", mod_name);
     app_handle
         .emit(
             "extraction-status",
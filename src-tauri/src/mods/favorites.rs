@@ -0,0 +1,63 @@
+use super::mod_utils::mod_key;
+use super::types::{Category, ModsResult};
+use crate::app_data;
+use std::collections::BTreeSet;
+
+const FAVORITES_FILE: &str = "favorites.json";
+
+/// Name of the virtual category listing favorite mods
+pub const FAVORITES_CATEGORY: &str = "Favorites";
+
+/// Mods marked as favorites, by source and mod name (see mod_key)
+pub fn load_favorites() -> Result<BTreeSet<String>, String> {
+    app_data::load_json(FAVORITES_FILE)
+}
+
+/// Set the favorite flag on every listed mod
+pub fn mark_favorites(categories: &mut [Category], favorites: &BTreeSet<String>) {
+    for mod_entry in categories.iter_mut().flat_map(|cat| cat.mods.iter_mut()) {
+        mod_entry.favorite = favorites.contains(&mod_key(mod_entry.source.as_deref(), &mod_entry.name));
+    }
+}
+
+/// Put copies of the favorite mods into a Favorites category at the top of the listing
+pub fn add_favorites_category(result: &mut ModsResult) {
+    let mods: Vec<_> = result
+        .categories
+        .iter()
+        .flat_map(|cat| cat.mods.iter().filter(|m| m.favorite).cloned())
+        .collect();
+    if mods.is_empty() {
+        return;
+    }
+    result.categories.insert(
+        0,
+        Category {
+            name: FAVORITES_CATEGORY.to_string(),
+            sort_order: -1000, // Above the Deprecated category
+            description: None,
+            parent: None,
+            mods,
+            subcategories: Vec::new(),
+        },
+    );
+}
+
+#[tauri::command]
+pub async fn get_favorite_mods() -> Result<Vec<String>, String> {
+    Ok(load_favorites()?.into_iter().collect())
+}
+
+/// Mark or unmark a mod's copy from the given source as favorite, returning the updated favorites
+#[tauri::command]
+pub async fn set_mod_favorite(mod_name: String, source: Option<String>, favorite: bool) -> Result<Vec<String>, String> {
+    let mut favorites = load_favorites()?;
+    let key = mod_key(source.as_deref(), &mod_name);
+    if favorite {
+        favorites.insert(key);
+    } else {
+        favorites.remove(&key);
+    }
+    app_data::save_json(FAVORITES_FILE, &favorites)?;
+    Ok(favorites.into_iter().collect())
+}
@@ -1,65 +1,213 @@
 use super::downloader::ModDownloader;
-use super::mod_download::is_mod_successfully_downloaded;
+use super::enable_lock::attach_enabling_locks;
+use super::enable_state;
+use super::installed::load_installed_mods;
 use super::parser::ModParser;
 use super::sideload::scan_sideload_directory;
-use super::deprecated::scan_for_deprecated_mods;
-use super::types::ModsResult;
+use super::search::filter_by_tag;
+use super::sorting::{sort_mods, ModSort};
+use super::versions::required_app_update;
+use super::categories::remember_listing;
+use super::deprecated::{scan_for_deprecated_mods, take_repo_deprecated};
+use super::favorites::{add_favorites_category, load_favorites, mark_favorites};
+use super::hidden::{load_hidden_mods, remove_hidden_mods};
+use super::dcs_version::{detect_dcs_version, mark_dcs_incompatible, profile_dcs_variant};
+use super::last_seen::mark_new_since_last_visit;
+use super::notes::{attach_notes, load_notes};
+use super::variants::{load_variant_choices, select_variants};
+use super::migration::adopt_renamed_mod_dirs;
+use super::mod_utils::{
+    get_mod_variant, get_mod_version, get_repo_download_dir, get_repo_source, is_mod_enabled,
+    list_enabled_mod_dirs, local_mod_dir, SIDELOAD_SOURCE,
+};
+use super::types::{Category, EnabledModEntry, ModUpdate, ModsFile, ModsResult, ModsSummary};
+use super::xml_cache::XmlCache;
+use super::prefetch::take_recent_prefetch;
 use crate::settings;
-use sha2::{Digest, Sha256};
+use crate::state_db::now_secs;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
 
+/// Enabled mods of every profile source, in the repository download directory or the sideload
+/// directory. Without a profile name those of the active profile
 #[tauri::command]
-pub async fn get_enabled_mods(profile_name: String) -> Result<Vec<String>, String> {
+pub async fn get_enabled_mods(profile_name: Option<String>) -> Result<Vec<EnabledModEntry>, String> {
     let settings = settings::Settings::load()?;
-    let base_downloads_dir = PathBuf::from(&settings.download_path);
-    let mut enabled_mods = Vec::new();
-
-    // Find the profile to get the repo_url
+    let profile_name = settings.profile_or_active(profile_name)?;
     let profile = settings
         .profiles
         .iter()
         .find(|p| p.name == profile_name)
         .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
 
-    // Calculate the XML-specific path for this profile
-    let mut hasher = Sha256::new();
-    hasher.update(profile.repo_url.as_bytes());
-    let hash_result = hasher.finalize();
-    let repo_hash = format!("{:x}", hash_result);
-    // Shrink the hash to 6 characters
-    let repo_hash = &repo_hash[..6];
-    let xml_specific_path = base_downloads_dir.join(repo_hash);
+    let repo_dir = get_repo_download_dir(&settings.profile_download_path(profile), &profile.repo_url);
+    info!("Checking for enabled mods within: {}", repo_dir.display());
 
-    println!("Checking for enabled mods within: {}", xml_specific_path.display());
+    let mut sources = vec![(get_repo_source(&profile.repo_url), Some(profile.repo_url.clone()), repo_dir)];
+    if !settings.sideload_path.is_empty() {
+        sources.push((SIDELOAD_SOURCE.to_string(), None, PathBuf::from(&settings.sideload_path)));
+    }
 
-    if xml_specific_path.exists() && xml_specific_path.is_dir() {
-        // Iterate within the specific XML source directory
-        let mod_dir_entries = std::fs::read_dir(&xml_specific_path).map_err(|e| e.to_string())?;
-        for mod_entry in mod_dir_entries.filter_map(Result::ok) {
-            let mod_path = mod_entry.path(); // Path to the specific mod directory
-            if mod_path.is_dir() {
-                if let Some(mod_name) = mod_path.file_name().and_then(|n| n.to_str()) {
-                    // Check if this specific mod is enabled for the given profile
-                    if super::mod_utils::is_mod_enabled(&mod_path, &profile_name) {
-                        enabled_mods.push(mod_name.to_string());
-                    }
-                }
-            }
+    let mut enabled_mods = Vec::new();
+    for (source, repo_url, dir) in sources {
+        for mod_dir in list_enabled_mod_dirs(&dir, &profile_name)? {
+            let Some(name) = mod_dir.file_name().and_then(|n| n.to_str()).map(String::from) else {
+                continue;
+            };
+            let enabled_at = enable_state::enabled_at(&settings, &mod_dir, &profile_name)?;
+            enabled_mods.push(EnabledModEntry {
+                version: get_mod_version(&mod_dir).ok(),
+                variant: get_mod_variant(&mod_dir),
+                name,
+                source: source.clone(),
+                repo_url: repo_url.clone(),
+                enabled_at,
+            });
         }
     }
 
     Ok(enabled_mods)
 }
 
+/// Cached repository XML: the path recorded in settings for the URL, or the default cache location
+fn cached_xml_path(settings: &settings::Settings, url: &str) -> Option<PathBuf> {
+    match settings.cached_xml_paths.get(url) {
+        Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => XmlCache::get_cache_path(url),
+    }
+}
+
+/// Compare a repository listing against the downloaded mods, returning its categories in display order
+fn with_local_versions(mods_file: ModsFile, download_path: &Path, url: &str) -> Vec<Category> {
+    let updated_mods = match ModParser::check_for_updates(&mods_file, download_path, url) {
+        Ok(updated) => {
+            // Debug logging for each mod after update check
+            for category in &updated.categories {
+                for mod_entry in &category.mods {
+                    debug!("After update check - Mod: {}, Version: {}, New Version: {:?}",
+                        mod_entry.name, mod_entry.version, mod_entry.new_version);
+                }
+            }
+            updated
+        },
+        Err(e) => {
+            warn!("Failed to check for updates: {}", e);
+            mods_file
+        }
+    };
+
+    let mut categories = updated_mods.categories;
+    categories.sort_by_key(|cat| cat.sort_order);
+    categories
+}
+
+/// Build the mod listing from the cached repository XML alone, without touching the network.
+/// Returns None if the profile has nothing cached yet.
+pub(crate) fn get_cached_mods(profile_index: usize) -> Result<Option<ModsResult>, String> {
+    let settings = settings::Settings::load()?;
+    let Some(profile) = settings.profiles.get(profile_index) else {
+        return Ok(None);
+    };
+    let url = profile.repo_url.trim_end_matches('/').to_string();
+
+    let Some((path, mods_file)) = cached_xml_path(&settings, &url)
+        .and_then(|path| XmlCache::load_xml(&path, &url).ok().map(|mods_file| (path, mods_file)))
+    else {
+        return Ok(None);
+    };
+
+    let app_update_required = required_app_update(mods_file.min_app_version.as_deref());
+    let header = mods_file.header.clone();
+    let warnings = mods_file.warnings.clone();
+    let categories = with_local_versions(mods_file, &settings.profile_download_path(profile), &url);
+    let mut result = finish_mods_result(&settings, profile_index, &url, categories, None);
+    result.app_update_required = app_update_required;
+    result.header = header;
+    result.warnings = warnings;
+    set_cache_info(&mut result, &settings, Some(&path));
+    remember_listing(profile_index, &url, &result);
+    Ok(Some(result))
+}
+
+/// Return the cached listing straight away and refresh it from the repository in the background,
+/// emitting `mods-refreshed` with the fresh listing. Without a cache this waits for the repository.
+/// With `favorites_category` favorite mods are also listed in a Favorites category at the top,
+/// `sort` orders the mods within each category and `tag` keeps only the mods carrying that tag.
 #[tauri::command]
-pub async fn get_mods(profile_index: usize) -> Result<ModsResult, String> {
+pub async fn get_mods(
+    app_handle: AppHandle,
+    profile_index: usize,
+    favorites_category: Option<bool>,
+    sort: Option<ModSort>,
+    tag: Option<String>,
+) -> Result<ModsResult, String> {
+    let with_favorites = favorites_category.unwrap_or(false);
+    let present = move |result: &mut ModsResult| {
+        if let Some(tag) = &tag {
+            filter_by_tag(result, tag);
+        }
+        if with_favorites {
+            add_favorites_category(result);
+        }
+        if let Some(sort) = sort {
+            match settings::Settings::load() {
+                Ok(settings) => sort_mods(result, sort, &settings),
+                Err(e) => warn!("Failed to load settings for sorting: {}", e),
+            }
+        }
+    };
+
+    if let Some(mut cached) = get_cached_mods(profile_index)? {
+        // A listing prefetched at startup is fresh enough the first time it is shown
+        let prefetched = settings::Settings::load()
+            .ok()
+            .and_then(|settings| settings.profiles.get(profile_index).map(|p| take_recent_prefetch(&p.repo_url)))
+            .unwrap_or(false);
+        if !prefetched {
+            let present_fresh = present.clone();
+            tokio::spawn(async move {
+                match fetch_mods(profile_index).await {
+                    Ok(mut fresh) => {
+                        present_fresh(&mut fresh);
+                        let _ = app_handle.emit(
+                            "mods-refreshed",
+                            serde_json::json!({
+                                "profile_index": profile_index,
+                                "result": fresh
+                            }),
+                        );
+                    }
+                    Err(e) => warn!("Failed to refresh mods in the background: {}", e),
+                }
+            });
+        }
+        present(&mut cached);
+        return Ok(cached);
+    }
+
+    let mut result = fetch_mods(profile_index).await?;
+    present(&mut result);
+    Ok(result)
+}
+
+/// Build the mod listing from the repository, falling back to the cached XML if it can't be reached
+pub(crate) async fn fetch_mods(profile_index: usize) -> Result<ModsResult, String> {
     let mut settings = settings::Settings::load()?;
     
     if profile_index >= settings.profiles.len() {
         return Ok(ModsResult {
             categories: Vec::new(),
             error: Some("Profile index out of bounds".to_string()),
+            summary: ModsSummary::default(),
+            app_update_required: None,
+            header: None,
+            warnings: Vec::new(),
+            fetched_at: None,
+            from_cache: false,
+            cache_age_secs: None,
+            stale: false,
         });
     }
     
@@ -67,8 +215,11 @@ pub async fn get_mods(profile_index: usize) -> Result<ModsResult, String> {
     let downloader = ModDownloader::new();
     let mut categories = Vec::new();
     let mut error = None;
-    let mut xml_loaded_from_cache = false;
-    let download_path = PathBuf::from(&settings.download_path);
+    let mut app_update_required = None;
+    let mut header = None;
+    let mut warnings = Vec::new();
+    let mut cached_from = None;
+    let download_path = settings.profile_download_path(&settings.profiles[profile_index]);
 
     // Try to fetch and parse mods from the URL
     match downloader.fetch_and_parse_mods(&url).await {
@@ -76,83 +227,45 @@ pub async fn get_mods(profile_index: usize) -> Result<ModsResult, String> {
             // Save the cache path if available
             if let Some(path) = cache_path {
                 if let Err(e) = super::xml_cache::update_cache_path_in_settings(&mut settings, &url, &path) {
-                    println!("Warning: Failed to update cache path in settings: {}", e);
+                    warn!("Failed to update cache path in settings: {}", e);
                 }
             }
 
-            // Pass the repo URL to check_for_updates
-            let updated_mods = match ModParser::check_for_updates(
-                &mods_file,
-                &download_path,
-                &url,
-            ) {
-                Ok(updated) => {
-                    // Debug logging for each mod after update check
-                    for category in &updated.categories {
-                        for mod_entry in &category.mods {
-                            println!("After update check - Mod: {}, Version: {}, New Version: {:?}",
-                                mod_entry.name, mod_entry.version, mod_entry.new_version);
-                        }
-                    }
-                    updated
-                },
-                Err(e) => {
-                    println!("Warning: Failed to check for updates: {}", e);
-                    mods_file
-                }
-            };
-
-            categories = updated_mods.categories;
-            categories.sort_by_key(|cat| cat.sort_order);
+            app_update_required = required_app_update(mods_file.min_app_version.as_deref());
+            header = mods_file.header.clone();
+            warnings = mods_file.warnings.clone();
+            adopt_renamed_mod_dirs(&mods_file, &download_path, &url);
+            categories = with_local_versions(mods_file, &download_path, &url);
         },
         Err(e) => {
             // Could not fetch from URL, try to load from cache
-            println!("Failed to load repository mods: {}", e);
+            warn!("Failed to load repository mods: {}", e);
             error = Some(format!("Failed to load repository XML: {}", e));
             
-            // Try to find a cached XML file for this profile
-            let cached_xml_path = if profile_index < settings.cached_xml_paths.len() && !settings.cached_xml_paths[profile_index].is_empty() {
-                Some(PathBuf::from(&settings.cached_xml_paths[profile_index]))
-            } else {
-                super::xml_cache::XmlCache::get_cache_path(&url)
-            };
-            
-            if let Some(path) = cached_xml_path {
-                match super::xml_cache::XmlCache::load_xml(&path) {
+            if let Some(path) = cached_xml_path(&settings, &url) {
+                match XmlCache::load_xml(&path, &url) {
                     Ok(cached_mods_file) => {
-                        println!("Successfully loaded cached XML from: {}", path.display());
-                        xml_loaded_from_cache = true;
-
-                        // Check for updates using the cached file, passing the repo URL
-                        let updated_mods = match ModParser::check_for_updates(
-                            &cached_mods_file,
-                            &download_path,
-                            &url,
-                        ) {
-                            Ok(updated) => updated,
-                            Err(e) => {
-                                println!("Warning: Failed to check for updates using cached XML: {}", e);
-                                cached_mods_file
-                            }
-                        };
-                        
-                        categories = updated_mods.categories;
-                        categories.sort_by_key(|cat| cat.sort_order);
+                        info!("Successfully loaded cached XML from: {}", path.display());
+                        cached_from = Some(path.clone());
+                        app_update_required = required_app_update(cached_mods_file.min_app_version.as_deref());
+                        header = cached_mods_file.header.clone();
+                        warnings = cached_mods_file.warnings.clone();
+                        categories = with_local_versions(cached_mods_file, &download_path, &url);
                     },
                     Err(cache_err) => {
-                        println!("Failed to load cached XML: {}", cache_err);
+                        warn!("Failed to load cached XML: {}", cache_err);
                         error = Some(format!("Failed to load repository XML and could not read cache: {}", e));
                     }
                 }
             } else {
-                println!("No cached XML available for URL: {}", url);
+                info!("No cached XML available for URL: {}", url);
                 error = Some(format!("Failed to load repository XML: {}. No cached version available.", e));
             }
         }
     }
     
     // Handle error message for cached data
-    if xml_loaded_from_cache {
+    if cached_from.is_some() {
         // If there's an error message that already mentions cached data, keep it
         if let Some(err_msg) = &error {
             if !err_msg.contains("cached") && !err_msg.contains("Cached") {
@@ -164,36 +277,130 @@ pub async fn get_mods(profile_index: usize) -> Result<ModsResult, String> {
         }
     }
     
+    let mut result = finish_mods_result(&settings, profile_index, &url, categories, error);
+    result.app_update_required = app_update_required;
+    result.header = header;
+    result.warnings = warnings;
+    set_cache_info(&mut result, &settings, cached_from.as_deref());
+    remember_listing(profile_index, &url, &result);
+    Ok(result)
+}
+
+/// Record how old a listing is: fetched just now, or when the cached XML it was read from was
+/// written. Cached listings older than the TTL in the settings are flagged as stale
+fn set_cache_info(result: &mut ModsResult, settings: &settings::Settings, cached_from: Option<&Path>) {
+    let now = now_secs();
+    result.from_cache = cached_from.is_some();
+    result.fetched_at = match cached_from {
+        Some(path) => XmlCache::fetched_at(path),
+        None => Some(now),
+    };
+    result.cache_age_secs = result.fetched_at.map(|fetched_at| now.saturating_sub(fetched_at));
+    result.stale = result.from_cache
+        && settings.cache_ttl_hours > 0
+        && result.cache_age_secs.is_some_and(|age| age > settings.cache_ttl_hours.saturating_mul(3600));
+}
+
+/// Fetch the repository XML and list only the downloaded mods that have an update, skipping the
+/// deprecated and sideload scans of `get_mods`. Hidden mods are left out. Without a profile index
+/// the active profile is checked
+#[tauri::command]
+pub async fn check_updates(profile_index: Option<usize>) -> Result<Vec<ModUpdate>, String> {
+    let mut settings = settings::Settings::load()?;
+    let profile_index = profile_index
+        .or_else(|| settings.active_profile_index())
+        .ok_or_else(|| "No profile to check, create one first".to_string())?;
+    let url = settings
+        .profiles
+        .get(profile_index)
+        .ok_or_else(|| "Profile index out of bounds".to_string())?
+        .repo_url
+        .trim_end_matches('/')
+        .to_string();
+
+    let (mods_file, cache_path) = ModDownloader::new()
+        .fetch_and_parse_mods(&url)
+        .await
+        .map_err(|e| format!("Failed to load repository XML: {}", e))?;
+    if let Some(path) = cache_path {
+        if let Err(e) = super::xml_cache::update_cache_path_in_settings(&mut settings, &url, &path) {
+            warn!("Failed to update cache path in settings: {}", e);
+        }
+    }
+
+    let download_path = settings.profile_download_path(&settings.profiles[profile_index]);
+    adopt_renamed_mod_dirs(&mods_file, &download_path, &url);
+    pending_updates(&mods_file, &download_path, &url)
+}
+
+/// Downloaded mods in `download_path` the repository has another version of, leaving out hidden ones
+pub fn pending_updates(mods_file: &ModsFile, download_path: &Path, url: &str) -> Result<Vec<ModUpdate>, String> {
+    let checked = ModParser::check_for_updates(mods_file, download_path, url)
+        .map_err(|e| e.to_string())?;
+    let hidden = load_hidden_mods(url).unwrap_or_default();
+    Ok(checked
+        .categories
+        .into_iter()
+        .flat_map(|cat| cat.mods)
+        .filter(|m| !hidden.contains(&m.name))
+        .filter_map(|m| {
+            Some(ModUpdate {
+                new_version: m.new_version?,
+                current_version: m.version,
+                name: m.name,
+            })
+        })
+        .collect())
+}
+
+/// Add deprecated and sideloaded mods to a repository listing, along with sources and summary counts
+fn finish_mods_result(
+    settings: &settings::Settings,
+    profile_index: usize,
+    url: &str,
+    mut categories: Vec<Category>,
+    error: Option<String>,
+) -> ModsResult {
+    mark_new_since_last_visit(&settings.profiles[profile_index].name, &mut categories);
+
     // Collect active mod names to identify deprecated mods
     let active_mod_names: HashSet<String> = categories
         .iter()
         .flat_map(|cat| cat.mods.iter().map(|m| m.name.clone()))
         .collect();
 
-    // Scan for deprecated mods within the specific XML source directory
-    if !settings.download_path.is_empty() {
-        // Calculate the XML-specific path for deprecation scanning
-        let base_downloads_dir = PathBuf::from(&settings.download_path);
-        let mut hasher = Sha256::new();
-        hasher.update(url.as_bytes()); // url holds the repo_url here
-        let hash_result = hasher.finalize();
-        let repo_hash = format!("{:x}", hash_result);
-        let repo_hash = &repo_hash[..6]; // Shrink the hash to 6 characters
-        let xml_specific_path = base_downloads_dir.join(repo_hash);
+    // Mods the repository itself deprecates join the Deprecated category when downloaded
+    let download_path = settings.profile_download_path(&settings.profiles[profile_index]);
+    let repo_dir = get_repo_download_dir(&download_path, url);
+    let repo_deprecated = take_repo_deprecated(&mut categories, &repo_dir);
 
-        match scan_for_deprecated_mods(&xml_specific_path, &active_mod_names) {
-            Ok(deprecated_category) => {
+    // Scan for deprecated mods within the specific XML source directory
+    if !download_path.as_os_str().is_empty() {
+        match scan_for_deprecated_mods(&repo_dir, &active_mod_names) {
+            Ok(mut deprecated_category) => {
+                deprecated_category.mods.extend(repo_deprecated);
                 if !deprecated_category.mods.is_empty() {
                     // Add the deprecated mods to the categories list
                     categories.push(deprecated_category);
                 }
             },
             Err(e) => {
-                println!("Failed to scan for deprecated mods: {}", e);
+                warn!("Failed to scan for deprecated mods: {}", e);
             }
         }
     }
     
+    // Everything so far, deprecated mods included, lives in this repository's directory
+    let repo_source = super::mod_utils::get_repo_source(url);
+    for mod_entry in categories.iter_mut().flat_map(|cat| cat.mods.iter_mut()) {
+        mod_entry.source = Some(repo_source.clone());
+    }
+
+    match load_hidden_mods(url) {
+        Ok(hidden) => remove_hidden_mods(&mut categories, &hidden),
+        Err(e) => warn!("Failed to load hidden mods: {}", e),
+    }
+
     // Add sideloaded mods
     if !settings.sideload_path.is_empty() {
         match scan_sideload_directory(&settings.sideload_path) {
@@ -207,65 +414,83 @@ pub async fn get_mods(profile_index: usize) -> Result<ModsResult, String> {
                 }
             },
             Err(e) => {
-                println!("Failed to scan sideload directory: {}", e);
+                warn!("Failed to scan sideload directory: {}", e);
             }
         }
     }
     
-    Ok(ModsResult {
+    match load_favorites() {
+        Ok(favorites) => mark_favorites(&mut categories, &favorites),
+        Err(e) => warn!("Failed to load favorites: {}", e),
+    }
+    match load_notes() {
+        Ok(notes) => attach_notes(&mut categories, &notes),
+        Err(e) => warn!("Failed to load mod notes: {}", e),
+    }
+    match load_variant_choices() {
+        Ok(choices) => select_variants(&mut categories, &choices),
+        Err(e) => warn!("Failed to load mod variant choices: {}", e),
+    }
+    let profile = &settings.profiles[profile_index];
+    attach_enabling_locks(&mut categories, settings, &profile.name);
+    mark_dcs_incompatible(
+        &mut categories,
+        detect_dcs_version(profile).as_deref(),
+        profile_dcs_variant(profile),
+    );
+
+    let summary = summarize_mods(&categories, settings, &settings.profiles[profile_index].name)
+        .unwrap_or_else(|e| {
+            warn!("Failed to count enabled mods: {}", e);
+            ModsSummary::default()
+        });
+    ModsResult {
         categories,
         error,
-    })
+        summary,
+        app_update_required: None,
+        header: None,
+        warnings: Vec::new(),
+        fetched_at: None,
+        from_cache: false,
+        cache_age_secs: None,
+        stale: false,
+    }
 }
 
-#[tauri::command]
-pub async fn get_downloaded_mods() -> Result<Vec<String>, String> {
-    let settings = settings::Settings::load()?;
-    let base_downloads_dir = PathBuf::from(&settings.download_path);
-
-    let mut downloaded_mods = Vec::new();
-
-    if base_downloads_dir.exists() {
-        // Iterate through the hashed subdirectories first
-        let hash_dir_entries = std::fs::read_dir(&base_downloads_dir).map_err(|e| e.to_string())?;
-        for hash_entry in hash_dir_entries.filter_map(Result::ok) {
-            let xml_specific_path = hash_entry.path();
-            // Ensure it's a directory (could be a stray file)
-            if xml_specific_path.is_dir() {
-                // Now iterate inside the XML-specific directory for mod directories
-                let mod_dir_entries = std::fs::read_dir(&xml_specific_path).map_err(|e| e.to_string())?;
-                for mod_entry in mod_dir_entries.filter_map(Result::ok) {
-                    let mod_path = mod_entry.path();
-                    if mod_path.is_dir() {
-                        if let Some(mod_name) = mod_path.file_name().and_then(|n| n.to_str()) {
-                            // Call the updated function with the XML-specific path
-                            if is_mod_successfully_downloaded(&xml_specific_path, mod_name) {
-                                // Avoid duplicates if a mod exists under multiple XML sources (unlikely but possible)
-                                if !downloaded_mods.contains(&mod_name.to_string()) {
-                                    downloaded_mods.push(mod_name.to_string());
-                                }
-                            }
-                        }
-                    }
-                }
+/// Count the mods of a listing by state, for a profile
+pub fn summarize_mods(
+    categories: &[Category],
+    settings: &settings::Settings,
+    profile_name: &str,
+) -> Result<ModsSummary, String> {
+    let mut summary = ModsSummary::default();
+    for category in categories {
+        let deprecated = category.name == "Deprecated";
+        for mod_entry in &category.mods {
+            summary.total += 1;
+            if deprecated {
+                summary.deprecated += 1;
             }
-        }
-    }
-
-    if !settings.sideload_path.is_empty() {
-        let sideload_dir = PathBuf::from(&settings.sideload_path);
-        if sideload_dir.exists() {
-            let entries = std::fs::read_dir(&sideload_dir).map_err(|e| e.to_string())?;
-            for entry in entries.filter_map(Result::ok) {
-                let path = entry.path();
-                if path.is_dir() {
-                    if let Some(mod_name) = path.file_name().and_then(|n| n.to_str()) {
-                        downloaded_mods.push(mod_name.to_string());
-                    }
+            if mod_entry.new_version.is_some() {
+                summary.updatable += 1;
+            }
+            if let Some(mod_dir) = local_mod_dir(settings, mod_entry.source.as_deref(), &mod_entry.name) {
+                summary.downloaded += 1;
+                if is_mod_enabled(&mod_dir, profile_name)? {
+                    summary.enabled += 1;
                 }
             }
         }
     }
+    Ok(summary)
+}
 
+/// Names of every downloaded and sideloaded mod, from the state database
+#[tauri::command]
+pub async fn get_downloaded_mods() -> Result<Vec<String>, String> {
+    let mut downloaded_mods: Vec<String> = load_installed_mods()?.into_iter().map(|m| m.name).collect();
+    // A mod downloaded from several repositories or download paths is listed once
+    downloaded_mods.dedup();
     Ok(downloaded_mods)
 }
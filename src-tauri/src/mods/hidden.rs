@@ -0,0 +1,73 @@
+use super::mod_utils::get_repo_source;
+use super::types::Category;
+use crate::app_data;
+use crate::settings::Settings;
+use std::collections::{BTreeSet, HashMap};
+
+const HIDDEN_FILE: &str = "hidden_mods.json";
+
+/// Hidden mod names keyed by repository source (repo-hash directory name)
+type HiddenStore = HashMap<String, BTreeSet<String>>;
+
+/// Names of the mods hidden for a repository
+pub fn load_hidden_mods(repo_url: &str) -> Result<BTreeSet<String>, String> {
+    let mut store: HiddenStore = app_data::load_json(HIDDEN_FILE)?;
+    Ok(store.remove(&get_repo_source(repo_url)).unwrap_or_default())
+}
+
+/// Drop hidden mods from a repository listing, so they show up neither in the list nor in
+/// update counts and bulk downloads
+pub fn remove_hidden_mods(categories: &mut Vec<Category>, hidden: &BTreeSet<String>) {
+    for category in categories.iter_mut() {
+        category.mods.retain(|m| !hidden.contains(&m.name));
+    }
+    categories.retain(|cat| !cat.mods.is_empty());
+}
+
+/// Repository URL of a profile, as used for its download directory listing
+fn profile_repo_url(profile_name: &str) -> Result<String, String> {
+    let settings = Settings::load()?;
+    settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .map(|p| p.repo_url.trim_end_matches('/').to_string())
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))
+}
+
+/// Add or remove a mod from the hidden list of a profile's repository
+fn set_hidden(profile_name: &str, mod_name: String, hidden: bool) -> Result<Vec<String>, String> {
+    let source = get_repo_source(&profile_repo_url(profile_name)?);
+    let mut store: HiddenStore = app_data::load_json(HIDDEN_FILE)?;
+    let names = store.entry(source.clone()).or_default();
+    if hidden {
+        names.insert(mod_name);
+    } else {
+        names.remove(&mod_name);
+    }
+    let names: Vec<String> = names.iter().cloned().collect();
+    if names.is_empty() {
+        store.remove(&source);
+    }
+    app_data::save_json(HIDDEN_FILE, &store)?;
+    Ok(names)
+}
+
+/// Hidden mods of a profile's repository, the active profile's when no name is given
+#[tauri::command]
+pub async fn get_hidden_mods(profile_name: Option<String>) -> Result<Vec<String>, String> {
+    let profile_name = Settings::load()?.profile_or_active(profile_name)?;
+    Ok(load_hidden_mods(&profile_repo_url(&profile_name)?)?.into_iter().collect())
+}
+
+/// Hide a mod of the profile's repository, returning the repository's hidden mods
+#[tauri::command]
+pub async fn hide_mod(profile_name: String, mod_name: String) -> Result<Vec<String>, String> {
+    set_hidden(&profile_name, mod_name, true)
+}
+
+/// Show a hidden mod again, returning the repository's remaining hidden mods
+#[tauri::command]
+pub async fn unhide_mod(profile_name: String, mod_name: String) -> Result<Vec<String>, String> {
+    set_hidden(&profile_name, mod_name, false)
+}
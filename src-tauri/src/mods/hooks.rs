@@ -0,0 +1,69 @@
+use crate::settings::{Profile, Settings};
+use std::path::Path;
+use tokio::process::Command;
+use tracing::error;
+
+/// What a hook script is run after, passed to it as the first argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Installed,
+    Enabled,
+}
+
+impl HookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::Installed => "installed",
+            HookEvent::Enabled => "enabled",
+        }
+    }
+}
+
+/// The command running a hook script. PowerShell scripts can't be started on their own
+fn hook_command(script: &Path) -> Command {
+    if script.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ps1")) {
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"]).arg(script);
+        command
+    } else {
+        Command::new(script)
+    }
+}
+
+/// Run a profile's hook script for a mod in the background, as
+/// `<script> <event> <mod name> <mod path> <profile name>`. Profiles without a script are
+/// skipped, and a failing script is only logged
+pub fn run_hook(profile: &Profile, event: HookEvent, mod_name: &str, mod_dir: &Path) {
+    let script = profile.hook_script.trim();
+    if script.is_empty() {
+        return;
+    }
+    let mut command = hook_command(Path::new(script));
+    command.arg(event.as_str()).arg(mod_name).arg(mod_dir).arg(&profile.name);
+
+    let script = script.to_string();
+    let mod_name = mod_name.to_string();
+    tauri::async_runtime::spawn(async move {
+        match command.status().await {
+            Ok(status) if status.success() => {}
+            Ok(status) => error!("Hook script {} for {} exited with {}", script, mod_name, status),
+            Err(e) => error!("Failed to run hook script {} for {}: {}", script, mod_name, e),
+        }
+    });
+}
+
+/// Run the hook of every profile using a repository, after one of its mods was downloaded
+pub fn run_install_hooks(repo_url: &str, mod_dir: &Path) {
+    let settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Failed to load settings for hook scripts: {}", e);
+            return;
+        }
+    };
+    let repo_url = repo_url.trim_end_matches('/');
+    let mod_name = mod_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+    for profile in settings.profiles.iter().filter(|p| p.repo_url.trim_end_matches('/') == repo_url) {
+        run_hook(profile, HookEvent::Installed, &mod_name, mod_dir);
+    }
+}
@@ -0,0 +1,122 @@
+//! `<include>` elements, which compose a repository from several XML files such as one per
+//! maintainer. Every file is cached under its own URL, so the composed listing can be rebuilt
+//! offline
+use super::downloader::ModDownloader;
+use super::types::ModsFile;
+use super::xml_cache::XmlCache;
+use std::collections::{HashSet, VecDeque};
+use tracing::{info, warn};
+
+/// How deep included files may include further files
+const MAX_INCLUDE_DEPTH: usize = 4;
+
+/// Resolve an include against the URL of the file naming it, absolute URLs staying as they are
+pub fn resolve_include_url(base_url: &str, include: &str) -> String {
+    let include = include.trim();
+    reqwest::Url::parse(base_url)
+        .and_then(|base| base.join(include))
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| include.to_string())
+}
+
+/// Files included by `mods_file`, resolved and one level deeper than it
+fn take_includes(mods_file: &mut ModsFile, file_url: &str, depth: usize, pending: &mut VecDeque<(String, usize)>) {
+    for include in std::mem::take(&mut mods_file.includes) {
+        if depth >= MAX_INCLUDE_DEPTH {
+            info!("Not following include {} of {}, includes nest too deep", include.url, file_url);
+            continue;
+        }
+        pending.push_back((resolve_include_url(file_url, &include.url), depth + 1));
+    }
+}
+
+/// Fetch the files a repository includes, and the ones they include in turn, and merge them into
+/// its listing. A file that can't be fetched comes from the cache when it has been seen before
+pub async fn fetch_includes(downloader: &ModDownloader, mods_file: &mut ModsFile, repo_url: &str) {
+    let mut visited = HashSet::from([repo_url.to_string()]);
+    let mut pending = VecDeque::new();
+    take_includes(mods_file, repo_url, 0, &mut pending);
+
+    while let Some((url, depth)) = pending.pop_front() {
+        if !visited.insert(url.clone()) {
+            continue;
+        }
+        let mut included = match downloader.fetch_repo_file(&url).await {
+            Ok((included, _)) => included,
+            Err(e) => {
+                warn!("Failed to load included repository file {}: {}", url, e);
+                match XmlCache::load_cached_file(&url) {
+                    Some(cached) => cached,
+                    None => continue,
+                }
+            }
+        };
+        take_includes(&mut included, &url, depth, &mut pending);
+        mods_file.merge_include(included, &url);
+    }
+}
+
+/// Merge the cached copies of the files a repository includes into its cached listing
+pub fn merge_cached_includes(mods_file: &mut ModsFile, repo_url: &str) {
+    merge_includes_from(mods_file, repo_url, XmlCache::load_cached_file);
+}
+
+/// Merge the files a repository includes into its listing, each one read with `load`
+pub fn merge_includes_from(mods_file: &mut ModsFile, repo_url: &str, load: impl Fn(&str) -> Option<ModsFile>) {
+    let mut visited = HashSet::from([repo_url.to_string()]);
+    let mut pending = VecDeque::new();
+    take_includes(mods_file, repo_url, 0, &mut pending);
+
+    while let Some((url, depth)) = pending.pop_front() {
+        if !visited.insert(url.clone()) {
+            continue;
+        }
+        let Some(mut included) = load(&url) else {
+            info!("No cached copy of included repository file {}", url);
+            continue;
+        };
+        take_includes(&mut included, &url, depth, &mut pending);
+        mods_file.merge_include(included, &url);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mods::parser::ModParser;
+
+    #[test]
+    fn test_merge_included_file() {
+        let mut master = ModParser::parse_mod_list(
+            r#"<mods>
+                <include url="maintainers/viper.xml"/>
+                <category name="Aircraft" sort_order="1">
+                    <mod name="Viper Liveries" version="1.0" url="https://example.com/a.zip"/>
+                </category>
+            </mods>"#,
+        )
+        .unwrap();
+        let included = ModParser::parse_mod_list(
+            r#"<mods>
+                <category name="Aircraft" sort_order="5">
+                    <mod name="Viper Liveries" version="0.9" url="https://example.com/old.zip"/>
+                    <mod name="Viper Cockpit" version="2.0" url="https://example.com/b.zip"/>
+                </category>
+                <category name="Maps" sort_order="2">
+                    <mod name="Caucasus Textures" version="1.0" url="https://example.com/c.zip"/>
+                </category>
+            </mods>"#,
+        )
+        .unwrap();
+
+        let include_url = resolve_include_url("https://example.com/repo/mods.xml", &master.includes[0].url);
+        assert_eq!(include_url, "https://example.com/repo/maintainers/viper.xml");
+        master.merge_include(included, &include_url);
+
+        let aircraft = &master.categories[0];
+        assert_eq!((aircraft.sort_order, aircraft.mods.len()), (1, 2));
+        assert_eq!((aircraft.mods[0].version.as_str(), aircraft.mods[0].origin.as_deref()), ("1.0", None));
+        assert_eq!(aircraft.mods[1].origin.as_deref(), Some(include_url.as_str()));
+        assert_eq!(master.categories[1].name, "Maps");
+    }
+}
@@ -0,0 +1,266 @@
+use super::hidden::load_hidden_mods;
+use super::mod_download::is_mod_successfully_downloaded;
+use super::mod_utils::{get_mod_variant, get_mod_version, get_repo_source, list_mod_dirs, list_source_dirs, SIDELOAD_SOURCE};
+use super::types::ModsFile;
+use super::versions::compare_versions;
+use crate::settings::Settings;
+use crate::state_db::{now_secs, with_db};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+
+/// Set once `installed_mods` has been filled from this session's mod folders
+static SYNCED: AtomicBool = AtomicBool::new(false);
+
+/// A downloaded or sideloaded mod, as last found on disk
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct InstalledMod {
+    pub name: String,
+    /// Repo-hash directory name, or "sideload"
+    pub source: String,
+    /// Repository of the profile downloading into `source`, unknown once no profile uses it
+    pub repo_url: Option<String>,
+    pub version: Option<String>,
+    pub variant: Option<String>,
+    pub path: String,
+}
+
+/// A downloaded mod with a newer version in its repository
+#[derive(Debug, Serialize, Clone)]
+pub struct RepoModUpdate {
+    pub repo_url: String,
+    pub source: String,
+    pub name: String,
+    pub current_version: String,
+    pub new_version: String,
+}
+
+/// Every complete mod folder in the download and sideload directories
+fn scan_installed_mods(settings: &Settings) -> Vec<InstalledMod> {
+    let repo_urls: HashMap<String, String> = settings
+        .profiles
+        .iter()
+        .map(|profile| profile.repo_url.trim_end_matches('/').to_string())
+        .map(|url| (get_repo_source(&url), url))
+        .collect();
+
+    let mut installed = Vec::new();
+    let mut add = |source: &str, repo_url: Option<&String>, mod_dir: &Path| {
+        installed.push(InstalledMod {
+            name: mod_dir.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            source: source.to_string(),
+            repo_url: repo_url.cloned(),
+            version: get_mod_version(mod_dir).ok(),
+            variant: get_mod_variant(mod_dir),
+            path: mod_dir.to_string_lossy().to_string(),
+        });
+    };
+    // Folders being replaced by an update are picked up once it is done
+    let source_dirs = list_source_dirs(settings)
+        .into_iter()
+        .filter(|(_, dir)| dir.extension().is_none_or(|ext| ext != "updating"));
+    for (source, dir) in source_dirs {
+        for mod_dir in list_mod_dirs(&dir) {
+            let name = mod_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if is_mod_successfully_downloaded(&dir, &name) {
+                add(&source, repo_urls.get(&source), &mod_dir);
+            }
+        }
+    }
+    if !settings.sideload_path.is_empty() {
+        for mod_dir in list_mod_dirs(Path::new(&settings.sideload_path)) {
+            add(SIDELOAD_SOURCE, None, &mod_dir);
+        }
+    }
+    installed
+}
+
+fn replace_installed_mods(conn: &mut Connection, installed: &[InstalledMod]) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM installed_mods", [])?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT OR REPLACE INTO installed_mods (path, source, name, repo_url, version, variant)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        for m in installed {
+            insert.execute(params![m.path, m.source, m.name, m.repo_url, m.version, m.variant])?;
+        }
+    }
+    tx.commit()
+}
+
+/// Record the mods on disk now in place of the previous ones. The download watcher calls this
+/// when mod folders change, so listing mods doesn't have to walk the directories
+pub fn sync_installed_mods(settings: &Settings) -> Result<(), String> {
+    let installed = scan_installed_mods(settings);
+    with_db(|db| replace_installed_mods(db, &installed))?;
+    SYNCED.store(true, AtomicOrdering::Relaxed);
+    Ok(())
+}
+
+/// Record a mod folder just downloaded, ahead of the watcher noticing it
+pub fn record_installed_mod(repo_url: &str, mod_dir: &Path) -> Result<(), String> {
+    let installed = InstalledMod {
+        name: mod_dir.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        source: get_repo_source(repo_url),
+        repo_url: Some(repo_url.to_string()),
+        version: get_mod_version(mod_dir).ok(),
+        variant: get_mod_variant(mod_dir),
+        path: mod_dir.to_string_lossy().to_string(),
+    };
+    with_db(|db| {
+        db.execute(
+            "INSERT OR REPLACE INTO installed_mods (path, source, name, repo_url, version, variant)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                installed.path,
+                installed.source,
+                installed.name,
+                installed.repo_url,
+                installed.version,
+                installed.variant
+            ],
+        )
+    })
+    .map(|_| ())
+}
+
+/// Drop a deleted mod folder from the record
+pub fn forget_installed_mod(mod_dir: &Path) -> Result<(), String> {
+    let path = mod_dir.to_string_lossy();
+    with_db(|db| {
+        db.execute("DELETE FROM installed_mods WHERE path = ?1", [&path])?;
+        db.execute("DELETE FROM mod_archives WHERE path = ?1", [&path])
+    })
+    .map(|_| ())
+}
+
+/// Fill `installed_mods` from disk if the watcher hasn't got to it yet this session
+fn ensure_synced() -> Result<(), String> {
+    if !SYNCED.load(AtomicOrdering::Relaxed) {
+        sync_installed_mods(&Settings::load()?)?;
+    }
+    Ok(())
+}
+
+/// Every downloaded and sideloaded mod, as recorded
+pub fn load_installed_mods() -> Result<Vec<InstalledMod>, String> {
+    ensure_synced()?;
+    with_db(|db| {
+        let mut select = db.prepare(
+            "SELECT name, source, repo_url, version, variant, path FROM installed_mods ORDER BY name, source",
+        )?;
+        let rows = select.query_map([], |row| {
+            Ok(InstalledMod {
+                name: row.get(0)?,
+                source: row.get(1)?,
+                repo_url: row.get(2)?,
+                version: row.get(3)?,
+                variant: row.get(4)?,
+                path: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+/// Remember the versions a repository lists, in place of those from its previous fetch
+pub fn record_repo_versions(repo_url: &str, mods_file: &ModsFile) -> Result<(), String> {
+    with_db(|db| {
+        let tx = db.transaction()?;
+        tx.execute("DELETE FROM repo_mods WHERE repo_url = ?1", [repo_url])?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR REPLACE INTO repo_mods (repo_url, name, version, checked_at) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            let checked_at = now_secs();
+            for m in mods_file.categories.iter().flat_map(|cat| cat.mods.iter()) {
+                insert.execute(params![repo_url, m.name, m.version, checked_at])?;
+            }
+        }
+        tx.commit()
+    })
+}
+
+/// Downloaded mods whose repository listed a newer version when it was last fetched
+fn newer_in_repo(conn: &Connection) -> rusqlite::Result<Vec<RepoModUpdate>> {
+    let mut select = conn.prepare(
+        "SELECT i.repo_url, i.source, i.name, i.version, r.version
+         FROM installed_mods i JOIN repo_mods r ON r.repo_url = i.repo_url AND r.name = i.name
+         WHERE i.version IS NOT NULL
+         ORDER BY i.repo_url, i.name",
+    )?;
+    let rows = select.query_map([], |row| {
+        Ok(RepoModUpdate {
+            repo_url: row.get(0)?,
+            source: row.get(1)?,
+            name: row.get(2)?,
+            current_version: row.get(3)?,
+            new_version: row.get(4)?,
+        })
+    })?;
+    // Versions don't order as text, they are compared here
+    let mut updates = Vec::new();
+    for update in rows {
+        let update = update?;
+        if compare_versions(&update.new_version, &update.current_version) == Ordering::Greater {
+            updates.push(update);
+        }
+    }
+    Ok(updates)
+}
+
+/// Mod updates across every repository, as of each one's last fetch. Hidden mods are left out
+/// and a mod shared by several download directories is listed once
+#[tauri::command]
+pub async fn get_all_updates() -> Result<Vec<RepoModUpdate>, String> {
+    ensure_synced()?;
+    let mut updates = with_db(|db| newer_in_repo(db))?;
+
+    let mut hidden = HashMap::new();
+    let mut seen = BTreeSet::new();
+    updates.retain(|update| {
+        let hidden = hidden
+            .entry(update.repo_url.clone())
+            .or_insert_with(|| load_hidden_mods(&update.repo_url).unwrap_or_default());
+        !hidden.contains(&update.name) && seen.insert((update.repo_url.clone(), update.name.clone()))
+    });
+    Ok(updates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_db::migrate;
+
+    #[test]
+    fn test_newer_in_repo() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        let installed = |name: &str, version: &str| InstalledMod {
+            name: name.to_string(),
+            source: "a1b2c3".to_string(),
+            repo_url: Some("https://example.com/repo.xml".to_string()),
+            version: Some(version.to_string()),
+            variant: None,
+            path: format!("/downloads/a1b2c3/{}", name),
+        };
+        replace_installed_mods(&mut conn, &[installed("ModA", "1.9"), installed("ModB", "2.0"), installed("ModC", "1.0")])
+            .unwrap();
+        for (name, version) in [("ModA", "1.10"), ("ModB", "1.0"), ("ModC", "1.0")] {
+            conn.execute(
+                "INSERT INTO repo_mods (repo_url, name, version, checked_at) VALUES ('https://example.com/repo.xml', ?1, ?2, 0)",
+                [name, version],
+            )
+            .unwrap();
+        }
+
+        let updates = newer_in_repo(&conn).unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!((updates[0].name.as_str(), updates[0].new_version.as_str()), ("ModA", "1.10"));
+    }
+}
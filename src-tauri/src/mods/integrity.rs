@@ -0,0 +1,143 @@
+use super::download_queue::get_queue;
+use super::mod_list::load_repo_mods;
+use super::mod_utils::{
+    get_mod_version, get_repo_source, list_mod_dirs, list_source_dirs, verify_mod_structure, SIDELOAD_SOURCE,
+};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// What to do about a broken download
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestedFix {
+    /// The repository still has the mod, download it again
+    Redownload,
+    /// Nothing to download it from any more, remove it
+    Delete,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CorruptDownload {
+    pub mod_name: String,
+    /// Repo-hash directory name, or "sideload"
+    pub source: String,
+    pub path: PathBuf,
+    pub issues: Vec<String>,
+    pub fix: SuggestedFix,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct DownloadCorruptionReport {
+    pub checked: usize,
+    pub problems: Vec<CorruptDownload>,
+}
+
+/// Problems with one extracted mod, empty if it looks complete
+fn check_mod_dir(mod_dir: &Path) -> Vec<String> {
+    let mut issues = Vec::new();
+    let name = mod_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    // The zip is removed once extraction finishes
+    if mod_dir.with_file_name(format!("{}.zip", name)).exists() {
+        issues.push("Extraction did not finish, the downloaded zip is still next to the mod".to_string());
+    }
+    if let Err(e) = verify_mod_structure(mod_dir) {
+        issues.push(e.to_string());
+    } else if std::fs::read_dir(mod_dir.join(&name)).is_ok_and(|mut entries| entries.next().is_none()) {
+        issues.push("Main subdirectory is empty".to_string());
+    }
+    if mod_dir.join("VERSION.txt").exists() {
+        match get_mod_version(mod_dir) {
+            Ok(version) if version.is_empty() => issues.push("VERSION.txt is empty".to_string()),
+            Ok(_) => {}
+            Err(e) => issues.push(format!("Failed to read VERSION.txt: {}", e)),
+        }
+    }
+    issues
+}
+
+/// Names of the mods each configured repository still offers, keyed by repo-hash directory name
+async fn available_mods(settings: &Settings) -> HashMap<String, HashSet<String>> {
+    let mut available = HashMap::new();
+    for profile in &settings.profiles {
+        let repo_url = profile.repo_url.trim_end_matches('/');
+        let source = get_repo_source(repo_url);
+        if available.contains_key(&source) {
+            continue;
+        }
+        let names = match load_repo_mods(repo_url).await {
+            Ok(mods_file) => mods_file
+                .categories
+                .into_iter()
+                .flat_map(|cat| cat.mods.into_iter().map(|m| m.name))
+                .collect(),
+            Err(e) => {
+                warn!("Could not load mods of {} for verification: {}", repo_url, e);
+                HashSet::new()
+            }
+        };
+        available.insert(source, names);
+    }
+    available
+}
+
+/// Check every extracted mod in the download and sideload directories for missing files, empty
+/// VERSION.txt and interrupted extractions, suggesting a fix for each broken one
+#[tauri::command]
+pub async fn verify_all_downloads() -> Result<DownloadCorruptionReport, String> {
+    let settings = Settings::load()?;
+    let active: Vec<String> = get_queue()
+        .active_downloads()
+        .await
+        .iter()
+        .map(|filename| filename.trim_end_matches(".zip").to_string())
+        .collect();
+    let available = available_mods(&settings).await;
+    let mut report = DownloadCorruptionReport::default();
+
+    let mut sources: Vec<(String, PathBuf)> = list_source_dirs(&settings)
+        .into_iter()
+        // Previous versions kept aside by an interrupted update aren't mods
+        .filter(|(_, dir)| dir.extension().is_none_or(|ext| ext != "updating"))
+        .collect();
+    if !settings.sideload_path.is_empty() {
+        sources.push((SIDELOAD_SOURCE.to_string(), PathBuf::from(&settings.sideload_path)));
+    }
+
+    for (source, dir) in sources {
+        for mod_dir in list_mod_dirs(&dir) {
+            let mod_name = mod_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if source != SIDELOAD_SOURCE && active.contains(&mod_name) {
+                continue;
+            }
+            report.checked += 1;
+
+            let issues = check_mod_dir(&mod_dir);
+            if issues.is_empty() {
+                continue;
+            }
+            let fix = if available.get(&source).is_some_and(|names| names.contains(&mod_name)) {
+                SuggestedFix::Redownload
+            } else {
+                SuggestedFix::Delete
+            };
+            report.problems.push(CorruptDownload {
+                mod_name,
+                source: source.clone(),
+                path: mod_dir,
+                issues,
+                fix,
+            });
+        }
+    }
+
+    info!(
+        "Verified {} downloaded mods, {} with problems",
+        report.checked,
+        report.problems.len()
+    );
+    Ok(report)
+}
@@ -0,0 +1,70 @@
+use super::types::{Category, RepoChange};
+use crate::app_data;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+const LAST_SEEN_FILE: &str = "last_seen.json";
+
+/// Repository version of every mod, keyed by mod name
+type RepoState = HashMap<String, String>;
+
+/// Repository state each profile had when this session first listed it, `None` for a profile
+/// listed for the first time. Kept for the whole session so the flags don't vanish on refresh.
+static SESSION_BASELINE: OnceLock<Mutex<HashMap<String, Option<RepoState>>>> = OnceLock::new();
+
+/// Flag mods added or updated in the repository since the previous session and remember the
+/// current repository state for the next one. Expects only the repository's own categories.
+pub fn mark_new_since_last_visit(profile_name: &str, categories: &mut [Category]) {
+    let current: RepoState = categories
+        .iter()
+        .flat_map(|cat| cat.mods.iter())
+        .map(|m| (m.name.clone(), m.new_version.clone().unwrap_or_else(|| m.version.clone())))
+        .collect();
+
+    let mut stored: HashMap<String, RepoState> = match app_data::load_json(LAST_SEEN_FILE) {
+        Ok(stored) => stored,
+        Err(e) => {
+            warn!("Failed to load last seen repository state: {}", e);
+            HashMap::new()
+        }
+    };
+
+    let mut baselines = SESSION_BASELINE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let baseline = baselines
+        .entry(profile_name.to_string())
+        .or_insert_with(|| stored.get(profile_name).cloned());
+
+    // On a first visit everything would be new, which tells the user nothing
+    if let Some(baseline) = baseline {
+        for mod_entry in categories.iter_mut().flat_map(|cat| cat.mods.iter_mut()) {
+            mod_entry.since_last_visit = match (baseline.get(&mod_entry.name), current.get(&mod_entry.name)) {
+                (None, _) => Some(RepoChange::Added),
+                (Some(seen), Some(version)) if seen != version => Some(RepoChange::Updated),
+                _ => None,
+            };
+        }
+    }
+
+    // An empty listing means the repository couldn't be loaded, not that every mod was removed
+    if current.is_empty() || stored.get(profile_name) == Some(&current) {
+        return;
+    }
+    stored.insert(profile_name.to_string(), current);
+    if let Err(e) = app_data::save_json(LAST_SEEN_FILE, &stored) {
+        warn!("Failed to save last seen repository state: {}", e);
+    }
+}
+
+/// Move a profile's last seen repository state to its new name
+pub fn rename_profile_last_seen(old_name: &str, new_name: &str) -> Result<(), String> {
+    let mut stored: HashMap<String, RepoState> = app_data::load_json(LAST_SEEN_FILE)?;
+    if let Some(state) = stored.remove(old_name) {
+        stored.insert(new_name.to_string(), state);
+        app_data::save_json(LAST_SEEN_FILE, &stored)?;
+    }
+    Ok(())
+}
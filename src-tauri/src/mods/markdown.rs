@@ -0,0 +1,213 @@
+//! Markdown subset for mod descriptions, rendered to HTML that is safe to insert as is: all of
+//! the source is escaped first, so the only markup in the output is the one added here. Covers
+//! headings, paragraphs, lists, fenced code, code spans, emphasis and http(s)/mailto links
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Links may only leave for the web or a mail client, never run script
+fn is_safe_url(url: &str) -> bool {
+    ["https://", "http://", "mailto:"].iter().any(|scheme| url.starts_with(scheme))
+}
+
+/// `[text](url)` at the start of `rest`, returning the text, the url and the length consumed
+fn parse_link(rest: &str) -> Option<(&str, &str, usize)> {
+    let text_end = rest.find("](")?;
+    let url_end = rest[text_end + 2..].find(')')? + text_end + 2;
+    let (text, url) = (&rest[1..text_end], &rest[text_end + 2..url_end]);
+    (!text.is_empty() && !url.contains(char::is_whitespace)).then_some((text, url, url_end + 1))
+}
+
+/// Render inline markup of already escaped text
+fn render_escaped_inline(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut previous: Option<char> = None;
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                html.push_str(&format!("<code>{}</code>", &rest[1..end + 1]));
+                rest = &rest[end + 2..];
+                previous = Some('`');
+                continue;
+            }
+        }
+        if let Some(marker) = ["**", "__"].into_iter().find(|m| rest.starts_with(m)) {
+            if let Some(end) = rest[2..].find(marker).filter(|&end| end > 0) {
+                html.push_str(&format!("<strong>{}</strong>", render_escaped_inline(&rest[2..end + 2])));
+                rest = &rest[end + 4..];
+                previous = Some(c);
+                continue;
+            }
+        }
+        // Underscores inside words, as in file_names, aren't emphasis
+        let opens_emphasis = c == '*' || (c == '_' && !previous.is_some_and(char::is_alphanumeric));
+        if opens_emphasis {
+            if let Some(end) = rest[1..].find(c).filter(|&end| end > 0) {
+                html.push_str(&format!("<em>{}</em>", render_escaped_inline(&rest[1..end + 1])));
+                rest = &rest[end + 2..];
+                previous = Some(c);
+                continue;
+            }
+        }
+        if c == '[' {
+            if let Some((label, url, consumed)) = parse_link(rest) {
+                if is_safe_url(url) {
+                    html.push_str(&format!("<a href=\"{}\">{}</a>", url, render_escaped_inline(label)));
+                } else {
+                    html.push_str(&render_escaped_inline(label));
+                }
+                rest = &rest[consumed..];
+                previous = Some(']');
+                continue;
+            }
+        }
+        html.push(c);
+        rest = &rest[c.len_utf8()..];
+        previous = Some(c);
+    }
+    html
+}
+
+fn render_inline(text: &str) -> String {
+    render_escaped_inline(&escape_html(text))
+}
+
+/// `# Heading` up to six levels deep
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then_some((level, text.trim()))
+}
+
+/// `- item`, `* item`, `+ item` or `1. item`, with the list element it belongs in
+fn list_item(line: &str) -> Option<(&'static str, &str)> {
+    if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|marker| line.strip_prefix(marker)) {
+        return Some(("ul", item));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let item = line[digits..].strip_prefix(". ")?;
+    (digits > 0).then_some(("ol", item))
+}
+
+#[derive(Default)]
+struct Renderer {
+    html: String,
+    paragraph: Vec<String>,
+    list: Option<(&'static str, Vec<String>)>,
+}
+
+impl Renderer {
+    fn flush_paragraph(&mut self) {
+        if !self.paragraph.is_empty() {
+            // Single line breaks are kept, plain text READMEs rely on them
+            self.html.push_str(&format!("<p>{}</p>", self.paragraph.join("<br>")));
+            self.paragraph.clear();
+        }
+    }
+
+    fn flush_list(&mut self) {
+        if let Some((tag, items)) = self.list.take() {
+            let items: String = items.iter().map(|item| format!("<li>{}</li>", item)).collect();
+            self.html.push_str(&format!("<{0}>{1}</{0}>", tag, items));
+        }
+    }
+
+    fn flush(&mut self) {
+        self.flush_paragraph();
+        self.flush_list();
+    }
+
+    fn code_block(&mut self, lines: &[&str]) {
+        self.html.push_str(&format!("<pre><code>{}</code></pre>", escape_html(&lines.join("\n"))));
+    }
+}
+
+/// Render a Markdown description to sanitized HTML
+pub fn render_markdown(source: &str) -> String {
+    let mut renderer = Renderer::default();
+    let mut code: Option<Vec<&str>> = None;
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if let Some(lines) = &mut code {
+            if trimmed.starts_with("```") {
+                renderer.code_block(lines);
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("```") {
+            renderer.flush();
+            code = Some(Vec::new());
+        } else if trimmed.is_empty() {
+            renderer.flush();
+        } else if let Some((level, text)) = heading(trimmed) {
+            renderer.flush();
+            renderer.html.push_str(&format!("<h{0}>{1}</h{0}>", level, render_inline(text)));
+        } else if let Some((tag, item)) = list_item(trimmed) {
+            renderer.flush_paragraph();
+            match &mut renderer.list {
+                Some((list_tag, items)) if *list_tag == tag => items.push(render_inline(item)),
+                _ => {
+                    renderer.flush_list();
+                    renderer.list = Some((tag, vec![render_inline(item)]));
+                }
+            }
+        } else {
+            renderer.flush_list();
+            renderer.paragraph.push(render_inline(trimmed));
+        }
+    }
+
+    // An unclosed fence runs to the end of the description
+    if let Some(lines) = code {
+        renderer.code_block(&lines);
+    }
+    renderer.flush();
+    renderer.html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_markdown_blocks_and_inline() {
+        let html = render_markdown("# Viper\nSquadron **liveries**\nfor the *F-16*\n\n- one\n- `two`\n\n1. first");
+        assert_eq!(
+            html,
+            "<h1>Viper</h1><p>Squadron <strong>liveries</strong><br>for the <em>F-16</em></p>\
+             <ul><li>one</li><li><code>two</code></li></ul><ol><li>first</li></ol>"
+        );
+        assert_eq!(render_markdown("copy file_name_here"), "<p>copy file_name_here</p>");
+    }
+
+    #[test]
+    fn test_render_markdown_escapes_html_and_unsafe_links() {
+        assert_eq!(
+            render_markdown("<script>alert(1)</script> [x](javascript:void)"),
+            "<p>&lt;script&gt;alert(1)&lt;/script&gt; x</p>"
+        );
+        assert_eq!(
+            render_markdown("[site](https://example.com/?a=1&b=\"2\")"),
+            "<p><a href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\">site</a></p>"
+        );
+        assert_eq!(render_markdown("```\n<b>\n```"), "<pre><code>&lt;b&gt;</code></pre>");
+    }
+}
@@ -0,0 +1,207 @@
+use super::enable_state::is_enabled_anywhere;
+use super::mod_enablement::{process_mod_roots, TargetDirs};
+use super::mod_management::ModOperationOutcome;
+use super::mod_utils::{get_mod_version, get_repo_download_dir, list_enabled_mod_dirs, list_mod_dirs};
+use super::types::{Mod, ModsFile};
+use crate::settings::{Profile, Settings};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+/// A mod enabled for a profile, identified by its path relative to the download directory
+struct EnabledMod {
+    profile: Profile,
+    relative: PathBuf,
+    mod_name: String,
+    version: String,
+}
+
+/// Enabled mods of every profile that live in the download directory, sideloaded mods don't move
+fn collect_enabled_mods(settings: &Settings, download_path: &Path) -> Result<Vec<EnabledMod>, String> {
+    let mut enabled = Vec::new();
+    for repo_dir in list_mod_dirs(download_path) {
+        for profile in &settings.profiles {
+            for mod_dir in list_enabled_mod_dirs(&repo_dir, &profile.name)? {
+                let Some(mod_name) = mod_dir.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                    continue;
+                };
+                enabled.push(EnabledMod {
+                    profile: profile.clone(),
+                    relative: mod_dir.strip_prefix(download_path).unwrap_or(&mod_dir).to_path_buf(),
+                    mod_name,
+                    version: get_mod_version(&mod_dir).unwrap_or_default(),
+                });
+            }
+        }
+    }
+    Ok(enabled)
+}
+
+/// Move a mod downloaded before the repository gave it an `id` into the directory named after
+/// the id, so it isn't taken for a deprecated mod. Enabled mods are linked into DCS by path and
+/// stay where they are until disabled
+fn adopt_renamed_mod_dir(repo_dir: &Path, mod_entry: &Mod) {
+    let Some(display_name) = mod_entry.display_name.as_deref() else {
+        return;
+    };
+    if display_name.contains(['/', '\\', ':']) {
+        return;
+    }
+    let (old_dir, new_dir) = (repo_dir.join(display_name), repo_dir.join(&mod_entry.name));
+    if !old_dir.is_dir() || new_dir.exists() {
+        return;
+    }
+    match Settings::load().and_then(|settings| is_enabled_anywhere(&settings, &old_dir)) {
+        Ok(false) => {}
+        Ok(true) => return info!("{} is enabled, disable it to move it to {}", old_dir.display(), new_dir.display()),
+        Err(e) => return warn!("Not moving {}: {}", old_dir.display(), e),
+    }
+    match std::fs::rename(&old_dir, &new_dir) {
+        Ok(()) => info!("Moved {} to {}", old_dir.display(), new_dir.display()),
+        Err(e) => warn!("Failed to move {} to {}: {}", old_dir.display(), new_dir.display(), e),
+    }
+}
+
+/// Move the downloaded mods of a freshly fetched repository into the directories named after
+/// their ids, before the listing is compared against the downloads
+pub fn adopt_renamed_mod_dirs(mods_file: &ModsFile, download_path: &Path, repo_url: &str) {
+    let repo_dir = get_repo_download_dir(download_path, repo_url);
+    for mod_entry in mods_file.categories.iter().flat_map(|category| &category.mods) {
+        adopt_renamed_mod_dir(&repo_dir, mod_entry);
+    }
+}
+
+/// Copy a directory tree, for moves across drives where a rename isn't possible
+pub fn copy_dir_all(source: &Path, dest: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dest)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let dest_path = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &dest_path)?;
+        } else {
+            std::fs::copy(entry.path(), &dest_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Move every entry of the old download directory into the new one
+fn move_download_contents(old_path: &Path, new_path: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(new_path)?;
+    for entry in std::fs::read_dir(old_path)? {
+        let entry = entry?;
+        let dest = new_path.join(entry.file_name());
+        if std::fs::rename(entry.path(), &dest).is_err() {
+            // Different drive, fall back to copying
+            if entry.file_type()?.is_dir() {
+                copy_dir_all(&entry.path(), &dest)?;
+                std::fs::remove_dir_all(entry.path())?;
+            } else {
+                std::fs::copy(entry.path(), &dest)?;
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Enable every mod again from `download_path`, reporting each one
+async fn enable_all(
+    app_handle: &AppHandle,
+    enabled: &[EnabledMod],
+    download_path: &Path,
+) -> Vec<ModOperationOutcome> {
+    let mut outcomes = Vec::new();
+    for entry in enabled {
+        let mod_dir = download_path.join(&entry.relative);
+        let targets = TargetDirs::from_profile(&entry.profile);
+        let result = process_mod_roots(
+            &mod_dir,
+            &targets,
+            &entry.mod_name,
+            &entry.version,
+            &entry.profile.name,
+            false,
+            app_handle,
+        )
+        .await;
+        outcomes.push(ModOperationOutcome {
+            mod_name: entry.mod_name.clone(),
+            success: result.is_ok(),
+            message: result.err().map(|e| format!("{} ({})", e, entry.profile.name)),
+        });
+    }
+    outcomes
+}
+
+/// Move the download directory to a new location, taking enabled mods out of DCS before the
+/// move and enabling them again from the new location so no symlink is left dangling
+#[tauri::command]
+pub async fn migrate_download_path(
+    app_handle: AppHandle,
+    new_path: String,
+) -> Result<Vec<ModOperationOutcome>, String> {
+    let mut settings = Settings::load()?;
+    let old_path = PathBuf::from(&settings.download_path);
+    let new_path_buf = PathBuf::from(&new_path);
+
+    if new_path.trim().is_empty() {
+        return Err("New download path is empty".to_string());
+    }
+    if new_path_buf == old_path {
+        return Ok(Vec::new());
+    }
+    if new_path_buf.starts_with(&old_path) || old_path.starts_with(&new_path_buf) {
+        return Err("The new download path can't be inside the current one or contain it".to_string());
+    }
+    if std::fs::read_dir(&new_path_buf).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(format!("{} is not empty", new_path_buf.display()));
+    }
+
+    let enabled = collect_enabled_mods(&settings, &old_path)?;
+    info!(
+        "Migrating downloads from {} to {}, {} enabled mods",
+        old_path.display(),
+        new_path_buf.display(),
+        enabled.len()
+    );
+
+    // Take the links into the old location out of DCS, the ENABLED files move with the mods
+    for entry in &enabled {
+        let targets = TargetDirs::from_profile(&entry.profile);
+        let result = process_mod_roots(
+            &old_path.join(&entry.relative),
+            &targets,
+            &entry.mod_name,
+            &entry.version,
+            &entry.profile.name,
+            true,
+            &app_handle,
+        )
+        .await;
+        if let Err(e) = result {
+            enable_all(&app_handle, &enabled, &old_path).await;
+            return Err(format!("Failed to disable {} before moving: {}", entry.mod_name, e));
+        }
+    }
+
+    if old_path.is_dir() {
+        if let Err(e) = move_download_contents(&old_path, &new_path_buf) {
+            // Put back what already moved so everything is in one place again
+            warn!("Failed to move download directory: {}", e);
+            if let Err(restore_err) = move_download_contents(&new_path_buf, &old_path) {
+                warn!("Failed to move downloads back: {}", restore_err);
+            }
+            enable_all(&app_handle, &enabled, &old_path).await;
+            return Err(format!("Failed to move download directory: {}", e));
+        }
+        // Leave the old directory behind only if something unexpected is still in it
+        let _ = std::fs::remove_dir(&old_path);
+    }
+
+    settings.download_path = new_path;
+    settings.save()?;
+
+    Ok(enable_all(&app_handle, &enabled, &new_path_buf).await)
+}
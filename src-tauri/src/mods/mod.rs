@@ -1,20 +1,91 @@
+pub mod asset_cache;
+pub mod auto_refresh;
+pub mod cache_limit;
+pub mod download_history;
 pub mod downloader;
+pub mod enable_lock;
+pub mod enable_state;
 pub mod download_queue;
 pub mod extraction;
+pub mod favorites;
+pub mod categories;
+pub mod changelog;
 pub mod handlers;
+pub mod hidden;
+pub mod hooks;
+pub mod includes;
+pub mod installed;
+pub mod integrity;
+pub mod last_seen;
+pub mod notes;
+pub mod backups;
+pub mod markdown;
 pub mod mod_download;
 pub mod mod_enablement;
+pub mod migration;
+pub mod mod_list;
 pub mod mod_management;
 pub mod mod_utils;
+pub mod parsed_cache;
 pub mod parser;
+pub mod patch_conflicts;
+pub mod prefetch;
+pub mod presets;
+pub mod profile_rename;
 pub mod progress;
+pub mod repo_history;
+pub mod repo_validation;
+pub mod search;
+pub mod self_check;
 pub mod sideload;
+pub mod sorting;
+pub mod staging;
+pub mod storage;
+pub mod throttle;
+pub mod dcs_version;
+pub mod dependencies;
+pub mod dedupe;
 pub mod deprecated;
+pub mod diagnostics;
+pub mod disk_space;
 pub mod types;
+pub mod url_install;
+pub mod variants;
+pub mod versions;
+pub mod watcher;
 pub mod xml_cache;
 
 // Re-export functions used by main.rs
-pub use handlers::{get_mods, get_downloaded_mods};
-pub use mod_management::{enable_mod, disable_mod, update_mod, delete_mod};
+pub use handlers::{get_mods, get_downloaded_mods, check_updates};
+pub use mod_management::{
+    enable_mod, disable_mod, update_mod, delete_mod, verify_mod, reapply_enabled_mods, get_mod_locks,
+    force_unlock_mod, force_disable_mod, open_mod_folder, set_mods_enabled,
+};
 pub use mod_download::download_mod;
-pub use download_queue::{queue_download, cancel_download};
\ No newline at end of file
+pub use download_queue::{queue_download, cancel_download, get_queue_status, set_queue_paused};
+pub use download_history::{get_download_history, clear_download_history};
+pub use installed::get_all_updates;
+pub use presets::{get_presets, save_preset, delete_preset, apply_preset};
+pub use profile_rename::rename_profile;
+pub use mod_list::{export_mod_list, import_mod_list};
+pub use patch_conflicts::get_patch_conflicts;
+pub use backups::{list_backups, restore_backup};
+pub use search::search_mods;
+pub use deprecated::{cleanup_deprecated_mods, migrate_deprecated_mod};
+pub use storage::{get_storage_usage, cleanup_orphaned_files};
+pub use migration::migrate_download_path;
+pub use favorites::{get_favorite_mods, set_mod_favorite};
+pub use hidden::{get_hidden_mods, hide_mod, unhide_mod};
+pub use notes::{get_mod_note, set_mod_note};
+pub use variants::set_mod_variant;
+pub use changelog::get_mod_changelog;
+pub use integrity::verify_all_downloads;
+pub use url_install::download_from_url;
+pub use diagnostics::export_diagnostics;
+pub use categories::{get_mod_categories, get_category_mods};
+pub use repo_validation::validate_repo;
+pub use self_check::run_self_check;
+pub use xml_cache::clear_xml_cache;
+pub use repo_history::{get_repo_snapshots, diff_repo_versions};
+pub use asset_cache::{get_mod_image, get_asset_cache_usage, purge_asset_cache};
+pub use cache_limit::get_cache_usage;
\ No newline at end of file
@@ -1,10 +1,30 @@
+use super::dedupe::{hash_archive, share_identical_mod};
 use super::downloader::ModDownloader;
-use super::extraction::extract_zip;
+use super::extraction::extract_zip_with_cancellation;
+use super::mod_utils::get_repo_download_dir;
+use super::staging::{move_into_place, staging_paths, StagingPaths};
 use crate::settings;
-use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 use tauri::Emitter;
 use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+/// Link a freshly extracted mod's files to an identical earlier download. Failing to only costs
+/// disk space, so errors are logged
+async fn share_with_identical_downloads(
+    mod_dir: PathBuf,
+    archive_hash: Result<std::io::Result<String>, tokio::task::JoinError>,
+) {
+    let archive_hash = match archive_hash {
+        Ok(Ok(hash)) => hash,
+        Ok(Err(e)) => return error!("Failed to hash the archive of {}: {}", mod_dir.display(), e),
+        Err(e) => return error!("Failed to hash the archive of {}: {}", mod_dir.display(), e),
+    };
+    let shared = tokio::task::spawn_blocking(move || share_identical_mod(&mod_dir, &archive_hash)).await;
+    if let Ok(Err(e)) = shared {
+        error!("Failed to look for identical downloads: {}", e);
+    }
+}
 
 /// Checks if a mod is successfully downloaded and extracted within a specific XML source directory.
 ///
@@ -34,12 +54,13 @@ pub fn is_mod_successfully_downloaded(xml_specific_path: &Path, mod_name: &str)
 // Remove existing mod directory before downloading a new one
 fn clean_existing_mod(extract_dir: &Path) -> Result<(), String> {
     if extract_dir.exists() {
-        println!("Removing existing mod directory: {}", extract_dir.display());
+        info!("Removing existing mod directory: {}", extract_dir.display());
         if let Err(e) = std::fs::remove_dir_all(extract_dir) {
-            eprintln!("Failed to remove existing mod directory: {}", e);
+            error!("Failed to remove existing mod directory: {}", e);
             return Err(e.to_string());
         }
     }
+
     Ok(())
 }
 
@@ -49,240 +70,20 @@ pub async fn download_mod(
     url: String,
     filename: String,
     repo_url: String, // Added repo_url parameter
+    size: Option<u64>,
+    profile_name: Option<String>,
 ) -> Result<(), String> {
-    println!("Starting mod download: {} from {} (Repo: {})", filename, url, repo_url);
-
-    let settings = settings::Settings::load()?;
-    let base_downloads_dir = PathBuf::from(&settings.download_path);
-
-    // Generate a unique subdirectory name from the repo_url hash
-    let mut hasher = Sha256::new();
-    hasher.update(repo_url.as_bytes());
-    let hash_result = hasher.finalize();
-    let repo_hash = format!("{:x}", hash_result);
-    let repo_hash = &repo_hash[..6]; // Shrink the hash to 6 characters
-    let xml_specific_path = base_downloads_dir.join(repo_hash);
-
-    // Create the XML-specific directory if it doesn't exist
-    if !xml_specific_path.exists() {
-        println!("Creating XML-specific download directory: {}", xml_specific_path.display());
-        std::fs::create_dir_all(&xml_specific_path)
-            .map_err(|e| format!("Failed to create XML-specific download directory: {}", e))?;
-    } else {
-         println!("Using existing XML-specific download directory: {}", xml_specific_path.display());
-    }
-
-    let mod_name = filename.trim_end_matches(".zip");
-    // Use xml_specific_path as the base for download/extraction
-    let file_path = xml_specific_path.join(&filename);
-    let extract_dir = xml_specific_path.join(mod_name);
-    let temp_file_path = file_path.with_extension("tmp");
-
-    // Clean existing mod directory within the specific subdirectory
-    // TODO: Update clean_existing_mod to handle potential errors better if needed
-    clean_existing_mod(&extract_dir)?;
-
-    // Notify that download is starting (this will update UI to show download is active)
-    if let Err(e) = app_handle.emit("download-started", &filename) {
-        eprintln!("Failed to emit download-started event: {}", e);
-    }
-
-    let downloader = ModDownloader::new();
-
-    // Download to temporary file first
-    println!(
-        "Starting download for {} to temporary file: {}",
+    // Same path as queued downloads, only nothing ever cancels it
+    download_mod_with_cancellation(
+        app_handle,
+        url,
         filename,
-        temp_file_path.display()
-    );
-    let download_result = downloader
-        .download_mod(app_handle.clone(), &url, &temp_file_path, &filename)
-        .await;
-
-    // If download failed, return error
-    if let Err(e) = download_result {
-        println!("Download failed for {}: {}", filename, e);
-        if temp_file_path.exists() {
-            let _ = std::fs::remove_file(&temp_file_path);
-        }
-        return Err(e.to_string());
-    }
-
-    // Move temp file to final location
-    println!(
-        "Download completed, moving temporary file to: {}",
-        file_path.display()
-    );
-    if let Err(e) = std::fs::rename(&temp_file_path, &file_path) {
-        println!("Failed to move temporary file: {}", e);
-        return Err(e.to_string());
-    }
-
-    // Verify file is a valid ZIP before trying to extract
-    println!("Verifying ZIP file: {}", file_path.display());
-    let file_size = match std::fs::metadata(&file_path) {
-        Ok(metadata) => metadata.len(),
-        Err(e) => {
-            let error_message = format!("Failed to get file metadata: {}", e);
-            println!("{}", error_message);
-            
-            // Emit the error event to the frontend
-            let _ = app_handle.emit(
-                "download-error",
-                serde_json::json!({
-                    "mod_name": filename,
-                    "error": error_message
-                }),
-            );
-            
-            return Err(error_message);
-        }
-    };
-
-    // Check file size - a tiny file is probably an error message, not a ZIP
-    if file_size < 100 {
-        // ZIP files should be much larger than 100 bytes
-        // Read the file content to see what the error is
-        let error_message = match std::fs::read_to_string(&file_path) {
-            Ok(content) => {
-                println!(
-                    "File too small to be a valid ZIP ({}B): {}",
-                    file_size, content
-                );
-                format!("Server returned error: {}", content)
-            }
-            Err(_) => {
-                println!("File too small to be a valid ZIP ({}B)", file_size);
-                format!(
-                    "Downloaded file is too small to be a valid ZIP ({} bytes)",
-                    file_size
-                )
-            }
-        };
-        
-        // Emit the error event to the frontend
-        let _ = app_handle.emit(
-            "download-error",
-            serde_json::json!({
-                "mod_name": filename,
-                "error": error_message
-            }),
-        );
-        
-        // Clean up the corrupted file
-        let _ = std::fs::remove_file(&file_path);
-        
-        return Err(error_message);
-    }
-
-    // Quick check if it starts with the ZIP header (PK..)
-    let file = match std::fs::File::open(&file_path) {
-        Ok(f) => f,
-        Err(e) => {
-            let error_message = format!("Failed to open file for validation: {}", e);
-            println!("{}", error_message);
-            
-            // Emit the error event to the frontend
-            let _ = app_handle.emit(
-                "download-error",
-                serde_json::json!({
-                    "mod_name": filename,
-                    "error": error_message
-                }),
-            );
-            
-            return Err(error_message);
-        }
-    };
-
-    let mut reader = std::io::BufReader::new(file);
-    let mut buffer = [0u8; 4];
-    if let Err(e) = std::io::Read::read_exact(&mut reader, &mut buffer) {
-        let error_message = format!("Failed to read file header: {}", e);
-        println!("{}", error_message);
-        
-        // Emit the error event to the frontend
-        let _ = app_handle.emit(
-            "download-error",
-            serde_json::json!({
-                "mod_name": filename,
-                "error": error_message
-            }),
-        );
-        
-        // Clean up the corrupted file
-        let _ = std::fs::remove_file(&file_path);
-        
-        return Err(error_message);
-    }
-
-    // ZIP files should start with "PK\x03\x04"
-    if buffer != [0x50, 0x4B, 0x03, 0x04] {
-        // Not a valid ZIP - could be an HTML error page
-        let content = std::fs::read_to_string(&file_path)
-            .unwrap_or_else(|_| "<binary content>".to_string());
-
-        println!(
-            "Invalid ZIP header: {:?} - Content starts with: {}",
-            buffer,
-            content.chars().take(100).collect::<String>()
-        );
-        
-        // Emit an error event
-        let error_message =
-            "Downloaded file is not a valid ZIP archive. File might be corrupted.".to_string();
-
-        // Emit an error event to the frontend
-        let _ = app_handle.emit(
-            "download-error",
-            serde_json::json!({
-                "mod_name": filename,
-                "error": error_message
-            }),
-        );
-        
-        // Clean up the corrupted file
-        let _ = std::fs::remove_file(&file_path);
-        
-        return Err(error_message);
-    }
-
-    // Extract the zip file
-    println!(
-        "Starting extraction from {} to {}",
-        file_path.display(),
-        extract_dir.display()
-    );
-    let extract_result = extract_zip(app_handle.clone(), &file_path, &extract_dir, &filename).await;
-
-    // If extraction failed, clean up and return error
-    if let Err(e) = extract_result {
-        println!("Extraction failed for {}: {}", filename, e);
-        
-        // Remove the downloaded zip file
-        let _ = std::fs::remove_file(&file_path);
-        
-        // Try to clean up any partially extracted files
-        if extract_dir.exists() {
-            println!("Cleaning up partial extraction at {}", extract_dir.display());
-            let _ = std::fs::remove_dir_all(&extract_dir);
-        }
-        
-        return Err(e);
-    }
-
-    println!("Extraction completed successfully for {}", filename);
-
-    // Remove the zip file after successful extraction
-    if let Err(e) = std::fs::remove_file(&file_path) {
-        eprintln!(
-            "Warning: Failed to remove zip file after successful extraction: {}",
-            e
-        );
-        // Don't fail the operation just because we couldn't clean up the zip
-    }
-
-    Ok(())
+        repo_url,
+        size,
+        profile_name,
+        CancellationToken::new(),
+    )
+    .await
 }
 
 pub async fn download_mod_with_cancellation(
@@ -290,6 +91,8 @@ pub async fn download_mod_with_cancellation(
     url: String,
     filename: String,
     repo_url: String,
+    size: Option<u64>,
+    profile_name: Option<String>,
     cancel_token: CancellationToken,
 ) -> Result<(), String> {
     // Check if cancelled before starting
@@ -297,58 +100,56 @@ pub async fn download_mod_with_cancellation(
         return Err("Download was cancelled".to_string());
     }
 
-    println!("Starting cancellable mod download: {} from {} (Repo: {})", filename, url, repo_url);
+    info!("Starting mod download: {} from {} (Repo: {})", filename, url, repo_url);
 
     let settings = settings::Settings::load()?;
-    let base_downloads_dir = PathBuf::from(&settings.download_path);
-
-    // Generate a unique subdirectory name from the repo_url hash
-    let mut hasher = Sha256::new();
-    hasher.update(repo_url.as_bytes());
-    let hash_result = hasher.finalize();
-    let repo_hash = format!("{:x}", hash_result);
-    let repo_hash = &repo_hash[..6]; // Shrink the hash to 6 characters
-    let xml_specific_path = base_downloads_dir.join(repo_hash);
+    // A unique subdirectory per repository, in the download path of the profile it is for
+    let download_path = settings.repo_download_path(&repo_url, profile_name.as_deref());
+    let xml_specific_path = get_repo_download_dir(&download_path, &repo_url);
 
     // Create the XML-specific directory if it doesn't exist
     if !xml_specific_path.exists() {
-        println!("Creating XML-specific download directory: {}", xml_specific_path.display());
+        info!("Creating XML-specific download directory: {}", xml_specific_path.display());
         std::fs::create_dir_all(&xml_specific_path)
             .map_err(|e| format!("Failed to create XML-specific download directory: {}", e))?;
     } else {
-         println!("Using existing XML-specific download directory: {}", xml_specific_path.display());
+        debug!("Using existing XML-specific download directory: {}", xml_specific_path.display());
     }
 
     let mod_name = filename.trim_end_matches(".zip");
-    // Use xml_specific_path as the base for download/extraction
-    let file_path = xml_specific_path.join(&filename);
     let extract_dir = xml_specific_path.join(mod_name);
-    let temp_file_path = file_path.with_extension("tmp");
+    // Downloaded and extracted in the staging directory when one is set, then moved into place
+    let StagingPaths {
+        temp_file: temp_file_path,
+        zip_file: file_path,
+        extract_dir: staged_dir,
+    } = staging_paths(&settings, &xml_specific_path, &filename)?;
 
     // Check if cancelled before proceeding
     if cancel_token.is_cancelled() {
         return Err("Download was cancelled".to_string());
     }
 
-    // Clean existing mod directory within the specific subdirectory
-    clean_existing_mod(&extract_dir)?;
+    // Clean existing mod directory within the specific subdirectory, or leftovers of an earlier
+    // attempt in the staging directory, where the mod stays in place until the new one is ready
+    clean_existing_mod(&staged_dir)?;
 
     // Notify that download is starting (this will update UI to show download is active)
     if let Err(e) = app_handle.emit("download-started", &filename) {
-        eprintln!("Failed to emit download-started event: {}", e);
+        error!("Failed to emit download-started event: {}", e);
     }
 
     let downloader = ModDownloader::new();
 
     // Download to temporary file first with cancellation support
-    println!(
+    info!(
         "Starting cancellable download for {} to temporary file: {}",
         filename,
         temp_file_path.display()
     );
     
     let download_result = downloader
-        .download_mod_with_cancellation(app_handle.clone(), &url, &temp_file_path, &filename, cancel_token.clone())
+        .download_mod_with_cancellation(app_handle.clone(), &url, &temp_file_path, &filename, size, cancel_token.clone())
         .await;
 
     // Check if cancelled after download attempt
@@ -366,9 +167,9 @@ pub async fn download_mod_with_cancellation(
         
         // Don't log as error for user-initiated cancellations
         if !error_msg.to_lowercase().contains("cancelled") {
-            println!("Download failed for {}: {}", filename, e);
+            warn!("Download failed for {}: {}", filename, e);
         } else {
-            println!("Download cancelled for {}", filename);
+            info!("Download cancelled for {}", filename);
         }
         
         if temp_file_path.exists() {
@@ -378,12 +179,12 @@ pub async fn download_mod_with_cancellation(
     }
 
     // Move temp file to final location
-    println!(
+    info!(
         "Download completed, moving temporary file to: {}",
         file_path.display()
     );
     if let Err(e) = std::fs::rename(&temp_file_path, &file_path) {
-        println!("Failed to move temporary file: {}", e);
+        warn!("Failed to move temporary file: {}", e);
         return Err(e.to_string());
     }
 
@@ -395,12 +196,12 @@ pub async fn download_mod_with_cancellation(
     }
 
     // Verify file is a valid ZIP before trying to extract (same validation as original)
-    println!("Verifying ZIP file: {}", file_path.display());
+    info!("Verifying ZIP file: {}", file_path.display());
     let file_size = match std::fs::metadata(&file_path) {
         Ok(metadata) => metadata.len(),
         Err(e) => {
             let error_message = format!("Failed to get file metadata: {}", e);
-            println!("{}", error_message);
+            warn!("{}", error_message);
             
             let _ = app_handle.emit(
                 "download-error",
@@ -418,11 +219,11 @@ pub async fn download_mod_with_cancellation(
     if file_size < 100 {
         let error_message = match std::fs::read_to_string(&file_path) {
             Ok(content) => {
-                println!("File too small to be a valid ZIP ({}B): {}", file_size, content);
+                warn!("File too small to be a valid ZIP ({}B): {}", file_size, content);
                 format!("Server returned error: {}", content)
             }
             Err(_) => {
-                println!("File too small to be a valid ZIP ({}B)", file_size);
+                warn!("File too small to be a valid ZIP ({}B)", file_size);
                 format!("Downloaded file is too small to be a valid ZIP ({} bytes)", file_size)
             }
         };
@@ -444,7 +245,7 @@ pub async fn download_mod_with_cancellation(
         Ok(f) => f,
         Err(e) => {
             let error_message = format!("Failed to open file for validation: {}", e);
-            println!("{}", error_message);
+            warn!("{}", error_message);
             
             let _ = app_handle.emit(
                 "download-error",
@@ -462,7 +263,7 @@ pub async fn download_mod_with_cancellation(
     let mut buffer = [0u8; 4];
     if let Err(e) = std::io::Read::read_exact(&mut reader, &mut buffer) {
         let error_message = format!("Failed to read file header: {}", e);
-        println!("{}", error_message);
+        warn!("{}", error_message);
         
         let _ = app_handle.emit(
             "download-error",
@@ -481,7 +282,7 @@ pub async fn download_mod_with_cancellation(
         let content = std::fs::read_to_string(&file_path)
             .unwrap_or_else(|_| "<binary content>".to_string());
 
-        println!(
+        warn!(
             "Invalid ZIP header: {:?} - Content starts with: {}",
             buffer,
             content.chars().take(100).collect::<String>()
@@ -510,45 +311,61 @@ pub async fn download_mod_with_cancellation(
     }
 
     // Extract the zip file with cancellation support
-    println!(
+    info!(
         "Starting cancellable extraction from {} to {}",
         file_path.display(),
-        extract_dir.display()
+        staged_dir.display()
     );
-    let extract_result = super::extraction::extract_zip_with_cancellation(
+    let extract_result = extract_zip_with_cancellation(
         app_handle.clone(), 
         &file_path, 
-        &extract_dir, 
+        &staged_dir, 
         &filename, 
         cancel_token.clone()
     ).await;
 
     // If extraction failed, clean up and return error
     if let Err(e) = extract_result {
-        println!("Extraction failed for {}: {}", filename, e);
+        warn!("Extraction failed for {}: {}", filename, e);
         
         // Remove the downloaded zip file
         let _ = std::fs::remove_file(&file_path);
         
         // Try to clean up any partially extracted files
-        if extract_dir.exists() {
-            println!("Cleaning up partial extraction at {}", extract_dir.display());
-            let _ = std::fs::remove_dir_all(&extract_dir);
+        if staged_dir.exists() {
+            info!("Cleaning up partial extraction at {}", staged_dir.display());
+            let _ = std::fs::remove_dir_all(&staged_dir);
         }
         
         return Err(e);
     }
 
-    println!("Extraction completed successfully for {}", filename);
+    info!("Extraction completed successfully for {}", filename);
+
+    // Identical archives from other repositories share the extracted files
+    let archive_hash = {
+        let file_path = file_path.clone();
+        tokio::task::spawn_blocking(move || hash_archive(&file_path)).await
+    };
 
     // Remove the zip file after successful extraction
     if let Err(e) = std::fs::remove_file(&file_path) {
-        eprintln!(
-            "Warning: Failed to remove zip file after successful extraction: {}",
+        warn!(
+            "Failed to remove zip file after successful extraction: {}",
             e
         );
         // Don't fail the operation just because we couldn't clean up the zip
     }
 
+    // A staged mod replaces the downloaded one only now it is complete
+    if staged_dir != extract_dir {
+        info!("Moving {} into place at {}", staged_dir.display(), extract_dir.display());
+        if let Err(e) = move_into_place(&staged_dir, &extract_dir) {
+            let _ = std::fs::remove_dir_all(&staged_dir);
+            return Err(format!("Failed to move {} into the download directory: {}", mod_name, e));
+        }
+    }
+
+    share_with_identical_downloads(extract_dir, archive_hash).await;
     Ok(())
 }
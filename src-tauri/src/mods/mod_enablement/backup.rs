@@ -0,0 +1,117 @@
+use std::path::{Path, PathBuf};
+use directories::ProjectDirs;
+use tokio::fs;
+use crate::mods::types::ModError;
+use super::mapping::TargetRoot;
+use tracing::info;
+
+/// Get the backup directory holding pristine DCS files for a profile and target root
+pub fn get_backup_root(profile_name: &str, target: TargetRoot) -> Option<PathBuf> {
+    let proj_dirs = ProjectDirs::from("com", "borderzone", "bzmm")?;
+    let dir_name = match target {
+        TargetRoot::SavedGames => "backups",
+        TargetRoot::Install => "install_backups",
+    };
+    Some(proj_dirs.data_dir().join(dir_name).join(profile_name))
+}
+
+/// Per-profile store of pristine copies of DCS files that enablement replaced or patched.
+///
+/// Backups mirror the layout of the DCS directory, so `<dcs>/Scripts/Export.lua`
+/// is stored as `<backup_root>/Scripts/Export.lua`.
+pub struct BackupStore {
+    backup_root: PathBuf,
+    dcs_dir: PathBuf,
+}
+
+impl BackupStore {
+    pub fn new(profile_name: &str, target: TargetRoot, dcs_dir: &Path) -> Result<Self, ModError> {
+        let backup_root = get_backup_root(profile_name, target).ok_or_else(|| {
+            ModError::SettingsError("Could not determine backup directory".to_string())
+        })?;
+
+        Ok(Self {
+            backup_root,
+            dcs_dir: dcs_dir.to_path_buf(),
+        })
+    }
+
+    /// Map a path inside the DCS directory to its location in the backup store
+    fn backup_path(&self, dest_path: &Path) -> Result<PathBuf, ModError> {
+        let relative = dest_path.strip_prefix(&self.dcs_dir).map_err(|_| {
+            ModError::EnablementError(format!(
+                "{} is outside of the DCS directory",
+                dest_path.display()
+            ))
+        })?;
+        Ok(self.backup_root.join(relative))
+    }
+
+    /// Check if a pristine copy is held for a DCS file
+    pub fn has_backup(&self, dest_path: &Path) -> bool {
+        self.backup_path(dest_path)
+            .map(|path| path.is_file())
+            .unwrap_or(false)
+    }
+
+    /// Copy a DCS file into the backup store, keeping any pristine copy already held
+    pub async fn backup_file(&self, dest_path: &Path) -> Result<(), ModError> {
+        let backup_path = self.backup_path(dest_path)?;
+        if backup_path.exists() {
+            return Ok(());
+        }
+
+        if let Some(parent) = backup_path.parent() {
+            fs::create_dir_all(parent).await.map_err(ModError::IoError)?;
+        }
+        fs::copy(dest_path, &backup_path).await.map_err(ModError::IoError)?;
+        info!("Backed up {} to {}", dest_path.display(), backup_path.display());
+        Ok(())
+    }
+
+    /// Put the pristine copy of a DCS file back in place and drop it from the store.
+    /// Returns false if no backup was held for the file.
+    pub async fn restore_file(&self, dest_path: &Path) -> Result<bool, ModError> {
+        let backup_path = self.backup_path(dest_path)?;
+        if !backup_path.is_file() {
+            return Ok(false);
+        }
+
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent).await.map_err(ModError::IoError)?;
+        }
+        // Copying onto a mod's symlink would overwrite the mod's own file
+        if dest_path.is_symlink() {
+            fs::remove_file(dest_path).await.map_err(ModError::IoError)?;
+        }
+        fs::copy(&backup_path, dest_path).await.map_err(ModError::IoError)?;
+        fs::remove_file(&backup_path).await.map_err(ModError::IoError)?;
+        info!("Restored {} from backup", dest_path.display());
+        Ok(true)
+    }
+
+    /// DCS paths of every file a pristine copy is held for
+    pub fn list_backed_up_files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        collect_files(&self.backup_root, &mut files);
+        files
+            .iter()
+            .filter_map(|path| path.strip_prefix(&self.backup_root).ok())
+            .map(|relative| self.dcs_dir.join(relative))
+            .collect()
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
@@ -1,9 +1,71 @@
-use std::path::Path;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
 use tokio::fs;
 use futures_util::future::BoxFuture;
 use crate::mods::types::ModError;
 use super::file_operations::*;
-use crate::mods::mod_utils::verify_symlink;
+use super::backup::BackupStore;
+use super::manifest::{
+    load_manifest_copy, remove_manifest_copy, save_manifest_copy, EnablementManifest, ManifestRecorder,
+};
+use super::mapping::{DirectoryStrategy, MappingConfig, TargetRoot};
+use super::targets::{mod_source_roots, TargetDirs};
+use super::patching::{parse_patch_header, PatchSyntax};
+use crate::mods::mod_utils::{get_manifest_path, verify_symlink, DestDir};
+use crate::mods::progress::{count_files, EnablementProgressTracker};
+use std::sync::Arc;
+use tauri::AppHandle;
+use tracing::{debug, info};
+
+/// State shared by every step of enabling or disabling a single mod
+pub struct EnableContext {
+    pub mod_name: String,
+    pub version: String,
+    pub backups: BackupStore,
+    pub progress: Arc<EnablementProgressTracker>,
+    pub mapping: MappingConfig,
+    /// The mod's main subdirectory, mapping rules are relative to it
+    pub source_root: PathBuf,
+    /// Directories of the source root with excluded entries inside, which are never linked whole
+    pub holding_excluded: HashSet<PathBuf>,
+    /// The DCS root this source root is enabled into
+    pub target: TargetRoot,
+    pub manifest: Arc<ManifestRecorder>,
+    /// Whether DCS files may be patched, patches are skipped and recorded otherwise
+    pub patch_files: bool,
+}
+
+impl EnableContext {
+    /// Report a source entry as handled; a directory linked as a whole counts for every file in it
+    fn entry_done(&self, path: &Path) {
+        let items = if path.is_dir() { count_files(path) } else { 1 };
+        self.progress.advance(items, path);
+    }
+
+    /// How a source directory at the given level should be brought into DCS
+    fn strategy_for(&self, path: &Path, level: u8) -> DirectoryStrategy {
+        self.mapping
+            .strategy_for_dir(&self.source_root, path, level, &self.holding_excluded)
+    }
+
+    /// Whether a source entry is matched by the mapping's exclude patterns and stays out of DCS
+    fn is_excluded(&self, path: &Path) -> bool {
+        self.mapping
+            .is_excluded(path.strip_prefix(&self.source_root).unwrap_or(path))
+    }
+
+    fn record_link(&self, source: &Path, dest: &Path) {
+        debug!("Linked {} -> {}", dest.display(), source.display());
+        self.manifest.link(self.target, source, dest);
+    }
+
+    /// Record a real directory content was put into; top level directories (Mods, Liveries, ...) are always kept
+    fn record_directory(&self, dest_dir: &Path, level: u8) {
+        if level >= 3 {
+            self.manifest.directory(self.target, dest_dir);
+        }
+    }
+}
 
 /// Remove a symlink in a cross-platform way
 async fn remove_symlink(path: &Path) -> Result<(), ModError> {
@@ -37,167 +99,297 @@ async fn create_symlink(source: &Path, dest: &Path) -> Result<(), ModError> {
     }
 }
 
-/// Process a directory at the 4th level and below (create symlinks, patch lua files)
-fn process_deep_directory<'a>(
-    source_dir: &'a Path,
-    dest_dir: &'a Path,
-    mod_name: &'a str,
-    version: &'a str,
-) -> BoxFuture<'a, Result<(), ModError>> {
-    Box::pin(async move {
-        if !dest_dir.exists() {
-            fs::create_dir_all(dest_dir).await.map_err(ModError::IoError)?;
-        }
+/// Replace an existing DCS file with a symlink, keeping a pristine copy in the backup store
+async fn replace_with_symlink(
+    source: &Path,
+    dest: &Path,
+    ctx: &EnableContext,
+) -> Result<(), ModError> {
+    ctx.backups.backup_file(dest).await?;
+    fs::remove_file(dest).await.map_err(ModError::IoError)?;
+    create_symlink(source, dest).await
+}
 
-        let mut entries = fs::read_dir(source_dir).await.map_err(ModError::IoError)?;
-        while let Some(entry) = entries.next_entry().await.map_err(ModError::IoError)? {
-            let path = entry.path();
-            let dest_path = dest_dir.join(path.file_name().unwrap());
+/// Remove a symlink created by this mod and put back any file it replaced
+async fn remove_symlink_and_restore(dest: &Path, backups: &BackupStore) -> Result<(), ModError> {
+    remove_symlink(dest).await?;
+    backups.restore_file(dest).await?;
+    Ok(())
+}
 
-            if path.is_dir() {
-                if dest_path.exists() {
-                    if dest_path.is_symlink() {
-                        if !verify_symlink(&dest_path, &path)? {
-                            remove_symlink(&dest_path).await?;
-                            create_symlink(&path, &dest_path).await?;
+/// Remove this mod's patch from a file and put back the pristine copy once no mod patches it any more
+async fn remove_patch_and_restore(
+    dest: &Path,
+    syntax: PatchSyntax,
+    mod_name: &str,
+    version: &str,
+    backups: &BackupStore,
+) -> Result<(), ModError> {
+    remove_patch_from_file(dest, syntax, mod_name, version)?;
+    let content = fs::read_to_string(dest).await.map_err(ModError::IoError)?;
+    if file_patches(dest, syntax)?.is_empty() && backups.has_backup(dest) {
+        backups.restore_file(dest).await?;
+    } else if content.trim().is_empty() {
+        fs::remove_file(dest).await.map_err(ModError::IoError)?;
+    }
+    Ok(())
+}
+
+/// Remove the destination directory if cleanup left it empty
+async fn remove_dir_if_empty(dest_dir: &Path) -> Result<(), ModError> {
+    let mut read_dir = fs::read_dir(dest_dir).await.map_err(ModError::IoError)?;
+    if read_dir.next_entry().await.map_err(ModError::IoError)?.is_none() {
+        fs::remove_dir(dest_dir).await.map_err(ModError::IoError)?;
+    }
+    Ok(())
+}
+
+/// Process a single entry at the link level and below (create symlinks, patch lua files)
+fn process_deep_entry<'a>(
+    path: &'a Path,
+    dest_path: &'a Path,
+    ctx: &'a EnableContext,
+    level: u8,
+) -> BoxFuture<'a, Result<(), ModError>> {
+    Box::pin(async move {
+        if path.is_dir() {
+            match ctx.strategy_for(path, level) {
+                DirectoryStrategy::Link => {
+                    if dest_path.exists() {
+                        if dest_path.is_symlink() {
+                            if !verify_symlink(dest_path, path)? {
+                                remove_symlink(dest_path).await?;
+                                create_symlink(path, dest_path).await?;
+                            }
+                            ctx.record_link(path, dest_path);
+                            ctx.entry_done(path);
+                        } else {
+                            // A real directory is already there, merge into it
+                            process_deep_directory(path, dest_path, ctx, level + 1).await?;
                         }
                     } else {
-                        process_deep_directory(&path, &dest_path, mod_name, version).await?;
+                        create_symlink(path, dest_path).await?;
+                        ctx.record_link(path, dest_path);
+                        ctx.entry_done(path);
                     }
-                } else {
-                    create_symlink(&path, &dest_path).await?;
                 }
-            } else if let Some(extension) = path.extension() {
-                if extension == "lua" {
-                    if dest_path.exists() {
-                        let patch_content = fs::read_to_string(&path).await.map_err(ModError::IoError)?;
-                        patch_lua_file(&dest_path, mod_name, version, &patch_content)?;
-                    } else {
-                        create_symlink(&path, &dest_path).await?;
+                DirectoryStrategy::Recurse => {
+                    // Replace a whole-directory link from an earlier enable with a real directory
+                    if dest_path.is_symlink() && verify_symlink(dest_path, path)? {
+                        remove_symlink(dest_path).await?;
                     }
+                    process_deep_directory(path, dest_path, ctx, level + 1).await?;
+                }
+            }
+            return Ok(());
+        }
+
+        if path.extension().is_some() {
+            if let Some(syntax) = PatchSyntax::for_path(path) {
+                if dest_path.is_symlink() && verify_symlink(dest_path, path)? {
+                    // Already linked to this mod, patching would modify the mod's own file
+                    ctx.record_link(path, dest_path);
+                } else if dest_path.exists() && !ctx.patch_files {
+                    debug!("Not patching {}, the profile doesn't allow patching", dest_path.display());
+                    ctx.manifest.skipped_patch(dest_path);
                 } else if dest_path.exists() {
+                    let patch_content = fs::read_to_string(path).await.map_err(ModError::IoError)?;
+                    // Keep the untouched original around before the first mod patches it
+                    if !dest_path.is_symlink() && file_patches(dest_path, syntax)?.is_empty() {
+                        ctx.backups.backup_file(dest_path).await?;
+                    }
+                    patch_file(dest_path, syntax, &ctx.mod_name, &ctx.version, &patch_content)?;
+                    ctx.manifest
+                        .patch(ctx.target, dest_path, &parse_patch_header(&patch_content, syntax)?.1);
+                } else {
+                    create_symlink(path, dest_path).await?;
+                    ctx.record_link(path, dest_path);
+                }
+            } else if dest_path.is_symlink() {
+                if !verify_symlink(dest_path, path)? {
                     return Err(ModError::FileConflictError(format!(
                         "File {} already exists",
                         dest_path.display()
                     )));
-                } else {
-                    create_symlink(&path, &dest_path).await?;
                 }
+                ctx.record_link(path, dest_path);
+            } else if dest_path.exists() {
+                replace_with_symlink(path, dest_path, ctx).await?;
+                ctx.record_link(path, dest_path);
+            } else {
+                create_symlink(path, dest_path).await?;
+                ctx.record_link(path, dest_path);
             }
+            ctx.entry_done(path);
         }
 
         Ok(())
     })
 }
 
-/// Clean up symlinks and patches from a directory (4th level and below only)
-fn cleanup_deep_directory<'a>(
-    source_dir: &'a Path,
-    dest_dir: &'a Path,
-    mod_name: &'a str,
-    version: &'a str,
+/// Process the contents of a directory at the link level and below
+async fn process_deep_directory(
+    source_dir: &Path,
+    dest_dir: &Path,
+    ctx: &EnableContext,
+    level: u8,
+) -> Result<(), ModError> {
+    if !dest_dir.exists() {
+        fs::create_dir_all(dest_dir).await.map_err(ModError::IoError)?;
+    }
+    ctx.record_directory(dest_dir, level - 1);
+    let dest = DestDir::read(dest_dir);
+
+    let mut entries = fs::read_dir(source_dir).await.map_err(ModError::IoError)?;
+    while let Some(entry) = entries.next_entry().await.map_err(ModError::IoError)? {
+        let path = entry.path();
+        if ctx.is_excluded(&path) {
+            ctx.entry_done(&path);
+            continue;
+        }
+        let dest_path = dest.join(path.file_name().unwrap());
+        process_deep_entry(&path, &dest_path, ctx, level).await?;
+    }
+
+    Ok(())
+}
+
+/// Clean up the symlink or patches for a single entry at the link level and below
+fn cleanup_deep_entry<'a>(
+    path: &'a Path,
+    dest_path: &'a Path,
+    ctx: &'a EnableContext,
+    level: u8,
 ) -> BoxFuture<'a, Result<(), ModError>> {
     Box::pin(async move {
-        if !dest_dir.exists() {
+        if !dest_path.exists() {
+            ctx.entry_done(path);
             return Ok(());
         }
 
-        let mut entries = fs::read_dir(source_dir).await.map_err(ModError::IoError)?;
-        while let Some(entry) = entries.next_entry().await.map_err(ModError::IoError)? {
-            let path = entry.path();
-            let dest_path = dest_dir.join(path.file_name().unwrap());
-
-            if !dest_path.exists() {
-                continue;
+        if path.is_dir() {
+            if dest_path.is_symlink() && verify_symlink(dest_path, path)? {
+                remove_symlink(dest_path).await?;
+                ctx.entry_done(path);
+            } else if !dest_path.is_symlink() {
+                cleanup_deep_directory(path, dest_path, ctx, level + 1).await?;
+                remove_dir_if_empty(dest_path).await?;
             }
-
-            if path.is_dir() {
-                if dest_path.is_symlink() && verify_symlink(&dest_path, &path)? {
-                    remove_symlink(&dest_path).await?;
-                } else if !dest_path.is_symlink() {
-                    cleanup_deep_directory(&path, &dest_path, mod_name, version).await?;
-                    // Remove directory if empty
-                    let mut read_dir = fs::read_dir(&dest_path).await.map_err(ModError::IoError)?;
-                    if read_dir.next_entry().await.map_err(ModError::IoError)?.is_none() {
-                        fs::remove_dir(&dest_path).await.map_err(ModError::IoError)?;
-                    }
-                }
-            } else if let Some(extension) = path.extension() {
-                if extension == "lua" {
-                    if dest_path.is_symlink() && verify_symlink(&dest_path, &path)? {
-                        remove_symlink(&dest_path).await?;
-                    } else {
-                        remove_lua_patch_from_file(&dest_path, mod_name, version)?;
-                        let content = fs::read_to_string(&dest_path).await.map_err(ModError::IoError)?;
-                        if content.trim().is_empty() {
-                            fs::remove_file(&dest_path).await.map_err(ModError::IoError)?;
-                        }
-                    }
-                } else if dest_path.is_symlink() && verify_symlink(&dest_path, &path)? {
-                    remove_symlink(&dest_path).await?;
+        } else if path.extension().is_some() {
+            if let Some(syntax) = PatchSyntax::for_path(path) {
+                if dest_path.is_symlink() && verify_symlink(dest_path, path)? {
+                    remove_symlink(dest_path).await?;
+                } else {
+                    remove_patch_and_restore(dest_path, syntax, &ctx.mod_name, &ctx.version, &ctx.backups)
+                        .await?;
                 }
+            } else if dest_path.is_symlink() && verify_symlink(dest_path, path)? {
+                remove_symlink_and_restore(dest_path, &ctx.backups).await?;
             }
+            ctx.entry_done(path);
         }
 
         Ok(())
     })
 }
 
-/// Process directories recursively, handling different levels appropriately
+/// Clean up symlinks and patches from a directory at the link level and below
+async fn cleanup_deep_directory(
+    source_dir: &Path,
+    dest_dir: &Path,
+    ctx: &EnableContext,
+    level: u8,
+) -> Result<(), ModError> {
+    if !dest_dir.exists() {
+        ctx.entry_done(source_dir);
+        return Ok(());
+    }
+    let dest = DestDir::read(dest_dir);
+
+    let mut entries = fs::read_dir(source_dir).await.map_err(ModError::IoError)?;
+    while let Some(entry) = entries.next_entry().await.map_err(ModError::IoError)? {
+        let path = entry.path();
+        if ctx.is_excluded(&path) {
+            ctx.entry_done(&path);
+            continue;
+        }
+        let dest_path = dest.join(path.file_name().unwrap());
+        cleanup_deep_entry(&path, &dest_path, ctx, level).await?;
+    }
+
+    Ok(())
+}
+
+/// Process the structural levels above the link level (Mods, Liveries, aircraft, ...)
+/// and hand directories at the link level over to the deep processing
 fn process_directory<'a>(
     source_dir: &'a Path,
     dest_dir: &'a Path,
-    mod_name: &'a str,
-    version: &'a str,
+    ctx: &'a EnableContext,
     level: u8,
     cleanup: bool,
 ) -> BoxFuture<'a, Result<(), ModError>> {
     Box::pin(async move {
+        if cleanup && !dest_dir.exists() {
+            // Nothing of this mod can be left in a directory that no longer exists
+            ctx.entry_done(source_dir);
+            return Ok(());
+        }
         if !dest_dir.exists() {
             fs::create_dir_all(dest_dir).await.map_err(ModError::IoError)?;
         }
+        if !cleanup {
+            ctx.record_directory(dest_dir, level - 1);
+        }
+        let dest = DestDir::read(dest_dir);
 
         let mut entries = fs::read_dir(source_dir).await.map_err(ModError::IoError)?;
         while let Some(entry) = entries.next_entry().await.map_err(ModError::IoError)? {
             let path = entry.path();
+            if ctx.is_excluded(&path) {
+                ctx.entry_done(&path);
+                continue;
+            }
             let file_name = path.file_name().unwrap(); // Safe to unwrap as we are reading directory entries
-            let dest_path = dest_dir.join(file_name);
+            let dest_path = dest.join(file_name);
 
             if path.is_dir() {
-                // Handle directories based on level
-                match level {
-                    // Level 2: Create directory and recurse
-                    2 => {
-                        if !dest_path.exists() {
-                            fs::create_dir_all(&dest_path).await.map_err(ModError::IoError)?;
+                match ctx.strategy_for(&path, level) {
+                    // Directory mapped to be linked as a whole
+                    DirectoryStrategy::Link => {
+                        if cleanup {
+                            cleanup_deep_entry(&path, &dest_path, ctx, level).await?;
+                        } else {
+                            process_deep_entry(&path, &dest_path, ctx, level).await?;
                         }
-                        process_directory(&path, &dest_path, mod_name, version, level + 1, cleanup).await?;
                     }
-                    // Level 3: Create directory and handle 4th level content
-                    3 => {
-                        if !dest_path.exists() && !cleanup {
-                            fs::create_dir_all(&dest_path).await.map_err(ModError::IoError)?;
+                    // Above the last structural level: create directory and recurse
+                    DirectoryStrategy::Recurse if level + 1 < ctx.mapping.link_depth() => {
+                        process_directory(&path, &dest_path, ctx, level + 1, cleanup).await?;
+                        // Top level directories (Mods, Liveries, ...) are always kept
+                        if cleanup && level >= 3 && dest_path.is_dir() {
+                            remove_dir_if_empty(&dest_path).await?;
                         }
+                    }
+                    // Last structural level: create directory and handle link level content
+                    DirectoryStrategy::Recurse => {
                         if cleanup {
-                            cleanup_deep_directory(&path, &dest_path, mod_name, version).await?;
+                            cleanup_deep_directory(&path, &dest_path, ctx, level + 1).await?;
                             // Attempt to remove the directory if it's empty after cleanup
-                            let mut read_dir = fs::read_dir(&dest_path).await.map_err(ModError::IoError)?;
-                            if read_dir.next_entry().await.map_err(ModError::IoError)?.is_none() {
-                                fs::remove_dir(&dest_path).await.map_err(ModError::IoError)?;
+                            if level >= 3 && dest_path.is_dir() {
+                                remove_dir_if_empty(&dest_path).await?;
                             }
                         } else {
-                            process_deep_directory(&path, &dest_path, mod_name, version).await?;
+                            process_deep_directory(&path, &dest_path, ctx, level + 1).await?;
                         }
                     }
-                    // Level 4+: Should never get here as it's handled by process_deep_directory/cleanup_deep_directory
-                    _ => {}
                 }
             } else if path.is_file() {
-                // Handle files directly at levels 2 and 3
+                // Handle files directly at the structural levels
                 if cleanup {
                     // Cleanup: Remove symlink if it exists and points to the correct source
                     if dest_path.exists() && dest_path.is_symlink() && verify_symlink(&dest_path, &path)? {
-                        remove_symlink(&dest_path).await?;
+                        remove_symlink_and_restore(&dest_path, &ctx.backups).await?;
                     }
                 } else {
                     // Enable: Create symlink, handling conflicts
@@ -210,8 +402,13 @@ fn process_directory<'a>(
                                 create_symlink(&path, &dest_path).await?;
                             }
                             // If correct symlink, do nothing
+                            ctx.record_link(&path, &dest_path);
+                        } else if dest_path.is_file() {
+                            // A real DCS file is in the way, back it up and replace it
+                            replace_with_symlink(&path, &dest_path, ctx).await?;
+                            ctx.record_link(&path, &dest_path);
                         } else {
-                            // Conflict: A real file/directory exists where we want to put a symlink
+                            // Conflict: A real directory exists where we want to put a symlink
                             return Err(ModError::FileConflictError(format!(
                                 "File conflict: {} already exists and is not a symlink.",
                                 dest_path.display()
@@ -220,8 +417,10 @@ fn process_directory<'a>(
                     } else {
                         // Destination doesn't exist, create the symlink
                         create_symlink(&path, &dest_path).await?;
+                        ctx.record_link(&path, &dest_path);
                     }
                 }
+                ctx.entry_done(&path);
             }
             // Ignore other entry types (like symlinks in the source mod directory)
         }
@@ -230,13 +429,222 @@ fn process_directory<'a>(
     })
 }
 
-// This is the entry point - starts at level 2 (mods, liveries, etc.)
-pub async fn process_second_level_dirs(
-    source_dir: &Path,
-    dcs_dir: &Path,
+/// Undo exactly the changes a manifest recorded, without looking at the mod's source folder
+async fn cleanup_from_manifest(
+    manifest: &EnablementManifest,
+    targets: &TargetDirs,
+    profile_name: &str,
+    app_handle: &AppHandle,
+) -> Result<(), ModError> {
+    let progress = EnablementProgressTracker::new(
+        app_handle.clone(),
+        &manifest.mod_name,
+        manifest.links.len() + manifest.patches.len(),
+        true,
+    );
+
+    for link in &manifest.links {
+        if verify_symlink(&link.dest, &link.source)? {
+            let backups = BackupStore::new(profile_name, link.target, targets.get(link.target)?)?;
+            remove_symlink_and_restore(&link.dest, &backups).await?;
+        }
+        progress.advance(1, &link.dest);
+    }
+
+    for patch in &manifest.patches {
+        if let Some(syntax) = PatchSyntax::for_path(&patch.dest) {
+            if patch.dest.is_file() && !patch.dest.is_symlink() {
+                let backups = BackupStore::new(profile_name, patch.target, targets.get(patch.target)?)?;
+                remove_patch_and_restore(&patch.dest, syntax, &manifest.mod_name, &manifest.version, &backups)
+                    .await?;
+            }
+        }
+        progress.advance(1, &patch.dest);
+    }
+
+    // Deepest directories first so emptied parents can go too
+    let mut directories: Vec<&Path> = manifest.directories.iter().map(|d| d.path.as_path()).collect();
+    directories.sort_by_key(|path| std::cmp::Reverse(path.components().count()));
+    for dir in directories {
+        if dir.is_dir() && !dir.is_symlink() {
+            remove_dir_if_empty(dir).await?;
+        }
+    }
+
+    progress.finish();
+    Ok(())
+}
+
+/// Enable (or with `cleanup`, disable) every source root of a mod into its target directory,
+/// starting at level 2 (Mods, Liveries, etc.)
+///
+/// Enabling records what it changed in a manifest next to the ENABLED file; disabling follows
+/// that manifest when there is one and only falls back to walking the source folder without it.
+pub async fn process_mod_roots(
+    mod_dir: &Path,
+    targets: &TargetDirs,
     mod_name: &str,
     version: &str,
+    profile_name: &str,
     cleanup: bool,
+    app_handle: &AppHandle,
 ) -> Result<(), ModError> {
-    process_directory(source_dir, dcs_dir, mod_name, version, 2, cleanup).await
+    let manifest_path = get_manifest_path(mod_dir, profile_name);
+    if cleanup {
+        if let Some(manifest) = EnablementManifest::load(&manifest_path)? {
+            cleanup_from_manifest(&manifest, targets, profile_name, app_handle).await?;
+            fs::remove_file(&manifest_path).await.map_err(ModError::IoError)?;
+            remove_manifest_copy(mod_dir, profile_name);
+            return Ok(());
+        }
+    }
+
+    let mapping = MappingConfig::for_mod(mod_dir)?;
+    let roots = mod_source_roots(mod_dir, mod_name, &mapping);
+
+    // Resolve every destination up front so a missing install directory fails before anything is linked
+    let mut resolved = Vec::new();
+    for (source_dir, target) in &roots {
+        resolved.push((source_dir, *target, targets.get(*target)?));
+    }
+
+    let progress = Arc::new(EnablementProgressTracker::new(
+        app_handle.clone(),
+        mod_name,
+        roots.iter().map(|(dir, _)| count_files(dir)).sum(),
+        cleanup,
+    ));
+    let manifest = Arc::new(ManifestRecorder::new(mod_name, version));
+
+    for (source_dir, target, dest_dir) in resolved {
+        let ctx = EnableContext {
+            mod_name: mod_name.to_string(),
+            version: version.to_string(),
+            backups: BackupStore::new(profile_name, target, dest_dir)?,
+            progress: progress.clone(),
+            mapping: mapping.clone(),
+            source_root: source_dir.to_path_buf(),
+            holding_excluded: mapping.dirs_holding_excluded(source_dir),
+            target,
+            manifest: manifest.clone(),
+            patch_files: targets.patch_files,
+        };
+        process_directory(source_dir, dest_dir, &ctx, 2, cleanup).await?;
+    }
+
+    if cleanup {
+        remove_manifest_copy(mod_dir, profile_name);
+    } else {
+        let manifest = manifest.take();
+        manifest.save(&manifest_path)?;
+        save_manifest_copy(mod_dir, profile_name, &manifest);
+    }
+    progress.finish();
+    Ok(())
+}
+
+/// Remove every symlink into one of `mod_dirs` and every patch block of the mod from a DCS tree,
+/// `level` being the level of the entries in `dir`. Returns whether anything was removed.
+fn cleanup_orphans_in<'a>(
+    dir: &'a Path,
+    mod_dirs: &'a [PathBuf],
+    mod_name: &'a str,
+    backups: &'a BackupStore,
+    level: u8,
+) -> BoxFuture<'a, Result<bool, ModError>> {
+    Box::pin(async move {
+        let mut removed_any = false;
+        let mut entries = fs::read_dir(dir).await.map_err(ModError::IoError)?;
+        while let Some(entry) = entries.next_entry().await.map_err(ModError::IoError)? {
+            let path = entry.path();
+            if path.is_symlink() {
+                let target = fs::read_link(&path).await.map_err(ModError::IoError)?;
+                if mod_dirs.iter().any(|mod_dir| target.starts_with(mod_dir)) {
+                    remove_symlink_and_restore(&path, backups).await?;
+                    removed_any = true;
+                }
+            } else if path.is_dir() {
+                if cleanup_orphans_in(&path, mod_dirs, mod_name, backups, level + 1).await? {
+                    removed_any = true;
+                    // Only directories this cleanup emptied go, top level ones are always kept
+                    if level >= 3 {
+                        remove_dir_if_empty(&path).await?;
+                    }
+                }
+            } else if let Some(syntax) = PatchSyntax::for_path(&path) {
+                let Ok(patches) = file_patches(&path, syntax) else {
+                    continue;
+                };
+                let versions: BTreeSet<String> = patches
+                    .into_iter()
+                    .filter(|patch| patch.mod_name == mod_name)
+                    .map(|patch| patch.version)
+                    .collect();
+                for version in versions {
+                    remove_patch_and_restore(&path, syntax, mod_name, &version, backups).await?;
+                    removed_any = true;
+                }
+            }
+        }
+        Ok(removed_any)
+    })
+}
+
+/// Remove what a mod left in DCS without needing its source folder, for mods whose folder was
+/// deleted or damaged while enabled.
+///
+/// Follows the enablement manifest, from the mod folder if it survived or else the copy kept in
+/// app data. Only mods enabled before manifests were kept have neither, for those every target
+/// directory is scanned for symlinks pointing into `mod_dirs` and for patch blocks carrying the
+/// mod's name.
+pub async fn force_cleanup_mod(
+    mod_dirs: &[PathBuf],
+    targets: &TargetDirs,
+    mod_name: &str,
+    profile_name: &str,
+    app_handle: &AppHandle,
+) -> Result<(), ModError> {
+    for mod_dir in mod_dirs {
+        let manifest_path = get_manifest_path(mod_dir, profile_name);
+        if let Some(manifest) = EnablementManifest::load(&manifest_path)? {
+            cleanup_from_manifest(&manifest, targets, profile_name, app_handle).await?;
+            fs::remove_file(&manifest_path).await.map_err(ModError::IoError)?;
+            remove_manifest_copy(mod_dir, profile_name);
+            return Ok(());
+        }
+    }
+    for mod_dir in mod_dirs {
+        if let Some(manifest) = load_manifest_copy(mod_dir, profile_name)? {
+            cleanup_from_manifest(&manifest, targets, profile_name, app_handle).await?;
+            remove_manifest_copy(mod_dir, profile_name);
+            return Ok(());
+        }
+    }
+
+    for target in [TargetRoot::SavedGames, TargetRoot::Install] {
+        // The install directory is optional, there is nothing to clean if the profile never had one
+        if target == TargetRoot::Install && targets.install.is_none() {
+            continue;
+        }
+        let dest_dir = targets.get(target)?;
+        let backups = BackupStore::new(profile_name, target, dest_dir)?;
+        info!("Scanning {} for leftovers of {}", dest_dir.display(), mod_name);
+        cleanup_orphans_in(dest_dir, mod_dirs, mod_name, &backups, 2).await?;
+    }
+    Ok(())
+}
+
+/// Strip the patch blocks of a previous mod version from DCS without touching any symlinks,
+/// so a following enable only has to add the new patches
+pub fn remove_version_patches(
+    mod_dir: &Path,
+    targets: &TargetDirs,
+    mod_name: &str,
+    version: &str,
+) -> Result<(), ModError> {
+    let mapping = MappingConfig::for_mod(mod_dir)?;
+    for (source_dir, target) in mod_source_roots(mod_dir, mod_name, &mapping) {
+        remove_patches_in_tree(&source_dir, targets.get(target)?, mod_name, version)?;
+    }
+    Ok(())
 }
@@ -1,35 +1,130 @@
 use std::path::Path;
 use std::fs;
+use crate::mods::mod_utils::DestDir;
 use crate::mods::types::ModError;
-use super::patching::{check_lua_patch, add_lua_patch, remove_lua_patch};
+use super::json_patching::{add_json_patch, list_json_patches, remove_json_patch};
+use super::patching::{check_patch, add_patch, list_patches, remove_patch, PatchInfo, PatchSyntax};
+use tracing::debug;
 
-/// Patch a lua file in place
-pub fn patch_lua_file(
+/// Patch a text file in place
+pub fn patch_file(
     file_path: &Path,
+    syntax: PatchSyntax,
     mod_name: &str,
     version: &str,
     patch: &str,
 ) -> Result<(), ModError> {
+    if syntax.is_keyed() {
+        add_json_patch(file_path, mod_name, version, patch)?;
+        debug!("Merged the patch of {} {} into {}", mod_name, version, file_path.display());
+        return Ok(());
+    }
     let content = fs::read_to_string(file_path).map_err(ModError::IoError)?;
     
-    if check_lua_patch(&content, mod_name, version) {
+    if check_patch(&content, syntax, mod_name, version) {
+        debug!("{} already has the patch of {} {}", file_path.display(), mod_name, version);
         return Ok(());
     }
 
-    let new_content = add_lua_patch(&content, mod_name, version, patch);
+    let new_content = add_patch(&content, syntax, mod_name, version, patch)?;
     fs::write(file_path, new_content).map_err(ModError::IoError)?;
+    debug!("Patched {} for {} {}", file_path.display(), mod_name, version);
 
     Ok(())
 }
 
-/// Remove a lua patch from a file in place
-pub fn remove_lua_patch_from_file(
+/// Remove a patch from a text file in place
+pub fn remove_patch_from_file(
     file_path: &Path,
+    syntax: PatchSyntax,
     mod_name: &str,
     version: &str,
 ) -> Result<(), ModError> {
+    if syntax.is_keyed() {
+        return remove_json_patch(file_path, mod_name, version);
+    }
     let content = fs::read_to_string(file_path).map_err(ModError::IoError)?;
-    let new_content = remove_lua_patch(&content, mod_name, version)?;
+    let new_content = remove_patch(&content, syntax, mod_name, version)?;
     fs::write(file_path, new_content).map_err(ModError::IoError)?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Patches present in a file, in the order they were applied
+pub fn file_patches(file_path: &Path, syntax: PatchSyntax) -> Result<Vec<PatchInfo>, ModError> {
+    if syntax.is_keyed() {
+        return list_json_patches(file_path);
+    }
+    let content = fs::read_to_string(file_path).map_err(ModError::IoError)?;
+    Ok(list_patches(&content, syntax))
+}
+
+/// Remove one version's patch blocks from every patched destination file of a source tree
+pub fn remove_patches_in_tree(
+    source_dir: &Path,
+    dest_dir: &Path,
+    mod_name: &str,
+    version: &str,
+) -> Result<(), ModError> {
+    let dest = DestDir::read(dest_dir);
+    for entry in fs::read_dir(source_dir).map_err(ModError::IoError)? {
+        let path = entry.map_err(ModError::IoError)?.path();
+        let dest_path = dest.join(path.file_name().unwrap());
+
+        if path.is_dir() {
+            if dest_path.is_dir() && !dest_path.is_symlink() {
+                remove_patches_in_tree(&path, &dest_path, mod_name, version)?;
+            }
+        } else if let Some(syntax) = PatchSyntax::for_path(&path) {
+            if dest_path.is_file() && !dest_path.is_symlink() {
+                remove_patch_from_file(&dest_path, syntax, mod_name, version)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer};
+    use tracing_subscriber::prelude::*;
+    use tracing_subscriber::reload;
+
+    /// Keeps the level and message of every event that got through the filter
+    struct Captured(Arc<Mutex<Vec<String>>>);
+
+    impl<S: Subscriber> Layer<S> for Captured {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let mut message = String::new();
+            event.record(&mut |_: &tracing::field::Field, value: &dyn std::fmt::Debug| {
+                message = format!("{:?}", value);
+            });
+            self.0.lock().unwrap().push(format!("{} {}", event.metadata().level(), message));
+        }
+    }
+
+    #[test]
+    fn test_patch_logged_only_at_debug() {
+        let dir = tempfile::tempdir().unwrap();
+        let lua = dir.path().join("Options.lua");
+        fs::write(&lua, "local x = 1").unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let (filter, handle) = reload::Layer::new(crate::logging::parse_filter("info").unwrap());
+        let subscriber = tracing_subscriber::registry().with(filter).with(Captured(events.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            patch_file(&lua, PatchSyntax::LUA, "Mod A", "1.0", "a = 1").unwrap();
+            assert!(events.lock().unwrap().is_empty());
+
+            handle.reload(crate::logging::parse_filter("debug").unwrap()).unwrap();
+            patch_file(&lua, PatchSyntax::LUA, "Mod B", "1.0", "b = 2").unwrap();
+        });
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].starts_with("DEBUG Patched"), "{}", events[0]);
+        assert!(events[0].ends_with("for Mod B 1.0"), "{}", events[0]);
+    }
+}
@@ -0,0 +1,260 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::mods::types::ModError;
+use super::patching::PatchInfo;
+
+/// Suffix of the file beside a patched JSON file that records what each mod merged into it. JSON
+/// has no comments, so the markers other formats keep in the file itself live there
+const SIDECAR_SUFFIX: &str = ".bzmm-patches";
+
+/// A key a patch set, with what the file held there before so removing the patch can put it back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MergedKey {
+    path: Vec<String>,
+    /// Whether the key was there before the patch, `previous` then being its value
+    existed: bool,
+    #[serde(default)]
+    previous: Value,
+}
+
+/// One mod's patch of a JSON file, as recorded in the sidecar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JsonPatch {
+    mod_name: String,
+    version: String,
+    keys: Vec<MergedKey>,
+}
+
+/// The file recording the patches of a JSON file
+pub fn sidecar_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(SIDECAR_SUFFIX);
+    dest.with_file_name(name)
+}
+
+fn load_patches(dest: &Path) -> Result<Vec<JsonPatch>, ModError> {
+    let path = sidecar_path(dest);
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(ModError::IoError)?;
+    serde_json::from_str(&content)
+        .map_err(|e| ModError::EnablementError(format!("Invalid {}: {}", path.display(), e)))
+}
+
+/// Write the sidecar, removing it once no patch is left
+fn save_patches(dest: &Path, patches: &[JsonPatch]) -> Result<(), ModError> {
+    let path = sidecar_path(dest);
+    if patches.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(ModError::IoError)?;
+        }
+        return Ok(());
+    }
+    let content = serde_json::to_string_pretty(patches)
+        .map_err(|e| ModError::EnablementError(format!("Failed to serialize JSON patches: {}", e)))?;
+    fs::write(&path, content).map_err(ModError::IoError)
+}
+
+/// Read a JSON file that patches are merged into, an empty file counting as an empty object
+fn read_object(path: &Path) -> Result<Map<String, Value>, ModError> {
+    let content = fs::read_to_string(path).map_err(ModError::IoError)?;
+    if content.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str(&content) {
+        Ok(Value::Object(object)) => Ok(object),
+        Ok(_) => Err(ModError::EnablementError(format!(
+            "{} doesn't hold a JSON object, patches can only merge keys into one",
+            path.display()
+        ))),
+        Err(e) => Err(ModError::EnablementError(format!("{} is not valid JSON: {}", path.display(), e))),
+    }
+}
+
+fn write_object(path: &Path, object: Map<String, Value>) -> Result<(), ModError> {
+    let content = serde_json::to_string_pretty(&Value::Object(object))
+        .map_err(|e| ModError::EnablementError(format!("Failed to serialize {}: {}", path.display(), e)))?;
+    fs::write(path, content + "\n").map_err(ModError::IoError)
+}
+
+/// Parse a mod's JSON patch, which has to be an object
+fn parse_patch(patch: &str) -> Option<Map<String, Value>> {
+    match serde_json::from_str(patch) {
+        Ok(Value::Object(object)) => Some(object),
+        _ => None,
+    }
+}
+
+/// Merge `patch` into `target`, objects key by key and anything else by replacing it, recording
+/// every key set
+fn merge(target: &mut Map<String, Value>, patch: Map<String, Value>, path: &mut Vec<String>, keys: &mut Vec<MergedKey>) {
+    for (key, value) in patch {
+        path.push(key.clone());
+        match value {
+            Value::Object(nested) if target.get(&key).is_some_and(Value::is_object) => {
+                if let Some(Value::Object(existing)) = target.get_mut(&key) {
+                    merge(existing, nested, path, keys);
+                }
+            }
+            value => {
+                let previous = target.insert(key, value);
+                keys.push(MergedKey {
+                    path: path.clone(),
+                    existed: previous.is_some(),
+                    previous: previous.unwrap_or_default(),
+                });
+            }
+        }
+        path.pop();
+    }
+}
+
+/// Put back what a key held before a patch set it, leaving it alone if its parent is gone
+fn restore(object: &mut Map<String, Value>, key: MergedKey) {
+    let Some((name, parents)) = key.path.split_last() else {
+        return;
+    };
+    let mut parent = object;
+    for part in parents {
+        match parent.get_mut(part) {
+            Some(Value::Object(nested)) => parent = nested,
+            _ => return,
+        }
+    }
+    if key.existed {
+        parent.insert(name.clone(), key.previous);
+    } else {
+        parent.remove(name);
+    }
+}
+
+/// Mods whose patches are merged into a JSON file, in the order they were applied
+pub fn list_json_patches(dest: &Path) -> Result<Vec<PatchInfo>, ModError> {
+    Ok(load_patches(dest)?
+        .into_iter()
+        .map(|patch| PatchInfo { mod_name: patch.mod_name, version: patch.version })
+        .collect())
+}
+
+/// Merge a mod's JSON object into a JSON file and record the keys it set. Does nothing if this
+/// mod version's patch is already merged
+pub fn add_json_patch(dest: &Path, mod_name: &str, version: &str, patch: &str) -> Result<(), ModError> {
+    let mut patches = load_patches(dest)?;
+    if patches.iter().any(|p| p.mod_name == mod_name && p.version == version) {
+        return Ok(());
+    }
+    let patch = parse_patch(patch).ok_or_else(|| {
+        ModError::EnablementError(format!(
+            "The patch {} ships for {} is not a JSON object",
+            mod_name,
+            dest.display()
+        ))
+    })?;
+
+    let mut object = read_object(dest)?;
+    let mut keys = Vec::new();
+    merge(&mut object, patch, &mut Vec::new(), &mut keys);
+    write_object(dest, object)?;
+
+    patches.push(JsonPatch {
+        mod_name: mod_name.to_string(),
+        version: version.to_string(),
+        keys,
+    });
+    save_patches(dest, &patches)
+}
+
+/// Take a mod version's patch out of a JSON file. A key a later patch set again is left as it
+/// is, that patch restores what this one replaced once it goes too
+pub fn remove_json_patch(dest: &Path, mod_name: &str, version: &str) -> Result<(), ModError> {
+    let mut patches = load_patches(dest)?;
+    let Some(index) = patches.iter().position(|p| p.mod_name == mod_name && p.version == version) else {
+        return Ok(());
+    };
+    let removed = patches.remove(index);
+
+    let mut object = read_object(dest)?;
+    for key in removed.keys.into_iter().rev() {
+        let later = patches[index..]
+            .iter_mut()
+            .flat_map(|patch| patch.keys.iter_mut())
+            .find(|later| later.path == key.path);
+        match later {
+            Some(later) => {
+                later.existed = key.existed;
+                later.previous = key.previous;
+            }
+            None => restore(&mut object, key),
+        }
+    }
+    write_object(dest, object)?;
+    save_patches(dest, &patches)
+}
+
+/// Keys a JSON patch sets, as dotted paths, used to spot patches that override each other
+pub fn merged_names(patch: &str) -> Vec<String> {
+    fn collect(object: &Map<String, Value>, prefix: &str, names: &mut Vec<String>) {
+        for (key, value) in object {
+            let name = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            match value {
+                Value::Object(nested) if !nested.is_empty() => collect(nested, &name, names),
+                _ => names.push(name),
+            }
+        }
+    }
+    let mut names = Vec::new();
+    if let Some(object) = parse_patch(patch) {
+        collect(&object, "", &mut names);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_patches_merge_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("options.json");
+        fs::write(&dest, r#"{"graphics": {"vr": false, "fps": 60}, "audio": 5}"#).unwrap();
+
+        add_json_patch(&dest, "Mod A", "1.0", r#"{"graphics": {"vr": true}, "mod_a": [1, 2]}"#).unwrap();
+        add_json_patch(&dest, "Mod B", "2.0", r#"{"graphics": {"vr": "auto"}}"#).unwrap();
+        add_json_patch(&dest, "Mod B", "2.0", r#"{"graphics": {"vr": "auto"}}"#).unwrap();
+        let merged: Value = serde_json::from_str(&fs::read_to_string(&dest).unwrap()).unwrap();
+        assert_eq!(merged["graphics"]["vr"], "auto");
+        assert_eq!(merged["graphics"]["fps"], 60);
+        assert_eq!(merged["mod_a"], serde_json::json!([1, 2]));
+        assert_eq!(
+            list_json_patches(&dest).unwrap(),
+            [
+                PatchInfo { mod_name: "Mod A".to_string(), version: "1.0".to_string() },
+                PatchInfo { mod_name: "Mod B".to_string(), version: "2.0".to_string() },
+            ]
+        );
+
+        // Mod B's value stays until it goes too, then the original comes back
+        remove_json_patch(&dest, "Mod A", "1.0").unwrap();
+        let merged: Value = serde_json::from_str(&fs::read_to_string(&dest).unwrap()).unwrap();
+        assert_eq!(merged["graphics"]["vr"], "auto");
+        assert!(merged.get("mod_a").is_none());
+
+        remove_json_patch(&dest, "Mod B", "2.0").unwrap();
+        let restored: Value = serde_json::from_str(&fs::read_to_string(&dest).unwrap()).unwrap();
+        assert_eq!(restored, serde_json::json!({"graphics": {"vr": false, "fps": 60}, "audio": 5}));
+        assert!(!sidecar_path(&dest).exists());
+    }
+
+    #[test]
+    fn test_json_patch_must_be_an_object() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("options.json");
+        fs::write(&dest, "{}").unwrap();
+        assert!(add_json_patch(&dest, "Mod A", "1.0", "[1, 2]").is_err());
+        assert_eq!(merged_names(r#"{"graphics": {"vr": true}, "audio": 5}"#), ["audio", "graphics.vr"]);
+    }
+}
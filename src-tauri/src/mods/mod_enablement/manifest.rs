@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use crate::mods::types::ModError;
+use super::mapping::TargetRoot;
+
+/// A symlink in DCS pointing into the mod
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestLink {
+    pub target: TargetRoot,
+    pub source: PathBuf,
+    pub dest: PathBuf,
+}
+
+/// A DCS file holding a patch block of the mod
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestPatch {
+    pub target: TargetRoot,
+    pub dest: PathBuf,
+    /// SHA-256 of the patch body as inserted
+    pub hash: String,
+}
+
+/// A real directory enablement created or merged into, removed again if cleanup leaves it empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestDirectory {
+    pub target: TargetRoot,
+    pub path: PathBuf,
+}
+
+/// Everything enabling a mod changed in DCS, so disabling can undo exactly that
+/// even if the mod's source folder changed in the meantime
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnablementManifest {
+    pub mod_name: String,
+    pub version: String,
+    pub links: Vec<ManifestLink>,
+    pub patches: Vec<ManifestPatch>,
+    pub directories: Vec<ManifestDirectory>,
+    /// DCS files the mod has patches for, left unpatched because the profile doesn't allow it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_patches: Vec<PathBuf>,
+}
+
+impl EnablementManifest {
+    /// Read a manifest, returning None if the mod was enabled without one
+    pub fn load(path: &Path) -> Result<Option<Self>, ModError> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path).map_err(ModError::IoError)?;
+        let manifest = serde_json::from_str(&content).map_err(|e| {
+            ModError::EnablementError(format!("Invalid manifest {}: {}", path.display(), e))
+        })?;
+        Ok(Some(manifest))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ModError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| ModError::EnablementError(format!("Failed to serialize manifest: {}", e)))?;
+        std::fs::write(path, content).map_err(ModError::IoError)
+    }
+}
+
+/// Directory of a profile's manifest copies. The manifest next to the ENABLED file goes when the
+/// mod folder is deleted, the copy is what a forced cleanup follows then
+pub fn get_manifest_copies_dir(profile_name: &str) -> Option<PathBuf> {
+    let proj_dirs = ProjectDirs::from("com", "borderzone", "bzmm")?;
+    Some(proj_dirs.data_dir().join("manifests").join(profile_name))
+}
+
+/// Copy of the manifest of a mod folder, named after the folder's path
+fn manifest_copy_path(mod_dir: &Path, profile_name: &str) -> Option<PathBuf> {
+    let hash = Sha256::digest(mod_dir.to_string_lossy().as_bytes());
+    Some(get_manifest_copies_dir(profile_name)?.join(format!("{:x}.json", hash)))
+}
+
+/// Keep a copy of a mod's manifest in app data. Only logged when it fails, the manifest in the
+/// mod folder is the one disabling uses
+pub fn save_manifest_copy(mod_dir: &Path, profile_name: &str, manifest: &EnablementManifest) {
+    let Some(path) = manifest_copy_path(mod_dir, profile_name) else {
+        return;
+    };
+    let saved = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(ModError::IoError)
+        .and_then(|_| manifest.save(&path));
+    if let Err(e) = saved {
+        warn!("Failed to keep a copy of the manifest of {}: {}", mod_dir.display(), e);
+    }
+}
+
+/// The copy of a mod's manifest, None if there is none
+pub fn load_manifest_copy(mod_dir: &Path, profile_name: &str) -> Result<Option<EnablementManifest>, ModError> {
+    match manifest_copy_path(mod_dir, profile_name) {
+        Some(path) => EnablementManifest::load(&path),
+        None => Ok(None),
+    }
+}
+
+/// Drop the copy of a mod's manifest once the mod is disabled
+pub fn remove_manifest_copy(mod_dir: &Path, profile_name: &str) {
+    if let Some(path) = manifest_copy_path(mod_dir, profile_name).filter(|path| path.exists()) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Collects manifest entries while enabling, shared by every target root of the mod
+pub struct ManifestRecorder {
+    manifest: Mutex<EnablementManifest>,
+}
+
+impl ManifestRecorder {
+    pub fn new(mod_name: &str, version: &str) -> Self {
+        Self {
+            manifest: Mutex::new(EnablementManifest {
+                mod_name: mod_name.to_string(),
+                version: version.to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn link(&self, target: TargetRoot, source: &Path, dest: &Path) {
+        self.manifest.lock().unwrap().links.push(ManifestLink {
+            target,
+            source: source.to_path_buf(),
+            dest: dest.to_path_buf(),
+        });
+    }
+
+    pub fn patch(&self, target: TargetRoot, dest: &Path, body: &str) {
+        self.manifest.lock().unwrap().patches.push(ManifestPatch {
+            target,
+            dest: dest.to_path_buf(),
+            hash: format!("{:x}", Sha256::digest(body.trim().as_bytes())),
+        });
+    }
+
+    pub fn skipped_patch(&self, dest: &Path) {
+        self.manifest.lock().unwrap().skipped_patches.push(dest.to_path_buf());
+    }
+
+    pub fn directory(&self, target: TargetRoot, path: &Path) {
+        let mut manifest = self.manifest.lock().unwrap();
+        if !manifest.directories.iter().any(|d| d.target == target && d.path == path) {
+            manifest.directories.push(ManifestDirectory {
+                target,
+                path: path.to_path_buf(),
+            });
+        }
+    }
+
+    /// Take the collected manifest, leaving an empty one behind
+    pub fn take(&self) -> EnablementManifest {
+        std::mem::take(&mut *self.manifest.lock().unwrap())
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::app_data;
+use crate::mods::mod_utils::get_repo_source;
+use crate::mods::types::ModError;
+
+/// Name of the optional mapping file a mod ships next to its VERSION.txt
+pub const MAPPING_FILE: &str = "MAPPING.json";
+
+/// Directory in app data holding the mapping each fetched repository ships, one file per repo source
+const REPO_MAPPINGS_DIR: &str = "repo_mappings";
+
+/// Level at which directories are linked instead of created, counting the main subdirectory's
+/// children (Mods, Liveries, ...) as level 2
+pub const DEFAULT_LINK_DEPTH: u8 = 4;
+
+/// Entries mods commonly ship that have no business in DCS, excluded unless a mapping re-includes
+/// them. The directories only at the top of the main subdirectory, where a mod's own docs and
+/// screenshots sit, as DCS itself has directories of those names deeper down
+pub const DEFAULT_EXCLUDES: &[&str] = &["/docs", "/screenshots", "Thumbs.db", "desktop.ini", ".DS_Store"];
+
+/// How a directory from the mod is brought into DCS
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirectoryStrategy {
+    /// Symlink the whole directory
+    Link,
+    /// Create a real directory and process its contents
+    Recurse,
+}
+
+/// Which DCS root a mod's main subdirectory is enabled into
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetRoot {
+    /// The profile's Saved Games directory (dcs_path)
+    #[default]
+    SavedGames,
+    /// The DCS installation directory (install_path)
+    Install,
+}
+
+/// Strategy override for one directory, path relative to the main subdirectory (e.g. "Mods/tech")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MappingRule {
+    #[serde(alias = "@path")]
+    pub path: String,
+    #[serde(alias = "@strategy")]
+    pub strategy: DirectoryStrategy,
+}
+
+/// Directory mapping configuration for enabling a mod, read from a mod's MAPPING.json or from the
+/// `<mapping>` element of a repository
+///
+/// ```json
+/// { "target": "install", "link_depth": 3, "rules": [{ "path": "Mods/tech/Shared", "strategy": "recurse" }],
+///   "exclude": ["*.psd", "!docs"] }
+/// ```
+///
+/// ```xml
+/// <mapping target="install" link_depth="3">
+///     <rule path="Mods/tech/Shared" strategy="recurse"/>
+///     <exclude>*.psd</exclude>
+/// </mapping>
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MappingConfig {
+    #[serde(default, alias = "@target")]
+    pub target: Option<TargetRoot>,
+    #[serde(default, alias = "@link_depth")]
+    pub link_depth: Option<u8>,
+    #[serde(default, alias = "rule")]
+    pub rules: Vec<MappingRule>,
+    /// Glob patterns for entries never brought into DCS, on top of DEFAULT_EXCLUDES; a leading
+    /// `!` re-includes entries matched by an earlier pattern
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// Where the mapping of the repository with this source (the name of its download directory) is kept
+fn repo_mapping_path(repo_source: &str) -> Option<PathBuf> {
+    Some(app_data::get_app_data_dir()?.join(REPO_MAPPINGS_DIR).join(format!("{}.json", repo_source)))
+}
+
+/// Keep the mapping a freshly fetched repository ships for enabling its mods, or forget the kept
+/// one when the repository no longer has one
+pub fn save_repo_mapping(repo_url: &str, mapping: Option<&MappingConfig>) -> Result<(), ModError> {
+    let Some(path) = repo_mapping_path(&get_repo_source(repo_url)) else {
+        return Ok(());
+    };
+    match mapping {
+        Some(mapping) => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(ModError::IoError)?;
+            }
+            let json = serde_json::to_string_pretty(mapping)
+                .map_err(|e| ModError::EnablementError(format!("Failed to serialize mapping: {}", e)))?;
+            fs::write(&path, json).map_err(ModError::IoError)
+        }
+        None if path.exists() => fs::remove_file(&path).map_err(ModError::IoError),
+        None => Ok(()),
+    }
+}
+
+impl MappingConfig {
+    /// Read a mapping file, returning None if it doesn't exist
+    fn load(path: &Path) -> Result<Option<Self>, ModError> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path).map_err(ModError::IoError)?;
+        let config = serde_json::from_str(&content).map_err(|e| {
+            ModError::DirectoryStructureError(format!("Invalid {}: {}", path.display(), e))
+        })?;
+        Ok(Some(config))
+    }
+
+    /// The mapping of the mod's repository, kept from its last fetch, overridden by the mod's
+    /// own mapping file. Mods are downloaded into a directory named after their repo source,
+    /// which finds the repository's mapping
+    pub fn for_mod(mod_dir: &Path) -> Result<Self, ModError> {
+        let repo_path = mod_dir
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|source| repo_mapping_path(&source.to_string_lossy()));
+        let repo_config = match repo_path {
+            Some(path) => Self::load(&path)?,
+            None => None,
+        };
+        let mod_config = Self::load(&mod_dir.join(MAPPING_FILE))?;
+
+        Ok(match (repo_config, mod_config) {
+            (Some(repo), Some(overrides)) => repo.merge(overrides),
+            (Some(config), None) | (None, Some(config)) => config,
+            (None, None) => Self::default(),
+        })
+    }
+
+    /// Combine two configs, with `overrides` winning on depth and on rules for the same path
+    fn merge(mut self, overrides: Self) -> Self {
+        if overrides.target.is_some() {
+            self.target = overrides.target;
+        }
+        if overrides.link_depth.is_some() {
+            self.link_depth = overrides.link_depth;
+        }
+        self.rules
+            .retain(|rule| !overrides.rules.iter().any(|o| o.path.eq_ignore_ascii_case(&rule.path)));
+        self.rules.extend(overrides.rules);
+        self.exclude.extend(overrides.exclude);
+        self
+    }
+
+    pub fn target(&self) -> TargetRoot {
+        self.target.unwrap_or_default()
+    }
+
+    pub fn link_depth(&self) -> u8 {
+        self.link_depth.unwrap_or(DEFAULT_LINK_DEPTH).max(2)
+    }
+
+    /// Strategy for a directory at `level`, `relative` being its path from the main subdirectory
+    pub fn strategy_for(&self, relative: &Path, level: u8) -> DirectoryStrategy {
+        let relative = normalize_path(relative);
+        self.rules
+            .iter()
+            .find(|rule| normalize_path(Path::new(&rule.path)).eq_ignore_ascii_case(&relative))
+            .map(|rule| rule.strategy)
+            .unwrap_or(if level >= self.link_depth() {
+                DirectoryStrategy::Link
+            } else {
+                DirectoryStrategy::Recurse
+            })
+    }
+
+    /// Like strategy_for, but a directory holding excluded entries is never linked as a whole,
+    /// since the link would bring those entries along. `holding_excluded` comes from
+    /// dirs_holding_excluded for the same source root
+    pub fn strategy_for_dir(
+        &self,
+        source_root: &Path,
+        dir: &Path,
+        level: u8,
+        holding_excluded: &HashSet<PathBuf>,
+    ) -> DirectoryStrategy {
+        let relative = dir.strip_prefix(source_root).unwrap_or(dir);
+        match self.strategy_for(relative, level) {
+            DirectoryStrategy::Link if holding_excluded.contains(dir) => DirectoryStrategy::Recurse,
+            strategy => strategy,
+        }
+    }
+
+    /// Whether an entry, `relative` being its path from the main subdirectory, is left out of DCS
+    ///
+    /// Patterns without a `/` match the entry name at any depth, others match the whole relative
+    /// path, a leading `/` anchoring a single name to the top. The last matching pattern wins.
+    pub fn is_excluded(&self, relative: &Path) -> bool {
+        let relative = normalize_path(relative);
+        let name = relative.rsplit('/').next().unwrap_or(&relative);
+        let mut excluded = false;
+        for pattern in DEFAULT_EXCLUDES.iter().copied().chain(self.exclude.iter().map(String::as_str)) {
+            let (negated, pattern) = match pattern.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, pattern),
+            };
+            let subject = if pattern.trim_end_matches('/').contains('/') { relative.as_str() } else { name };
+            let pattern = pattern.trim_matches('/');
+            if glob_match(pattern, subject) {
+                excluded = !negated;
+            }
+        }
+        excluded
+    }
+
+    /// Directories below a source root with excluded entries somewhere inside them, found in a
+    /// single walk before enabling so no directory is searched again for every level above it
+    pub fn dirs_holding_excluded(&self, source_root: &Path) -> HashSet<PathBuf> {
+        let mut dirs = HashSet::new();
+        self.collect_holding_excluded(source_root, source_root, &mut dirs);
+        dirs
+    }
+
+    /// Add `dir` and every directory below it holding excluded entries, returning whether `dir` does
+    fn collect_holding_excluded(&self, source_root: &Path, dir: &Path, dirs: &mut HashSet<PathBuf>) -> bool {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return false;
+        };
+        let mut holds_excluded = false;
+        for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+            // Excluded directories are skipped whole, nothing below them needs looking at
+            let excluded = self.is_excluded(path.strip_prefix(source_root).unwrap_or(&path));
+            if excluded || (path.is_dir() && self.collect_holding_excluded(source_root, &path, dirs)) {
+                holds_excluded = true;
+            }
+        }
+        if holds_excluded {
+            dirs.insert(dir.to_path_buf());
+        }
+        holds_excluded
+    }
+}
+
+/// Case-insensitive glob match: `*` and `?` stay within one path component, `**` spans several
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn matches(pattern: &[char], text: &[char]) -> bool {
+        match pattern.split_first() {
+            None => text.is_empty(),
+            Some(('*', rest)) if rest.first() == Some(&'*') => {
+                let rest = &rest[1..];
+                (0..=text.len()).any(|i| matches(rest, &text[i..]))
+            }
+            Some(('*', rest)) => (0..=text.len())
+                .take_while(|&i| i == 0 || text[i - 1] != '/')
+                .any(|i| matches(rest, &text[i..])),
+            Some(('?', rest)) => text.first().is_some_and(|&c| c != '/') && matches(rest, &text[1..]),
+            Some((&c, rest)) => text.first() == Some(&c) && matches(rest, &text[1..]),
+        }
+    }
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    matches(&pattern, &text)
+}
+
+/// Turn a relative path into a "/"-separated string so rules match on every platform
+fn normalize_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_strategy_matches_fixed_levels() {
+        let config = MappingConfig::default();
+        assert_eq!(config.strategy_for(Path::new("Mods"), 2), DirectoryStrategy::Recurse);
+        assert_eq!(config.strategy_for(Path::new("Mods/aircraft"), 3), DirectoryStrategy::Recurse);
+        assert_eq!(config.strategy_for(Path::new("Mods/aircraft/F-16"), 4), DirectoryStrategy::Link);
+    }
+
+    #[test]
+    fn test_rules_and_overrides() {
+        let repo = MappingConfig {
+            target: Some(TargetRoot::Install),
+            link_depth: Some(3),
+            rules: vec![MappingRule {
+                path: "Liveries/F-16".to_string(),
+                strategy: DirectoryStrategy::Recurse,
+            }],
+            exclude: Vec::new(),
+        };
+        let overrides = MappingConfig {
+            target: None,
+            link_depth: None,
+            rules: vec![MappingRule {
+                path: "liveries/f-16".to_string(),
+                strategy: DirectoryStrategy::Link,
+            }],
+            exclude: Vec::new(),
+        };
+
+        let config = repo.merge(overrides);
+        assert_eq!(config.link_depth(), 3);
+        assert_eq!(config.target(), TargetRoot::Install);
+        assert_eq!(config.rules.len(), 1);
+        assert_eq!(config.strategy_for(Path::new("Liveries/F-16"), 3), DirectoryStrategy::Link);
+        assert_eq!(config.strategy_for(Path::new("Mods/tech"), 3), DirectoryStrategy::Link);
+        assert_eq!(config.strategy_for(Path::new("Mods"), 2), DirectoryStrategy::Recurse);
+    }
+
+    #[test]
+    fn test_exclude_patterns() {
+        let defaults = MappingConfig::default();
+        assert!(defaults.is_excluded(Path::new("Screenshots")));
+        assert!(defaults.is_excluded(Path::new("docs")));
+        assert!(defaults.is_excluded(Path::new("Liveries/F-16/thumbs.db")));
+        assert!(!defaults.is_excluded(Path::new("Mods/aircraft/F-16/Screenshots")));
+        assert!(!defaults.is_excluded(Path::new("Mods/tech/Docs")));
+
+        let config = MappingConfig {
+            exclude: vec!["*.psd".to_string(), "Mods/aircraft/**/extras".to_string(), "!docs".to_string()],
+            ..Default::default()
+        };
+        assert!(config.is_excluded(Path::new("Liveries/F-16/source.PSD")));
+        assert!(config.is_excluded(Path::new("Mods/aircraft/F-16/Cockpit/extras")));
+        assert!(!config.is_excluded(Path::new("Mods/tech/extras")));
+        assert!(!config.is_excluded(Path::new("docs")));
+    }
+
+    #[test]
+    fn test_dirs_holding_excluded_are_not_linked() {
+        let root = tempfile::tempdir().unwrap();
+        let f16 = root.path().join("Mods/aircraft/F-16");
+        let tech = root.path().join("Mods/tech/Radar");
+        fs::create_dir_all(f16.join("Cockpit")).unwrap();
+        fs::create_dir_all(&tech).unwrap();
+        fs::write(f16.join("Cockpit/Thumbs.db"), "").unwrap();
+
+        let config = MappingConfig::default();
+        let holding = config.dirs_holding_excluded(root.path());
+        assert!(holding.contains(&root.path().join("Mods/aircraft")));
+        assert!(!holding.contains(&root.path().join("Mods/tech")));
+        assert_eq!(config.strategy_for_dir(root.path(), &f16, 4, &holding), DirectoryStrategy::Recurse);
+        assert_eq!(config.strategy_for_dir(root.path(), &tech, 4, &holding), DirectoryStrategy::Link);
+    }
+}
@@ -1,5 +1,18 @@
 mod patching;
+mod json_patching;
 mod file_operations;
 mod directory_ops;
+mod backup;
+mod mapping;
+mod verify;
+mod targets;
+mod manifest;
 
-pub use directory_ops::process_second_level_dirs;
\ No newline at end of file
+pub use backup::{get_backup_root, BackupStore};
+pub use directory_ops::{force_cleanup_mod, process_mod_roots, remove_version_patches};
+pub use manifest::{get_manifest_copies_dir, EnablementManifest};
+pub use mapping::{save_repo_mapping, MappingConfig, TargetRoot, MAPPING_FILE};
+pub use file_operations::file_patches;
+pub use patching::{assigned_names, parse_patch_header, PatchAnchor, PatchSyntax};
+pub use targets::{mod_source_roots, TargetDirs};
+pub use verify::{verify_enabled_mod, ModHealthReport};
\ No newline at end of file
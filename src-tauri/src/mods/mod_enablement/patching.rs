@@ -1,86 +1,232 @@
+use std::path::Path;
 use serde_json::json;
 use crate::mods::types::ModError;
+use super::json_patching::merged_names;
 
-pub const PATCH_MARKER: &str = "-- This was added automatically by BorderZone Mod Manager. DO NOT EDIT! --";
+const MARKER_TEXT: &str = "This was added automatically by BorderZone Mod Manager. DO NOT EDIT!";
 
-/// Check if a lua file already has a patch for this mod version
-pub fn check_lua_patch(content: &str, mod_name: &str, version: &str) -> bool {
-    let mut lines = content.lines().peekable();
-    while let Some(line) = lines.next() {
-        if line.trim() == PATCH_MARKER {
-            // Found marker, read json line
-            if let Some(json_line) = lines.next() {
-                if let Ok(info) = serde_json::from_str::<serde_json::Value>(
-                    json_line.trim_start_matches("-- "),
-                ) {
-                    if let (Some(name), Some(ver)) = (
-                        info["mod_name"].as_str(),
-                        info["version"].as_str(),
-                    ) {
-                        if name == mod_name && ver == version {
-                            // Skip rest of the patch block
-                            for line in lines.by_ref() {
-                                if line.trim() == PATCH_MARKER {
-                                    break;
-                                }
-                            }
-                            return true;
-                        }
-                    }
-                }
-                // Skip rest of this block even if it wasn't our patch
-                for line in lines.by_ref() {
-                    if line.trim() == PATCH_MARKER {
-                        break;
-                    }
-                }
+/// Directive placing the patch after the statement starting at the first matching line
+const INSERT_AFTER_DIRECTIVE: &str = "BZMM-INSERT-AFTER:";
+/// Directive placing the patch before the last matching line
+const INSERT_BEFORE_DIRECTIVE: &str = "BZMM-INSERT-BEFORE:";
+
+/// Comment syntax of a patchable text file. Patch blocks use the same marker and JSON
+/// metadata lines in every format, only written as that format's comments. JSON has no
+/// comments, its patches are merged key by key and recorded beside the file (see json_patching).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatchSyntax {
+    comment: &'static str,
+    keyed: bool,
+}
+
+impl PatchSyntax {
+    pub const LUA: Self = Self { comment: "--", keyed: false };
+    pub const INI: Self = Self { comment: ";", keyed: false };
+    pub const JSON: Self = Self { comment: "//", keyed: true };
+
+    /// Syntax for a file based on its extension, None if files of that type can't be patched.
+    /// DCS .cfg files (autoexec.cfg, ...) are lua.
+    pub fn for_path(path: &Path) -> Option<Self> {
+        match lowercase_extension(path)?.as_str() {
+            "lua" | "cfg" => Some(Self::LUA),
+            "ini" => Some(Self::INI),
+            "json" => Some(Self::JSON),
+            _ => None,
+        }
+    }
+
+    /// Whether patches are merged into the file's keys instead of added as marked blocks, so
+    /// the patches of a file are listed from its sidecar rather than its content
+    pub fn is_keyed(&self) -> bool {
+        self.keyed
+    }
+
+    pub fn marker(&self) -> String {
+        format!("{} {} {}", self.comment, MARKER_TEXT, self.comment)
+    }
+
+    fn is_marker(&self, line: &str) -> bool {
+        line.trim() == self.marker()
+    }
+
+    /// Parse the JSON metadata line following a start marker
+    fn parse_info(&self, line: &str) -> Option<PatchInfo> {
+        let json_part = line.trim().strip_prefix(self.comment)?.trim_start();
+        let info = serde_json::from_str::<serde_json::Value>(json_part).ok()?;
+        Some(PatchInfo {
+            mod_name: info["mod_name"].as_str()?.to_string(),
+            version: info["version"].as_str()?.to_string(),
+        })
+    }
+
+    /// Pattern of a header directive line, if the line is that directive
+    fn directive<'a>(&self, line: &'a str, directive: &str) -> Option<&'a str> {
+        line.trim()
+            .strip_prefix(self.comment)?
+            .trim_start()
+            .strip_prefix(directive)
+            .map(str::trim)
+    }
+}
+
+fn lowercase_extension(path: &Path) -> Option<String> {
+    Some(path.extension()?.to_str()?.to_ascii_lowercase())
+}
+
+/// Check if a file already has a patch for this mod version
+pub fn check_patch(content: &str, syntax: PatchSyntax, mod_name: &str, version: &str) -> bool {
+    list_patches(content, syntax)
+        .iter()
+        .any(|info| info.mod_name == mod_name && info.version == version)
+}
+
+/// Where a patch block goes in the destination file
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchAnchor {
+    /// At the end of the file (default)
+    Append,
+    /// After the statement starting at the first line that begins with the pattern
+    InsertAfter(String),
+    /// Before the last line that begins with the pattern, e.g. a final `return`
+    InsertBefore(String),
+}
+
+/// Split the directive header off a patch file, returning the anchor and the remaining patch body.
+/// A directive without a pattern is rejected, it would match the first or last line of any file
+pub fn parse_patch_header(patch: &str, syntax: PatchSyntax) -> Result<(PatchAnchor, String), ModError> {
+    let mut anchor = PatchAnchor::Append;
+    let mut body = Vec::new();
+    let mut in_header = true;
+
+    for line in patch.lines() {
+        if in_header {
+            if let Some(pattern) = syntax.directive(line, INSERT_AFTER_DIRECTIVE) {
+                anchor = PatchAnchor::InsertAfter(anchor_pattern(pattern, INSERT_AFTER_DIRECTIVE)?);
+                continue;
+            }
+            if let Some(pattern) = syntax.directive(line, INSERT_BEFORE_DIRECTIVE) {
+                anchor = PatchAnchor::InsertBefore(anchor_pattern(pattern, INSERT_BEFORE_DIRECTIVE)?);
+                continue;
             }
+            in_header = false;
+        }
+        body.push(line);
+    }
+
+    Ok((anchor, body.join("\n")))
+}
+
+fn anchor_pattern(pattern: &str, directive: &str) -> Result<String, ModError> {
+    if pattern.is_empty() {
+        return Err(ModError::EnablementError(format!(
+            "{} has no pattern, it would match any line",
+            directive.trim_end_matches(':')
+        )));
+    }
+    Ok(pattern.to_string())
+}
+
+/// Indices of the lines outside of existing patch blocks, so anchors never land inside another mod's patch
+fn unpatched_line_indices(lines: &[&str], syntax: PatchSyntax) -> Vec<usize> {
+    let mut indices = Vec::new();
+    let mut in_block = false;
+    for (i, line) in lines.iter().enumerate() {
+        if syntax.is_marker(line) {
+            in_block = !in_block;
+        } else if !in_block {
+            indices.push(i);
+        }
+    }
+    indices
+}
+
+/// Index of the last line of the statement starting at `start`, following `{`/`}` nesting
+fn statement_end(lines: &[&str], start: usize) -> usize {
+    let mut depth = 0i32;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        depth += line.matches('{').count() as i32 - line.matches('}').count() as i32;
+        if depth <= 0 {
+            return i;
         }
     }
-    false
+    lines.len() - 1
 }
 
-/// Add a lua patch to the end of a file
-pub fn add_lua_patch(content: &str, mod_name: &str, version: &str, patch: &str) -> String {
+/// Add a patch to a file, at the end or at the anchor declared in the patch header
+pub fn add_patch(
+    content: &str,
+    syntax: PatchSyntax,
+    mod_name: &str,
+    version: &str,
+    patch: &str,
+) -> Result<String, ModError> {
     let info_json = json!({
         "mod_name": mod_name,
         "version": version
     });
-
-    format!(
-        "{}\n\n{}\n-- {}\n{}\n{}",
-        content.trim_end(),
-        PATCH_MARKER,
+    let (anchor, body) = parse_patch_header(patch, syntax)?;
+    let marker = syntax.marker();
+    let block = format!(
+        "{}\n{} {}\n{}\n{}",
+        marker,
+        syntax.comment,
         info_json,
-        patch.trim(),
-        PATCH_MARKER
-    )
+        body.trim(),
+        marker
+    );
+
+    let lines: Vec<&str> = content.lines().collect();
+    let candidates = unpatched_line_indices(&lines, syntax);
+    let insert_at = match &anchor {
+        PatchAnchor::Append => {
+            return Ok(format!("{}\n\n{}", content.trim_end(), block));
+        }
+        PatchAnchor::InsertAfter(pattern) => candidates
+            .iter()
+            .find(|&&i| lines[i].trim_start().starts_with(pattern.as_str()))
+            .map(|&i| statement_end(&lines, i) + 1),
+        PatchAnchor::InsertBefore(pattern) => candidates
+            .iter()
+            .rev()
+            .find(|&&i| lines[i].trim_start().starts_with(pattern.as_str()))
+            .copied(),
+    };
+
+    let insert_at = insert_at.ok_or_else(|| {
+        ModError::EnablementError(format!(
+            "Patch anchor {:?} for {} not found in destination file",
+            anchor, mod_name
+        ))
+    })?;
+
+    let mut result: Vec<&str> = lines[..insert_at].to_vec();
+    result.extend(block.lines());
+    result.extend(&lines[insert_at..]);
+    Ok(result.join("\n"))
 }
 
-/// Remove a lua patch from a file
-pub fn remove_lua_patch(content: &str, mod_name: &str, version: &str) -> Result<String, ModError> {
+/// Remove a patch from a file
+pub fn remove_patch(
+    content: &str,
+    syntax: PatchSyntax,
+    mod_name: &str,
+    version: &str,
+) -> Result<String, ModError> {
     let mut result = Vec::new();
     let mut lines = content.lines().peekable();
-    
+
     while let Some(line) = lines.next() {
-        if line.trim() == PATCH_MARKER {
+        if syntax.is_marker(line) {
             if let Some(json_line) = lines.next() {
-                if let Ok(info) = serde_json::from_str::<serde_json::Value>(
-                    json_line.trim_start_matches("-- "),
-                ) {
-                    if let (Some(name), Some(ver)) = (
-                        info["mod_name"].as_str(),
-                        info["version"].as_str(),
-                    ) {
-                        if name == mod_name && ver == version {
-                            // Skip until end marker
-                            for line in lines.by_ref() {
-                                if line.trim() == PATCH_MARKER {
-                                    break;
-                                }
+                if let Some(info) = syntax.parse_info(json_line) {
+                    if info.mod_name == mod_name && info.version == version {
+                        // Skip until end marker
+                        for line in lines.by_ref() {
+                            if syntax.is_marker(line) {
+                                break;
                             }
-                            continue;
                         }
+                        continue;
                     }
                 }
                 // If we get here, it wasn't our patch, add both lines back
@@ -89,7 +235,7 @@ pub fn remove_lua_patch(content: &str, mod_name: &str, version: &str) -> Result<
                 // Add rest of block
                 for line in lines.by_ref() {
                     result.push(line);
-                    if line.trim() == PATCH_MARKER {
+                    if syntax.is_marker(line) {
                         break;
                     }
                 }
@@ -100,4 +246,152 @@ pub fn remove_lua_patch(content: &str, mod_name: &str, version: &str) -> Result<
     }
 
     Ok(result.join("\n"))
-}
\ No newline at end of file
+}
+
+/// Mod name and version recorded in a patch block header
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchInfo {
+    pub mod_name: String,
+    pub version: String,
+}
+
+/// List the patch blocks present in a file, in file order
+pub fn list_patches(content: &str, syntax: PatchSyntax) -> Vec<PatchInfo> {
+    let mut patches = Vec::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        if !syntax.is_marker(line) {
+            continue;
+        }
+        if let Some(json_line) = lines.next() {
+            if let Some(info) = syntax.parse_info(json_line) {
+                patches.push(info);
+            }
+            // Skip to the end marker of this block
+            for line in lines.by_ref() {
+                if syntax.is_marker(line) {
+                    break;
+                }
+            }
+        }
+    }
+
+    patches
+}
+
+/// Names a patch body assigns to (`x = ...`, `local x = ...`, `a.b = ...`, or the keys of a JSON
+/// patch), used to spot patches that override each other
+pub fn assigned_names(patch: &str, syntax: PatchSyntax) -> Vec<String> {
+    if syntax.is_keyed() {
+        return merged_names(patch);
+    }
+    let mut names = Vec::new();
+    for line in patch.lines() {
+        let line = line.trim();
+        if line.starts_with(syntax.comment) {
+            continue;
+        }
+        let line = line.strip_prefix("local ").unwrap_or(line);
+        let Some(eq) = line.find('=') else {
+            continue;
+        };
+        if line[eq..].starts_with("==") || line[..eq].ends_with(['~', '<', '>', '=']) {
+            continue;
+        }
+        let name = line[..eq].trim();
+        let is_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '[' | ']' | '"' | '\''));
+        if is_name && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LUA: PatchSyntax = PatchSyntax::LUA;
+    /// Lua marker written by earlier versions, existing patches must keep matching it
+    const PATCH_MARKER: &str = "-- This was added automatically by BorderZone Mod Manager. DO NOT EDIT! --";
+
+    #[test]
+    fn test_list_lua_patches() {
+        let content = "local x = 1";
+        let content = add_patch(content, LUA, "Mod A", "1.0.0", "a = 1").unwrap();
+        let content = add_patch(&content, LUA, "Mod B", "2.1", "b = 2").unwrap();
+        assert!(content.contains(PATCH_MARKER));
+
+        let patches = list_patches(&content, LUA);
+        assert_eq!(
+            patches,
+            vec![
+                PatchInfo { mod_name: "Mod A".to_string(), version: "1.0.0".to_string() },
+                PatchInfo { mod_name: "Mod B".to_string(), version: "2.1".to_string() },
+            ]
+        );
+
+        let content = remove_patch(&content, LUA, "Mod A", "1.0.0").unwrap();
+        let patches = list_patches(&content, LUA);
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].mod_name, "Mod B");
+    }
+
+    #[test]
+    fn test_anchored_lua_patches() {
+        let content = "local M = {\n    a = 1,\n}\nfunction M.f()\n    return 1\nend\nreturn M";
+
+        let patch = "-- BZMM-INSERT-AFTER: local M = {\nM.b = 2";
+        let patched = add_patch(content, LUA, "Mod A", "1.0", patch).unwrap();
+        let lines: Vec<&str> = patched.lines().collect();
+        assert_eq!(lines[3], PATCH_MARKER);
+        assert_eq!(lines[5], "M.b = 2");
+        assert!(!patched.contains("BZMM-INSERT-AFTER"));
+
+        let patch = "-- BZMM-INSERT-BEFORE: return\nM.c = 3";
+        let patched = add_patch(&patched, LUA, "Mod B", "1.0", patch).unwrap();
+        let lines: Vec<&str> = patched.lines().collect();
+        assert_eq!(lines[lines.len() - 1], "return M");
+        assert_eq!(lines[lines.len() - 3], "M.c = 3");
+        assert_eq!(list_patches(&patched, LUA).len(), 2);
+
+        let restored = remove_patch(&patched, LUA, "Mod A", "1.0").unwrap();
+        let restored = remove_patch(&restored, LUA, "Mod B", "1.0").unwrap();
+        assert_eq!(restored, content);
+
+        let missing = "-- BZMM-INSERT-BEFORE: not_there\nx = 1";
+        assert!(add_patch(content, LUA, "Mod C", "1.0", missing).is_err());
+
+        // An empty pattern would match the first line of any file
+        let empty = "-- BZMM-INSERT-AFTER:   \nx = 1";
+        assert!(parse_patch_header(empty, LUA).is_err());
+        assert!(add_patch(content, LUA, "Mod C", "1.0", empty).is_err());
+    }
+
+    #[test]
+    fn test_ini_patches() {
+        let syntax = PatchSyntax::for_path(Path::new("Config/Options.INI")).unwrap();
+        assert_eq!(syntax, PatchSyntax::INI);
+        assert_eq!(PatchSyntax::for_path(Path::new("Config/Options.JSON")), Some(PatchSyntax::JSON));
+        assert!(PatchSyntax::JSON.is_keyed() && !syntax.is_keyed());
+
+        let content = "[graphics]\nwidth=1920";
+        let patched = add_patch(content, syntax, "Mod A", "1.0", "; BZMM-INSERT-AFTER: [graphics]\nvsync=1").unwrap();
+        assert_eq!(patched.lines().nth(1), Some(syntax.marker().as_str()));
+        assert!(check_patch(&patched, syntax, "Mod A", "1.0"));
+        assert!(list_patches(&patched, LUA).is_empty());
+
+        let restored = remove_patch(&patched, syntax, "Mod A", "1.0").unwrap();
+        assert_eq!(restored, content);
+    }
+
+    #[test]
+    fn test_assigned_names() {
+        let patch = "-- comment = 1\nlocal x = 1\nM.options[\"a\"] = true\nif a == b then\n  y = 2\nend\nfoo(z = 3)";
+        assert_eq!(assigned_names(patch, LUA), vec!["x", "M.options[\"a\"]", "y"]);
+    }
+}
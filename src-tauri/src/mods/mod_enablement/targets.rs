@@ -0,0 +1,66 @@
+use std::path::{Path, PathBuf};
+use crate::mods::types::ModError;
+use crate::settings::Profile;
+use super::mapping::{MappingConfig, TargetRoot};
+
+/// Optional directory in a mod (next to the main subdirectory) whose contents go into the DCS install directory
+pub const INSTALL_SUBDIR: &str = "INSTALL";
+
+/// The directories a profile enables mods into
+pub struct TargetDirs {
+    pub saved_games: PathBuf,
+    pub install: Option<PathBuf>,
+    /// Whether DCS files may be patched, off for profiles keeping them untouched
+    pub patch_files: bool,
+}
+
+impl TargetDirs {
+    pub fn from_profile(profile: &Profile) -> Self {
+        Self {
+            saved_games: PathBuf::from(&profile.dcs_path),
+            install: Some(profile.install_path.trim())
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            patch_files: !profile.disable_lua_patching,
+        }
+    }
+
+    /// Destination directory for a target root, failing if the profile doesn't have it set up
+    pub fn get(&self, target: TargetRoot) -> Result<&Path, ModError> {
+        let dir = match target {
+            TargetRoot::SavedGames => self.saved_games.as_path(),
+            TargetRoot::Install => self.install.as_deref().ok_or_else(|| {
+                ModError::SettingsError(
+                    "This mod installs into the DCS install directory, which is not set for the profile"
+                        .to_string(),
+                )
+            })?,
+        };
+
+        if !dir.exists() {
+            return Err(ModError::DirectoryStructureError(format!(
+                "Target directory {} does not exist",
+                dir.display()
+            )));
+        }
+        Ok(dir)
+    }
+}
+
+/// Source directories of a mod paired with the root each one is enabled into.
+///
+/// The main subdirectory goes to the root set by the mapping (Saved Games by default),
+/// an `INSTALL` directory always goes to the DCS install directory.
+pub fn mod_source_roots(
+    mod_dir: &Path,
+    mod_name: &str,
+    mapping: &MappingConfig,
+) -> Vec<(PathBuf, TargetRoot)> {
+    let mut roots = vec![(mod_dir.join(mod_name), mapping.target())];
+
+    let install_dir = mod_dir.join(INSTALL_SUBDIR);
+    if install_dir.is_dir() {
+        roots.push((install_dir, TargetRoot::Install));
+    }
+    roots
+}
@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::mods::enable_lock::LockStatus;
+use crate::mods::types::ModError;
+use crate::mods::mod_utils::{verify_symlink, DestDir};
+use super::mapping::{DirectoryStrategy, MappingConfig};
+use super::file_operations::file_patches;
+use super::patching::PatchSyntax;
+
+/// Result of checking an enabled mod against what enablement should have produced
+#[derive(Debug, Serialize, Default)]
+pub struct ModHealthReport {
+    pub mod_name: String,
+    pub version: Option<String>,
+    pub enabled: bool,
+    pub ok: bool,
+    /// ENABLING lock left on the mod, stale if an enable was interrupted
+    pub lock: Option<LockStatus>,
+    /// Problems with the mod directory itself (missing README.txt, main subdirectory, ...)
+    pub structure_issues: Vec<String>,
+    /// Destination paths that are missing or don't point back into the mod
+    pub broken_links: Vec<String>,
+    /// Lua files that exist in DCS but lack the patch block for the installed version
+    pub missing_patches: Vec<String>,
+    /// Lua files left unpatched on purpose, as the profile doesn't allow patching
+    pub skipped_patches: Vec<String>,
+    /// Problems with VERSION.txt or patch blocks left behind by other versions
+    pub version_issues: Vec<String>,
+}
+
+impl ModHealthReport {
+    pub fn new(mod_name: &str) -> Self {
+        Self {
+            mod_name: mod_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the overall status from the collected problems
+    pub fn finish(mut self) -> Self {
+        self.ok = self.structure_issues.is_empty()
+            && self.broken_links.is_empty()
+            && self.missing_patches.is_empty()
+            && self.version_issues.is_empty()
+            && !self.lock.as_ref().is_some_and(|lock| lock.stale);
+        self
+    }
+}
+
+/// Check a text file that was patched rather than linked
+fn verify_patched_file(
+    dest_path: &Path,
+    syntax: PatchSyntax,
+    mod_name: &str,
+    version: &str,
+    report: &mut ModHealthReport,
+) {
+    let patches = match file_patches(dest_path, syntax) {
+        Ok(patches) => patches,
+        Err(_) => {
+            report.missing_patches.push(dest_path.display().to_string());
+            return;
+        }
+    };
+
+    let own_patches: Vec<_> = patches
+        .into_iter()
+        .filter(|p| p.mod_name == mod_name)
+        .collect();
+
+    if !own_patches.iter().any(|p| p.version == version) {
+        report.missing_patches.push(dest_path.display().to_string());
+    }
+    for stale in own_patches.iter().filter(|p| p.version != version) {
+        report.version_issues.push(format!(
+            "{} contains a patch for version {} instead of {}",
+            dest_path.display(),
+            stale.version,
+            version
+        ));
+    }
+}
+
+/// Check a file that should have been symlinked into DCS
+fn verify_linked_file(path: &Path, dest_path: &Path, report: &mut ModHealthReport) -> Result<(), ModError> {
+    if !dest_path.exists() || !verify_symlink(dest_path, path)? {
+        report.broken_links.push(dest_path.display().to_string());
+    }
+    Ok(())
+}
+
+/// What every step of checking one source root of an enabled mod needs
+struct VerifyContext<'a> {
+    /// The mod's main subdirectory, mapping rules are relative to it
+    source_root: &'a Path,
+    mapping: &'a MappingConfig,
+    /// Directories of the source root with excluded entries inside, which enabling never links whole
+    holding_excluded: HashSet<PathBuf>,
+    mod_name: &'a str,
+    version: &'a str,
+}
+
+impl VerifyContext<'_> {
+    /// Whether a source entry is matched by the mapping's exclude patterns and was left out of DCS
+    fn is_excluded(&self, path: &Path) -> bool {
+        self.mapping
+            .is_excluded(path.strip_prefix(self.source_root).unwrap_or(path))
+    }
+}
+
+/// Check the 4th level and below, mirroring process_deep_directory
+fn verify_deep_directory(
+    source_dir: &Path,
+    dest_dir: &Path,
+    ctx: &VerifyContext,
+    report: &mut ModHealthReport,
+) -> Result<(), ModError> {
+    let dest = DestDir::read(dest_dir);
+    for entry in fs::read_dir(source_dir).map_err(ModError::IoError)? {
+        let path = entry.map_err(ModError::IoError)?.path();
+        if ctx.is_excluded(&path) {
+            continue;
+        }
+        let dest_path = dest.join(path.file_name().unwrap());
+
+        if path.is_dir() {
+            verify_linked_directory(&path, &dest_path, ctx, report)?;
+        } else if let Some(syntax) = PatchSyntax::for_path(&path)
+            .filter(|_| dest_path.exists() && !dest_path.is_symlink())
+        {
+            verify_patched_file(&dest_path, syntax, ctx.mod_name, ctx.version, report);
+        } else {
+            verify_linked_file(&path, &dest_path, report)?;
+        }
+    }
+    Ok(())
+}
+
+/// Check an entry that was linked as a whole directory, or merged into an existing one
+fn verify_linked_directory(
+    path: &Path,
+    dest_path: &Path,
+    ctx: &VerifyContext,
+    report: &mut ModHealthReport,
+) -> Result<(), ModError> {
+    if dest_path.is_symlink() {
+        verify_linked_file(path, dest_path, report)
+    } else if dest_path.is_dir() {
+        verify_deep_directory(path, dest_path, ctx, report)
+    } else {
+        report.broken_links.push(dest_path.display().to_string());
+        Ok(())
+    }
+}
+
+/// Check the structural levels, mirroring process_directory
+fn verify_directory(
+    source_dir: &Path,
+    dest_dir: &Path,
+    ctx: &VerifyContext,
+    level: u8,
+    report: &mut ModHealthReport,
+) -> Result<(), ModError> {
+    let dest = DestDir::read(dest_dir);
+    for entry in fs::read_dir(source_dir).map_err(ModError::IoError)? {
+        let path = entry.map_err(ModError::IoError)?.path();
+        if ctx.is_excluded(&path) {
+            continue;
+        }
+        let dest_path = dest.join(path.file_name().unwrap());
+
+        if path.is_dir() {
+            match ctx.mapping.strategy_for_dir(ctx.source_root, &path, level, &ctx.holding_excluded) {
+                DirectoryStrategy::Link => verify_linked_directory(&path, &dest_path, ctx, report)?,
+                _ if !dest_path.is_dir() => {
+                    report.broken_links.push(dest_path.display().to_string());
+                }
+                DirectoryStrategy::Recurse if level + 1 < ctx.mapping.link_depth() => {
+                    verify_directory(&path, &dest_path, ctx, level + 1, report)?
+                }
+                DirectoryStrategy::Recurse => verify_deep_directory(&path, &dest_path, ctx, report)?,
+            }
+        } else if path.is_file() {
+            verify_linked_file(&path, &dest_path, report)?;
+        }
+    }
+    Ok(())
+}
+
+/// Walk an enabled mod's main subdirectory and record everything missing from the DCS directory
+pub fn verify_enabled_mod(
+    source_dir: &Path,
+    dcs_dir: &Path,
+    mapping: &MappingConfig,
+    mod_name: &str,
+    version: &str,
+    report: &mut ModHealthReport,
+) -> Result<(), ModError> {
+    let ctx = VerifyContext {
+        source_root: source_dir,
+        mapping,
+        holding_excluded: mapping.dirs_holding_excluded(source_dir),
+        mod_name,
+        version,
+    };
+    verify_directory(source_dir, dcs_dir, &ctx, 2, report)
+}
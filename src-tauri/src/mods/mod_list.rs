@@ -0,0 +1,177 @@
+use super::download_queue::{get_queue, DownloadTarget};
+use super::downloader::ModDownloader;
+use super::mod_download::is_mod_successfully_downloaded;
+use super::mod_management::{enable_mod, ModOperationOutcome};
+use super::mod_utils::{get_mod_version, get_repo_download_dir, list_enabled_mod_dirs};
+use super::types::ModsFile;
+use super::xml_cache::XmlCache;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+/// Version of the shared mod list file format
+const MOD_LIST_FORMAT_VERSION: u32 = 1;
+
+/// One enabled mod in a shared mod list
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SharedModEntry {
+    pub name: String,
+    pub version: String,
+    /// Repository URL the mod was downloaded from, `None` for sideloaded mods
+    pub source: Option<String>,
+}
+
+/// A profile's enabled mods in a form that can be shared with other users
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SharedModList {
+    pub format_version: u32,
+    pub repo_url: String,
+    pub mods: Vec<SharedModEntry>,
+}
+
+fn shared_entries(mod_dirs: Vec<PathBuf>, source: Option<&str>) -> Vec<SharedModEntry> {
+    mod_dirs
+        .iter()
+        .filter_map(|dir| {
+            let name = dir.file_name()?.to_str()?.to_string();
+            let version = get_mod_version(dir).unwrap_or_else(|_| "Unknown".to_string());
+            Some(SharedModEntry {
+                name,
+                version,
+                source: source.map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// Build the shared mod list for a profile from its enabled mods
+pub fn build_mod_list(settings: &Settings, profile_name: &str) -> Result<SharedModList, String> {
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+
+    let repo_dir = get_repo_download_dir(&settings.profile_download_path(profile), &profile.repo_url);
+    let mut mods = shared_entries(
+        list_enabled_mod_dirs(&repo_dir, profile_name)?,
+        Some(&profile.repo_url),
+    );
+    if !settings.sideload_path.is_empty() {
+        mods.extend(shared_entries(
+            list_enabled_mod_dirs(Path::new(&settings.sideload_path), profile_name)?,
+            None,
+        ));
+    }
+    mods.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(SharedModList {
+        format_version: MOD_LIST_FORMAT_VERSION,
+        repo_url: profile.repo_url.clone(),
+        mods,
+    })
+}
+
+/// Write the profile's enabled mods to a JSON file that can be posted for others to import
+#[tauri::command]
+pub async fn export_mod_list(profile_name: String, file_path: String) -> Result<SharedModList, String> {
+    let settings = Settings::load()?;
+    let mod_list = build_mod_list(&settings, &profile_name)?;
+
+    let content = serde_json::to_string_pretty(&mod_list)
+        .map_err(|e| format!("Failed to serialize mod list: {}", e))?;
+    std::fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write mod list file: {}", e))?;
+
+    info!("Exported {} mods to {}", mod_list.mods.len(), file_path);
+    Ok(mod_list)
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ModListImportResult {
+    /// Mods that were already downloaded and have been enabled
+    pub enabled: Vec<ModOperationOutcome>,
+    /// Mods queued for download, enabled automatically once extracted
+    pub queued: Vec<String>,
+    /// Entries that could not be matched to the profile's repository or sideload directory
+    pub unmatched: Vec<String>,
+}
+
+/// Load the profile's repository, falling back to the cached copy when offline
+pub(crate) async fn load_repo_mods(repo_url: &str) -> Result<ModsFile, String> {
+    let url = repo_url.trim_end_matches('/');
+    match ModDownloader::new().fetch_and_parse_mods(url).await {
+        Ok((mods_file, _)) => Ok(mods_file),
+        Err(e) => {
+            warn!("Failed to fetch repository for import, trying cache: {}", e);
+            let path = XmlCache::get_cache_path(url)
+                .ok_or_else(|| format!("Failed to load repository XML: {}", e))?;
+            XmlCache::load_xml(&path, url).map_err(|cache_err| {
+                format!("Failed to load repository XML: {}. Cache unavailable: {}", e, cache_err)
+            })
+        }
+    }
+}
+
+/// Read a shared mod list, enable what is already installed and queue downloads for the rest
+#[tauri::command]
+pub async fn import_mod_list(
+    app_handle: AppHandle,
+    profile_name: String,
+    file_path: String,
+) -> Result<ModListImportResult, String> {
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read mod list file: {}", e))?;
+    let mod_list: SharedModList = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse mod list file: {}", e))?;
+
+    let settings = Settings::load()?;
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+    let repo_url = profile.repo_url.clone();
+    let repo_dir = get_repo_download_dir(&settings.profile_download_path(profile), &repo_url);
+
+    let repo_mods = load_repo_mods(&repo_url).await?;
+    let mut result = ModListImportResult::default();
+
+    for entry in mod_list.mods {
+        let sideloaded = !settings.sideload_path.is_empty()
+            && Path::new(&settings.sideload_path).join(&entry.name).is_dir();
+
+        if sideloaded || is_mod_successfully_downloaded(&repo_dir, &entry.name) {
+            let outcome = enable_mod(app_handle.clone(), entry.name.clone(), profile_name.clone(), None).await;
+            result.enabled.push(ModOperationOutcome::from_result(&entry.name, outcome));
+            continue;
+        }
+
+        let repo_mod = repo_mods
+            .categories
+            .iter()
+            .flat_map(|cat| cat.mods.iter())
+            .find(|m| m.name == entry.name);
+
+        match repo_mod.and_then(|m| Some((m.url.clone()?, m.size))) {
+            Some((url, size)) => {
+                get_queue()
+                    .add_download(
+                        app_handle.clone(),
+                        url,
+                        format!("{}.zip", entry.name),
+                        repo_url.clone(),
+                        size,
+                        DownloadTarget::enabling(profile_name.clone()),
+                    )
+                    .await;
+                result.queued.push(entry.name);
+            }
+            None => result.unmatched.push(entry.name),
+        }
+    }
+
+    Ok(result)
+}
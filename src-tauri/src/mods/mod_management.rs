@@ -1,25 +1,91 @@
 use crate::mods::mod_enablement::*;
+use crate::mods::dcs_version::dcs_compatibility_note;
+use crate::mods::dependencies::enable_requirements;
+use crate::mods::enable_state;
+use crate::mods::enable_lock::{read_lock_status, write_enabling_lock, LockStatus};
+use crate::mods::hooks::{run_hook, HookEvent};
 use crate::mods::mod_utils::*;
+use crate::mods::progress::{calculate_progress, BatchProgress};
 use crate::mods::types::ModError;
+use crate::mods::variants::{variant_for_download, variant_mismatch_note};
 use crate::settings::Settings;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
-use std::path::PathBuf;
-use tauri::AppHandle;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
 use tokio::fs;
+use tracing::{debug, error, info, instrument, warn};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ModResult {
-    success: bool,
-    message: Option<String>,
+    pub success: bool,
+    pub message: Option<String>,
 }
 
-/// Finds the directory for a given mod, checking the profile-specific download path first, then sideload.
+/// Outcome of one mod within an operation covering several mods
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModOperationOutcome {
+    pub mod_name: String,
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+impl ModOperationOutcome {
+    pub fn from_result(mod_name: &str, result: Result<ModResult, String>) -> Self {
+        match result {
+            Ok(result) => Self {
+                mod_name: mod_name.to_string(),
+                success: result.success,
+                message: result.message,
+            },
+            Err(e) => Self {
+                mod_name: mod_name.to_string(),
+                success: false,
+                message: Some(e),
+            },
+        }
+    }
+}
+
+/// Resolve a mod in one explicit source, so a name present in several places hits the intended copy
+fn find_mod_dir_in_source(settings: &Settings, mod_name: &str, source: &str) -> Result<PathBuf, ModError> {
+    // A source is a single directory name, never a path
+    if Path::new(source).components().count() != 1 || source.contains(['/', '\\', '.']) {
+        return Err(ModError::SettingsError(format!("Invalid mod source '{}'", source)));
+    }
+
+    let base_dir = if source == SIDELOAD_SOURCE {
+        if settings.sideload_path.is_empty() {
+            return Err(ModError::SettingsError("Sideload path is not set".to_string()));
+        }
+        PathBuf::from(&settings.sideload_path)
+    } else {
+        source_download_dir(settings, source)
+    };
+
+    let mod_dir = base_dir.join(mod_name);
+    if mod_dir.is_dir() {
+        Ok(mod_dir)
+    } else {
+        Err(ModError::DirectoryStructureError(format!(
+            "Could not find mod '{}' in source '{}'",
+            mod_name, source
+        )))
+    }
+}
+
+/// Finds the directory for a given mod. With a source (repo-hash directory name or "sideload")
+/// only that copy is considered, otherwise the profile-specific download path is checked first, then sideload.
 async fn find_mod_dir(
     settings: &Settings,
     mod_name: &str,
     profile_name: &str,
+    source: Option<&str>,
 ) -> Result<PathBuf, ModError> {
+    if let Some(source) = source {
+        return find_mod_dir_in_source(settings, mod_name, source);
+    }
+
     // Find the profile to get the repo_url
     let profile = settings
         .profiles
@@ -30,31 +96,25 @@ async fn find_mod_dir(
         })?;
 
     // Calculate the XML-specific path
-    let base_downloads_dir = PathBuf::from(&settings.download_path);
-    let mut hasher = Sha256::new();
-    hasher.update(profile.repo_url.as_bytes());
-    let hash_result = hasher.finalize();
-    let repo_hash = format!("{:x}", hash_result);
-    let repo_hash = &repo_hash[..6]; // Shrink the hash to 6 characters
-    let xml_specific_path = base_downloads_dir.join(repo_hash);
+    let xml_specific_path = get_repo_download_dir(&settings.profile_download_path(profile), &profile.repo_url);
     let mod_path_in_xml_dir = xml_specific_path.join(mod_name);
 
-    println!("Searching for mod '{}' in specific path: {}", mod_name, mod_path_in_xml_dir.display());
+    debug!("Searching for mod '{}' in specific path: {}", mod_name, mod_path_in_xml_dir.display());
     if mod_path_in_xml_dir.is_dir() {
         return Ok(mod_path_in_xml_dir);
     }
-    println!("Mod '{}' not found in specific path.", mod_name);
+    debug!("Mod '{}' not found in specific path.", mod_name);
 
     // If not found in profile-specific dir, check sideload path
     if !settings.sideload_path.is_empty() {
-        println!("Checking sideload path: {}", settings.sideload_path);
+        debug!("Checking sideload path: {}", settings.sideload_path);
         let sideload_dir = PathBuf::from(&settings.sideload_path).join(mod_name);
         if sideload_dir.exists() {
             return Ok(sideload_dir);
         }
-         println!("Mod '{}' not found in sideload path.", mod_name);
+        debug!("Mod '{}' not found in sideload path.", mod_name);
     } else {
-        println!("Sideload path is empty, skipping check.");
+        debug!("Sideload path is empty, skipping check.");
     }
 
     Err(ModError::DirectoryStructureError(format!(
@@ -65,7 +125,59 @@ async fn find_mod_dir(
 }
 
 #[tauri::command]
-pub async fn enable_mod(mod_name: String, profile_name: String) -> Result<ModResult, String> {
+pub async fn enable_mod(
+    app_handle: AppHandle,
+    mod_name: String,
+    profile_name: String,
+    source: Option<String>,
+) -> Result<ModResult, String> {
+    // Whatever the mod needs goes in first, a sideloaded copy has no repository entry to check
+    let (note, dcs_note, variant_note) = if source.as_deref() == Some(SIDELOAD_SOURCE) {
+        (None, None, None)
+    } else {
+        (
+            enable_requirements(&app_handle, &profile_name, &mod_name).await?,
+            dcs_compatibility_note(&profile_name, &mod_name),
+            variant_mismatch_note(&profile_name, &mod_name),
+        )
+    };
+
+    let mut result = enable_single_mod(app_handle, mod_name, profile_name, source).await?;
+    let notes: Vec<String> =
+        result.message.take().into_iter().chain(note).chain(dcs_note).chain(variant_note).collect();
+    result.message = (!notes.is_empty()).then(|| notes.join(". "));
+    Ok(result)
+}
+
+/// Warning for a mod enabled without the patches its profile doesn't allow, naming the DCS files
+/// it would have changed
+fn skipped_patches_note(mod_dir: &Path, profile_name: &str, mod_name: &str) -> Option<String> {
+    let manifest = EnablementManifest::load(&get_manifest_path(mod_dir, profile_name)).ok()??;
+    if manifest.skipped_patches.is_empty() {
+        return None;
+    }
+    let files: Vec<String> = manifest
+        .skipped_patches
+        .iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .collect();
+    info!("{} enabled for {} without patching {}", mod_name, profile_name, files.join(", "));
+    Some(format!(
+        "Lua patching is off for this profile, so {} is only partially installed: {} left unpatched",
+        mod_name,
+        files.join(", ")
+    ))
+}
+
+/// Enable one mod for a profile, without looking at its requirements
+#[instrument(name = "enable", skip_all, fields(mod_name = %mod_name, profile = %profile_name))]
+pub(crate) async fn enable_single_mod(
+    app_handle: AppHandle,
+    mod_name: String,
+    profile_name: String,
+    source: Option<String>,
+) -> Result<ModResult, String> {
     let result: Result<ModResult, ModError> = async move {
         let settings = Settings::load().map_err(ModError::SettingsError)?;
         let profile = settings
@@ -74,64 +186,65 @@ pub async fn enable_mod(mod_name: String, profile_name: String) -> Result<ModRes
             .find(|p| p.name == profile_name)
             .ok_or_else(|| ModError::SettingsError("Profile not found".to_string()))?;
 
-        let dcs_dir = PathBuf::from(&profile.dcs_path);
-        if !dcs_dir.exists() {
+        let targets = TargetDirs::from_profile(profile);
+        if !targets.saved_games.exists() {
             return Err(ModError::DirectoryStructureError(
                 "DCS path does not exist".to_string(),
             ));
         }
 
         // Pass profile_name to find_mod_dir
-        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name).await?;
+        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name, source.as_deref()).await?;
         verify_mod_structure(&mod_dir)?;
 
-        let enabled_path = get_enabled_file_path(&mod_dir, &profile_name);
         let enabling_path = get_enabling_file_path(&mod_dir, &profile_name);
 
-        if enabled_path.exists() {
+        if is_mod_enabled(&mod_dir, &profile_name).map_err(ModError::EnablementError)? {
             return Ok(ModResult {
                 success: true,
                 message: Some("Mod already enabled".to_string()),
             });
         }
 
-        if enabling_path.exists() {
-            return Err(ModError::EnablementError(
-                "Mod is currently being enabled".to_string(),
-            ));
+        if let Some(lock) = read_lock_status(&mod_dir, &profile_name) {
+            return Err(ModError::EnablementError(if lock.stale {
+                "Mod has a stale lock from an interrupted enable, force unlock it first".to_string()
+            } else {
+                "Mod is currently being enabled".to_string()
+            }));
         }
 
-        fs::write(&enabling_path, "")
-            .await
-            .map_err(ModError::IoError)?;
+        write_enabling_lock(&mod_dir, &profile_name)?;
 
         let version = get_mod_version(&mod_dir)?;
-        let main_subdir = mod_dir.join(&mod_name);
 
         let process_result =
-            process_second_level_dirs(&main_subdir, &dcs_dir, &mod_name, &version, false).await;
+            process_mod_roots(&mod_dir, &targets, &mod_name, &version, &profile_name, false, &app_handle)
+                .await;
 
         if let Err(ref e) = process_result {
-            println!("Error during enablement: {}", e);
+            warn!("Error during enablement: {}", e);
             if let Err(cleanup_err) =
-                process_second_level_dirs(&main_subdir, &dcs_dir, &mod_name, &version, true).await
+                process_mod_roots(&mod_dir, &targets, &mod_name, &version, &profile_name, true, &app_handle)
+                    .await
             {
-                println!("Warning: Cleanup also failed: {}", cleanup_err);
+                warn!("Cleanup also failed: {}", cleanup_err);
             }
         }
 
         if let Err(e) = fs::remove_file(&enabling_path).await {
-            println!("Warning: Failed to clean up ENABLING file: {}", e);
+            warn!("Failed to clean up ENABLING file: {}", e);
         }
 
         process_result?;
-        fs::write(&enabled_path, "")
-            .await
-            .map_err(ModError::IoError)?;
+        enable_state::set_enabled(&mod_dir, &profile_name, true)?;
+        if profile.hook_on_enable {
+            run_hook(profile, HookEvent::Enabled, &mod_name, &mod_dir);
+        }
 
         Ok(ModResult {
             success: true,
-            message: None,
+            message: skipped_patches_note(&mod_dir, &profile_name, &mod_name),
         })
     }
     .await;
@@ -143,7 +256,13 @@ pub async fn enable_mod(mod_name: String, profile_name: String) -> Result<ModRes
 }
 
 #[tauri::command]
-pub async fn disable_mod(mod_name: String, profile_name: String) -> Result<ModResult, String> {
+#[instrument(name = "disable", skip_all, fields(mod_name = %mod_name, profile = %profile_name))]
+pub async fn disable_mod(
+    app_handle: AppHandle,
+    mod_name: String,
+    profile_name: String,
+    source: Option<String>,
+) -> Result<ModResult, String> {
     let result: Result<ModResult, ModError> = async move {
         let settings = Settings::load().map_err(ModError::SettingsError)?;
         let profile = settings
@@ -153,25 +272,32 @@ pub async fn disable_mod(mod_name: String, profile_name: String) -> Result<ModRe
             .ok_or_else(|| ModError::SettingsError("Profile not found".to_string()))?;
 
         // Pass profile_name to find_mod_dir
-        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name).await?;
-        verify_mod_structure(&mod_dir)?;
+        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name, source.as_deref()).await?;
+
+        // With a manifest cleanup doesn't depend on the source folder, which may have changed since enabling
+        let has_manifest = get_manifest_path(&mod_dir, &profile_name).exists();
+        if !has_manifest {
+            verify_mod_structure(&mod_dir)?;
+        }
 
-        let enabled_path = get_enabled_file_path(&mod_dir, &profile_name);
-        if !enabled_path.exists() {
+        if !is_mod_enabled(&mod_dir, &profile_name).map_err(ModError::EnablementError)? {
             return Ok(ModResult {
                 success: true,
                 message: Some("Mod already disabled".to_string()),
             });
         }
 
-        let version = get_mod_version(&mod_dir)?;
-        let main_subdir = mod_dir.join(&mod_name);
-        let dcs_dir = PathBuf::from(&profile.dcs_path);
+        let version = match get_mod_version(&mod_dir) {
+            Ok(version) => version,
+            // The manifest records the enabled version
+            Err(_) if has_manifest => String::new(),
+            Err(e) => return Err(e),
+        };
+        let targets = TargetDirs::from_profile(profile);
 
-        process_second_level_dirs(&main_subdir, &dcs_dir, &mod_name, &version, true).await?;
-        fs::remove_file(&enabled_path)
-            .await
-            .map_err(ModError::IoError)?;
+        process_mod_roots(&mod_dir, &targets, &mod_name, &version, &profile_name, true, &app_handle)
+            .await?;
+        enable_state::set_enabled(&mod_dir, &profile_name, false)?;
 
         Ok(ModResult {
             success: true,
@@ -186,13 +312,202 @@ pub async fn disable_mod(mod_name: String, profile_name: String) -> Result<ModRe
     }
 }
 
+/// Re-run enablement for a mod that is already marked enabled, recreating missing links and patches
+async fn reapply_mod(
+    app_handle: &AppHandle,
+    mod_dir: &Path,
+    targets: &TargetDirs,
+    profile_name: &str,
+) -> Result<(), ModError> {
+    verify_mod_structure(mod_dir)?;
+    let mod_name = mod_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| ModError::DirectoryStructureError("Invalid mod path".to_string()))?;
+
+    if get_enabling_file_path(mod_dir, profile_name).exists() {
+        return Err(ModError::EnablementError(
+            "Mod is currently being enabled".to_string(),
+        ));
+    }
+
+    let version = get_mod_version(mod_dir)?;
+    process_mod_roots(mod_dir, targets, mod_name, &version, profile_name, false, app_handle).await
+}
+
+/// Desired state of one mod in `set_mods_enabled`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModStateChange {
+    pub mod_name: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Enable and disable several mods one after the other, emitting a single `batch-progress` stream
+/// and reporting each mod. Disables run first so mods leaving free up files the new ones need.
 #[tauri::command]
-pub async fn delete_mod(mod_name: String, profile_name: String) -> Result<ModResult, String> {
+pub async fn set_mods_enabled(
+    app_handle: AppHandle,
+    profile_name: String,
+    changes: Vec<ModStateChange>,
+) -> Result<Vec<ModOperationOutcome>, String> {
+    let (to_enable, to_disable): (Vec<_>, Vec<_>) = changes.into_iter().partition(|c| c.enabled);
+    let total = to_enable.len() + to_disable.len();
+    let mut outcomes = Vec::with_capacity(total);
+
+    for change in to_disable.into_iter().chain(to_enable) {
+        let result = if change.enabled {
+            enable_mod(app_handle.clone(), change.mod_name.clone(), profile_name.clone(), change.source).await
+        } else {
+            disable_mod(app_handle.clone(), change.mod_name.clone(), profile_name.clone(), change.source).await
+        };
+        let outcome = ModOperationOutcome::from_result(&change.mod_name, result);
+
+        let processed = outcomes.len() + 1;
+        if let Err(e) = app_handle.emit(
+            "batch-progress",
+            BatchProgress {
+                mod_name: change.mod_name,
+                operation: if change.enabled { "enable" } else { "disable" }.to_string(),
+                success: outcome.success,
+                processed,
+                total,
+                progress_percent: calculate_progress(processed as u64, total as u64),
+            },
+        ) {
+            error!("Failed to emit batch-progress event: {}", e);
+        }
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
+/// Re-apply every mod marked enabled for a profile, e.g. after a DCS update or repair wiped Saved Games
+#[tauri::command]
+pub async fn reapply_enabled_mods(
+    app_handle: AppHandle,
+    profile_name: String,
+) -> Result<Vec<ModOperationOutcome>, String> {
+    let settings = Settings::load()?;
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+
+    let targets = TargetDirs::from_profile(profile);
+    if !targets.saved_games.exists() {
+        return Err("DCS path does not exist".to_string());
+    }
+
+    let mut outcomes = Vec::new();
+    for mod_dir in list_profile_enabled_mod_dirs(&settings, profile)? {
+        let mod_name = mod_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        info!("Re-applying enabled mod: {}", mod_name);
+
+        let outcome = match reapply_mod(&app_handle, &mod_dir, &targets, &profile_name).await {
+            Ok(()) => ModOperationOutcome {
+                mod_name,
+                success: true,
+                message: None,
+            },
+            Err(e) => {
+                warn!("Failed to re-apply {}: {}", mod_name, e);
+                ModOperationOutcome {
+                    mod_name,
+                    success: false,
+                    message: Some(e.to_string()),
+                }
+            }
+        };
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
+#[tauri::command]
+pub async fn verify_mod(mod_name: String, profile_name: String) -> Result<ModHealthReport, String> {
+    let result: Result<ModHealthReport, ModError> = async move {
+        let settings = Settings::load().map_err(ModError::SettingsError)?;
+        let profile = settings
+            .profiles
+            .iter()
+            .find(|p| p.name == profile_name)
+            .ok_or_else(|| ModError::SettingsError("Profile not found".to_string()))?;
+
+        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name, None).await?;
+        let mut report = ModHealthReport::new(&mod_name);
+
+        if let Err(e) = verify_mod_structure(&mod_dir) {
+            report.structure_issues.push(e.to_string());
+            return Ok(report.finish());
+        }
+
+        match get_mod_version(&mod_dir) {
+            Ok(version) if !version.is_empty() => report.version = Some(version),
+            Ok(_) => report.version_issues.push("VERSION.txt is empty".to_string()),
+            Err(e) => report.version_issues.push(format!("Failed to read VERSION.txt: {}", e)),
+        }
+
+        report.enabled = is_mod_enabled(&mod_dir, &profile_name).map_err(ModError::EnablementError)?;
+        report.lock = read_lock_status(&mod_dir, &profile_name);
+        if report.enabled {
+            if let Some(version) = report.version.clone() {
+                let targets = TargetDirs::from_profile(profile);
+                let mapping = MappingConfig::for_mod(&mod_dir)?;
+                for (source_dir, target) in mod_source_roots(&mod_dir, &mod_name, &mapping) {
+                    match targets.get(target) {
+                        Ok(dest_dir) => verify_enabled_mod(
+                            &source_dir,
+                            dest_dir,
+                            &mapping,
+                            &mod_name,
+                            &version,
+                            &mut report,
+                        )?,
+                        Err(e) => report.structure_issues.push(e.to_string()),
+                    }
+                }
+                // Patches left out on purpose aren't missing
+                if let Some(manifest) = EnablementManifest::load(&get_manifest_path(&mod_dir, &profile_name))? {
+                    let skipped: Vec<String> =
+                        manifest.skipped_patches.iter().map(|path| path.display().to_string()).collect();
+                    report.missing_patches.retain(|path| !skipped.contains(path));
+                    report.skipped_patches = skipped;
+                }
+            }
+        }
+
+        Ok(report.finish())
+    }
+    .await;
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_mod(
+    app_handle: AppHandle,
+    mod_name: String,
+    profile_name: String,
+    source: Option<String>,
+) -> Result<ModResult, String> {
     let result: Result<ModResult, ModError> = async move {
         let settings = Settings::load().map_err(ModError::SettingsError)?;
 
-        // Check if mod is in sideload directory
-        if !settings.sideload_path.is_empty() {
+        // Check if mod is in sideload directory, unless another copy was asked for explicitly
+        let sideload_requested = source.as_deref().is_none_or(|source| source == SIDELOAD_SOURCE);
+        if sideload_requested && !settings.sideload_path.is_empty() {
             let sideload_dir = PathBuf::from(&settings.sideload_path);
             if sideload_dir.join(&mod_name).exists() {
                 return Err(ModError::EnablementError(
@@ -202,23 +517,27 @@ pub async fn delete_mod(mod_name: String, profile_name: String) -> Result<ModRes
         }
 
         // Pass profile_name to find_mod_dir
-        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name).await?;
+        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name, source.as_deref()).await?;
 
         // Check if the mod is enabled for the current profile
-        let enabled_path = get_enabled_file_path(&mod_dir, &profile_name);
-        if enabled_path.exists() {
+        if is_mod_enabled(&mod_dir, &profile_name).map_err(ModError::EnablementError)? {
             // Disable the mod first
-            disable_mod(mod_name.clone(), profile_name.clone())
+            disable_mod(app_handle.clone(), mod_name.clone(), profile_name.clone(), source.clone())
                 .await
                 .map_err(ModError::EnablementError)?;
         }
 
         // Delete the mod directory
         match fs::remove_dir_all(&mod_dir).await {
-            Ok(_) => Ok(ModResult {
-                success: true,
-                message: Some("Mod deleted successfully".to_string()),
-            }),
+            Ok(_) => {
+                if let Err(e) = super::installed::forget_installed_mod(&mod_dir) {
+                    warn!("Failed to forget deleted mod {}: {}", mod_name, e);
+                }
+                Ok(ModResult {
+                    success: true,
+                    message: Some("Mod deleted successfully".to_string()),
+                })
+            }
             Err(e) => Err(ModError::IoError(e)),
         }
     }
@@ -230,6 +549,224 @@ pub async fn delete_mod(mod_name: String, profile_name: String) -> Result<ModRes
     }
 }
 
+/// Show a mod's directory in the system file manager (Explorer, Finder, ...)
+#[tauri::command]
+pub async fn open_mod_folder(mod_name: String, profile_name: String) -> Result<ModResult, String> {
+    let result: Result<ModResult, ModError> = async move {
+        let settings = Settings::load().map_err(ModError::SettingsError)?;
+        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name, None).await?;
+
+        #[cfg(target_os = "windows")]
+        let program = "explorer";
+        #[cfg(target_os = "macos")]
+        let program = "open";
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let program = "xdg-open";
+
+        // The file manager keeps running on its own, there is nothing to wait for
+        std::process::Command::new(program)
+            .arg(&mod_dir)
+            .spawn()
+            .map_err(ModError::IoError)?;
+
+        Ok(ModResult {
+            success: true,
+            message: Some(mod_dir.display().to_string()),
+        })
+    }
+    .await;
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// List the ENABLING locks on a profile's mods, flagging the ones left behind by an interrupted
+/// enable. Without a profile name the active profile's
+#[tauri::command]
+pub async fn get_mod_locks(profile_name: Option<String>) -> Result<Vec<LockStatus>, String> {
+    let settings = Settings::load()?;
+    let profile_name = settings.profile_or_active(profile_name)?;
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+
+    Ok(list_profile_mod_dirs(&settings, profile)
+        .iter()
+        .filter_map(|mod_dir| read_lock_status(mod_dir, &profile_name))
+        .collect())
+}
+
+/// Clear a stale ENABLING lock, rolling back whatever the interrupted enable left in DCS
+#[tauri::command]
+pub async fn force_unlock_mod(
+    app_handle: AppHandle,
+    mod_name: String,
+    profile_name: String,
+) -> Result<ModResult, String> {
+    let result: Result<ModResult, ModError> = async move {
+        let settings = Settings::load().map_err(ModError::SettingsError)?;
+        let profile = settings
+            .profiles
+            .iter()
+            .find(|p| p.name == profile_name)
+            .ok_or_else(|| ModError::SettingsError("Profile not found".to_string()))?;
+
+        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name, None).await?;
+        let Some(lock) = read_lock_status(&mod_dir, &profile_name) else {
+            return Ok(ModResult {
+                success: true,
+                message: Some("Mod is not locked".to_string()),
+            });
+        };
+        if !lock.stale {
+            return Err(ModError::EnablementError(
+                "Mod is currently being enabled".to_string(),
+            ));
+        }
+
+        // Same rollback as a failed enable
+        verify_mod_structure(&mod_dir)?;
+        let version = get_mod_version(&mod_dir)?;
+        let targets = TargetDirs::from_profile(profile);
+        process_mod_roots(&mod_dir, &targets, &mod_name, &version, &profile_name, true, &app_handle)
+            .await?;
+
+        fs::remove_file(get_enabling_file_path(&mod_dir, &profile_name))
+            .await
+            .map_err(ModError::IoError)?;
+        // An interrupted enable never finished, so the mod ends up disabled
+        enable_state::set_enabled(&mod_dir, &profile_name, false)?;
+
+        Ok(ModResult {
+            success: true,
+            message: Some("Stale lock removed and partial changes rolled back".to_string()),
+        })
+    }
+    .await;
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Disable a mod whose source folder is gone or broken, removing its leftovers from DCS and its markers
+#[tauri::command]
+pub async fn force_disable_mod(
+    app_handle: AppHandle,
+    mod_name: String,
+    profile_name: String,
+) -> Result<ModResult, String> {
+    let result: Result<ModResult, ModError> = async move {
+        let settings = Settings::load().map_err(ModError::SettingsError)?;
+        let profile = settings
+            .profiles
+            .iter()
+            .find(|p| p.name == profile_name)
+            .ok_or_else(|| ModError::SettingsError("Profile not found".to_string()))?;
+
+        // Without the mod directory, look for links into every place it could have been
+        let mod_dirs = match find_mod_dir(&settings, &mod_name, &profile_name, None).await {
+            Ok(mod_dir) => vec![mod_dir],
+            Err(_) => {
+                let mut candidates = vec![get_repo_download_dir(
+                    &settings.profile_download_path(profile),
+                    &profile.repo_url,
+                )
+                .join(&mod_name)];
+                if !settings.sideload_path.is_empty() {
+                    candidates.push(PathBuf::from(&settings.sideload_path).join(&mod_name));
+                }
+                candidates
+            }
+        };
+
+        if let Some(lock) = mod_dirs
+            .iter()
+            .find_map(|mod_dir| read_lock_status(mod_dir, &profile_name))
+            .filter(|lock| !lock.stale)
+        {
+            return Err(ModError::EnablementError(format!(
+                "{} is currently being enabled",
+                lock.mod_name
+            )));
+        }
+
+        let targets = TargetDirs::from_profile(profile);
+        force_cleanup_mod(&mod_dirs, &targets, &mod_name, &profile_name, &app_handle).await?;
+
+        for mod_dir in &mod_dirs {
+            // Recorded by source and name, so this works for a folder that is gone too
+            enable_state::set_enabled(mod_dir, &profile_name, false)?;
+            let enabling_path = get_enabling_file_path(mod_dir, &profile_name);
+            if enabling_path.exists() {
+                fs::remove_file(&enabling_path).await.map_err(ModError::IoError)?;
+            }
+        }
+
+        Ok(ModResult {
+            success: true,
+            message: Some("Leftovers removed and mod marked disabled".to_string()),
+        })
+    }
+    .await;
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Where the enabled version of a mod is kept while an update downloads
+fn get_previous_version_dir(mod_dir: &Path, mod_name: &str) -> PathBuf {
+    let parent = mod_dir.parent().unwrap_or(mod_dir);
+    parent.with_extension("updating").join(mod_name)
+}
+
+/// Remove the directory holding previous versions once no update is using it
+async fn remove_updating_dir(previous_dir: &Path) {
+    if let Some(parent) = previous_dir.parent() {
+        // Fails while another update still keeps a mod there, which is fine
+        let _ = fs::remove_dir(parent).await;
+    }
+}
+
+/// Relative paths of every file and directory in a mod, ignoring marker and metadata files
+fn collect_layout(root: &Path, dir: &Path, layout: &mut BTreeSet<PathBuf>) -> Result<(), ModError> {
+    for entry in std::fs::read_dir(dir).map_err(ModError::IoError)? {
+        let path = entry.map_err(ModError::IoError)?.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        if path.is_dir() {
+            collect_layout(root, &path, layout)?;
+        } else if dir == root {
+            // Only MAPPING.json at the top level affects where files go
+            if relative.as_os_str() != MAPPING_FILE {
+                continue;
+            }
+        }
+        layout.insert(relative);
+    }
+    Ok(())
+}
+
+/// Check whether two versions of a mod would produce the same links and mapping in DCS
+fn same_file_layout(old_dir: &Path, new_dir: &Path) -> bool {
+    let mut old_layout = BTreeSet::new();
+    let mut new_layout = BTreeSet::new();
+    if collect_layout(old_dir, old_dir, &mut old_layout).is_err()
+        || collect_layout(new_dir, new_dir, &mut new_layout).is_err()
+    {
+        return false;
+    }
+    let old_mapping = std::fs::read(old_dir.join(MAPPING_FILE)).ok();
+    let new_mapping = std::fs::read(new_dir.join(MAPPING_FILE)).ok();
+    old_layout == new_layout && old_mapping == new_mapping
+}
+
 #[tauri::command]
 pub async fn update_mod(
     app_handle: AppHandle,
@@ -251,24 +788,16 @@ pub async fn update_mod(
         }
 
         // Find the mod directory using the profile name
-        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name).await?;
+        let mod_dir = find_mod_dir(&settings, &mod_name, &profile_name, None).await?;
 
         // Check if mod is enabled for the current profile
-        let was_enabled =
-            fs::metadata(get_enabled_file_path(&mod_dir, &profile_name)).await.is_ok();
+        let was_enabled = is_mod_enabled(&mod_dir, &profile_name).map_err(ModError::EnablementError)?;
 
         // If mod is being enabled, error out
-        fs::metadata(get_enabling_file_path(&mod_dir, &profile_name)).await.map_err(|_|
-            ModError::EnablementError(
+        if fs::metadata(get_enabling_file_path(&mod_dir, &profile_name)).await.is_ok() {
+            return Err(ModError::EnablementError(
                 "Cannot update mod while it is being enabled".to_string(),
-            )
-        )?;
-
-        // If enabled, disable first
-        if was_enabled {
-            disable_mod(mod_name.clone(), profile_name.clone())
-                .await
-                .map_err(ModError::EnablementError)?;
+            ));
         }
 
         // Find the profile to get the repo_url for the download
@@ -280,39 +809,83 @@ pub async fn update_mod(
                 ModError::SettingsError(format!("Profile '{}' not found for update", profile_name))
             })?;
         let repo_url = profile.repo_url.clone();
-
-        // Download the updated version, passing the repo_url
         let filename = format!("{}.zip", mod_name);
-        let download_result =
-            super::mod_download::download_mod(app_handle, url, filename, repo_url).await;
+        // The URL may be another variant than the one downloaded before, switching to it
+        let variant = variant_for_download(&repo_url, &mod_name, &url);
 
-        match download_result {
-            Ok(_) => {
-                // Re-enable if it was enabled before
-                if was_enabled {
-                    enable_mod(mod_name.clone(), profile_name)
-                        .await
-                        .map_err(ModError::EnablementError)?;
-                }
+        if !was_enabled {
+            super::mod_download::download_mod(app_handle.clone(), url, filename, repo_url, None, Some(profile_name.clone()))
+                .await
+                .map_err(ModError::DownloadError)?;
+            record_mod_variant(&mod_dir, variant.as_deref())?;
+            return Ok(ModResult {
+                success: true,
+                message: Some("Mod updated successfully".to_string()),
+            });
+        }
 
-                Ok(ModResult {
-                    success: true,
-                    message: Some("Mod updated successfully".to_string()),
-                })
-            }
-            Err(e) => {
-                // If download fails and mod was enabled, try to re-enable it
-                if was_enabled {
-                    if let Err(enable_err) = enable_mod(mod_name.clone(), profile_name).await {
-                        println!(
-                            "Failed to re-enable mod after failed update: {}",
-                            enable_err
-                        );
-                    }
-                }
-                Err(ModError::DownloadError(e))
+        // Keep the enabled version aside while downloading, it is needed to disable it
+        // if the new version turns out to have a different file layout
+        let old_version = get_mod_version(&mod_dir)?;
+        let previous_dir = get_previous_version_dir(&mod_dir, &mod_name);
+        if previous_dir.exists() {
+            fs::remove_dir_all(&previous_dir).await.map_err(ModError::IoError)?;
+        }
+        if let Some(parent) = previous_dir.parent() {
+            fs::create_dir_all(parent).await.map_err(ModError::IoError)?;
+        }
+        fs::rename(&mod_dir, &previous_dir).await.map_err(ModError::IoError)?;
+
+        if let Err(e) =
+            super::mod_download::download_mod(app_handle.clone(), url, filename, repo_url, None, Some(profile_name.clone())).await
+        {
+            // Put the enabled version back, its links and patches were never touched
+            if mod_dir.exists() {
+                let _ = fs::remove_dir_all(&mod_dir).await;
             }
+            fs::rename(&previous_dir, &mod_dir).await.map_err(ModError::IoError)?;
+            remove_updating_dir(&previous_dir).await;
+            return Err(ModError::DownloadError(e));
         }
+        record_mod_variant(&mod_dir, variant.as_deref())?;
+
+        let targets = TargetDirs::from_profile(profile);
+        let message = if same_file_layout(&previous_dir, &mod_dir) {
+            // Symlinks point at the same paths as before, only the patch blocks need the new version
+            info!("File layout of {} unchanged, upgrading patches in place", mod_name);
+            let new_version = get_mod_version(&mod_dir)?;
+            remove_version_patches(&mod_dir, &targets, &mod_name, &old_version)?;
+            process_mod_roots(&mod_dir, &targets, &mod_name, &new_version, &profile_name, false, &app_handle)
+                .await?;
+            // The new folder has no marker file yet
+            enable_state::set_enabled(&mod_dir, &profile_name, true)?;
+            fs::remove_dir_all(&previous_dir).await.map_err(ModError::IoError)?;
+            "Mod updated in place"
+        } else {
+            // Swap the old version back in to disable it, then enable the new one
+            info!("File layout of {} changed, re-enabling", mod_name);
+            let new_dir = previous_dir.with_file_name(format!("{}.new", mod_name));
+            fs::rename(&mod_dir, &new_dir).await.map_err(ModError::IoError)?;
+            fs::rename(&previous_dir, &mod_dir).await.map_err(ModError::IoError)?;
+            let disable_result =
+                disable_mod(app_handle.clone(), mod_name.clone(), profile_name.clone(), None).await;
+
+            fs::remove_dir_all(&mod_dir).await.map_err(ModError::IoError)?;
+            // The previous version was moved back to mod_dir and removed with it just above
+            fs::rename(&new_dir, &mod_dir).await.map_err(ModError::IoError)?;
+            disable_result.map_err(ModError::EnablementError)?;
+
+            enable_mod(app_handle.clone(), mod_name.clone(), profile_name, None)
+                .await
+                .map_err(ModError::EnablementError)?;
+            "Mod updated successfully"
+        };
+
+        remove_updating_dir(&previous_dir).await;
+        Ok(ModResult {
+            success: true,
+            message: Some(message.to_string()),
+        })
     }
     .await;
 
@@ -1,7 +1,39 @@
+use crate::mods::enable_state;
+use crate::mods::mod_download::is_mod_successfully_downloaded;
 use crate::mods::types::ModError;
+use crate::settings::{Profile, Settings};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Source identifier of sideloaded mods, repository mods use their repo-hash directory name
+pub const SIDELOAD_SOURCE: &str = "sideload";
+
+/// Marker file naming the variant of a mod that was downloaded
+const VARIANT_FILE: &str = "VARIANT.txt";
+
+/// Get the source identifier of a repository: the first 6 hex chars of sha256(repo_url)
+pub fn get_repo_source(repo_url: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(repo_url.as_bytes());
+    let repo_hash = format!("{:x}", hasher.finalize());
+    repo_hash[..6].to_string()
+}
+
+/// Key of a mod in the stores kept per local copy: "<source>/<mod name>", as the frontend's
+/// enabledKey builds it, so a downloaded and a sideloaded mod of the same name stay apart
+pub fn mod_key(source: Option<&str>, mod_name: &str) -> String {
+    format!("{}/{}", source.unwrap_or_default(), mod_name)
+}
+
+/// Get the directory holding mods downloaded from a specific repository.
+/// Each repository gets `<download_path>/<repo source>`.
+pub fn get_repo_download_dir(download_path: &Path, repo_url: &str) -> PathBuf {
+    download_path.join(get_repo_source(repo_url))
+}
+
 /// Check if a directory follows the expected mod structure
 pub fn verify_mod_structure(mod_path: &Path) -> Result<(), ModError> {
     // Check for required files
@@ -45,6 +77,41 @@ pub fn verify_symlink(link_path: &Path, expected_target: &Path) -> Result<bool,
     Ok(target == expected_target)
 }
 
+/// Entries of a destination directory indexed case-insensitively, so mod entries merge into
+/// the casing already used in DCS (`Liveries` into `liveries`) instead of creating a parallel tree
+pub struct DestDir {
+    path: PathBuf,
+    names: HashMap<String, OsString>,
+}
+
+impl DestDir {
+    pub fn read(path: &Path) -> Self {
+        let names = fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| {
+                        let name = entry.file_name();
+                        (name.to_string_lossy().to_lowercase(), name)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            path: path.to_path_buf(),
+            names,
+        }
+    }
+
+    /// Destination path for a source entry name, using an existing entry's casing if there is one
+    pub fn join(&self, name: &OsStr) -> PathBuf {
+        match self.names.get(&name.to_string_lossy().to_lowercase()) {
+            Some(existing) => self.path.join(existing),
+            None => self.path.join(name),
+        }
+    }
+}
+
 /// Get the version from VERSION.txt
 pub fn get_mod_version(mod_path: &Path) -> Result<String, ModError> {
     let version = fs::read_to_string(mod_path.join("VERSION.txt"))
@@ -54,6 +121,22 @@ pub fn get_mod_version(mod_path: &Path) -> Result<String, ModError> {
     Ok(version)
 }
 
+/// Get the variant a mod was downloaded as, from VARIANT.txt
+pub fn get_mod_variant(mod_path: &Path) -> Option<String> {
+    let variant = fs::read_to_string(mod_path.join(VARIANT_FILE)).ok()?;
+    Some(variant.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Record the variant a mod was downloaded as, next to its VERSION.txt
+pub fn record_mod_variant(mod_path: &Path, variant: Option<&str>) -> Result<(), ModError> {
+    let path = mod_path.join(VARIANT_FILE);
+    match variant {
+        Some(variant) => fs::write(path, variant).map_err(ModError::IoError),
+        None if path.exists() => fs::remove_file(path).map_err(ModError::IoError),
+        None => Ok(()),
+    }
+}
+
 /// Get path to ENABLED file for a profile
 pub fn get_enabled_file_path(mod_path: &Path, profile_name: &str) -> PathBuf {
     mod_path.join(format!("ENABLED-{}.txt", profile_name))
@@ -64,8 +147,112 @@ pub fn get_enabling_file_path(mod_path: &Path, profile_name: &str) -> PathBuf {
     mod_path.join(format!("ENABLING-{}.txt", profile_name))
 }
 
-/// Check if a mod is enabled for a profile
-pub fn is_mod_enabled(mod_path: &Path, profile_name: &str) -> bool {
-    get_enabled_file_path(mod_path, profile_name).exists()
+/// Get path to the manifest of changes enabling made to DCS for a profile
+pub fn get_manifest_path(mod_path: &Path, profile_name: &str) -> PathBuf {
+    mod_path.join(format!("MANIFEST-{}.json", profile_name))
 }
 
+/// The directory of a repo-hash source, in whichever download path holds it. Profiles may
+/// download to their own paths, the global one is assumed when none has it
+pub fn source_download_dir(settings: &Settings, source: &str) -> PathBuf {
+    let paths = settings.download_paths();
+    paths
+        .iter()
+        .map(|path| path.join(source))
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| paths[0].join(source))
+}
+
+/// Repo-hash directories across every download path, named by their source
+pub fn list_source_dirs(settings: &Settings) -> Vec<(String, PathBuf)> {
+    settings
+        .download_paths()
+        .iter()
+        .flat_map(|path| list_mod_dirs(path))
+        .map(|dir| (dir.file_name().unwrap_or_default().to_string_lossy().to_string(), dir))
+        .collect()
+}
+
+/// Where the local copy of a mod from the given source lives, if it has been downloaded or sideloaded
+pub fn local_mod_dir(settings: &Settings, source: Option<&str>, mod_name: &str) -> Option<PathBuf> {
+    let mod_dir = match source? {
+        SIDELOAD_SOURCE if settings.sideload_path.is_empty() => return None,
+        SIDELOAD_SOURCE => Path::new(&settings.sideload_path).join(mod_name),
+        repo => {
+            let repo_dir = source_download_dir(settings, repo);
+            if !is_mod_successfully_downloaded(&repo_dir, mod_name) {
+                return None;
+            }
+            repo_dir.join(mod_name)
+        }
+    };
+    mod_dir.is_dir().then_some(mod_dir)
+}
+
+/// Check if a mod is enabled for a profile, as recorded in the enable state registry
+pub fn is_mod_enabled(mod_path: &Path, profile_name: &str) -> Result<bool, String> {
+    enable_state::is_enabled(&Settings::load()?, mod_path, profile_name)
+}
+
+/// Total size in bytes of the files under a directory, without following symlinks
+pub fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            Some(if metadata.is_dir() {
+                dir_size(&entry.path())
+            } else {
+                metadata.len()
+            })
+        })
+        .sum()
+}
+
+/// List every mod directory under `dir`
+pub fn list_mod_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+/// Keep the mod directories enabled for a profile
+fn filter_enabled(settings: &Settings, mod_dirs: Vec<PathBuf>, profile_name: &str) -> Result<Vec<PathBuf>, String> {
+    let mut enabled = Vec::new();
+    for mod_dir in mod_dirs {
+        if enable_state::is_enabled(settings, &mod_dir, profile_name)? {
+            enabled.push(mod_dir);
+        }
+    }
+    Ok(enabled)
+}
+
+/// List the mod directories under `dir` that are enabled for a profile
+pub fn list_enabled_mod_dirs(dir: &Path, profile_name: &str) -> Result<Vec<PathBuf>, String> {
+    filter_enabled(&Settings::load()?, list_mod_dirs(dir), profile_name)
+}
+
+/// List the mod directories of a profile, across its repository download directory and the sideload directory
+pub fn list_profile_mod_dirs(settings: &Settings, profile: &Profile) -> Vec<PathBuf> {
+    let mut mod_dirs = list_mod_dirs(&get_repo_download_dir(
+        &settings.profile_download_path(profile),
+        &profile.repo_url,
+    ));
+    if !settings.sideload_path.is_empty() {
+        mod_dirs.extend(list_mod_dirs(Path::new(&settings.sideload_path)));
+    }
+    mod_dirs
+}
+
+/// List the mod directories enabled for a profile, across its repository download directory and the sideload directory
+pub fn list_profile_enabled_mod_dirs(settings: &Settings, profile: &Profile) -> Result<Vec<PathBuf>, String> {
+    filter_enabled(settings, list_profile_mod_dirs(settings, profile), &profile.name)
+}
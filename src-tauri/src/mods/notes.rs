@@ -0,0 +1,37 @@
+use super::mod_utils::mod_key;
+use super::types::Category;
+use crate::app_data;
+use std::collections::BTreeMap;
+
+const NOTES_FILE: &str = "notes.json";
+
+/// Notes the user attached to mods, keyed by source and mod name (see mod_key)
+pub fn load_notes() -> Result<BTreeMap<String, String>, String> {
+    app_data::load_json(NOTES_FILE)
+}
+
+/// Attach the stored note to every listed mod that has one
+pub fn attach_notes(categories: &mut [Category], notes: &BTreeMap<String, String>) {
+    for mod_entry in categories.iter_mut().flat_map(|cat| cat.mods.iter_mut()) {
+        mod_entry.note = notes.get(&mod_key(mod_entry.source.as_deref(), &mod_entry.name)).cloned();
+    }
+}
+
+#[tauri::command]
+pub async fn get_mod_note(mod_name: String, source: Option<String>) -> Result<Option<String>, String> {
+    Ok(load_notes()?.remove(&mod_key(source.as_deref(), &mod_name)))
+}
+
+/// Set the note of a mod's copy from the given source, an empty note removes it
+#[tauri::command]
+pub async fn set_mod_note(mod_name: String, source: Option<String>, note: String) -> Result<(), String> {
+    let mut notes = load_notes()?;
+    let key = mod_key(source.as_deref(), &mod_name);
+    let note = note.trim();
+    if note.is_empty() {
+        notes.remove(&key);
+    } else {
+        notes.insert(key, note.to_string());
+    }
+    app_data::save_json(NOTES_FILE, &notes)
+}
@@ -0,0 +1,377 @@
+//! Parsed repository files kept next to the XML cache as JSON, keyed by the file's content, so a
+//! repository that didn't change isn't parsed again. `ModsFile` and its parts can't round-trip
+//! through their own serde derives, which read XML and write what the frontend expects, so the
+//! snapshots go through mirror types
+use super::mod_enablement::MappingConfig;
+use super::parser::ModParser;
+use super::types::{
+    Category, LocalizedText, Mod, ModError, ModMirror, ModRequirement, ModVariant, ModsFile, ParseWarning, RepoChange,
+    RepoHeader, RepoInclude,
+};
+use super::xml_cache::XmlCache;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::warn;
+
+#[derive(Serialize, Deserialize)]
+struct FileSnapshot {
+    format: u32,
+    min_app_version: Option<String>,
+    header: Option<HeaderSnapshot>,
+    includes: Vec<String>,
+    #[serde(default)]
+    mapping: Option<MappingConfig>,
+    categories: Vec<CategorySnapshot>,
+    warnings: Vec<ParseWarning>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HeaderSnapshot {
+    name: Option<String>,
+    maintainer: Option<String>,
+    support_url: Option<String>,
+    motd: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CategorySnapshot {
+    name: String,
+    sort_order: i32,
+    description: Option<String>,
+    parent: Option<String>,
+    mods: Vec<ModSnapshot>,
+    subcategories: Vec<CategorySnapshot>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ModSnapshot {
+    name: String,
+    id: Option<String>,
+    display_name: Option<String>,
+    version: String,
+    url: Option<String>,
+    /// `(url, region, priority)`
+    mirrors: Vec<(String, Option<String>, Option<i32>)>,
+    new_version: Option<String>,
+    downgrade_version: Option<String>,
+    description: String,
+    description_html: String,
+    /// `(lang, text)`
+    localized_names: Vec<(String, String)>,
+    localized_descriptions: Vec<(String, String)>,
+    changelog_url: Option<String>,
+    icon_url: Option<String>,
+    screenshot_url: Option<String>,
+    author: Option<String>,
+    homepage_url: Option<String>,
+    license: Option<String>,
+    size: Option<u64>,
+    base_size: Option<u64>,
+    platform: Option<String>,
+    dcs_version: Option<String>,
+    dcs_variants: Option<String>,
+    dcs_incompatible: bool,
+    deprecated: bool,
+    replaced_by: Option<String>,
+    origin: Option<String>,
+    source: Option<String>,
+    favorite: bool,
+    note: Option<String>,
+    since_last_visit: Option<RepoChange>,
+    tags: Vec<String>,
+    tags_attribute: Option<String>,
+    install_notes: Option<String>,
+    /// `(name, min_version)`
+    requires: Vec<(String, Option<String>)>,
+    variants: Vec<VariantSnapshot>,
+    variant: Option<String>,
+    installed_variant: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VariantSnapshot {
+    id: String,
+    name: Option<String>,
+    url: String,
+    size: Option<u64>,
+    description: String,
+}
+
+fn texts_to_pairs(texts: Vec<LocalizedText>) -> Vec<(String, String)> {
+    texts.into_iter().map(|t| (t.lang, t.text)).collect()
+}
+
+fn pairs_to_texts(pairs: Vec<(String, String)>) -> Vec<LocalizedText> {
+    pairs.into_iter().map(|(lang, text)| LocalizedText { lang, text }).collect()
+}
+
+impl From<ModsFile> for FileSnapshot {
+    fn from(file: ModsFile) -> Self {
+        FileSnapshot {
+            format: file.format,
+            min_app_version: file.min_app_version,
+            header: file.header.map(|h| HeaderSnapshot {
+                name: h.name,
+                maintainer: h.maintainer,
+                support_url: h.support_url,
+                motd: h.motd,
+            }),
+            includes: file.includes.into_iter().map(|include| include.url).collect(),
+            mapping: file.mapping,
+            categories: file.categories.into_iter().map(CategorySnapshot::from).collect(),
+            warnings: file.warnings,
+        }
+    }
+}
+
+impl From<FileSnapshot> for ModsFile {
+    fn from(snapshot: FileSnapshot) -> Self {
+        ModsFile {
+            format: snapshot.format,
+            min_app_version: snapshot.min_app_version,
+            header: snapshot.header.map(|h| RepoHeader {
+                name: h.name,
+                maintainer: h.maintainer,
+                support_url: h.support_url,
+                motd: h.motd,
+            }),
+            includes: snapshot.includes.into_iter().map(|url| RepoInclude { url }).collect(),
+            mapping: snapshot.mapping,
+            categories: snapshot.categories.into_iter().map(Category::from).collect(),
+            warnings: snapshot.warnings,
+        }
+    }
+}
+
+impl From<Category> for CategorySnapshot {
+    fn from(category: Category) -> Self {
+        CategorySnapshot {
+            name: category.name,
+            sort_order: category.sort_order,
+            description: category.description,
+            parent: category.parent,
+            mods: category.mods.into_iter().map(ModSnapshot::from).collect(),
+            subcategories: category.subcategories.into_iter().map(CategorySnapshot::from).collect(),
+        }
+    }
+}
+
+impl From<CategorySnapshot> for Category {
+    fn from(snapshot: CategorySnapshot) -> Self {
+        Category {
+            name: snapshot.name,
+            sort_order: snapshot.sort_order,
+            description: snapshot.description,
+            parent: snapshot.parent,
+            mods: snapshot.mods.into_iter().map(Mod::from).collect(),
+            subcategories: snapshot.subcategories.into_iter().map(Category::from).collect(),
+        }
+    }
+}
+
+impl From<Mod> for ModSnapshot {
+    fn from(m: Mod) -> Self {
+        ModSnapshot {
+            name: m.name,
+            id: m.id,
+            display_name: m.display_name,
+            version: m.version,
+            url: m.url,
+            mirrors: m.mirrors.into_iter().map(|mirror| (mirror.url, mirror.region, mirror.priority)).collect(),
+            new_version: m.new_version,
+            downgrade_version: m.downgrade_version,
+            description: m.description,
+            description_html: m.description_html,
+            localized_names: texts_to_pairs(m.localized_names),
+            localized_descriptions: texts_to_pairs(m.localized_descriptions),
+            changelog_url: m.changelog_url,
+            icon_url: m.icon_url,
+            screenshot_url: m.screenshot_url,
+            author: m.author,
+            homepage_url: m.homepage_url,
+            license: m.license,
+            size: m.size,
+            base_size: m.base_size,
+            platform: m.platform,
+            dcs_version: m.dcs_version,
+            dcs_variants: m.dcs_variants,
+            dcs_incompatible: m.dcs_incompatible,
+            deprecated: m.deprecated,
+            replaced_by: m.replaced_by,
+            origin: m.origin,
+            source: m.source,
+            favorite: m.favorite,
+            note: m.note,
+            since_last_visit: m.since_last_visit,
+            tags: m.tags,
+            tags_attribute: m.tags_attribute,
+            install_notes: m.install_notes,
+            requires: m.requires.into_iter().map(|r| (r.name, r.min_version)).collect(),
+            variants: m
+                .variants
+                .into_iter()
+                .map(|v| VariantSnapshot {
+                    id: v.id,
+                    name: v.name,
+                    url: v.url,
+                    size: v.size,
+                    description: v.description,
+                })
+                .collect(),
+            variant: m.variant,
+            installed_variant: m.installed_variant,
+        }
+    }
+}
+
+impl From<ModSnapshot> for Mod {
+    fn from(s: ModSnapshot) -> Self {
+        Mod {
+            name: s.name,
+            id: s.id,
+            display_name: s.display_name,
+            version: s.version,
+            url: s.url,
+            mirrors: s
+                .mirrors
+                .into_iter()
+                .map(|(url, region, priority)| ModMirror { url, region, priority })
+                .collect(),
+            new_version: s.new_version,
+            downgrade_version: s.downgrade_version,
+            description: s.description,
+            description_html: s.description_html,
+            localized_names: pairs_to_texts(s.localized_names),
+            localized_descriptions: pairs_to_texts(s.localized_descriptions),
+            changelog_url: s.changelog_url,
+            icon_url: s.icon_url,
+            screenshot_url: s.screenshot_url,
+            author: s.author,
+            homepage_url: s.homepage_url,
+            license: s.license,
+            size: s.size,
+            base_size: s.base_size,
+            platform: s.platform,
+            dcs_version: s.dcs_version,
+            dcs_variants: s.dcs_variants,
+            dcs_incompatible: s.dcs_incompatible,
+            deprecated: s.deprecated,
+            replaced_by: s.replaced_by,
+            origin: s.origin,
+            source: s.source,
+            favorite: s.favorite,
+            note: s.note,
+            since_last_visit: s.since_last_visit,
+            tags: s.tags,
+            tags_attribute: s.tags_attribute,
+            install_notes: s.install_notes,
+            requires: s
+                .requires
+                .into_iter()
+                .map(|(name, min_version)| ModRequirement { name, min_version })
+                .collect(),
+            variants: s
+                .variants
+                .into_iter()
+                .map(|v| ModVariant {
+                    id: v.id,
+                    name: v.name,
+                    url: v.url,
+                    size: v.size,
+                    description: v.description,
+                })
+                .collect(),
+            variant: s.variant,
+            installed_variant: s.installed_variant,
+            // Profile state, attached when a listing is built
+            enabling_lock: None,
+        }
+    }
+}
+
+/// Snapshot of a repository file with this content, parsed for `language`. The app version is part
+/// of the key, a release that parses differently doesn't pick up older snapshots
+fn snapshot_path(dir: &Path, content: &[u8], language: &str) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(content);
+    hasher.update([0]);
+    hasher.update(language.as_bytes());
+    hasher.update([0]);
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    dir.join(format!("{:x}.json", hasher.finalize()))
+}
+
+fn snapshot_dir() -> Option<PathBuf> {
+    Some(XmlCache::get_cache_dir()?.join("parsed"))
+}
+
+fn read_snapshot(path: &Path) -> Option<ModsFile> {
+    let snapshot: FileSnapshot = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    // Marks the snapshot as used for the cache size limit
+    let _ = fs::File::options().write(true).open(path).and_then(|file| file.set_modified(SystemTime::now()));
+    Some(snapshot.into())
+}
+
+fn write_snapshot(dir: &Path, path: &Path, mods_file: &ModsFile) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let json = serde_json::to_vec(&FileSnapshot::from(mods_file.clone()))?;
+    // Written aside and renamed, a snapshot read half written would only be parsed again but
+    // concurrent loads of the same repository are common
+    let partial = path.with_extension("part");
+    fs::write(&partial, json)?;
+    fs::rename(&partial, path)
+}
+
+/// Parse a repository file for `language`, or load it from its snapshot when a file with the
+/// same content was parsed for that language before
+pub fn parse_repo_file(path: &Path, language: &str) -> Result<ModsFile, ModError> {
+    let (Some(dir), Ok(content)) = (snapshot_dir(), fs::read(path)) else {
+        return ModParser::parse_mod_list_file(path, language);
+    };
+    let snapshot = snapshot_path(&dir, &content, language);
+    drop(content);
+    if let Some(mods_file) = read_snapshot(&snapshot) {
+        return Ok(mods_file);
+    }
+
+    let mods_file = ModParser::parse_mod_list_file(path, language)?;
+    if let Err(e) = write_snapshot(&dir, &snapshot, &mods_file) {
+        warn!("Failed to keep the parsed copy of {}: {}", path.display(), e);
+    }
+    Ok(mods_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut mods_file = ModParser::parse_mod_list(
+            r#"<mods>
+                <header name="Test Repo" maintainer="Someone">Update before Tuesday</header>
+                <include url="extra.xml"/>
+                <category name="Aircraft" sort_order="1">
+                    <mod name="ModA" version="1.0" url="https://example.com/a.zip" tags="liveries, MP-safe">Some *text*
+                        <url region="EU" priority="1">https://eu.example.com/a.zip</url>
+                        <requires version="2.0">ModB</requires>
+                        <variant id="2k" name="2K" url="https://example.com/a-2k.zip" size="10">Lighter</variant>
+                    </mod>
+                    <category name="Liveries" sort_order="2">
+                        <mod name="ModB" version="2.0" url="https://example.com/b.zip"/>
+                    </category>
+                </category>
+            </mods>"#,
+        )
+        .unwrap();
+        mods_file.warnings.push(ParseWarning { element: "<mod>".to_string(), line: 3, reason: "No name".to_string() });
+
+        let restored: ModsFile = FileSnapshot::from(mods_file.clone()).into();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), serde_json::to_value(&mods_file).unwrap());
+        assert_eq!(restored.includes, mods_file.includes);
+        assert_eq!(restored.warnings, mods_file.warnings);
+    }
+}
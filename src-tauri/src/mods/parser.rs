@@ -1,17 +1,191 @@
+use std::cmp::Ordering;
 use std::fs;
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
-use super::types::{ModError, ModsFile};
-use quick_xml::de::from_str;
-use sha2::{Digest, Sha256};
+use super::mod_utils::{get_mod_variant, get_repo_download_dir};
+use super::types::{Mod, ModError, ModsFile, ParseWarning};
+use super::versions::compare_versions;
+use flate2::read::GzDecoder;
+use quick_xml::de::{from_reader, DeError};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::QName;
+use quick_xml::Reader;
+use tracing::info;
+
+/// Newest repository format this version of BZMM can read
+pub const SUPPORTED_REPO_FORMAT: u32 = 1;
+
+/// Buffer for reading repository files, the root element has to start within it for its
+/// format version to be checked before parsing
+const REPO_READ_BUFFER: usize = 64 * 1024;
+
+/// First bytes of a gzip stream, `.xml.gz` repositories and gzip-encoded responses start with them
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 
 pub struct ModParser;
 
+/// `<mod name="...">` for warnings about an entry
+fn describe_mod(tag: &BytesStart) -> String {
+    match tag.try_get_attribute("name").ok().flatten().and_then(|attr| attr.unescape_value().ok()) {
+        Some(name) => format!("<mod name=\"{}\">", name),
+        None => "<mod>".to_string(),
+    }
+}
+
 impl ModParser {
+    /// Parse a repository with its texts as written, without picking translations
     pub fn parse_mod_list(xml: &str) -> Result<ModsFile, ModError> {
-        let mods_file: ModsFile = from_str(xml)?;
+        Self::parse_mod_list_in(xml.as_bytes(), "")
+    }
+
+    /// Parse a repository, leaving out the mods that don't parse instead of failing on them.
+    /// The skipped entries are listed in the file's `warnings`
+    pub fn parse_mod_list_lenient(xml: &str, language: &str) -> Result<ModsFile, ModError> {
+        match Self::parse_mod_list_in(xml.as_bytes(), language) {
+            Err(ModError::ParseError(e)) => Self::parse_without_invalid_mods(xml, e, language),
+            result => result,
+        }
+    }
+
+    /// Parse a repository file as it is read, without loading the whole document first. When it
+    /// doesn't parse the file is read again leniently, skipping the broken mods. Gzipped files
+    /// are decompressed on the way
+    pub fn parse_mod_list_file(path: &Path, language: &str) -> Result<ModsFile, ModError> {
+        match Self::parse_mod_list_in(Self::open_repo_file(path)?, language) {
+            Err(ModError::ParseError(e)) => {
+                info!("Repository {} doesn't parse ({}), skipping broken entries", path.display(), e);
+                let mut xml = String::new();
+                Self::open_repo_file(path)?.read_to_string(&mut xml)?;
+                Self::parse_without_invalid_mods(&xml, e, language)
+            }
+            result => result,
+        }
+    }
+
+    /// Open a repository file for reading, through a decompressor when it is gzipped
+    fn open_repo_file(path: &Path) -> std::io::Result<Box<dyn BufRead>> {
+        let mut reader = BufReader::with_capacity(REPO_READ_BUFFER, fs::File::open(path)?);
+        if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+            return Ok(Box::new(BufReader::with_capacity(REPO_READ_BUFFER, GzDecoder::new(reader))));
+        }
+        Ok(Box::new(reader))
+    }
+
+    /// The text of a fetched repository, decompressing it when it is gzipped
+    pub fn repo_text(body: &[u8]) -> Result<String, ModError> {
+        if !body.starts_with(&GZIP_MAGIC) {
+            return Ok(String::from_utf8_lossy(body).into_owned());
+        }
+        let mut xml = String::new();
+        GzDecoder::new(body).read_to_string(&mut xml)?;
+        Ok(xml)
+    }
+
+    /// Parse a document that failed with `error` again with its broken mods cut out. The error
+    /// stands when the problem lies outside the mods
+    fn parse_without_invalid_mods(xml: &str, error: DeError, language: &str) -> Result<ModsFile, ModError> {
+        let (cleaned, warnings) = Self::drop_invalid_mods(xml);
+        if warnings.is_empty() {
+            return Err(ModError::ParseError(error));
+        }
+        let mut mods_file = Self::parse_mod_list_in(cleaned.as_bytes(), language)?;
+        mods_file.warnings = warnings;
         Ok(mods_file)
     }
 
+    /// Cut every `<mod>` that doesn't deserialize on its own out of a document, with a warning
+    /// for each
+    fn drop_invalid_mods(xml: &str) -> (String, Vec<ParseWarning>) {
+        let mut reader = Reader::from_str(xml);
+        let mut cleaned = String::with_capacity(xml.len());
+        let mut copied = 0;
+        let mut warnings = Vec::new();
+        loop {
+            let start = reader.buffer_position() as usize;
+            let (element, end) = match reader.read_event() {
+                Ok(Event::Empty(tag)) if tag.name().as_ref() == b"mod" => {
+                    (describe_mod(&tag), reader.buffer_position() as usize)
+                }
+                Ok(Event::Start(tag)) if tag.name().as_ref() == b"mod" => {
+                    let element = describe_mod(&tag);
+                    let name = tag.name().as_ref().to_vec();
+                    if reader.read_to_end(QName(&name)).is_err() {
+                        break;
+                    }
+                    (element, reader.buffer_position() as usize)
+                }
+                Ok(Event::Eof) | Err(_) => break,
+                Ok(_) => continue,
+            };
+            if let Err(e) = quick_xml::de::from_str::<Mod>(&xml[start..end]) {
+                warnings.push(ParseWarning {
+                    element,
+                    line: xml[..start].matches('\n').count() + 1,
+                    reason: e.to_string(),
+                });
+                cleaned.push_str(&xml[copied..start]);
+                copied = end;
+            }
+        }
+        cleaned.push_str(&xml[copied..]);
+        (cleaned, warnings)
+    }
+
+    /// Parse a repository from a reader, picking translated texts for a language tag such as
+    /// "de-AT". The mods are deserialized as the document streams in
+    pub fn parse_mod_list_in<R: BufRead>(mut reader: R, language: &str) -> Result<ModsFile, ModError> {
+        // Check the format first, newer schemas would otherwise fail with a cryptic error
+        match Self::read_format_version(reader.fill_buf()?)? {
+            1 => {
+                let mut mods_file: ModsFile = from_reader(reader)?;
+                mods_file.flatten_categories();
+                mods_file.retain_platform(std::env::consts::OS);
+                if let Some(header) = &mut mods_file.header {
+                    header.motd = header.motd.trim().to_string();
+                }
+                for mod_entry in mods_file.categories.iter_mut().flat_map(|cat| cat.mods.iter_mut()) {
+                    mod_entry.normalize_parsed(language);
+                }
+                Ok(mods_file)
+            }
+            format => Err(ModError::UnsupportedRepoFormat(format, SUPPORTED_REPO_FORMAT)),
+        }
+    }
+
+    /// Read the `format` attribute of the root element from the start of a document, 1 when it
+    /// is missing
+    fn read_format_version(head: &[u8]) -> Result<u32, ModError> {
+        let mut reader = Reader::from_reader(head);
+        let mut buf = Vec::new();
+        loop {
+            // Malformed XML, or a root element cut off at the end of the buffer, is left to the
+            // deserializer to report
+            let Ok(event) = reader.read_event_into(&mut buf) else {
+                return Ok(1);
+            };
+            match event {
+                Event::Start(root) | Event::Empty(root) => {
+                    let Some(attr) = root
+                        .try_get_attribute("format")
+                        .map_err(|e| DeError::from(quick_xml::Error::from(e)))?
+                    else {
+                        return Ok(1);
+                    };
+                    let value = attr.unescape_value().map_err(DeError::from)?;
+                    return value.trim().parse().map_err(|_| {
+                        ModError::ParseError(DeError::Custom(format!(
+                            "Invalid repository format version '{}'",
+                            value
+                        )))
+                    });
+                }
+                // Let the deserializer report a missing root element
+                Event::Eof => return Ok(1),
+                _ => {}
+            }
+        }
+    }
+
     /// Checks for local updates against the provided XML mod list, considering the source repository URL.
     pub fn check_for_updates(
         xml_mods: &ModsFile,
@@ -21,44 +195,48 @@ impl ModParser {
         let mut updated_mods = xml_mods.clone();
 
         // Calculate the XML-specific path
-        let mut hasher = Sha256::new();
-        hasher.update(repo_url.as_bytes());
-        let hash_result = hasher.finalize();
-        let repo_hash = format!("{:x}", hash_result);
-        let repo_hash = &repo_hash[..6]; // Shrink the hash to 6 characters
-        let xml_specific_path = base_download_path.join(repo_hash);
+        let xml_specific_path = get_repo_download_dir(base_download_path, repo_url);
 
-        println!("Checking for updates within: {}", xml_specific_path.display());
+        info!("Checking for updates within: {}", xml_specific_path.display());
 
         for category in &mut updated_mods.categories {
             for mod_entry in &mut category.mods {
-                println!("Checking updates for mod: {}", mod_entry.name);
+                info!("Checking updates for mod: {}", mod_entry.name);
 
                 // Check if mod is downloaded within the XML-specific directory
                 let mod_dir = xml_specific_path.join(&mod_entry.name);
                 if !mod_dir.is_dir() {
                     // Mod not downloaded from this specific source
-                    println!("Mod dir not found in XML-specific path: {:?}", mod_dir);
+                    info!("Mod dir not found in XML-specific path: {:?}", mod_dir);
                     continue;
                 }
 
+                mod_entry.installed_variant = get_mod_variant(&mod_dir);
+
                 // Read VERSION.txt
                 let version_path = mod_dir.join("VERSION.txt");
                 if !version_path.exists() {
-                    println!("VERSION.txt not found in {:?}", version_path);
+                    info!("VERSION.txt not found in {:?}", version_path);
                     continue;
                 }
 
                 if let Ok(local_version) = fs::read_to_string(version_path) {
                     let local_version = local_version.trim();
-                    println!("Local version: {}, XML version: {}", local_version, mod_entry.version);
+                    info!("Local version: {}, XML version: {}", local_version, mod_entry.version);
                     
-                    // If XML version is different from local version, set newVersion
-                    if local_version != mod_entry.version {
-                        println!("Update found! Setting new_version to {}", mod_entry.version);
-                        mod_entry.new_version = Some(mod_entry.version.clone());
-                        mod_entry.version = local_version.to_string();
+                    // A newer repository version is an update, an older one is only flagged
+                    match compare_versions(&mod_entry.version, local_version) {
+                        Ordering::Greater => {
+                            info!("Update found! Setting new_version to {}", mod_entry.version);
+                            mod_entry.new_version = Some(mod_entry.version.clone());
+                        }
+                        Ordering::Less => {
+                            info!("Local version is newer than the repository's {}", mod_entry.version);
+                            mod_entry.downgrade_version = Some(mod_entry.version.clone());
+                        }
+                        Ordering::Equal => {}
                     }
+                    mod_entry.version = local_version.to_string();
                 }
             }
         }
@@ -70,7 +248,12 @@ impl ModParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sha2::{Digest, Sha256};
     use tempfile::tempdir;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use crate::mods::mod_enablement::TargetRoot;
     use crate::mods::types::{Category, Mod};
 
     // Helper to create a dummy repo hash for testing
@@ -82,6 +265,70 @@ mod tests {
         repo_hash.to_string()
     }
 
+    #[test]
+    fn test_parse_mod_list_rejects_newer_format() {
+        let xml = r#"<?xml version="1.0"?>
+        <mods format="2">
+            <category name="Essential" sort_order="1" />
+        </mods>"#;
+
+        assert!(matches!(
+            ModParser::parse_mod_list(xml),
+            Err(ModError::UnsupportedRepoFormat(2, SUPPORTED_REPO_FORMAT))
+        ));
+    }
+
+    #[test]
+    fn test_parse_mod_list_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("repo.xml");
+        let mods = (0..500)
+            .map(|i| format!(r#"<mod name="Mod {0}" version="1.{0}" url="https://example.com/{0}.zip"/>"#, i))
+            .collect::<String>();
+        fs::write(&path, format!(r#"<mods><category name="Bulk" sort_order="1">{}</category></mods>"#, mods)).unwrap();
+
+        let mods_file = ModParser::parse_mod_list_file(&path, "").unwrap();
+        assert_eq!(mods_file.categories[0].mods.len(), 500);
+        assert_eq!(mods_file.categories[0].mods[499].version, "1.499");
+
+        let gz_path = dir.path().join("repo.xml.gz");
+        let mut encoder = GzEncoder::new(fs::File::create(&gz_path).unwrap(), Compression::default());
+        encoder.write_all(&fs::read(&path).unwrap()).unwrap();
+        encoder.finish().unwrap();
+        assert_eq!(ModParser::parse_mod_list_file(&gz_path, "").unwrap().categories[0].mods.len(), 500);
+        let body = fs::read(&gz_path).unwrap();
+        assert_eq!(ModParser::repo_text(&body).unwrap(), fs::read_to_string(&path).unwrap());
+
+        fs::write(&path, r#"<mods format="2"><category name="Bulk" sort_order="1"/></mods>"#).unwrap();
+        assert!(matches!(
+            ModParser::parse_mod_list_file(&path, ""),
+            Err(ModError::UnsupportedRepoFormat(2, SUPPORTED_REPO_FORMAT))
+        ));
+    }
+
+    #[test]
+    fn test_parse_mod_list_lenient() {
+        let xml = r#"<mods>
+            <category name="Essential" sort_order="1">
+                <mod name="Missing Version" url="https://example.com/a.zip"/>
+                <mod name="Good Mod" version="1.0" url="https://example.com/b.zip"/>
+                <mod name="Bad Size" version="1.0" url="https://example.com/c.zip" size="big">
+                    Description
+                </mod>
+            </category>
+        </mods>"#;
+
+        assert!(matches!(ModParser::parse_mod_list(xml), Err(ModError::ParseError(_))));
+        let mods_file = ModParser::parse_mod_list_lenient(xml, "").unwrap();
+        assert_eq!(mods_file.categories[0].mods.len(), 1);
+        assert_eq!(mods_file.categories[0].mods[0].name, "Good Mod");
+        let skipped: Vec<_> = mods_file.warnings.iter().map(|w| (w.element.as_str(), w.line)).collect();
+        assert_eq!(skipped, [("<mod name=\"Missing Version\">", 3), ("<mod name=\"Bad Size\">", 5)]);
+
+        // Broken outside the mods, nothing to recover
+        assert!(ModParser::parse_mod_list_lenient("<mods><category name=\"A\"/></mods>", "").is_err());
+    }
+
     #[test]
     fn test_parse_mod_list() {
         let xml = r#"<?xml version="1.0"?>
@@ -103,6 +350,250 @@ mod tests {
         assert_eq!(mods.categories[0].mods[0].name, "Test Mod");
     }
 
+    #[test]
+    fn test_parse_mod_media() {
+        let xml = r#"<mods>
+            <category name="Essential" sort_order="1">
+                <mod name="Test Mod" version="1.0.0" icon="http://example.com/icon.png"/>
+            </category>
+        </mods>"#;
+
+        let mod_entry = &ModParser::parse_mod_list(xml).unwrap().categories[0].mods[0];
+        assert_eq!(mod_entry.icon_url.as_deref(), Some("http://example.com/icon.png"));
+        assert_eq!(mod_entry.screenshot_url, None);
+    }
+
+    #[test]
+    fn test_parse_mod_size() {
+        let xml = r#"<mods>
+            <category name="Essential" sort_order="1">
+                <mod name="Sized" version="1.0.0" size="52428800"/>
+                <mod name="Unsized" version="1.0.0"/>
+            </category>
+        </mods>"#;
+
+        let mods = ModParser::parse_mod_list(xml).unwrap();
+        assert_eq!(mods.categories[0].mods[0].size, Some(52_428_800));
+        assert_eq!(mods.categories[0].mods[1].size, None);
+    }
+
+    #[test]
+    fn test_parse_mod_tags() {
+        let xml = r#"<mods>
+            <category name="Essential" sort_order="1">
+                <mod name="Test Mod" version="1.0.0" tags="liveries, MP-safe">
+                    <tag>scripts</tag>
+                    <tag>liveries</tag>
+                </mod>
+            </category>
+        </mods>"#;
+
+        let mod_entry = &ModParser::parse_mod_list(xml).unwrap().categories[0].mods[0];
+        assert_eq!(mod_entry.tags, ["scripts", "liveries", "MP-safe"]);
+    }
+
+    #[test]
+    fn test_parse_install_notes() {
+        let xml = r#"<mods>
+            <category name="Essential" sort_order="1">
+                <mod name="Test Mod" version="1.0.0">Description text
+                    <notes>Requires a mission restart</notes>
+                </mod>
+            </category>
+        </mods>"#;
+
+        let mod_entry = &ModParser::parse_mod_list(xml).unwrap().categories[0].mods[0];
+        assert_eq!(mod_entry.install_notes.as_deref(), Some("Requires a mission restart"));
+        assert_eq!(mod_entry.description, "Description text");
+    }
+
+    #[test]
+    fn test_parse_nested_categories() {
+        let xml = r#"<mods>
+            <category name="Aircraft" sort_order="2" description="Aircraft mods">
+                <mod name="Viper" version="1.0" url="https://example.com/viper.zip"/>
+                <category name="Liveries" sort_order="2">
+                    <category name="Viper" sort_order="1">
+                        <mod name="Viper Liveries" version="1.0" url="https://example.com/vl.zip"/>
+                    </category>
+                </category>
+                <category name="Cockpits" sort_order="1">
+                    <mod name="Viper Cockpit" version="1.0" url="https://example.com/vc.zip"/>
+                </category>
+                <mod name="Hornet" version="1.0" url="https://example.com/hornet.zip"/>
+            </category>
+            <category name="Essential" sort_order="1">
+                <mod name="Common" version="1.0" url="https://example.com/common.zip"/>
+            </category>
+        </mods>"#;
+
+        let mods = ModParser::parse_mod_list(xml).unwrap();
+        let names: Vec<&str> = mods.categories.iter().map(|cat| cat.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Aircraft", "Aircraft / Cockpits", "Aircraft / Liveries", "Aircraft / Liveries / Viper", "Essential"]
+        );
+        assert_eq!(mods.categories[0].description.as_deref(), Some("Aircraft mods"));
+        assert_eq!(mods.categories[0].mods.len(), 2);
+        assert_eq!(mods.categories[3].parent.as_deref(), Some("Aircraft / Liveries"));
+        assert!(mods.categories[..4].iter().all(|cat| cat.sort_order == 2));
+    }
+
+    #[test]
+    fn test_parse_mod_id_replaces_name() {
+        let xml = r#"<mods>
+            <category name="Aircraft" sort_order="1">
+                <mod id="viper-liveries" name="Viper Liveries 2024" version="1.0"/>
+                <mod id="Hornet" name="Hornet" version="1.0"/>
+            </category>
+        </mods>"#;
+
+        let mods = ModParser::parse_mod_list(xml).unwrap();
+        let viper = &mods.categories[0].mods[0];
+        assert_eq!(viper.name, "viper-liveries");
+        assert_eq!(viper.display_name.as_deref(), Some("Viper Liveries 2024"));
+        assert_eq!(mods.categories[0].mods[1].display_name, None);
+    }
+
+    #[test]
+    fn test_parse_localized_texts() {
+        let xml = r#"<mods>
+            <category name="Aircraft" sort_order="1">
+                <mod name="Viper Liveries" version="1.0">Squadron liveries
+                    <name lang="de">Viper-Lackierungen</name>
+                    <description lang="de-DE">Staffellackierungen</description>
+                    <name lang="fr-CA">Livrées Viper</name>
+                </mod>
+            </category>
+        </mods>"#;
+
+        let german = ModParser::parse_mod_list_in(xml.as_bytes(), "de_AT").unwrap();
+        let mod_entry = &german.categories[0].mods[0];
+        assert_eq!(mod_entry.name, "Viper Liveries");
+        assert_eq!(mod_entry.display_name.as_deref(), Some("Viper-Lackierungen"));
+        assert_eq!(mod_entry.description, "Staffellackierungen");
+
+        let french = ModParser::parse_mod_list_in(xml.as_bytes(), "fr").unwrap();
+        assert_eq!(french.categories[0].mods[0].display_name.as_deref(), Some("Livrées Viper"));
+        assert_eq!(french.categories[0].mods[0].description, "Squadron liveries");
+
+        let english = ModParser::parse_mod_list_in(xml.as_bytes(), "en-US").unwrap();
+        assert_eq!(english.categories[0].mods[0].display_name, None);
+    }
+
+    #[test]
+    fn test_retain_platform() {
+        let xml = r#"<mods>
+            <category name="Tools" sort_order="1">
+                <mod name="Launcher" version="1.0" platform="windows"/>
+                <mod name="Common" version="1.0"/>
+            </category>
+            <category name="Server" sort_order="2">
+                <mod name="Server Assets" version="1.0" platform="Linux, macos"/>
+            </category>
+        </mods>"#;
+
+        let mut windows: ModsFile = quick_xml::de::from_str(xml).unwrap();
+        windows.retain_platform("windows");
+        assert_eq!(windows.categories.len(), 1);
+        assert_eq!(windows.categories[0].mods.len(), 2);
+
+        let mut linux: ModsFile = quick_xml::de::from_str(xml).unwrap();
+        linux.retain_platform("linux");
+        let names: Vec<&str> = linux.categories.iter().flat_map(|c| &c.mods).map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["Common", "Server Assets"]);
+    }
+
+    #[test]
+    fn test_parse_mod_mirrors() {
+        let xml = r#"<mods>
+            <category name="Essential" sort_order="1">
+                <mod name="Test Mod" version="1.0.0">
+                    <url priority="2">https://us.example.com/mod.zip</url>
+                    <url region="EU" priority="5">https://eu.example.com/mod.zip</url>
+                    <url priority="1">https://cdn.example.com/mod.zip</url>
+                </mod>
+            </category>
+        </mods>"#;
+
+        let mods = ModParser::parse_mod_list(xml).unwrap();
+        let mod_entry = &mods.categories[0].mods[0];
+        assert_eq!(mod_entry.mirrors.len(), 3);
+        assert_eq!(mod_entry.url.as_deref(), Some("https://cdn.example.com/mod.zip"));
+        assert_eq!(
+            mod_entry.download_urls("eu"),
+            ["https://eu.example.com/mod.zip", "https://cdn.example.com/mod.zip", "https://us.example.com/mod.zip"]
+        );
+    }
+
+    #[test]
+    fn test_parse_repo_header() {
+        let xml = r#"<mods>
+            <header name="BorderZone" maintainer="Viper" support="https://example.com/discord">
+                Update the Hornet liveries before Tuesday's mission
+            </header>
+            <category name="Essential" sort_order="1"/>
+        </mods>"#;
+
+        let header = ModParser::parse_mod_list(xml).unwrap().header.unwrap();
+        assert_eq!(header.name.as_deref(), Some("BorderZone"));
+        assert_eq!(header.support_url.as_deref(), Some("https://example.com/discord"));
+        assert_eq!(header.motd, "Update the Hornet liveries before Tuesday's mission");
+        assert!(ModParser::parse_mod_list("<mods><category name=\"A\" sort_order=\"1\"/></mods>").unwrap().header.is_none());
+    }
+
+    #[test]
+    fn test_parse_repo_mapping() {
+        let xml = r#"<mods>
+            <mapping target="install" link_depth="3">
+                <rule path="Mods/tech/Shared" strategy="recurse"/>
+                <rule path="Liveries" strategy="link"/>
+                <exclude>*.psd</exclude>
+                <exclude>!docs</exclude>
+            </mapping>
+            <category name="Essential" sort_order="1"/>
+        </mods>"#;
+
+        let mapping = ModParser::parse_mod_list(xml).unwrap().mapping.unwrap();
+        assert_eq!(mapping.target(), TargetRoot::Install);
+        assert_eq!(mapping.link_depth(), 3);
+        assert_eq!(mapping.rules.len(), 2);
+        assert_eq!(mapping.rules[0].path, "Mods/tech/Shared");
+        assert_eq!(mapping.exclude, ["*.psd", "!docs"]);
+        assert!(ModParser::parse_mod_list("<mods><category name=\"A\" sort_order=\"1\"/></mods>").unwrap().mapping.is_none());
+    }
+
+    #[test]
+    fn test_parse_mod_variants() {
+        let xml = r#"<mods>
+            <category name="Terrain" sort_order="1">
+                <mod name="Caucasus Textures" version="1.0">Sharper ground textures
+                    <variant id="4k" name="4K textures" url="https://example.com/4k.zip" size="4096"/>
+                    <variant id="2k" name="2K textures" url="https://example.com/2k.zip" size="2048">Lighter on VRAM</variant>
+                </mod>
+            </category>
+        </mods>"#;
+
+        let mut mods = ModParser::parse_mod_list(xml).unwrap();
+        let mod_entry = &mut mods.categories[0].mods[0];
+        assert_eq!(mod_entry.variant.as_deref(), Some("4k"));
+        assert_eq!(mod_entry.url.as_deref(), Some("https://example.com/4k.zip"));
+        assert_eq!(mod_entry.variants[1].description, "Lighter on VRAM");
+
+        // The installed variant wins over the default, a choice the mod offers over both
+        mod_entry.installed_variant = Some("2k".to_string());
+        mod_entry.select_variant(Some("8k"));
+        assert_eq!((mod_entry.url.as_deref(), mod_entry.size), (Some("https://example.com/2k.zip"), Some(2048)));
+        mod_entry.select_variant(Some("4k"));
+        assert_eq!(mod_entry.variant.as_deref(), Some("4k"));
+        // A variant without a size doesn't keep the size of the one selected before
+        mod_entry.variants[0].size = None;
+        mod_entry.select_variant(Some("2k"));
+        mod_entry.select_variant(Some("4k"));
+        assert_eq!(mod_entry.size, None);
+        assert_eq!(mod_entry.variant_for_url("https://example.com/2k.zip").map(|v| v.id.as_str()), Some("2k"));
+    }
+
     #[test]
     fn test_check_for_updates() {
         let base_temp_dir = tempdir().unwrap();
@@ -125,19 +616,62 @@ mod tests {
         fs::write(other_mod_dir.join("VERSION.txt"), "0.9.0").unwrap(); // Different version
 
         let mods = ModsFile {
+            format: 1,
+            min_app_version: None,
+            header: None,
+            includes: Vec::new(),
+            mapping: None,
+            warnings: Vec::new(),
             categories: vec![
                 Category {
                     name: "Essential".to_string(),
                     sort_order: 1,
+                    description: None,
+                    parent: None,
                     mods: vec![
                         Mod {
                             name: "Test Mod".to_string(),
                             version: "1.0.1".to_string(), // XML has newer version
                             url: Some("http://example.com/mod.zip".to_string()),
+                            id: None,
+                            display_name: None,
+                            mirrors: Vec::new(),
                             new_version: None,
+                            downgrade_version: None,
                             description: "Test description".to_string(),
+                            description_html: String::new(),
+                            localized_names: Vec::new(),
+                            localized_descriptions: Vec::new(),
+                            changelog_url: None,
+                            icon_url: None,
+                            screenshot_url: None,
+                            author: None,
+                            homepage_url: None,
+                            license: None,
+                            size: None,
+                            base_size: None,
+                            platform: None,
+                            dcs_version: None,
+                            dcs_variants: None,
+                            dcs_incompatible: false,
+                            deprecated: false,
+                            replaced_by: None,
+                            origin: None,
+                            source: None,
+                            favorite: false,
+                            note: None,
+                            since_last_visit: None,
+                            tags: Vec::new(),
+                            tags_attribute: None,
+                            install_notes: None,
+                            requires: Vec::new(),
+                            variants: Vec::new(),
+                            variant: None,
+                            installed_variant: None,
+                            enabling_lock: None,
                         }
                     ],
+                    subcategories: Vec::new(),
                 }
             ],
         };
@@ -151,19 +685,62 @@ mod tests {
 
         // Check against the second repo URL (should not find the mod in its specific dir)
         let mods_for_other_repo = ModsFile {
+            format: 1,
+            min_app_version: None,
+            header: None,
+            includes: Vec::new(),
+            mapping: None,
+            warnings: Vec::new(),
              categories: vec![
                 Category {
                     name: "Essential".to_string(),
                     sort_order: 1,
+                    description: None,
+                    parent: None,
                     mods: vec![
                         Mod {
                             name: "Test Mod".to_string(), // Same mod name
                             version: "1.0.0".to_string(), // XML version
                             url: Some("http://another.com/mod.zip".to_string()),
+                            id: None,
+                            display_name: None,
+                            mirrors: Vec::new(),
                             new_version: None,
+                            downgrade_version: None,
                             description: "Test description".to_string(),
+                            description_html: String::new(),
+                            localized_names: Vec::new(),
+                            localized_descriptions: Vec::new(),
+                            changelog_url: None,
+                            icon_url: None,
+                            screenshot_url: None,
+                            author: None,
+                            homepage_url: None,
+                            license: None,
+                            size: None,
+                            base_size: None,
+                            platform: None,
+                            dcs_version: None,
+                            dcs_variants: None,
+                            dcs_incompatible: false,
+                            deprecated: false,
+                            replaced_by: None,
+                            origin: None,
+                            source: None,
+                            favorite: false,
+                            note: None,
+                            since_last_visit: None,
+                            tags: Vec::new(),
+                            tags_attribute: None,
+                            install_notes: None,
+                            requires: Vec::new(),
+                            variants: Vec::new(),
+                            variant: None,
+                            installed_variant: None,
+                            enabling_lock: None,
                         }
                     ],
+                    subcategories: Vec::new(),
                 }
             ],
         };
@@ -174,4 +751,24 @@ mod tests {
         assert_eq!(updated_mod_other.version, "0.9.0"); // Local version from the second subdir
         assert_eq!(updated_mod_other.new_version, Some("1.0.0".to_string())); // Available update based on XML
     }
+
+    #[test]
+    fn test_check_for_updates_flags_downgrades() {
+        let base_temp_dir = tempdir().unwrap();
+        let repo_url = "http://example.com/repo.xml";
+        let mod_dir = base_temp_dir.path().join(get_test_repo_hash(repo_url)).join("Test Mod");
+        fs::create_dir_all(&mod_dir).unwrap();
+        let mods = ModParser::parse_mod_list(
+            r#"<mods><category name="Essential" sort_order="1"><mod name="Test Mod" version="1.0.1"/></category></mods>"#,
+        )
+        .unwrap();
+
+        // A local version newer than the repository's is flagged, not offered as an update
+        fs::write(mod_dir.join("VERSION.txt"), "1.0.10").unwrap();
+        let result = ModParser::check_for_updates(&mods, base_temp_dir.path(), repo_url).unwrap();
+        let downgraded_mod = &result.categories[0].mods[0];
+        assert_eq!(downgraded_mod.version, "1.0.10");
+        assert_eq!(downgraded_mod.new_version, None);
+        assert_eq!(downgraded_mod.downgrade_version, Some("1.0.1".to_string()));
+    }
 }
@@ -0,0 +1,163 @@
+use super::mod_enablement::{
+    assigned_names, file_patches, mod_source_roots, parse_patch_header, MappingConfig,
+    PatchAnchor, PatchSyntax, TargetDirs,
+};
+use super::mod_utils::list_profile_enabled_mod_dirs;
+use crate::settings::Settings;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One patch block found in a destination file
+#[derive(Debug, Serialize)]
+pub struct PatchEntry {
+    pub mod_name: String,
+    pub version: String,
+    /// Whether the mod places its patch with an insertion anchor instead of appending
+    pub anchored: bool,
+}
+
+/// A destination file patched by more than one mod
+#[derive(Debug, Serialize)]
+pub struct PatchConflict {
+    pub file: String,
+    /// Patches in the order they appear in the file
+    pub patches: Vec<PatchEntry>,
+    /// Whether the result depends on the order the mods were enabled in
+    pub ordering_sensitive: bool,
+    pub reasons: Vec<String>,
+}
+
+/// Record every patchable file of a mod whose destination is a real file, i.e. one that gets patched
+fn collect_patched_files(
+    source_root: &Path,
+    source_dir: &Path,
+    dest_root: &Path,
+    mod_name: &str,
+    patched: &mut BTreeMap<PathBuf, Vec<(String, PathBuf)>>,
+) {
+    let Ok(entries) = fs::read_dir(source_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_patched_files(source_root, &path, dest_root, mod_name, patched);
+        } else if PatchSyntax::for_path(&path).is_some() {
+            let Ok(relative) = path.strip_prefix(source_root) else {
+                continue;
+            };
+            let dest_path = dest_root.join(relative);
+            if dest_path.is_file() && !dest_path.is_symlink() {
+                patched
+                    .entry(dest_path)
+                    .or_default()
+                    .push((mod_name.to_string(), path));
+            }
+        }
+    }
+}
+
+/// Work out why the patches in a file could behave differently depending on enable order
+fn ordering_reasons(
+    patches: &[PatchEntry],
+    sources: &[(String, PathBuf)],
+    syntax: PatchSyntax,
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+
+    for patch in patches.iter().filter(|p| p.anchored) {
+        reasons.push(format!(
+            "{} inserts its patch at an anchor, its position relative to other patches depends on enable order",
+            patch.mod_name
+        ));
+    }
+
+    let mut assigned: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (mod_name, source) in sources {
+        let body = fs::read_to_string(source).unwrap_or_default();
+        let body = parse_patch_header(&body, syntax).map(|(_, body)| body).unwrap_or_default();
+        for name in assigned_names(&body, syntax) {
+            let mods = assigned.entry(name).or_default();
+            if !mods.contains(mod_name) {
+                mods.push(mod_name.clone());
+            }
+        }
+    }
+    for (name, mods) in assigned.into_iter().filter(|(_, mods)| mods.len() > 1) {
+        reasons.push(format!(
+            "{} all assign `{}`, the last patch in the file wins",
+            mods.join(", "),
+            name
+        ));
+    }
+
+    reasons
+}
+
+/// List destination files patched by more than one mod for a profile, the active one when no
+/// name is given
+#[tauri::command]
+pub async fn get_patch_conflicts(profile_name: Option<String>) -> Result<Vec<PatchConflict>, String> {
+    let settings = Settings::load()?;
+    let profile_name = settings.profile_or_active(profile_name)?;
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+    let targets = TargetDirs::from_profile(profile);
+
+    let mut patched = BTreeMap::new();
+    for mod_dir in list_profile_enabled_mod_dirs(&settings, profile)? {
+        let Some(mod_name) = mod_dir.file_name().and_then(|n| n.to_str()).map(String::from) else {
+            continue;
+        };
+        let mapping = MappingConfig::for_mod(&mod_dir).map_err(|e| e.to_string())?;
+        for (source_dir, target) in mod_source_roots(&mod_dir, &mod_name, &mapping) {
+            if let Ok(dest_root) = targets.get(target) {
+                collect_patched_files(&source_dir, &source_dir, dest_root, &mod_name, &mut patched);
+            }
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    for (dest_path, sources) in patched {
+        let Some(syntax) = PatchSyntax::for_path(&dest_path) else {
+            continue;
+        };
+        let Ok(patches) = file_patches(&dest_path, syntax) else {
+            continue;
+        };
+        let patches: Vec<PatchEntry> = patches
+            .into_iter()
+            .map(|info| {
+                let anchored = sources
+                    .iter()
+                    .find(|(mod_name, _)| *mod_name == info.mod_name)
+                    .and_then(|(_, source)| fs::read_to_string(source).ok())
+                    .and_then(|body| parse_patch_header(&body, syntax).ok())
+                    .is_some_and(|(anchor, _)| anchor != PatchAnchor::Append);
+                PatchEntry {
+                    mod_name: info.mod_name,
+                    version: info.version,
+                    anchored,
+                }
+            })
+            .collect();
+        if patches.len() < 2 {
+            continue;
+        }
+
+        let reasons = ordering_reasons(&patches, &sources, syntax);
+        conflicts.push(PatchConflict {
+            file: dest_path.display().to_string(),
+            ordering_sensitive: !reasons.is_empty(),
+            patches,
+            reasons,
+        });
+    }
+
+    Ok(conflicts)
+}
@@ -0,0 +1,81 @@
+use super::downloader::ModDownloader;
+use super::xml_cache::update_cache_path_in_settings;
+use crate::settings::Settings;
+use futures_util::stream::{self, StreamExt};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Repositories fetched at the same time, so a long profile list doesn't flood the network
+const PREFETCH_CONCURRENCY: usize = 3;
+
+/// How long a prefetched listing counts as fresh, opening its profile for the first time in
+/// that time doesn't fetch it again
+const PREFETCH_FRESH_FOR: Duration = Duration::from_secs(5 * 60);
+
+/// When each repository was prefetched
+static PREFETCHED: Mutex<Option<HashMap<String, Instant>>> = Mutex::new(None);
+
+/// Whether a repository was prefetched recently enough to skip refreshing it. The mark is used
+/// up, only the first load after the prefetch skips the refresh
+pub fn take_recent_prefetch(repo_url: &str) -> bool {
+    let mut prefetched = PREFETCHED.lock().unwrap_or_else(|e| e.into_inner());
+    prefetched
+        .as_mut()
+        .and_then(|prefetched| prefetched.remove(repo_url.trim_end_matches('/')))
+        .is_some_and(|at| at.elapsed() < PREFETCH_FRESH_FOR)
+}
+
+/// Fetch the repository of every profile but the active one in the background, which the mods
+/// page loads itself, so switching profiles shows their mods from a fresh cache right away
+pub fn start_prefetch() {
+    tauri::async_runtime::spawn(async move {
+        let Ok(settings) = Settings::load() else {
+            return;
+        };
+        let active_url = settings
+            .profiles
+            .iter()
+            .find(|profile| profile.name == settings.active_profile)
+            .map(|profile| profile.repo_url.trim_end_matches('/').to_string());
+        let urls: BTreeSet<String> = settings
+            .profiles
+            .iter()
+            .map(|profile| profile.repo_url.trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty() && Some(url) != active_url.as_ref())
+            .collect();
+        if urls.is_empty() {
+            return;
+        }
+        info!("Prefetching {} repositories", urls.len());
+
+        let fetched: Vec<_> = stream::iter(urls)
+            .map(|url| async move {
+                let fetched = ModDownloader::new().fetch_and_parse_mods(&url).await;
+                (url, fetched)
+            })
+            .buffer_unordered(PREFETCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        // Reloaded, the settings may have changed while the repositories were fetched
+        let Ok(mut settings) = Settings::load() else {
+            return;
+        };
+        for (url, fetched) in fetched {
+            match fetched {
+                Ok((_, cache_path)) => {
+                    if let Some(path) = cache_path {
+                        if let Err(e) = update_cache_path_in_settings(&mut settings, &url, &path) {
+                            warn!("Failed to update cache path in settings: {}", e);
+                        }
+                    }
+                    let mut prefetched = PREFETCHED.lock().unwrap_or_else(|e| e.into_inner());
+                    prefetched.get_or_insert_with(HashMap::new).insert(url, Instant::now());
+                }
+                Err(e) => warn!("Prefetching {} failed: {}", url, e),
+            }
+        }
+    });
+}
@@ -0,0 +1,127 @@
+use super::mod_management::{disable_mod, enable_mod, ModOperationOutcome};
+use super::mod_utils::list_profile_enabled_mod_dirs;
+use crate::app_data;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use tauri::AppHandle;
+
+const PRESETS_FILE: &str = "presets.json";
+
+/// A named set of mods to have enabled for a profile
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnablementPreset {
+    pub name: String,
+    pub mods: Vec<String>,
+}
+
+/// Presets keyed by profile name
+type PresetStore = HashMap<String, Vec<EnablementPreset>>;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresetApplyResult {
+    pub enabled: Vec<ModOperationOutcome>,
+    pub disabled: Vec<ModOperationOutcome>,
+}
+
+/// Names of the mods currently enabled for a profile
+fn current_enabled_mods(settings: &Settings, profile_name: &str) -> Result<BTreeSet<String>, String> {
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+
+    Ok(list_profile_enabled_mod_dirs(settings, profile)?
+        .iter()
+        .filter_map(|dir| dir.file_name().and_then(|n| n.to_str()).map(String::from))
+        .collect())
+}
+
+/// A profile's presets, the active profile's when no name is given
+#[tauri::command]
+pub async fn get_presets(profile_name: Option<String>) -> Result<Vec<EnablementPreset>, String> {
+    let profile_name = Settings::load()?.profile_or_active(profile_name)?;
+    let store: PresetStore = app_data::load_json(PRESETS_FILE)?;
+    Ok(store.get(&profile_name).cloned().unwrap_or_default())
+}
+
+/// Save the profile's currently enabled mods under a preset name, replacing a preset with the same name
+#[tauri::command]
+pub async fn save_preset(profile_name: Option<String>, preset_name: String) -> Result<EnablementPreset, String> {
+    if preset_name.trim().is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+
+    let settings = Settings::load()?;
+    let profile_name = settings.profile_or_active(profile_name)?;
+    let preset = EnablementPreset {
+        name: preset_name.trim().to_string(),
+        mods: current_enabled_mods(&settings, &profile_name)?.into_iter().collect(),
+    };
+
+    let mut store: PresetStore = app_data::load_json(PRESETS_FILE)?;
+    let presets = store.entry(profile_name).or_default();
+    presets.retain(|p| p.name != preset.name);
+    presets.push(preset.clone());
+    app_data::save_json(PRESETS_FILE, &store)?;
+
+    Ok(preset)
+}
+
+#[tauri::command]
+pub async fn delete_preset(profile_name: Option<String>, preset_name: String) -> Result<(), String> {
+    let profile_name = Settings::load()?.profile_or_active(profile_name)?;
+    let mut store: PresetStore = app_data::load_json(PRESETS_FILE)?;
+    if let Some(presets) = store.get_mut(&profile_name) {
+        presets.retain(|p| p.name != preset_name);
+    }
+    app_data::save_json(PRESETS_FILE, &store)
+}
+
+/// Move a profile's presets to its new name
+pub fn rename_profile_presets(old_name: &str, new_name: &str) -> Result<(), String> {
+    let mut store: PresetStore = app_data::load_json(PRESETS_FILE)?;
+    if let Some(presets) = store.remove(old_name) {
+        store.insert(new_name.to_string(), presets);
+        app_data::save_json(PRESETS_FILE, &store)?;
+    }
+    Ok(())
+}
+
+/// Enable and disable only the mods that differ between the current state and the preset
+#[tauri::command]
+pub async fn apply_preset(
+    app_handle: AppHandle,
+    profile_name: Option<String>,
+    preset_name: String,
+) -> Result<PresetApplyResult, String> {
+    let settings = Settings::load()?;
+    let profile_name = settings.profile_or_active(profile_name)?;
+    let store: PresetStore = app_data::load_json(PRESETS_FILE)?;
+    let preset = store
+        .get(&profile_name)
+        .and_then(|presets| presets.iter().find(|p| p.name == preset_name))
+        .ok_or_else(|| format!("Preset '{}' not found", preset_name))?;
+
+    let current = current_enabled_mods(&settings, &profile_name)?;
+    let wanted: BTreeSet<String> = preset.mods.iter().cloned().collect();
+
+    let mut result = PresetApplyResult {
+        enabled: Vec::new(),
+        disabled: Vec::new(),
+    };
+
+    // Disable first so mods leaving the set free up any files the new ones need
+    for mod_name in current.difference(&wanted) {
+        let outcome = disable_mod(app_handle.clone(), mod_name.clone(), profile_name.clone(), None).await;
+        result.disabled.push(ModOperationOutcome::from_result(mod_name, outcome));
+    }
+
+    for mod_name in wanted.difference(&current) {
+        let outcome = enable_mod(app_handle.clone(), mod_name.clone(), profile_name.clone(), None).await;
+        result.enabled.push(ModOperationOutcome::from_result(mod_name, outcome));
+    }
+
+    Ok(result)
+}
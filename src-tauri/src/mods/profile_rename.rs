@@ -0,0 +1,180 @@
+use super::enable_state::{has_profile_state, rename_profile_state};
+use super::last_seen::rename_profile_last_seen;
+use super::mod_enablement::{get_backup_root, get_manifest_copies_dir, TargetRoot};
+use super::mod_utils::{
+    get_enabled_file_path, get_enabling_file_path, get_manifest_path, list_mod_dirs, list_source_dirs,
+};
+use super::presets::rename_profile_presets;
+use crate::settings::Settings;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{error, info, warn};
+
+/// Characters a profile name can't have, it is part of file and folder names
+const INVALID_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Every mod folder a profile can have markers in: all download pools and the sideload directory
+fn all_mod_dirs(settings: &Settings) -> Vec<PathBuf> {
+    let mut mod_dirs: Vec<PathBuf> = list_source_dirs(settings)
+        .into_iter()
+        .flat_map(|(_, dir)| list_mod_dirs(&dir))
+        .collect();
+    if !settings.sideload_path.is_empty() {
+        mod_dirs.extend(list_mod_dirs(Path::new(&settings.sideload_path)));
+    }
+    mod_dirs
+}
+
+/// Files and folders named after a profile, paired with where they go under the new name:
+/// ENABLED markers, enablement manifests and their copies, and the backups of DCS files
+fn profile_file_moves(settings: &Settings, old_name: &str, new_name: &str) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let mut moves = Vec::new();
+    for mod_dir in all_mod_dirs(settings) {
+        if get_enabling_file_path(&mod_dir, old_name).exists() {
+            return Err(format!(
+                "{} is being enabled for {}, wait for it to finish before renaming the profile",
+                mod_dir.display(),
+                old_name
+            ));
+        }
+        moves.push((get_enabled_file_path(&mod_dir, old_name), get_enabled_file_path(&mod_dir, new_name)));
+        moves.push((get_manifest_path(&mod_dir, old_name), get_manifest_path(&mod_dir, new_name)));
+    }
+    for target in [TargetRoot::SavedGames, TargetRoot::Install] {
+        if let (Some(old), Some(new)) = (get_backup_root(old_name, target), get_backup_root(new_name, target)) {
+            moves.push((old, new));
+        }
+    }
+    if let (Some(old), Some(new)) = (get_manifest_copies_dir(old_name), get_manifest_copies_dir(new_name)) {
+        moves.push((old, new));
+    }
+    moves.retain(|(old, _)| old.exists());
+
+    // Left behind by a deleted profile of that name, they would be taken for this one's
+    if let Some((_, taken)) = moves.iter().find(|(_, new)| new.exists()) {
+        return Err(format!(
+            "{} belongs to an earlier profile named {}, remove it or pick another name",
+            taken.display(),
+            new_name
+        ));
+    }
+    Ok(moves)
+}
+
+/// Put back files moved so far, newest first
+fn undo_moves(done: &[(PathBuf, PathBuf)]) {
+    for (old, new) in done.iter().rev() {
+        if let Err(e) = fs::rename(new, old) {
+            error!("Failed to move {} back to {}: {}", new.display(), old.display(), e);
+        }
+    }
+}
+
+/// Move a profile's marker files and backups to a new name, all or none of them
+fn move_profile_files(settings: &Settings, old_name: &str, new_name: &str) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let moves = profile_file_moves(settings, old_name, new_name)?;
+    for (done, (old, new)) in moves.iter().enumerate() {
+        if let Err(e) = fs::rename(old, new) {
+            undo_moves(&moves[..done]);
+            return Err(format!("Failed to rename {}: {}", old.display(), e));
+        }
+    }
+    Ok(moves)
+}
+
+/// Rename a profile along with everything recorded under its name, so its mods stay enabled.
+/// The marker files and enable state move first and are moved back when the settings can't be saved
+#[tauri::command]
+pub async fn rename_profile(index: usize, new_name: String) -> Result<Settings, String> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if new_name.contains(INVALID_NAME_CHARS) {
+        return Err(format!(
+            "Profile names can't contain any of {}",
+            INVALID_NAME_CHARS.iter().collect::<String>()
+        ));
+    }
+
+    let mut settings = Settings::load()?;
+    let old_name = settings
+        .profiles
+        .get(index)
+        .ok_or_else(|| "Profile index out of bounds".to_string())?
+        .name
+        .clone();
+    if old_name == new_name {
+        return Ok(settings);
+    }
+    if settings.profiles.iter().any(|p| p.name == new_name) {
+        return Err(format!("A profile named '{}' already exists", new_name));
+    }
+
+    if has_profile_state(&new_name)? {
+        return Err(format!(
+            "Mods are still recorded as enabled for an earlier profile named {}, pick another name",
+            new_name
+        ));
+    }
+
+    let moves = move_profile_files(&settings, &old_name, &new_name)?;
+    if let Err(e) = rename_profile_state(&old_name, &new_name) {
+        undo_moves(&moves);
+        return Err(format!("Failed to move enabled mods to the new profile name: {}", e));
+    }
+    settings.profiles[index].name = new_name.clone();
+    if settings.active_profile == old_name {
+        settings.active_profile = new_name.clone();
+    }
+    if let Err(e) = settings.save() {
+        if let Err(undo_err) = rename_profile_state(&new_name, &old_name) {
+            error!("Failed to move enabled mods back to {}: {}", old_name, undo_err);
+        }
+        undo_moves(&moves);
+        return Err(e);
+    }
+    info!("Renamed profile {} to {}, moving {} files", old_name, new_name, moves.len());
+
+    // Conveniences, the rename stands without them
+    if let Err(e) = rename_profile_presets(&old_name, &new_name) {
+        warn!("Failed to move presets to the new profile name: {}", e);
+    }
+    if let Err(e) = rename_profile_last_seen(&old_name, &new_name) {
+        warn!("Failed to move last seen repository state to the new profile name: {}", e);
+    }
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_profile_files_all_or_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = Settings {
+            download_path: dir.path().join("downloads").to_string_lossy().to_string(),
+            ..Settings::default()
+        };
+        let mod_a = dir.path().join("downloads").join("repo").join("ModA");
+        let mod_b = dir.path().join("downloads").join("repo").join("ModB");
+        fs::create_dir_all(&mod_a).unwrap();
+        fs::create_dir_all(&mod_b).unwrap();
+        fs::write(get_enabled_file_path(&mod_a, "Rename Test Old"), "").unwrap();
+        fs::write(get_manifest_path(&mod_a, "Rename Test Old"), "{}").unwrap();
+        fs::write(get_enabled_file_path(&mod_b, "Rename Test Old"), "").unwrap();
+
+        // A stale marker under the new name stops the rename before anything moves
+        fs::write(get_enabled_file_path(&mod_b, "Rename Test New"), "").unwrap();
+        assert!(move_profile_files(&settings, "Rename Test Old", "Rename Test New").is_err());
+        assert!(get_enabled_file_path(&mod_a, "Rename Test Old").exists());
+
+        fs::remove_file(get_enabled_file_path(&mod_b, "Rename Test New")).unwrap();
+        move_profile_files(&settings, "Rename Test Old", "Rename Test New").unwrap();
+        assert!(get_enabled_file_path(&mod_a, "Rename Test New").exists());
+        assert!(get_manifest_path(&mod_a, "Rename Test New").exists());
+        assert!(get_enabled_file_path(&mod_b, "Rename Test New").exists());
+        assert!(!get_enabled_file_path(&mod_a, "Rename Test Old").exists());
+    }
+}
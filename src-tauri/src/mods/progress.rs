@@ -1,4 +1,8 @@
 use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+use tauri::{AppHandle, Emitter};
+use tracing::error;
 
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -15,4 +19,103 @@ pub fn calculate_progress(downloaded: u64, total_size: u64) -> f32 {
     } else {
         0.0
     }
-}
\ No newline at end of file
+}
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnablementProgress {
+    pub mod_name: String,
+    pub operation: String,
+    pub processed: usize,
+    pub total: usize,
+    pub current_file: String,
+    pub progress_percent: f32,
+}
+
+/// Payload of `batch-progress`, emitted after each mod of a batch enable/disable
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub mod_name: String,
+    pub operation: String,
+    pub success: bool,
+    pub processed: usize,
+    pub total: usize,
+    pub progress_percent: f32,
+}
+
+/// Count the files below a directory, used as the unit of work for enable/disable progress
+pub fn count_files(dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                count_files(&path)
+            } else {
+                1
+            }
+        })
+        .sum()
+}
+
+/// Emits `enablement-progress` events while a mod's files are linked or unlinked
+pub struct EnablementProgressTracker {
+    app_handle: AppHandle,
+    mod_name: String,
+    operation: String,
+    total: usize,
+    processed: AtomicUsize,
+    last_emitted_percent: AtomicI32,
+}
+
+impl EnablementProgressTracker {
+    pub fn new(app_handle: AppHandle, mod_name: &str, total: usize, cleanup: bool) -> Self {
+        Self {
+            app_handle,
+            mod_name: mod_name.to_string(),
+            operation: if cleanup { "disable" } else { "enable" }.to_string(),
+            total,
+            processed: AtomicUsize::new(0),
+            last_emitted_percent: AtomicI32::new(0),
+        }
+    }
+
+    /// Record `items` processed files and emit an event if a whole percentage point was crossed
+    pub fn advance(&self, items: usize, current_file: &Path) {
+        let processed = self.processed.fetch_add(items, Ordering::Relaxed) + items;
+        let progress = calculate_progress(processed as u64, self.total as u64);
+        let current_percent = progress.floor() as i32;
+
+        if current_percent > self.last_emitted_percent.load(Ordering::Relaxed) {
+            self.last_emitted_percent.store(current_percent, Ordering::Relaxed);
+            self.emit(processed, progress, current_file);
+        }
+    }
+
+    /// Always emit 100% at the end
+    pub fn finish(&self) {
+        if self.last_emitted_percent.load(Ordering::Relaxed) < 100 {
+            self.last_emitted_percent.store(100, Ordering::Relaxed);
+            self.emit(self.total, 100.0, Path::new(""));
+        }
+    }
+
+    fn emit(&self, processed: usize, progress: f32, current_file: &Path) {
+        if let Err(e) = self.app_handle.emit(
+            "enablement-progress",
+            EnablementProgress {
+                mod_name: self.mod_name.clone(),
+                operation: self.operation.clone(),
+                processed,
+                total: self.total,
+                current_file: current_file.display().to_string(),
+                progress_percent: progress,
+            },
+        ) {
+            error!("Failed to emit enablement-progress event: {}", e);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Earlier copies of the cached repository files, kept when a fetch brings a changed file, so a
+//! listing can be compared with what the repository offered before
+use super::includes::merge_includes_from;
+use super::parser::ModParser;
+use super::types::ModsFile;
+use super::xml_cache::XmlCache;
+use crate::settings::app_language;
+use crate::state_db::now_secs;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Earlier copies kept of each cached repository file
+const SNAPSHOTS_KEPT: usize = 10;
+
+/// Included files are fetched right after the file including them, a copy fetched this much
+/// later still belongs to the same fetch
+const FETCH_WINDOW_SECS: u64 = 120;
+
+/// A fetch of a repository that can be compared
+#[derive(Debug, Serialize, Clone)]
+pub struct RepoSnapshot {
+    /// Seconds since the Unix epoch
+    pub fetched_at: u64,
+    /// The cached copy in use rather than an earlier one
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RepoModVersion {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RepoModChange {
+    pub name: String,
+    pub old_version: String,
+    pub new_version: String,
+}
+
+/// What changed in a repository between two fetches
+#[derive(Debug, Serialize, Default)]
+pub struct RepoDiff {
+    pub from_fetched_at: Option<u64>,
+    pub to_fetched_at: Option<u64>,
+    pub added: Vec<RepoModVersion>,
+    pub removed: Vec<RepoModVersion>,
+    /// Mods listed with another version, newer or not
+    pub updated: Vec<RepoModChange>,
+}
+
+/// Where the earlier copies of a cached file go
+pub(super) fn history_dir(cache_file: &Path) -> PathBuf {
+    let stem = cache_file.file_stem().unwrap_or_default();
+    cache_file.with_file_name("history").join(stem)
+}
+
+/// Earlier copies in a history directory by when they were fetched, oldest first
+fn list_snapshots(dir: &Path) -> Vec<(u64, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<(u64, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter_map(|path| Some((path.file_stem()?.to_str()?.parse().ok()?, path)))
+        .collect();
+    snapshots.sort();
+    snapshots
+}
+
+/// Keep the cached copy a new download is about to replace, unless the repository file didn't
+/// change. Only the newest `SNAPSHOTS_KEPT` copies stay
+pub fn keep_replaced_copy(cache_file: &Path, replacement: &Path) -> io::Result<()> {
+    if !cache_file.is_file() || fs::read(cache_file)? == fs::read(replacement)? {
+        return Ok(());
+    }
+    let fetched_at = XmlCache::fetched_at(cache_file).unwrap_or_else(now_secs);
+    let dir = history_dir(cache_file);
+    fs::create_dir_all(&dir)?;
+    fs::rename(cache_file, dir.join(format!("{}.xml", fetched_at)))?;
+
+    let snapshots = list_snapshots(&dir);
+    for (_, path) in snapshots.iter().take(snapshots.len().saturating_sub(SNAPSHOTS_KEPT)) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Copies of a repository file with when each was fetched, oldest first and the current one last
+fn copies(url: &str) -> Vec<(u64, PathBuf)> {
+    let Some(current) = XmlCache::get_cache_path(url) else {
+        return Vec::new();
+    };
+    let mut copies = list_snapshots(&history_dir(&current));
+    if let Some(fetched_at) = XmlCache::fetched_at(&current) {
+        copies.push((fetched_at, current));
+    }
+    copies
+}
+
+/// The copy of a repository file that was in use at `at`
+fn copy_at(url: &str, at: u64) -> Option<(u64, PathBuf)> {
+    copies(url).into_iter().rev().find(|(fetched_at, _)| *fetched_at <= at + FETCH_WINDOW_SECS)
+}
+
+/// A repository's listing as of `at`, its included files as they were then too. Without a time
+/// the current cached listing
+fn listing_at(repo_url: &str, at: Option<u64>) -> Result<(u64, ModsFile), String> {
+    let no_copy = || format!("No cached copy of {} from that time", repo_url);
+    let (fetched_at, path) = match at {
+        Some(at) => copy_at(repo_url, at).ok_or_else(no_copy)?,
+        None => copies(repo_url).pop().ok_or_else(no_copy)?,
+    };
+    let language = app_language();
+    let mut mods_file = ModParser::parse_mod_list_file(&path, &language).map_err(|e| e.to_string())?;
+    merge_includes_from(&mut mods_file, repo_url, |url| {
+        let (_, path) = copy_at(url, fetched_at)?;
+        ModParser::parse_mod_list_file(&path, &language).ok()
+    });
+    Ok((fetched_at, mods_file))
+}
+
+fn mod_versions(mods_file: &ModsFile) -> BTreeMap<&str, &str> {
+    let mut versions = BTreeMap::new();
+    for m in mods_file.categories.iter().flat_map(|cat| cat.mods.iter()) {
+        versions.entry(m.name.as_str()).or_insert(m.version.as_str());
+    }
+    versions
+}
+
+fn diff_listings(old: &ModsFile, new: &ModsFile) -> RepoDiff {
+    let (old, new) = (mod_versions(old), mod_versions(new));
+    let version = |(name, version): (&&str, &&str)| RepoModVersion {
+        name: name.to_string(),
+        version: version.to_string(),
+    };
+    RepoDiff {
+        added: new.iter().filter(|(name, _)| !old.contains_key(*name)).map(version).collect(),
+        removed: old.iter().filter(|(name, _)| !new.contains_key(*name)).map(version).collect(),
+        updated: new
+            .iter()
+            .filter_map(|(name, new_version)| {
+                let old_version = old.get(name).filter(|old_version| *old_version != new_version)?;
+                Some(RepoModChange {
+                    name: name.to_string(),
+                    old_version: old_version.to_string(),
+                    new_version: new_version.to_string(),
+                })
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Fetches of a repository that differ from one another, newest first
+#[tauri::command]
+pub async fn get_repo_snapshots(repo_url: String) -> Result<Vec<RepoSnapshot>, String> {
+    let mut copies = copies(repo_url.trim_end_matches('/'));
+    let current = copies.pop();
+    Ok(current
+        .map(|(fetched_at, _)| RepoSnapshot { fetched_at, current: true })
+        .into_iter()
+        .chain(copies.into_iter().rev().map(|(fetched_at, _)| RepoSnapshot { fetched_at, current: false }))
+        .collect())
+}
+
+/// Mods added, removed and updated in a repository between the listings in use at two times,
+/// such as a week ago and now. `from` defaults to the fetch before the current one and `to` to
+/// the current listing
+#[tauri::command]
+pub async fn diff_repo_versions(repo_url: String, from: Option<u64>, to: Option<u64>) -> Result<RepoDiff, String> {
+    let repo_url = repo_url.trim_end_matches('/');
+    let from = match from {
+        Some(from) => from,
+        None => {
+            let copies = copies(repo_url);
+            let previous = copies.len().checked_sub(2).map(|index| copies[index].0);
+            previous.ok_or_else(|| "No earlier copy of this repository is kept yet".to_string())?
+        }
+    };
+    let (from_fetched_at, old) = listing_at(repo_url, Some(from))?;
+    let (to_fetched_at, new) = listing_at(repo_url, to)?;
+
+    let mut diff = diff_listings(&old, &new);
+    diff.from_fetched_at = Some(from_fetched_at);
+    diff.to_fetched_at = Some(to_fetched_at);
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_listings() {
+        let old = ModParser::parse_mod_list(
+            r#"<mods><category name="Aircraft" sort_order="1">
+                <mod name="ModA" version="1.0" url="https://example.com/a.zip"/>
+                <mod name="ModB" version="2.0" url="https://example.com/b.zip"/>
+                <mod name="ModC" version="1.0" url="https://example.com/c.zip"/>
+            </category></mods>"#,
+        )
+        .unwrap();
+        let new = ModParser::parse_mod_list(
+            r#"<mods><category name="Aircraft" sort_order="1">
+                <mod name="ModA" version="1.1" url="https://example.com/a.zip"/>
+                <mod name="ModC" version="1.0" url="https://example.com/c.zip"/>
+                <mod name="ModD" version="0.9" url="https://example.com/d.zip"/>
+            </category></mods>"#,
+        )
+        .unwrap();
+
+        let diff = diff_listings(&old, &new);
+        assert_eq!(diff.added, [RepoModVersion { name: "ModD".to_string(), version: "0.9".to_string() }]);
+        assert_eq!(diff.removed, [RepoModVersion { name: "ModB".to_string(), version: "2.0".to_string() }]);
+        assert_eq!(
+            diff.updated,
+            [RepoModChange { name: "ModA".to_string(), old_version: "1.0".to_string(), new_version: "1.1".to_string() }]
+        );
+    }
+}
@@ -0,0 +1,731 @@
+use super::downloader::ModDownloader;
+use super::parser::{ModParser, SUPPORTED_REPO_FORMAT};
+use super::types::{ModError, PLATFORMS};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+const MOD_ATTRIBUTES: &[&str] = &[
+    "id", "name", "version", "url", "changelog", "icon", "screenshot", "author", "homepage", "license", "size", "tags",
+    "dcs_version", "dcs_variants", "deprecated", "replaced_by", "platform",
+];
+const CATEGORY_ATTRIBUTES: &[&str] = &["name", "sort_order", "description"];
+const REQUIRES_ATTRIBUTES: &[&str] = &["version"];
+const URL_ATTRIBUTES: &[&str] = &["region", "priority"];
+const HEADER_ATTRIBUTES: &[&str] = &["name", "maintainer", "support"];
+const VARIANT_ATTRIBUTES: &[&str] = &["id", "name", "url", "size"];
+const MAPPING_ATTRIBUTES: &[&str] = &["target", "link_depth"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// BZMM can't load the repository
+    Error,
+    /// The repository loads, but probably not the way the maintainer meant
+    Warning,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RepoIssue {
+    pub severity: IssueSeverity,
+    pub line: usize,
+    pub column: usize,
+    /// Element the problem was found on, e.g. `<mod name="F-16 Livery">`
+    pub element: Option<String>,
+    pub message: String,
+    pub hint: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RepoValidationReport {
+    /// True when there are no errors, warnings don't count
+    pub valid: bool,
+    pub mod_count: usize,
+    pub issues: Vec<RepoIssue>,
+}
+
+/// Walks a repository XML and records issues with their position
+struct Validator<'a> {
+    xml: &'a str,
+    issues: Vec<RepoIssue>,
+}
+
+impl Validator<'_> {
+    /// 1-based line and column of a byte offset
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let before = &self.xml[..offset.min(self.xml.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+        (line, column)
+    }
+
+    fn push(
+        &mut self,
+        severity: IssueSeverity,
+        offset: usize,
+        element: Option<String>,
+        message: String,
+        hint: &str,
+    ) {
+        let (line, column) = self.position(offset);
+        self.issues.push(RepoIssue {
+            severity,
+            line,
+            column,
+            element,
+            message,
+            hint: hint.to_string(),
+        });
+    }
+}
+
+/// Tag name with its name attribute, to point at the offending element
+fn describe(tag: &BytesStart) -> String {
+    let name = String::from_utf8_lossy(tag.name().as_ref()).to_string();
+    match attributes(tag).get("name") {
+        Some(value) => format!("<{} name=\"{}\">", name, value),
+        None => format!("<{}>", name),
+    }
+}
+
+fn attributes(tag: &BytesStart) -> HashMap<String, String> {
+    tag.attributes()
+        .filter_map(Result::ok)
+        .map(|attr| {
+            let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+            let value = attr.unescape_value().map(|v| v.to_string()).unwrap_or_default();
+            (key, value)
+        })
+        .collect()
+}
+
+/// Check an XML document against the repository schema
+fn report_missing_url(validator: &mut Validator, offset: usize, element: Option<String>) {
+    validator.push(
+        IssueSeverity::Warning,
+        offset,
+        element,
+        "Mod has no url, users won't be able to download it".to_string(),
+        "Add url=\"https://...\" or <url> elements pointing at the mod's zip",
+    );
+}
+
+pub fn validate_repo_xml(xml: &str) -> RepoValidationReport {
+    use IssueSeverity::{Error, Warning};
+
+    let mut validator = Validator { xml, issues: Vec::new() };
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<String> = Vec::new();
+    // Mods by id, or name when they have none, with the line they were first listed on
+    let mut seen_mods: HashMap<String, usize> = HashMap::new();
+    // Names of mods that have an id, <requires> can give either
+    let mut display_names: HashSet<String> = HashSet::new();
+    let mut category_mods: Vec<usize> = Vec::new();
+    // Names given in <requires>, with where they were given, checked once every mod has been seen
+    let mut requirements: Vec<(String, usize, Option<String>)> = Vec::new();
+    // Replacements named by deprecated mods, checked the same way
+    let mut replacements: Vec<(String, usize, Option<String>)> = Vec::new();
+    // A mod without a url attribute, reported when it closes without any <url> either
+    let mut missing_url: Option<(usize, Option<String>)> = None;
+    // Variant ids of the mod being read, which have to be unique within it
+    let mut variant_ids: HashSet<String> = HashSet::new();
+    let mut saw_root = false;
+    let mut saw_header = false;
+
+    loop {
+        let offset = reader.buffer_position() as usize;
+        let event = match reader.read_event() {
+            Ok(event) => event,
+            Err(e) => {
+                validator.push(
+                    Error,
+                    reader.error_position() as usize,
+                    stack.last().map(|name| format!("<{}>", name)),
+                    format!("Malformed XML: {}", e),
+                    "Check that every tag is closed, attribute values are quoted and & is written as &amp;",
+                );
+                break;
+            }
+        };
+        let (tag, is_empty) = match &event {
+            Event::Start(tag) => (tag, false),
+            Event::Empty(tag) => (tag, true),
+            Event::End(_) => {
+                match stack.pop().as_deref() {
+                    Some("category") => {
+                        category_mods.pop();
+                    }
+                    Some("mod") => {
+                        if let Some((offset, element)) = missing_url.take() {
+                            report_missing_url(&mut validator, offset, element);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            Event::Text(text) if stack.last().is_some_and(|name| name == "requires") => {
+                if let Ok(required) = text.unescape() {
+                    if let Some(last) = requirements.last_mut() {
+                        last.0.push_str(required.trim());
+                    }
+                }
+                continue;
+            }
+            Event::Text(text) if stack.last().is_some_and(|name| name == "url") => {
+                if let Ok(url) = text.unescape() {
+                    if !url.trim().starts_with("http") {
+                        validator.push(
+                            Error,
+                            offset,
+                            Some("<url>".to_string()),
+                            format!("Mirror URL '{}' is not an http(s) link", url.trim()),
+                            "Use a direct https:// link to the zip",
+                        );
+                    }
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let name = String::from_utf8_lossy(tag.name().as_ref()).to_string();
+        let attrs = attributes(tag);
+        let element = Some(describe(tag));
+        match (stack.last().map(String::as_str), name.as_str()) {
+            (None, "mods") => {
+                saw_root = true;
+                if let Some(format) = attrs.get("format") {
+                    match format.trim().parse::<u32>() {
+                        Ok(version) if version > SUPPORTED_REPO_FORMAT => validator.push(
+                            Error,
+                            offset,
+                            element.clone(),
+                            format!(
+                                "Format version {} is newer than this BZMM supports ({})",
+                                version, SUPPORTED_REPO_FORMAT
+                            ),
+                            "Lower the format attribute, or users will need to update BZMM",
+                        ),
+                        Ok(_) => {}
+                        Err(_) => validator.push(
+                            Error,
+                            offset,
+                            element.clone(),
+                            format!("Format version '{}' is not a number", format),
+                            "Use format=\"1\" or leave the attribute out",
+                        ),
+                    }
+                }
+            }
+            (None, _) => validator.push(
+                Error,
+                offset,
+                element.clone(),
+                format!("The root element is <{}> instead of <mods>", name),
+                "Wrap the categories in a single <mods> element",
+            ),
+            (Some("mods"), "header") => {
+                if std::mem::replace(&mut saw_header, true) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        "The repository has more than one <header>".to_string(),
+                        "Merge the announcements into a single header",
+                    );
+                }
+                if let Some(support) = attrs.get("support").filter(|url| !url.trim().starts_with("http")) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Support link '{}' is not an http(s) link", support),
+                        "Link a web page such as a forum thread or a Discord invite",
+                    );
+                }
+                for key in attrs.keys().filter(|key| !HEADER_ATTRIBUTES.contains(&key.as_str())) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown header attribute '{}' is ignored", key),
+                        "The header only uses name, maintainer and support, the announcement goes inside it",
+                    );
+                }
+            }
+            (Some("mods"), "include") => {
+                match attrs.get("url").map(|url| url.trim()) {
+                    None | Some("") => validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        "Include has no url attribute".to_string(),
+                        "Point it at another repository file, e.g. <include url=\"maintainers/viper.xml\"/>",
+                    ),
+                    Some(url) if url.contains("://") && !url.starts_with("http") => validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        format!("Include URL '{}' is not an http(s) link", url),
+                        "Use an https:// link, or a path relative to this file",
+                    ),
+                    _ => {}
+                }
+                for key in attrs.keys().filter(|key| key.as_str() != "url") {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown include attribute '{}' is ignored", key),
+                        "Includes only take a url",
+                    );
+                }
+            }
+            (Some("mods"), "mapping") => {
+                if attrs.get("target").is_some_and(|target| !["saved_games", "install"].contains(&target.trim())) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        format!("Mapping target '{}' is unknown", attrs["target"]),
+                        "Use target=\"saved_games\" or target=\"install\"",
+                    );
+                }
+                if attrs.get("link_depth").is_some_and(|depth| depth.trim().parse::<u8>().is_err()) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        "link_depth is not a whole number".to_string(),
+                        "Give the level directories start being linked at, e.g. link_depth=\"3\"",
+                    );
+                }
+                for key in attrs.keys().filter(|key| !MAPPING_ATTRIBUTES.contains(&key.as_str())) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown mapping attribute '{}' is ignored", key),
+                        "The mapping only uses target and link_depth, rules and excludes go inside it",
+                    );
+                }
+            }
+            (Some("mapping"), "rule") => {
+                if attrs.get("path").is_none_or(|path| path.trim().is_empty()) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        "Mapping rule has no path".to_string(),
+                        "Give the directory relative to the main mod subdirectory, e.g. path=\"Mods/tech\"",
+                    );
+                }
+                if !attrs.get("strategy").is_some_and(|strategy| ["link", "recurse"].contains(&strategy.trim())) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        "Mapping rule strategy is missing or unknown".to_string(),
+                        "Use strategy=\"link\" or strategy=\"recurse\"",
+                    );
+                }
+            }
+            (Some("mapping"), "exclude") => {}
+            (Some("mods" | "category"), "category") => {
+                for required in ["name", "sort_order"] {
+                    if !attrs.contains_key(required) {
+                        validator.push(
+                            Error,
+                            offset,
+                            element.clone(),
+                            format!("Category is missing the {} attribute", required),
+                            "Categories need both name and sort_order, e.g. <category name=\"Aircraft\" sort_order=\"1\">",
+                        );
+                    }
+                }
+                if attrs.get("sort_order").is_some_and(|order| order.trim().parse::<i32>().is_err()) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        "sort_order is not a whole number".to_string(),
+                        "Use an integer such as sort_order=\"10\"",
+                    );
+                }
+                for key in attrs.keys().filter(|key| !CATEGORY_ATTRIBUTES.contains(&key.as_str())) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown category attribute '{}' is ignored", key),
+                        "Check the spelling, categories only use name, sort_order and description",
+                    );
+                }
+                if is_empty {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        "Category has no mods".to_string(),
+                        "Add <mod> entries or subcategories, or remove the category",
+                    );
+                } else {
+                    category_mods.push(0);
+                }
+            }
+            (Some("category"), "mod") => {
+                if let Some(count) = category_mods.last_mut() {
+                    *count += 1;
+                }
+                variant_ids.clear();
+                for required in ["name", "version"] {
+                    if attrs.get(required).is_none_or(|value| value.trim().is_empty()) {
+                        validator.push(
+                            Error,
+                            offset,
+                            element.clone(),
+                            format!("Mod is missing the {} attribute", required),
+                            "Every mod needs a name and a version, e.g. <mod name=\"My Mod\" version=\"1.0\" url=\"...\">",
+                        );
+                    }
+                }
+                match attrs.get("url") {
+                    None if is_empty => report_missing_url(&mut validator, offset, element.clone()),
+                    None => missing_url = Some((offset, element.clone())),
+                    Some(url) if !url.starts_with("http") => validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        format!("Download URL '{}' is not an http(s) link", url),
+                        "Use a direct https:// link to the zip",
+                    ),
+                    _ => {}
+                }
+                if let (Some(_), Some(name)) = (attrs.get("id"), attrs.get("name")) {
+                    display_names.insert(name.clone());
+                }
+                if let Some(mod_name) = attrs.get("id").or(attrs.get("name")) {
+                    if let Some(first_line) = seen_mods.get(mod_name) {
+                        let message = format!("Mod '{}' is already listed on line {}", mod_name, first_line);
+                        validator.push(
+                            Error,
+                            offset,
+                            element.clone(),
+                            message,
+                            "Mod ids, or names for mods without one, must be unique across the repository, they name the download folder",
+                        );
+                    } else {
+                        let (line, _) = validator.position(offset);
+                        seen_mods.insert(mod_name.clone(), line);
+                    }
+                    if mod_name.contains(['/', '\\', ':']) {
+                        validator.push(
+                            Error,
+                            offset,
+                            element.clone(),
+                            format!("Mod id or name '{}' contains path characters", mod_name),
+                            "Remove /, \\ and : from it, it is used as a folder name",
+                        );
+                    }
+                }
+                if let Some(deprecated) = attrs.get("deprecated").filter(|d| !matches!(d.as_str(), "true" | "false")) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        format!("deprecated='{}' is neither true nor false", deprecated),
+                        "Use deprecated=\"true\", or replaced_by=\"Other Mod\" which implies it",
+                    );
+                }
+                let platforms = attrs.get("platform").into_iter().flat_map(|p| p.split(',')).map(str::trim);
+                for platform in platforms.filter(|p| !p.is_empty() && !PLATFORMS.contains(&p.to_lowercase().as_str())) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown platform '{}', no installation will match it", platform),
+                        "Use windows, linux or macos, separated by commas",
+                    );
+                }
+                if let Some(replaced_by) = attrs.get("replaced_by") {
+                    replacements.push((replaced_by.trim().to_string(), offset, element.clone()));
+                }
+                if let Some(size) = attrs.get("size").filter(|size| size.parse::<u64>().is_err()) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        format!("Size '{}' is not a number of bytes", size),
+                        "Give the zip's size in bytes, e.g. size=\"52428800\", or leave it out",
+                    );
+                }
+                for key in attrs.keys().filter(|key| !MOD_ATTRIBUTES.contains(&key.as_str())) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown mod attribute '{}' is ignored", key),
+                        "Check the spelling against the attributes listed in the repository format",
+                    );
+                }
+            }
+            (Some("mod"), "tag" | "notes") => {}
+            (Some("mod"), "name" | "description") => {
+                if attrs.get("lang").is_none_or(|lang| lang.trim().is_empty()) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("<{}> has no lang attribute, it is never shown", name),
+                        "Translations give their language, e.g. <name lang=\"de\">...</name>",
+                    );
+                }
+                for key in attrs.keys().filter(|key| key.as_str() != "lang") {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown {} attribute '{}' is ignored", name, key),
+                        "Translations only take a lang attribute",
+                    );
+                }
+            }
+            (Some("mod"), "url") => {
+                missing_url = None;
+                for key in attrs.keys().filter(|key| !URL_ATTRIBUTES.contains(&key.as_str())) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown url attribute '{}' is ignored", key),
+                        "Mirrors can only give a region and a priority, e.g. <url region=\"eu\" priority=\"1\">",
+                    );
+                }
+                if let Some(priority) = attrs.get("priority").filter(|p| p.parse::<i32>().is_err()) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        format!("Mirror priority '{}' is not a whole number", priority),
+                        "Lower priorities are tried first, e.g. priority=\"1\"",
+                    );
+                }
+            }
+            (Some("mod"), "variant") => {
+                // Variants bring their own downloads
+                missing_url = None;
+                for required in ["id", "url"] {
+                    if attrs.get(required).is_none_or(|value| value.trim().is_empty()) {
+                        validator.push(
+                            Error,
+                            offset,
+                            element.clone(),
+                            format!("Variant is missing the {} attribute", required),
+                            "Variants need an id and a url, e.g. <variant id=\"2k\" name=\"2K textures\" url=\"...\">",
+                        );
+                    }
+                }
+                if let Some(url) = attrs.get("url").filter(|url| !url.trim().is_empty() && !url.starts_with("http")) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        format!("Variant URL '{}' is not an http(s) link", url),
+                        "Use a direct https:// link to the zip",
+                    );
+                }
+                if let Some(id) = attrs.get("id").map(|id| id.trim()).filter(|id| !id.is_empty()) {
+                    if !variant_ids.insert(id.to_string()) {
+                        validator.push(
+                            Error,
+                            offset,
+                            element.clone(),
+                            format!("Variant id '{}' is used twice in this mod", id),
+                            "Give every variant of a mod its own id",
+                        );
+                    }
+                }
+                if attrs.get("size").is_some_and(|size| size.trim().parse::<u64>().is_err()) {
+                    validator.push(
+                        Error,
+                        offset,
+                        element.clone(),
+                        "Variant size is not a whole number of bytes".to_string(),
+                        "Give the size of the zip in bytes, e.g. size=\"1048576\"",
+                    );
+                }
+                for key in attrs.keys().filter(|key| !VARIANT_ATTRIBUTES.contains(&key.as_str())) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown variant attribute '{}' is ignored", key),
+                        "Variants only use id, name, url and size",
+                    );
+                }
+            }
+            (Some("mod"), "requires") => {
+                for key in attrs.keys().filter(|key| !REQUIRES_ATTRIBUTES.contains(&key.as_str())) {
+                    validator.push(
+                        Warning,
+                        offset,
+                        element.clone(),
+                        format!("Unknown requires attribute '{}' is ignored", key),
+                        "Only a minimum version can be given, e.g. <requires version=\"1.2\">Other Mod</requires>",
+                    );
+                }
+                requirements.push((String::new(), offset, element.clone()));
+            }
+            (Some(parent), _) => validator.push(
+                Error,
+                offset,
+                element.clone(),
+                format!("<{}> is not expected inside <{}>", name, parent),
+                "<mods> holds a <header>, a <mapping>, <include> and <category> elements, which hold <mod> elements and nested <category> elements, mods can hold <url>, <variant>, <name>, <description>, <tag>, <notes> and <requires> elements, a mapping <rule> and <exclude> elements",
+            ),
+        }
+
+        if !is_empty {
+            stack.push(name);
+        }
+    }
+
+    for (required, offset, element) in requirements {
+        if required.is_empty() {
+            validator.push(
+                Error,
+                offset,
+                element,
+                "<requires> doesn't name a mod".to_string(),
+                "Put the name of the required mod inside the element",
+            );
+        } else if !seen_mods.contains_key(&required) && !display_names.contains(&required) {
+            validator.push(
+                Warning,
+                offset,
+                element,
+                format!("Required mod '{}' is not in the repository", required),
+                "Users will have to install it themselves, check the spelling against the mod's name attribute",
+            );
+        }
+    }
+
+    for (replaced_by, offset, element) in replacements {
+        if !replaced_by.is_empty() && !seen_mods.contains_key(&replaced_by) && !display_names.contains(&replaced_by) {
+            validator.push(
+                Warning,
+                offset,
+                element,
+                format!("Replacement mod '{}' is not in the repository", replaced_by),
+                "Users won't be able to migrate, check the spelling against the replacement's id or name",
+            );
+        }
+    }
+
+    if !saw_root && validator.issues.is_empty() {
+        validator.push(
+            Error,
+            0,
+            None,
+            "The document has no <mods> root element".to_string(),
+            "Repository files start with <mods> and list <category> elements inside it",
+        );
+    }
+
+    let mut issues = validator.issues;
+    let valid = !issues.iter().any(|issue| issue.severity == Error);
+    // Anything the walk missed still shows up when actually loading the file
+    if valid {
+        if let Err(e) = ModParser::parse_mod_list(xml) {
+            issues.push(RepoIssue {
+                severity: Error,
+                line: 1,
+                column: 1,
+                element: None,
+                message: e.to_string(),
+                hint: "The file doesn't match the repository schema".to_string(),
+            });
+        }
+    }
+
+    RepoValidationReport {
+        valid: !issues.iter().any(|issue| issue.severity == Error),
+        mod_count: seen_mods.len(),
+        issues,
+    }
+}
+
+/// Validate a repository XML from a URL or a local file, for repository maintainers
+#[tauri::command]
+pub async fn validate_repo(source: String) -> Result<RepoValidationReport, String> {
+    let xml = if source.starts_with("http://") || source.starts_with("https://") {
+        ModDownloader::new()
+            .fetch_mod_list(&source)
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", source, e))?
+    } else {
+        std::fs::read(&source)
+            .map_err(ModError::from)
+            .and_then(|body| ModParser::repo_text(&body))
+            .map_err(|e| format!("Failed to read {}: {}", source, e))?
+    };
+    Ok(validate_repo_xml(&xml))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_reports_position_of_missing_version() {
+        let xml = "<mods>\n  <category name=\"Aircraft\" sort_order=\"1\">\n    <mod name=\"Viper\" url=\"https://example.com/v.zip\"/>\n  </category>\n</mods>";
+        let report = validate_repo_xml(xml);
+
+        assert!(!report.valid);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!((report.issues[0].line, report.issues[0].column), (3, 5));
+        assert_eq!(report.issues[0].element.as_deref(), Some("<mod name=\"Viper\">"));
+    }
+
+    #[test]
+    fn test_validate_accepts_every_parsed_mod_attribute() {
+        let xml = r#"<mods>
+            <category name="Aircraft" sort_order="1">
+                <mod name="Viper" version="1.0" url="https://example.com/v.zip" dcs_version="2.9"
+                     dcs_variants="stable, openbeta" platform="windows"/>
+            </category>
+        </mods>"#;
+        let report = validate_repo_xml(xml);
+
+        assert!(report.valid);
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+    }
+    #[test]
+    fn test_validate_mapping() {
+        let xml = r#"<mods>
+            <mapping target="install" link_depth="3">
+                <rule path="Mods/tech/Shared" strategy="recurse"/>
+                <exclude>*.psd</exclude>
+            </mapping>
+            <category name="Aircraft" sort_order="1"/>
+        </mods>"#;
+        let report = validate_repo_xml(xml);
+        assert!(report.valid, "{:?}", report.issues);
+        let on_mapping = |issue: &&RepoIssue| {
+            issue.element.as_deref().is_some_and(|e| e.starts_with("<mapping") || e.starts_with("<rule"))
+        };
+        assert_eq!(report.issues.iter().filter(on_mapping).count(), 0, "{:?}", report.issues);
+
+        let xml = r#"<mods>
+            <mapping target="dcs" link_depth="deep"><rule path="Mods" strategy="copy"/></mapping>
+            <category name="Aircraft" sort_order="1"/>
+        </mods>"#;
+        let report = validate_repo_xml(xml);
+        assert!(!report.valid);
+        let errors = report.issues.iter().filter(|issue| issue.severity == IssueSeverity::Error).count();
+        assert_eq!(errors, 3, "{:?}", report.issues);
+    }
+}
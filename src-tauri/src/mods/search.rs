@@ -0,0 +1,128 @@
+use super::handlers::{fetch_mods, get_cached_mods};
+use super::mod_utils::{is_mod_enabled, local_mod_dir};
+use super::types::{Mod, ModsResult};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+
+/// Optional filters for `search_mods`, each one ignored when unset
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSearchFilters {
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub downloaded: Option<bool>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub has_update: Option<bool>,
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// Check whether every whitespace separated term of the query appears in the mod's name or description
+fn matches_query(mod_entry: &Mod, terms: &[String]) -> bool {
+    let name = mod_entry.display_name.as_deref().unwrap_or(&mod_entry.name).to_lowercase();
+    let description = mod_entry.description.to_lowercase();
+    terms
+        .iter()
+        .all(|term| name.contains(term.as_str()) || description.contains(term.as_str()))
+}
+
+/// Keep only the mods carrying a tag, and the categories that still have any
+pub fn filter_by_tag(result: &mut ModsResult, tag: &str) {
+    for category in &mut result.categories {
+        category.mods.retain(|mod_entry| mod_entry.has_tag(tag));
+    }
+    result.categories.retain(|cat| !cat.mods.is_empty());
+}
+
+/// Search a profile's mods by name and description, returning only the matching mods and the
+/// categories that still have any
+#[tauri::command]
+pub async fn search_mods(
+    profile_index: usize,
+    query: String,
+    filters: Option<ModSearchFilters>,
+) -> Result<ModsResult, String> {
+    let filters = filters.unwrap_or_default();
+    // Searching shouldn't hit the network on every keystroke, the cached listing is enough
+    let mut result = match get_cached_mods(profile_index)? {
+        Some(cached) => cached,
+        None => fetch_mods(profile_index).await?,
+    };
+
+    let settings = Settings::load()?;
+    let Some(profile) = settings.profiles.get(profile_index) else {
+        return Ok(result);
+    };
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+    if let Some(tag) = &filters.tag {
+        filter_by_tag(&mut result, tag);
+    }
+    if let Some(category) = &filters.category {
+        result
+            .categories
+            .retain(|cat| cat.name.eq_ignore_ascii_case(category));
+    }
+
+    let mut state_error = None;
+    for category in &mut result.categories {
+        category.mods.retain(|mod_entry| {
+            if !matches_query(mod_entry, &terms) {
+                return false;
+            }
+            if filters
+                .has_update
+                .is_some_and(|has_update| has_update != mod_entry.new_version.is_some())
+            {
+                return false;
+            }
+            if filters.downloaded.is_none() && filters.enabled.is_none() {
+                return true;
+            }
+
+            let mod_dir = local_mod_dir(&settings, mod_entry.source.as_deref(), &mod_entry.name);
+            let downloaded = mod_dir.is_some();
+            let enabled = match mod_dir.map(|dir| is_mod_enabled(&dir, &profile.name)) {
+                Some(Ok(enabled)) => enabled,
+                Some(Err(e)) => {
+                    state_error.get_or_insert(e);
+                    false
+                }
+                None => false,
+            };
+            filters.downloaded.is_none_or(|wanted| wanted == downloaded)
+                && filters.enabled.is_none_or(|wanted| wanted == enabled)
+        });
+    }
+    if let Some(e) = state_error {
+        return Err(e);
+    }
+    result.categories.retain(|cat| !cat.mods.is_empty());
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_matches_all_terms_in_name_or_description() {
+        let mod_entry = Mod::new_sideloaded(
+            "F-16 Livery Pack".to_string(),
+            "1.0".to_string(),
+            "Squadron liveries for the Viper".to_string(),
+        );
+        let terms = |query: &str| -> Vec<String> {
+            query.split_whitespace().map(str::to_lowercase).collect()
+        };
+
+        assert!(matches_query(&mod_entry, &terms("")));
+        assert!(matches_query(&mod_entry, &terms("f-16 viper")));
+        assert!(matches_query(&mod_entry, &terms("SQUADRON")));
+        assert!(!matches_query(&mod_entry, &terms("viper hornet")));
+    }
+}
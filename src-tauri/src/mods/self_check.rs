@@ -0,0 +1,256 @@
+use super::downloader::ModDownloader;
+use crate::settings::{check_dcs_path, Settings, SettingsUpdate};
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long a repository gets to answer before it counts as unreachable
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Something will likely go wrong later, but the app can carry on
+    Warning,
+    /// Downloading or enabling mods won't work until this is fixed
+    Error,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SelfCheckItem {
+    /// What was checked, e.g. `download_path` or `symlinks`, for the UI to offer a fix
+    pub check: String,
+    /// Profile the check was made for, none for app-wide ones
+    pub profile: Option<String>,
+    pub status: CheckStatus,
+    pub message: String,
+    /// What to do about it, for anything that isn't ok
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct SelfCheckReport {
+    /// True when no check found an error, warnings don't count
+    pub ok: bool,
+    pub checks: Vec<SelfCheckItem>,
+}
+
+impl SelfCheckReport {
+    fn push(&mut self, check: &str, profile: Option<&str>, result: Result<String, (CheckStatus, String, &str)>) {
+        let item = match result {
+            Ok(message) => SelfCheckItem {
+                check: check.to_string(),
+                profile: profile.map(str::to_string),
+                status: CheckStatus::Ok,
+                message,
+                hint: None,
+            },
+            Err((status, message, hint)) => SelfCheckItem {
+                check: check.to_string(),
+                profile: profile.map(str::to_string),
+                status,
+                message,
+                hint: Some(hint.to_string()),
+            },
+        };
+        self.checks.push(item);
+    }
+}
+
+/// Write and remove a scratch file to find out whether a directory takes new files
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(".bzmm-write-check");
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)
+}
+
+/// Whether the app may create symlinks in a directory. Windows only allows it with Developer
+/// Mode on or as administrator
+fn check_symlinks(dir: &Path) -> std::io::Result<()> {
+    let target = dir.join(".bzmm-symlink-check");
+    let link = dir.join(".bzmm-symlink-check.link");
+    fs::write(&target, b"")?;
+    #[cfg(windows)]
+    let created = std::os::windows::fs::symlink_file(&target, &link);
+    #[cfg(not(windows))]
+    let created = std::os::unix::fs::symlink(&target, &link);
+    let _ = fs::remove_file(&link);
+    let _ = fs::remove_file(&target);
+    created
+}
+
+/// A directory that has to exist and take new files. Optional ones are skipped when not set
+fn check_directory(report: &mut SelfCheckReport, check: &str, profile: Option<&str>, path: &str, required: bool) {
+    let path = path.trim();
+    if path.is_empty() {
+        if required {
+            report.push(
+                check,
+                profile,
+                Err((CheckStatus::Error, "No directory is set".to_string(), "Pick one in the settings.")),
+            );
+        }
+        return;
+    }
+    let dir = Path::new(path);
+    let result = if !dir.is_dir() {
+        Err((
+            CheckStatus::Error,
+            format!("{} doesn't exist", dir.display()),
+            "Create the folder or pick another one in the settings. A removed drive has to be plugged in again.",
+        ))
+    } else {
+        check_writable(dir).map(|()| format!("{} is writable", dir.display())).map_err(|e| {
+            (
+                CheckStatus::Error,
+                format!("{} can't be written to: {}", dir.display(), e),
+                "Check the folder's permissions, or pick one outside protected locations like Program Files.",
+            )
+        })
+    };
+    report.push(check, profile, result);
+}
+
+/// Problems a hand-edited settings file can have that the settings dialog would have refused
+fn check_settings(report: &mut SelfCheckReport, settings: &Settings) {
+    let update = SettingsUpdate {
+        max_concurrent_downloads: Some(settings.max_concurrent_downloads),
+        auto_refresh_minutes: Some(settings.auto_refresh_minutes),
+        proxy: Some(settings.proxy.clone()),
+        ..Default::default()
+    };
+    let mut problems: Vec<String> = update.validate().err().into_iter().collect();
+
+    let mut names = BTreeSet::new();
+    for profile in &settings.profiles {
+        if !names.insert(profile.name.as_str()) {
+            problems.push(format!("More than one profile is named '{}'", profile.name));
+        }
+        if profile.repo_url.trim().is_empty() {
+            problems.push(format!("Profile '{}' has no repository URL", profile.name));
+        }
+        if let Some(message) = check_dcs_path(&profile.dcs_path).message {
+            problems.push(format!("Profile '{}': {}", profile.name, message));
+        }
+    }
+
+    let result = if problems.is_empty() {
+        Ok("Settings are valid".to_string())
+    } else {
+        Err((CheckStatus::Warning, problems.join(". "), "Review these in the app and profile settings."))
+    };
+    report.push("settings", None, result);
+}
+
+/// Whether each repository answers at all. An HTTP error still means the network is fine
+async fn check_repositories(report: &mut SelfCheckReport, settings: &Settings) {
+    let repo_urls: BTreeSet<&str> = settings
+        .profiles
+        .iter()
+        .map(|p| p.repo_url.trim())
+        .filter(|url| !url.is_empty())
+        .collect();
+    let downloader = ModDownloader::new();
+    let answers = join_all(
+        repo_urls.iter().map(|url| tokio::time::timeout(REACHABILITY_TIMEOUT, downloader.head_status(url))),
+    )
+    .await;
+
+    for (url, answer) in repo_urls.iter().zip(answers) {
+        let result = match answer {
+            Ok(Ok(status)) if status.is_success() || status.is_redirection() => {
+                Ok(format!("{} is reachable", url))
+            }
+            // Some servers don't answer HEAD requests, the listing may still load
+            Ok(Ok(status)) => Err((
+                CheckStatus::Warning,
+                format!("{} answered with {}", url, status),
+                "Check the repository URL, or ask the repository maintainer whether it moved.",
+            )),
+            Ok(Err(e)) => Err((
+                CheckStatus::Warning,
+                format!("{} can't be reached: {}", url, e),
+                "Check the internet connection and the proxy settings. Mods listed from the cache still work.",
+            )),
+            Err(_) => Err((
+                CheckStatus::Warning,
+                format!("{} didn't answer within {} seconds", url, REACHABILITY_TIMEOUT.as_secs()),
+                "Check the internet connection and the proxy settings. Mods listed from the cache still work.",
+            )),
+        };
+        report.push("repository", None, result);
+    }
+}
+
+/// Check what the app needs to work: settings, the directories it writes to, symlinks in each
+/// profile's DCS folder and the repositories. The frontend runs it at startup to show warnings
+#[tauri::command]
+pub async fn run_self_check() -> Result<SelfCheckReport, String> {
+    let mut report = SelfCheckReport::default();
+    let settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            report.push(
+                "settings",
+                None,
+                Err((CheckStatus::Error, e, "Fix or remove the settings file, then restart the app.")),
+            );
+            return Ok(report);
+        }
+    };
+    check_settings(&mut report, &settings);
+
+    check_directory(&mut report, "download_path", None, &settings.download_path, true);
+    check_directory(&mut report, "sideload_path", None, &settings.sideload_path, false);
+    check_directory(&mut report, "staging_path", None, &settings.staging_path, false);
+    for profile in &settings.profiles {
+        let name = Some(profile.name.as_str());
+        check_directory(&mut report, "download_path", name, &profile.download_path, false);
+
+        let dcs_dir = Path::new(profile.dcs_path.trim());
+        if profile.dcs_path.trim().is_empty() || !dcs_dir.is_dir() {
+            continue;
+        }
+        let result = check_symlinks(dcs_dir).map(|()| "Mods can be linked into DCS".to_string()).map_err(|e| {
+            (
+                CheckStatus::Error,
+                format!("Symlinks can't be created in {}: {}", dcs_dir.display(), e),
+                "On Windows, turn on Developer Mode (Settings > For developers) or run the app as administrator.",
+            )
+        });
+        report.push("symlinks", name, result);
+    }
+
+    check_repositories(&mut report, &settings).await;
+
+    report.ok = report.checks.iter().all(|item| item.status != CheckStatus::Error);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_directory() {
+        let dir = std::env::temp_dir().join("bzmm_test_self_check");
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut report = SelfCheckReport::default();
+        check_directory(&mut report, "download_path", None, dir.to_str().unwrap(), true);
+        check_directory(&mut report, "sideload_path", None, "", false);
+        check_directory(&mut report, "staging_path", None, "", true);
+        check_directory(&mut report, "download_path", Some("Stable"), dir.join("missing").to_str().unwrap(), false);
+
+        let statuses: Vec<_> = report.checks.iter().map(|item| item.status).collect();
+        assert_eq!(statuses, [CheckStatus::Ok, CheckStatus::Error, CheckStatus::Error]);
+        assert_eq!(report.checks[2].profile.as_deref(), Some("Stable"));
+        assert!(fs::read_dir(&dir).unwrap().next().is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
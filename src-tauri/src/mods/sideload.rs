@@ -1,9 +1,10 @@
 use super::types::{Category, Mod, ModError};
 use std::fs;
 use std::path::Path;
+use tracing::{error, info};
 
 pub fn read_mod_metadata(mod_dir: &Path) -> Result<Mod, ModError> {
-    println!("Reading metadata for directory: {:?}", mod_dir);
+    info!("Reading metadata for directory: {:?}", mod_dir);
     let name = mod_dir
         .file_name()
         .and_then(|n| n.to_str())
@@ -22,15 +23,15 @@ pub fn read_mod_metadata(mod_dir: &Path) -> Result<Mod, ModError> {
         .trim()
         .to_string();
 
-    println!("Found sideloaded mod: {} ({})", name, version);
+    info!("Found sideloaded mod: {} ({})", name, version);
     Ok(Mod::new_sideloaded(name, version, description))
 }
 
 pub fn scan_sideload_directory(sideload_path: &str) -> Result<Category, ModError> {
-    println!("Scanning sideload directory: {}", sideload_path);
+    info!("Scanning sideload directory: {}", sideload_path);
     let sideload_dir = Path::new(sideload_path);
     if !sideload_dir.exists() {
-        println!("Sideload directory does not exist");
+        info!("Sideload directory does not exist");
         return Ok(Category::new_sideloaded(Vec::new()));
     }
 
@@ -43,15 +44,15 @@ pub fn scan_sideload_directory(sideload_path: &str) -> Result<Category, ModError
         if path.is_dir() {
             match read_mod_metadata(&path) {
                 Ok(mod_info) => {
-                    println!("Successfully read metadata for {:?}", path);
+                    info!("Successfully read metadata for {:?}", path);
                     sideloaded_mods.push(mod_info);
                 }
-                Err(e) => eprintln!("Failed to read metadata for {:?}: {}", path, e),
+                Err(e) => error!("Failed to read metadata for {:?}: {}", path, e),
             }
         }
     }
 
-    println!("Found {} sideloaded mods", sideloaded_mods.len());
+    info!("Found {} sideloaded mods", sideloaded_mods.len());
     Ok(Category::new_sideloaded(sideloaded_mods))
 }
 
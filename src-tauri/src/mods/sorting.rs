@@ -0,0 +1,60 @@
+use super::mod_utils::{dir_size, local_mod_dir};
+use super::types::{Mod, ModsResult};
+use super::versions::compare_versions;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Order of the mods within each category returned by `get_mods`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModSort {
+    /// Alphabetical
+    Name,
+    /// Newest repository version first
+    Version,
+    /// Largest download on disk first, mods that aren't downloaded last
+    Size,
+    DownloadedFirst,
+    UpdateAvailableFirst,
+}
+
+/// Version the repository offers, which differs from the listed version when an update is available
+fn repo_version(mod_entry: &Mod) -> &str {
+    mod_entry.new_version.as_deref().unwrap_or(&mod_entry.version)
+}
+
+fn by_name(a: &Mod, b: &Mod) -> Ordering {
+    let shown = |m: &Mod| m.display_name.as_deref().unwrap_or(&m.name).to_lowercase();
+    shown(a).cmp(&shown(b))
+}
+
+/// Sort the mods of every category, keeping the categories in their own order
+pub fn sort_mods(result: &mut ModsResult, sort: ModSort, settings: &Settings) {
+    // Sizes and download state need the disk, look each mod up only once
+    let mut local: HashMap<(Option<String>, String), Option<u64>> = HashMap::new();
+    if matches!(sort, ModSort::Size | ModSort::DownloadedFirst) {
+        for mod_entry in result.categories.iter().flat_map(|cat| cat.mods.iter()) {
+            let key = (mod_entry.source.clone(), mod_entry.name.clone());
+            local.entry(key).or_insert_with(|| {
+                let dir = local_mod_dir(settings, mod_entry.source.as_deref(), &mod_entry.name)?;
+                Some(if sort == ModSort::Size { dir_size(&dir) } else { 0 })
+            });
+        }
+    }
+    let local_size = |m: &Mod| local.get(&(m.source.clone(), m.name.clone())).copied().flatten();
+
+    for category in &mut result.categories {
+        category.mods.sort_by(|a, b| {
+            let ordering = match sort {
+                ModSort::Name => Ordering::Equal,
+                ModSort::Version => compare_versions(repo_version(b), repo_version(a)),
+                ModSort::Size => local_size(b).cmp(&local_size(a)),
+                ModSort::DownloadedFirst => local_size(b).is_some().cmp(&local_size(a).is_some()),
+                ModSort::UpdateAvailableFirst => b.new_version.is_some().cmp(&a.new_version.is_some()),
+            };
+            ordering.then_with(|| by_name(a, b))
+        });
+    }
+}
@@ -0,0 +1,119 @@
+use super::migration::copy_dir_all;
+use crate::settings::Settings;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where a download is written and unpacked before it takes its place in the repository directory
+pub struct StagingPaths {
+    /// The download while it is in progress
+    pub temp_file: PathBuf,
+    /// The finished download, until it is extracted
+    pub zip_file: PathBuf,
+    /// What it extracts to, the mod directory itself when nothing is staged elsewhere
+    pub extract_dir: PathBuf,
+}
+
+/// Staging locations for downloading `filename` into `repo_dir`: a subdirectory of the staging
+/// path named like the repository directory, or the repository directory when none is set
+pub fn staging_paths(settings: &Settings, repo_dir: &Path, filename: &str) -> Result<StagingPaths, String> {
+    let dir = match settings.staging_path.trim() {
+        "" => repo_dir.to_path_buf(),
+        staging => Path::new(staging).join(repo_dir.file_name().unwrap_or_default()),
+    };
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create staging directory {}: {}", dir.display(), e))?;
+
+    let zip_file = dir.join(filename);
+    Ok(StagingPaths {
+        temp_file: zip_file.with_extension("tmp"),
+        extract_dir: dir.join(filename.trim_end_matches(".zip")),
+        zip_file,
+    })
+}
+
+/// Next to `dest` in the directory updates keep previous versions in, so nothing takes a mod
+/// there for a real one: `<name>.incoming` for the staged mod copied onto the download drive,
+/// `<name>.outgoing` for the replaced one until the new one is in place
+fn aside_dir(dest: &Path, suffix: &str) -> PathBuf {
+    let parent = dest.parent().unwrap_or(dest);
+    let name = dest.file_name().unwrap_or_default().to_string_lossy();
+    parent.with_extension("updating").join(format!("{}.{}", name, suffix))
+}
+
+fn remove_if_exists(dir: &Path) -> std::io::Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+/// Replace `dest` with the extracted mod in `staged`. The mod is first brought next to `dest`, by
+/// a rename on one drive or a copy across drives, and `dest` is only touched once that worked, so
+/// a failed copy leaves the previous version in place
+pub fn move_into_place(staged: &Path, dest: &Path) -> std::io::Result<()> {
+    let incoming = aside_dir(dest, "incoming");
+    let outgoing = aside_dir(dest, "outgoing");
+    remove_if_exists(&incoming)?;
+    remove_if_exists(&outgoing)?;
+    if let Some(parent) = incoming.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let copied = fs::rename(staged, &incoming).is_err();
+    if copied {
+        if let Err(e) = copy_dir_all(staged, &incoming) {
+            let _ = fs::remove_dir_all(&incoming);
+            return Err(e);
+        }
+    }
+
+    let replacing = dest.exists();
+    if replacing {
+        fs::rename(dest, &outgoing)?;
+    }
+    if let Err(e) = fs::rename(&incoming, dest) {
+        if replacing {
+            let _ = fs::rename(&outgoing, dest);
+        }
+        return Err(e);
+    }
+    if replacing {
+        fs::remove_dir_all(&outgoing)?;
+    }
+    if let Some(parent) = incoming.parent() {
+        // Fails while an update keeps a previous version there, which is fine
+        let _ = fs::remove_dir(parent);
+    }
+    if copied {
+        fs::remove_dir_all(staged)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staging_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_dir = dir.path().join("downloads").join("a1b2c3");
+        let mut settings = Settings::default();
+
+        let paths = staging_paths(&settings, &repo_dir, "ModA.zip").unwrap();
+        assert_eq!(paths.extract_dir, repo_dir.join("ModA"));
+        assert_eq!(paths.temp_file, repo_dir.join("ModA.tmp"));
+
+        settings.staging_path = dir.path().join("staging").to_string_lossy().to_string();
+        let paths = staging_paths(&settings, &repo_dir, "ModA.zip").unwrap();
+        assert_eq!(paths.zip_file, dir.path().join("staging").join("a1b2c3").join("ModA.zip"));
+
+        fs::create_dir_all(&paths.extract_dir).unwrap();
+        fs::write(paths.extract_dir.join("VERSION.txt"), "2.0").unwrap();
+        fs::create_dir_all(repo_dir.join("ModA")).unwrap();
+        fs::write(repo_dir.join("ModA").join("VERSION.txt"), "1.0").unwrap();
+        move_into_place(&paths.extract_dir, &repo_dir.join("ModA")).unwrap();
+        assert_eq!(fs::read_to_string(repo_dir.join("ModA").join("VERSION.txt")).unwrap(), "2.0");
+        assert!(!paths.extract_dir.exists());
+        assert!(!repo_dir.with_extension("updating").exists());
+    }
+}
@@ -0,0 +1,279 @@
+use super::download_queue::get_queue;
+use super::enable_state::has_enabled_mods_from;
+use super::mod_list::load_repo_mods;
+use super::mod_utils::{dir_size, get_repo_source};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Disk usage of the mods of one category
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryUsage {
+    pub name: String,
+    pub mod_count: usize,
+    pub bytes: u64,
+}
+
+/// Disk usage of one repo-hash directory in the download path
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepoUsage {
+    pub directory: String,
+    /// Repository the directory belongs to, `None` if no profile uses it any more
+    pub repo_url: Option<String>,
+    pub bytes: u64,
+    pub categories: Vec<CategoryUsage>,
+}
+
+/// Where the disk space used by bzmm goes
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct StorageUsage {
+    pub repos: Vec<RepoUsage>,
+    pub sideload_bytes: u64,
+    /// Zips and `.tmp` files left by downloads, and previous versions kept by interrupted updates
+    pub leftover_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Whether a file in a repo directory is left over from a download rather than part of a mod
+fn is_download_artifact(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip") || ext.eq_ignore_ascii_case("tmp"))
+}
+
+/// Map each mod of a repository to its category, failing quietly when the repository can't be loaded
+async fn mod_categories(repo_url: &str) -> HashMap<String, String> {
+    match load_repo_mods(repo_url).await {
+        Ok(mods_file) => mods_file
+            .categories
+            .into_iter()
+            .flat_map(|cat| {
+                let name = cat.name;
+                cat.mods.into_iter().map(move |m| (m.name, name.clone()))
+            })
+            .collect(),
+        Err(e) => {
+            warn!("Could not load categories for {}: {}", repo_url, e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Measure the download directory per repository and category, the sideload directory and download leftovers
+#[tauri::command]
+pub async fn get_storage_usage() -> Result<StorageUsage, String> {
+    let settings = Settings::load()?;
+    let mut usage = StorageUsage::default();
+
+    // Repo-hash directory names of the repositories profiles point at
+    let mut repo_urls: HashMap<String, String> = HashMap::new();
+    for profile in &settings.profiles {
+        let repo_url = profile.repo_url.trim_end_matches('/');
+        repo_urls.insert(get_repo_source(repo_url), repo_url.to_string());
+    }
+
+    // Profiles may download to paths of their own
+    for download_path in settings.download_paths() {
+        if let Ok(entries) = std::fs::read_dir(&download_path) {
+            for entry in entries.filter_map(Result::ok) {
+                let repo_dir = entry.path();
+                if !repo_dir.is_dir() {
+                    continue;
+                }
+                let directory = entry.file_name().to_string_lossy().to_string();
+                // Previous versions kept aside by an update that never finished
+                if repo_dir.extension().is_some_and(|ext| ext == "updating") {
+                    usage.leftover_bytes += dir_size(&repo_dir);
+                    continue;
+                }
+
+                let repo_url = repo_urls.get(&directory).cloned();
+                let categories = match &repo_url {
+                    Some(url) => mod_categories(url).await,
+                    None => HashMap::new(),
+                };
+
+                let mut by_category: BTreeMap<String, (usize, u64)> = BTreeMap::new();
+                let mut repo_bytes = 0;
+                for mod_entry in std::fs::read_dir(&repo_dir).map_err(|e| e.to_string())? {
+                    let path = mod_entry.map_err(|e| e.to_string())?.path();
+                    if is_download_artifact(&path) {
+                        usage.leftover_bytes += path.metadata().map(|m| m.len()).unwrap_or(0);
+                        continue;
+                    }
+                    if !path.is_dir() {
+                        continue;
+                    }
+
+                    let mod_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                    let category = categories
+                        .get(&mod_name)
+                        .cloned()
+                        .unwrap_or_else(|| "Deprecated".to_string());
+                    let bytes = dir_size(&path);
+                    let totals = by_category.entry(category).or_default();
+                    totals.0 += 1;
+                    totals.1 += bytes;
+                    repo_bytes += bytes;
+                }
+
+                usage.repos.push(RepoUsage {
+                    directory,
+                    repo_url,
+                    bytes: repo_bytes,
+                    categories: by_category
+                        .into_iter()
+                        .map(|(name, (mod_count, bytes))| CategoryUsage { name, mod_count, bytes })
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    if !settings.sideload_path.is_empty() {
+        usage.sideload_bytes = dir_size(Path::new(&settings.sideload_path));
+    }
+
+    usage.total_bytes = usage.repos.iter().map(|repo| repo.bytes).sum::<u64>()
+        + usage.sideload_bytes
+        + usage.leftover_bytes;
+    Ok(usage)
+}
+
+/// Kind of leftover found by `cleanup_orphaned_files`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanKind {
+    /// A downloaded zip whose mod folder was never extracted or has been deleted
+    ZipWithoutFolder,
+    /// A partial download
+    TempFile,
+    /// A repo-hash directory with nothing in it
+    EmptyRepoDir,
+    /// A repo-hash directory of a repository no profile uses any more, with no mod in it enabled
+    UnusedRepoDir,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrphanedFile {
+    pub path: PathBuf,
+    pub kind: OrphanKind,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct OrphanCleanupReport {
+    pub files: Vec<OrphanedFile>,
+    pub total_bytes: u64,
+    /// Whether the files were deleted, or only reported
+    pub deleted: bool,
+}
+
+/// Find download leftovers in one repo-hash directory, skipping files of downloads in progress
+fn find_orphans_in_repo_dir(
+    repo_dir: &Path,
+    active: &[String],
+    orphans: &mut Vec<OrphanedFile>,
+) -> std::io::Result<()> {
+    let mut is_empty = true;
+    for entry in std::fs::read_dir(repo_dir)? {
+        let path = entry?.path();
+        is_empty = false;
+        if !path.is_file() {
+            continue;
+        }
+
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        if active.iter().any(|filename| filename.trim_end_matches(".zip") == stem) {
+            continue;
+        }
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        let kind = match extension.as_deref() {
+            Some("tmp") => OrphanKind::TempFile,
+            Some("zip") if !repo_dir.join(&stem).is_dir() => OrphanKind::ZipWithoutFolder,
+            _ => continue,
+        };
+        let bytes = path.metadata().map(|m| m.len()).unwrap_or(0);
+        orphans.push(OrphanedFile { path, kind, bytes });
+    }
+
+    if is_empty {
+        orphans.push(OrphanedFile {
+            path: repo_dir.to_path_buf(),
+            kind: OrphanKind::EmptyRepoDir,
+            bytes: 0,
+        });
+    }
+    Ok(())
+}
+
+/// Whether a directory in the download path is a repo-hash directory no profile uses, left behind
+/// by a deleted profile or a changed repository URL. Directories with a mod recorded as enabled,
+/// even for a profile deleted since, or a download running are kept
+fn is_unused_repo_dir(
+    used_sources: &HashSet<String>,
+    repo_dir: &Path,
+    active: &[String],
+) -> bool {
+    let name = repo_dir.file_name().unwrap_or_default().to_string_lossy();
+    if name.len() != 6 || !name.chars().all(|c| c.is_ascii_hexdigit()) || used_sources.contains(name.as_ref()) {
+        return false;
+    }
+    let downloading = active.iter().any(|filename| {
+        let stem = filename.trim_end_matches(".zip");
+        repo_dir.join(filename).exists() || repo_dir.join(format!("{}.tmp", stem)).exists()
+    });
+    // Kept when the enable state can't be read, rather than taking its mods for unused
+    !downloading && has_enabled_mods_from(&name).is_ok_and(|enabled| !enabled)
+}
+
+/// Find zips without extracted folders, partial downloads, empty repo-hash directories and those
+/// of repositories no profile uses in the download path, deleting them only when `delete` is set
+/// so the user can review the list first
+#[tauri::command]
+pub async fn cleanup_orphaned_files(delete: bool) -> Result<OrphanCleanupReport, String> {
+    let settings = Settings::load()?;
+    let active = get_queue().active_downloads().await;
+    let mut report = OrphanCleanupReport::default();
+    let used_sources: HashSet<String> = settings
+        .profiles
+        .iter()
+        .map(|profile| get_repo_source(profile.repo_url.trim_end_matches('/')))
+        .collect();
+
+    for download_path in settings.download_paths() {
+        if let Ok(entries) = std::fs::read_dir(&download_path) {
+            for entry in entries.filter_map(Result::ok) {
+                let path = entry.path();
+                if !path.is_dir() {
+                    continue;
+                }
+                if is_unused_repo_dir(&used_sources, &path, &active) {
+                    let bytes = dir_size(&path);
+                    report.files.push(OrphanedFile { path, kind: OrphanKind::UnusedRepoDir, bytes });
+                    continue;
+                }
+                find_orphans_in_repo_dir(&path, &active, &mut report.files).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    report.total_bytes = report.files.iter().map(|file| file.bytes).sum();
+
+    if delete {
+        for file in &report.files {
+            let result = match file.kind {
+                OrphanKind::EmptyRepoDir => std::fs::remove_dir(&file.path),
+                OrphanKind::UnusedRepoDir => std::fs::remove_dir_all(&file.path),
+                _ => std::fs::remove_file(&file.path),
+            };
+            result.map_err(|e| format!("Failed to delete {}: {}", file.path.display(), e))?;
+            info!("Deleted orphaned file: {}", file.path.display());
+        }
+        report.deleted = true;
+    }
+
+    Ok(report)
+}
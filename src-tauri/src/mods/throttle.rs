@@ -0,0 +1,84 @@
+use crate::settings::Settings;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Bandwidth cap shared by every mod download, so parallel downloads split one budget
+pub struct Throttle {
+    /// Kilobytes per second, 0 for no limit
+    limit_kbps: AtomicU64,
+    /// When the bytes handed out so far have been paid for at the current rate
+    next_free: Mutex<Instant>,
+}
+
+impl Throttle {
+    fn new() -> Self {
+        let limit_kbps = Settings::load()
+            .map(|settings| settings.max_download_speed_kbps)
+            .unwrap_or(0);
+        Self {
+            limit_kbps: AtomicU64::new(limit_kbps),
+            next_free: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Change the cap, downloads in flight follow it from their next chunk
+    pub fn set_limit_kbps(&self, limit_kbps: u64) {
+        self.limit_kbps.store(limit_kbps, Ordering::Relaxed);
+        // Forget the schedule of the old rate, a lower limit shouldn't punish bytes already sent
+        *self.next_free.lock().unwrap() = Instant::now();
+    }
+
+    /// How long to hold back after receiving `bytes`, reserving their share of the budget
+    fn reserve(&self, bytes: u64) -> Duration {
+        let limit_kbps = self.limit_kbps.load(Ordering::Relaxed);
+        if limit_kbps == 0 {
+            return Duration::ZERO;
+        }
+
+        let now = Instant::now();
+        let mut next_free = self.next_free.lock().unwrap();
+        let start = (*next_free).max(now);
+        *next_free = start + Duration::from_secs_f64(bytes as f64 / (limit_kbps * 1024) as f64);
+        next_free.saturating_duration_since(now)
+    }
+
+    /// Wait until `bytes` more fit under the cap
+    pub async fn consume(&self, bytes: u64) {
+        let wait = self.reserve(bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+// Global throttle instance
+static THROTTLE: std::sync::OnceLock<Throttle> = std::sync::OnceLock::new();
+
+pub fn get_throttle() -> &'static Throttle {
+    THROTTLE.get_or_init(Throttle::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_spreads_bytes_over_limit() {
+        let throttle = Throttle {
+            limit_kbps: AtomicU64::new(0),
+            next_free: Mutex::new(Instant::now()),
+        };
+        assert_eq!(throttle.reserve(1024 * 1024), Duration::ZERO);
+
+        throttle.set_limit_kbps(100);
+        let first = throttle.reserve(100 * 1024);
+        let second = throttle.reserve(100 * 1024);
+        assert!(first > Duration::from_millis(900) && first <= Duration::from_secs(1));
+        assert!(second > Duration::from_millis(1900) && second <= Duration::from_secs(2));
+
+        // Lifting the limit lets the next chunk through at once
+        throttle.set_limit_kbps(0);
+        assert_eq!(throttle.reserve(100 * 1024), Duration::ZERO);
+    }
+}
@@ -1,21 +1,228 @@
+use super::enable_lock::LockStatus;
+use super::markdown::render_markdown;
+use super::mod_enablement::MappingConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use thiserror::Error;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Mod {
+    /// Identifies the mod: names its download directory and zip and keys its enable state,
+    /// favorites and notes. The `id` attribute when the repository gives one, otherwise `name`
     #[serde(rename(deserialize = "@name"))]
     pub name: String,
+    /// Stable `id` attribute, which replaces `name` when the repository is parsed
+    #[serde(rename(deserialize = "@id"))]
+    #[serde(default, skip_serializing)]
+    pub id: Option<String>,
+    /// The `name` attribute when an `id` took its place, free to change between releases
+    #[serde(default, skip_deserializing)]
+    pub display_name: Option<String>,
     #[serde(rename(deserialize = "@version"))]
     pub version: String,
     #[serde(rename(deserialize = "@url"))]
     #[serde(default)]
     pub url: Option<String>,
+    /// Further download locations from `<url>` children, tried when `url` fails
+    #[serde(rename(deserialize = "url"))]
+    #[serde(default)]
+    pub mirrors: Vec<ModMirror>,
     #[serde(default)]
     pub new_version: Option<String>,
+    /// Repository version when it is older than the downloaded one, which isn't offered as an update
+    #[serde(default, skip_deserializing)]
+    pub downgrade_version: Option<String>,
+    /// Text content of the `<mod>` element, which has to come before any `<requires>` children
     #[serde(default)]
     #[serde(rename(deserialize = "$text"))]
     pub description: String,
+    /// `description` as Markdown rendered to sanitized HTML
+    #[serde(default, skip_deserializing)]
+    pub description_html: String,
+    /// Translations of the name from `<name lang="...">` children, picked from when parsing
+    #[serde(rename(deserialize = "name"))]
+    #[serde(default, skip_serializing)]
+    pub localized_names: Vec<LocalizedText>,
+    /// Translations of the description from `<description lang="...">` children
+    #[serde(rename(deserialize = "description"))]
+    #[serde(default, skip_serializing)]
+    pub localized_descriptions: Vec<LocalizedText>,
+    #[serde(rename(deserialize = "@changelog"))]
+    #[serde(default)]
+    pub changelog_url: Option<String>,
+    #[serde(rename(deserialize = "@icon"))]
+    #[serde(default)]
+    pub icon_url: Option<String>,
+    #[serde(rename(deserialize = "@screenshot"))]
+    #[serde(default)]
+    pub screenshot_url: Option<String>,
+    #[serde(rename(deserialize = "@author"))]
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(rename(deserialize = "@homepage"))]
+    #[serde(default)]
+    pub homepage_url: Option<String>,
+    #[serde(rename(deserialize = "@license"))]
+    #[serde(default)]
+    pub license: Option<String>,
+    /// Download size in bytes as given by the repository
+    #[serde(rename(deserialize = "@size"))]
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// `size` of the mod itself, kept for variants that don't give their own
+    #[serde(skip)]
+    pub base_size: Option<u64>,
+    /// Comma separated operating systems the mod is for, such as "windows" or "linux", all
+    /// when missing. Mods for other systems are dropped when the repository is parsed
+    #[serde(rename(deserialize = "@platform"))]
+    #[serde(default)]
+    pub platform: Option<String>,
+    /// DCS versions the mod is made for, a minimum like "2.9" or a range like "2.8-2.9"
+    #[serde(rename(deserialize = "@dcs_version"))]
+    #[serde(default)]
+    pub dcs_version: Option<String>,
+    /// Comma separated DCS variants the mod works with, such as "Stable, OpenBeta", all when missing
+    #[serde(rename(deserialize = "@dcs_variants"))]
+    #[serde(default)]
+    pub dcs_variants: Option<String>,
+    /// Set when the profile's DCS installation falls outside `dcs_version` or `dcs_variants`
+    #[serde(default, skip_deserializing)]
+    pub dcs_incompatible: bool,
+    /// No longer maintained: marked so by the repository, or found locally but not listed
+    #[serde(rename(deserialize = "@deprecated"))]
+    #[serde(default)]
+    pub deprecated: bool,
+    /// Mod the repository offers in place of this deprecated one
+    #[serde(rename(deserialize = "@replaced_by"))]
+    #[serde(default)]
+    pub replaced_by: Option<String>,
+    /// Included repository file that listed the mod, `None` when the main file does
+    #[serde(default, skip_deserializing)]
+    pub origin: Option<String>,
+    /// Where the local copy lives: a repo-hash directory name, or "sideload". Lets commands
+    /// pick the intended copy when the same name exists in several places
+    #[serde(default, skip_deserializing)]
+    pub source: Option<String>,
+    #[serde(default, skip_deserializing)]
+    pub favorite: bool,
+    /// Free-text note the user attached to the mod
+    #[serde(default, skip_deserializing)]
+    pub note: Option<String>,
+    /// How the mod changed in the repository since the previous session
+    #[serde(default, skip_deserializing)]
+    pub since_last_visit: Option<RepoChange>,
+    /// Labels such as "liveries" or "MP-safe", from `<tag>` children and the `tags` attribute
+    #[serde(rename(deserialize = "tag"))]
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Comma separated `tags` attribute, folded into `tags` when the repository is parsed
+    #[serde(rename(deserialize = "@tags"))]
+    #[serde(default, skip_serializing)]
+    pub tags_attribute: Option<String>,
+    /// Installation caveats from the `<notes>` element, such as "requires mission restart",
+    /// confirmed by the user before the mod is enabled
+    #[serde(rename(deserialize = "notes"))]
+    #[serde(default)]
+    pub install_notes: Option<String>,
+    /// Other mods of the repository this one needs, from its `<requires>` elements
+    #[serde(rename(deserialize = "requires"))]
+    #[serde(default)]
+    pub requires: Vec<ModRequirement>,
+    /// Alternative downloads of the mod from `<variant>` children, such as 4K or 2K textures
+    #[serde(rename(deserialize = "variant"))]
+    #[serde(default)]
+    pub variants: Vec<ModVariant>,
+    /// Id of the variant `url` and `size` point at: the user's choice, else the installed one,
+    /// else the first
+    #[serde(default, skip_deserializing)]
+    pub variant: Option<String>,
+    /// Id of the variant the local copy was downloaded as
+    #[serde(default, skip_deserializing)]
+    pub installed_variant: Option<String>,
+    /// ENABLING lock on the local copy for the profile, from an enable running right now or one
+    /// that was interrupted
+    #[serde(default, skip_deserializing)]
+    pub enabling_lock: Option<LockStatus>,
+}
+
+/// `<variant id="2k" name="2K textures" url="https://..." size="...">Lighter on VRAM</variant>`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModVariant {
+    #[serde(rename(deserialize = "@id"))]
+    pub id: String,
+    #[serde(rename(deserialize = "@name"))]
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(rename(deserialize = "@url"))]
+    pub url: String,
+    #[serde(rename(deserialize = "@size"))]
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(rename(deserialize = "$text"))]
+    #[serde(default)]
+    pub description: String,
+}
+
+/// `<url region="EU" priority="1">https://...</url>`, lower priorities are tried first
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModMirror {
+    #[serde(rename(deserialize = "$text"))]
+    pub url: String,
+    #[serde(rename(deserialize = "@region"))]
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(rename(deserialize = "@priority"))]
+    #[serde(default)]
+    pub priority: Option<i32>,
+}
+
+/// `<name lang="de">Viper-Lackierungen</name>`, a translation of one of the mod's texts
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LocalizedText {
+    #[serde(rename(deserialize = "@lang"))]
+    #[serde(default)]
+    pub lang: String,
+    #[serde(rename(deserialize = "$text"))]
+    #[serde(default)]
+    pub text: String,
+}
+
+/// The translation for a language tag: the exact tag first, then any of the same language, so
+/// "de-AT" falls back to "de" or "de-DE"
+fn best_translation<'a>(translations: &'a [LocalizedText], language: &str) -> Option<&'a str> {
+    let normalize = |tag: &str| tag.trim().replace('_', "-").to_lowercase();
+    let language = normalize(language);
+    if language.is_empty() {
+        return None;
+    }
+    let base = |tag: &str| tag.split('-').next().unwrap_or_default().to_string();
+    translations
+        .iter()
+        .find(|t| normalize(&t.lang) == language)
+        .or_else(|| translations.iter().find(|t| base(&normalize(&t.lang)) == base(&language)))
+        .map(|t| t.text.trim())
+        .filter(|text| !text.is_empty())
+}
+
+/// `<requires version="1.2">Other Mod</requires>`, the version being the lowest one that will do
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModRequirement {
+    #[serde(rename(deserialize = "$text"))]
+    pub name: String,
+    #[serde(rename(deserialize = "@version"))]
+    #[serde(default)]
+    pub min_version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoChange {
+    Added,
+    Updated,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,21 +232,197 @@ pub struct Category {
     pub name: String,
     #[serde(rename(deserialize = "@sort_order"))]
     pub sort_order: i32,
+    #[serde(rename(deserialize = "@description"))]
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Full name of the category this one is nested in, its own name starting with it
+    #[serde(default, skip_deserializing)]
+    pub parent: Option<String>,
     #[serde(rename(deserialize = "mod"))]
+    #[serde(default)]
     pub mods: Vec<Mod>,
+    /// Nested `<category>` elements, moved into the top-level list when the repository is parsed
+    #[serde(rename(deserialize = "category"))]
+    #[serde(default, skip_serializing)]
+    pub subcategories: Vec<Category>,
 }
 
+/// Joins the names of nested categories, e.g. "Aircraft / Liveries"
+pub const CATEGORY_PATH_SEPARATOR: &str = " / ";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ModsFile {
+    /// Format version from the root element, 1 when the repository doesn't state one
+    #[serde(rename(deserialize = "@format"))]
+    #[serde(default = "default_repo_format")]
+    pub format: u32,
+    /// Oldest BZMM release that understands everything the repository uses
+    #[serde(rename(deserialize = "@min_app_version"))]
+    #[serde(default)]
+    pub min_app_version: Option<String>,
+    /// Who runs the repository and what they want users to know
+    #[serde(default)]
+    pub header: Option<RepoHeader>,
+    /// Further repository files whose categories join this one's, resolved after parsing
+    #[serde(rename(deserialize = "include"))]
+    #[serde(default, skip_serializing)]
+    pub includes: Vec<RepoInclude>,
+    /// How the repository's mods are brought into DCS, kept for enabling them when it is fetched
+    #[serde(default, skip_serializing)]
+    pub mapping: Option<MappingConfig>,
     #[serde(rename(deserialize = "category"))]
+    #[serde(default)]
     pub categories: Vec<Category>,
+    /// Entries that were skipped because they didn't parse, filled in by lenient parsing
+    #[serde(skip)]
+    pub warnings: Vec<ParseWarning>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A repository entry that was left out of the listing and why
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ParseWarning {
+    /// The offending element, such as `<mod name="Viper">`
+    pub element: String,
+    pub line: usize,
+    pub reason: String,
+}
+
+/// `<include url="maintainers/viper.xml"/>`, relative URLs being resolved against the including file
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RepoInclude {
+    #[serde(rename(deserialize = "@url"))]
+    pub url: String,
+}
+
+/// `<header name="..." maintainer="..." support="https://...">Message of the day</header>`,
+/// shown above the mod list
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoHeader {
+    #[serde(rename(deserialize = "@name"))]
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(rename(deserialize = "@maintainer"))]
+    #[serde(default)]
+    pub maintainer: Option<String>,
+    #[serde(rename(deserialize = "@support"))]
+    #[serde(default)]
+    pub support_url: Option<String>,
+    /// Announcement such as "update X before Tuesday's mission", empty when there is none
+    #[serde(rename(deserialize = "$text"))]
+    #[serde(default)]
+    pub motd: String,
+}
+
+fn default_repo_format() -> u32 {
+    1
+}
+
+/// Operating system names a `platform` attribute can give
+pub const PLATFORMS: &[&str] = &["windows", "linux", "macos"];
+
+impl ModsFile {
+    /// Drop the mods meant for other operating systems than `os`, and the categories left
+    /// empty by it
+    pub fn retain_platform(&mut self, os: &str) {
+        self.categories.retain_mut(|category| {
+            let listed = category.mods.len();
+            category.mods.retain(|m| m.supports_platform(os));
+            listed == 0 || !category.mods.is_empty()
+        });
+    }
+
+    /// Fold an included file into this listing. Categories of the same name are merged, and a
+    /// mod already listed keeps its first listing. The included mods are attributed to `origin`
+    pub fn merge_include(&mut self, mut included: ModsFile, origin: &str) {
+        self.warnings.append(&mut included.warnings);
+        let mut listed: HashSet<String> =
+            self.categories.iter().flat_map(|cat| cat.mods.iter().map(|m| m.name.clone())).collect();
+        for mut category in included.categories {
+            category.mods.retain(|m| listed.insert(m.name.clone()));
+            for mod_entry in &mut category.mods {
+                mod_entry.origin = Some(origin.to_string());
+            }
+            match self.categories.iter_mut().find(|cat| cat.name == category.name) {
+                Some(existing) => existing.mods.append(&mut category.mods),
+                None => self.categories.push(category),
+            }
+        }
+    }
+
+    /// Move nested categories into the top-level list, which the rest of the app works with.
+    /// Flat repositories come out unchanged
+    pub fn flatten_categories(&mut self) {
+        let mut flat = Vec::with_capacity(self.categories.len());
+        for category in std::mem::take(&mut self.categories) {
+            let sort_order = category.sort_order;
+            category.flatten_into(&mut flat, None, sort_order);
+        }
+        self.categories = flat;
+    }
+}
+
+/// Counts over every mod in a ModsResult, for the active profile
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct ModsSummary {
+    pub total: usize,
+    pub downloaded: usize,
+    pub enabled: usize,
+    pub updatable: usize,
+    pub deprecated: usize,
+}
+
+/// A mod enabled for a profile, from the repository download directory or the sideload directory
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnabledModEntry {
+    pub name: String,
+    pub version: Option<String>,
+    /// Variant of the mod that was downloaded, for mods offering several
+    pub variant: Option<String>,
+    /// Repo-hash directory name, or "sideload"
+    pub source: String,
+    /// Repository the mod was downloaded from, `None` for sideloaded mods
+    pub repo_url: Option<String>,
+    /// When the mod was enabled, in seconds since the Unix epoch
+    pub enabled_at: Option<u64>,
+}
+
+/// A downloaded mod the repository has a different version of
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModUpdate {
+    pub name: String,
+    pub current_version: String,
+    pub new_version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModsResult {
     pub categories: Vec<Category>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub summary: ModsSummary,
+    /// BZMM version the repository asks for when this one is older, the listing may be incomplete
+    #[serde(default)]
+    pub app_update_required: Option<String>,
+    /// Repository name, maintainer and announcement from its `<header>`
+    #[serde(default)]
+    pub header: Option<RepoHeader>,
+    /// Repository entries left out because they couldn't be parsed
+    #[serde(default)]
+    pub warnings: Vec<ParseWarning>,
+    /// When the listing was last fetched from the repository, seconds since the Unix epoch
+    #[serde(default)]
+    pub fetched_at: Option<u64>,
+    /// Whether the listing is the cached copy rather than what the repository just sent
+    #[serde(default)]
+    pub from_cache: bool,
+    /// Seconds since `fetched_at`
+    #[serde(default)]
+    pub cache_age_secs: Option<u64>,
+    /// Cached listing older than the cache TTL in the settings
+    #[serde(default)]
+    pub stale: bool,
 }
 
 #[derive(Debug, Error)]
@@ -76,16 +459,160 @@ pub enum ModError {
 
     #[error("HTTP error: {0}")]
     HttpError(String),
+
+    #[error("This repository uses format version {0}, which needs a newer version of BZMM (this one reads up to version {1})")]
+    UnsupportedRepoFormat(u32, u32),
+
+    #[error("Not enough disk space: the download needs {} MB, {} MB are free", .0 / 1_048_576, .1 / 1_048_576)]
+    InsufficientDiskSpace(u64, u64),
 }
 
 impl Mod {
+    /// Fold the `tags` attribute into the tag list, trimmed and without duplicates
+    fn merge_tags_attribute(&mut self) {
+        let from_attribute = self.tags_attribute.take().unwrap_or_default();
+        let tags = std::mem::take(&mut self.tags);
+        for tag in tags.iter().map(String::as_str).chain(from_attribute.split(',')) {
+            let tag = tag.trim();
+            if !tag.is_empty() && !self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                self.tags.push(tag.to_string());
+            }
+        }
+    }
+
+    /// Download locations in the order to try them: mirrors of the preferred region first, then
+    /// by priority, the `url` attribute counting as priority 0 ahead of mirrors sharing it
+    pub fn download_urls(&self, preferred_region: &str) -> Vec<String> {
+        let attribute = self.url.iter().map(|url| (url.as_str(), None, 0));
+        let mirrors = self
+            .mirrors
+            .iter()
+            .map(|m| (m.url.as_str(), m.region.as_deref(), m.priority.unwrap_or(0)));
+        let mut candidates: Vec<(&str, Option<&str>, i32)> = attribute.chain(mirrors).collect();
+        let outside_region = |region: Option<&str>| {
+            preferred_region.is_empty() || !region.is_some_and(|r| r.eq_ignore_ascii_case(preferred_region))
+        };
+        // Stable, so equal entries keep the repository's order
+        candidates.sort_by_key(|&(_, region, priority)| (outside_region(region), priority));
+
+        let mut urls: Vec<String> = Vec::new();
+        for (url, _, _) in candidates {
+            let url = url.trim();
+            if !url.is_empty() && !urls.iter().any(|u| u == url) {
+                urls.push(url.to_string());
+            }
+        }
+        urls
+    }
+
+    /// Tidy up a mod straight from the repository XML: tags from the attribute join the tag
+    /// list, an `id` takes the place of `name`, translations for `language` replace the default
+    /// texts, a replacement implies deprecation, a mod listing only `<url>` children gets
+    /// its preferred one as `url`, and a mod with variants downloads the first one by default
+    pub fn normalize_parsed(&mut self, language: &str) {
+        self.merge_tags_attribute();
+        self.replaced_by = self.replaced_by.take().map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+        self.deprecated |= self.replaced_by.is_some();
+        if let Some(id) = self.id.take().map(|id| id.trim().to_string()).filter(|id| !id.is_empty()) {
+            if id != self.name {
+                self.display_name = Some(std::mem::replace(&mut self.name, id));
+            }
+        }
+        // A translated name is only shown, the name keeps identifying the mod
+        if let Some(name) = best_translation(&self.localized_names, language) {
+            if name != self.name {
+                self.display_name = Some(name.to_string());
+            }
+        }
+        if let Some(description) = best_translation(&self.localized_descriptions, language) {
+            self.description = description.to_string();
+        }
+        self.description_html = render_markdown(self.description.trim());
+        if self.url.is_none() {
+            self.url = self.download_urls("").into_iter().next();
+        }
+        self.variants.retain(|v| !v.id.trim().is_empty() && !v.url.trim().is_empty());
+        self.base_size = self.size;
+        self.select_variant(None);
+    }
+
+    /// Whether the mod is meant for an operating system, as named by `std::env::consts::OS`
+    pub fn supports_platform(&self, os: &str) -> bool {
+        self.platform.as_deref().is_none_or(|platforms| {
+            let mut listed = platforms.split(',').map(str::trim).filter(|p| !p.is_empty()).peekable();
+            listed.peek().is_none() || listed.any(|p| p.eq_ignore_ascii_case(os))
+        })
+    }
+
+    /// Point `url` and `size` at a variant: `chosen` when the mod has it, otherwise the installed
+    /// variant, otherwise the first. Mirrors belong to the main download and are dropped
+    pub fn select_variant(&mut self, chosen: Option<&str>) {
+        let installed = self.installed_variant.as_deref();
+        let Some(variant) = [chosen, installed]
+            .into_iter()
+            .flatten()
+            .find_map(|id| self.variants.iter().find(|v| v.id == id))
+            .or(self.variants.first())
+        else {
+            return;
+        };
+        self.variant = Some(variant.id.clone());
+        self.url = Some(variant.url.clone());
+        self.size = variant.size.or(self.base_size);
+        self.mirrors.clear();
+    }
+
+    /// The variant offered for download at `url`, if any
+    pub fn variant_for_url(&self, url: &str) -> Option<&ModVariant> {
+        self.variants.iter().find(|v| v.url.trim() == url.trim())
+    }
+
+    /// Whether the mod carries a tag, ignoring case
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
     pub fn new_sideloaded(name: String, version: String, description: String) -> Self {
         Self {
             name,
             version,
+            id: None,
+            display_name: None,
             url: None,
+            mirrors: Vec::new(),
             new_version: None,
+            downgrade_version: None,
+            description_html: render_markdown(description.trim()),
             description,
+            localized_names: Vec::new(),
+            localized_descriptions: Vec::new(),
+            changelog_url: None,
+            icon_url: None,
+            screenshot_url: None,
+            author: None,
+            homepage_url: None,
+            license: None,
+            size: None,
+            base_size: None,
+            platform: None,
+            dcs_version: None,
+            dcs_variants: None,
+            dcs_incompatible: false,
+            deprecated: false,
+            replaced_by: None,
+            origin: None,
+            source: Some(super::mod_utils::SIDELOAD_SOURCE.to_string()),
+            favorite: false,
+            note: None,
+            since_last_visit: None,
+            tags: Vec::new(),
+            tags_attribute: None,
+            install_notes: None,
+            requires: Vec::new(),
+            variants: Vec::new(),
+            variant: None,
+            installed_variant: None,
+            enabling_lock: None,
         }
     }
     
@@ -93,19 +620,74 @@ impl Mod {
         Self {
             name,
             version,
+            id: None,
+            display_name: None,
             url: None,
+            mirrors: Vec::new(),
             new_version: None,
+            downgrade_version: None,
+            description_html: render_markdown(description.trim()),
             description,
+            localized_names: Vec::new(),
+            localized_descriptions: Vec::new(),
+            changelog_url: None,
+            icon_url: None,
+            screenshot_url: None,
+            author: None,
+            homepage_url: None,
+            license: None,
+            size: None,
+            base_size: None,
+            platform: None,
+            dcs_version: None,
+            dcs_variants: None,
+            dcs_incompatible: false,
+            deprecated: true,
+            replaced_by: None,
+            origin: None,
+            source: None,
+            favorite: false,
+            note: None,
+            since_last_visit: None,
+            tags: Vec::new(),
+            tags_attribute: None,
+            install_notes: None,
+            requires: Vec::new(),
+            variants: Vec::new(),
+            variant: None,
+            installed_variant: None,
+            enabling_lock: None,
         }
     }
 }
 
 impl Category {
+    /// Push the category and, right after it, its subcategories in their own sort order. Nested
+    /// categories are named by their path and share the sort order of their top-level ancestor,
+    /// so sorting the flat list by it keeps them together
+    fn flatten_into(mut self, flat: &mut Vec<Category>, parent: Option<&str>, sort_order: i32) {
+        let mut subcategories = std::mem::take(&mut self.subcategories);
+        subcategories.sort_by_key(|cat| cat.sort_order);
+        if let Some(parent) = parent {
+            self.name = format!("{}{}{}", parent, CATEGORY_PATH_SEPARATOR, self.name);
+            self.parent = Some(parent.to_string());
+        }
+        self.sort_order = sort_order;
+        let name = self.name.clone();
+        flat.push(self);
+        for subcategory in subcategories {
+            subcategory.flatten_into(flat, Some(&name), sort_order);
+        }
+    }
+
     pub fn new_sideloaded(mods: Vec<Mod>) -> Self {
         Self {
             name: "Sideloaded".to_string(),
             sort_order: 0, // Will be set by the handler
+            description: None,
+            parent: None,
             mods,
+            subcategories: Vec::new(),
         }
     }
     
@@ -113,7 +695,10 @@ impl Category {
         Self {
             name: "Deprecated".to_string(),
             sort_order: -999, // Always first (with an extra safety margin)
+            description: None,
+            parent: None,
             mods,
+            subcategories: Vec::new(),
         }
     }
 }
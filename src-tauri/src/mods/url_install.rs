@@ -0,0 +1,147 @@
+use super::downloader::ModDownloader;
+use super::extraction::extract_zip;
+use super::mod_utils::verify_mod_structure;
+use super::sideload::read_mod_metadata;
+use super::types::Mod;
+use crate::settings::Settings;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Decode `%XX` escapes in a URL path segment
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Work out the mod name from the Content-Disposition filename, falling back to the last URL path
+/// segment, without the .zip extension
+fn infer_mod_name(url: &str, content_disposition: Option<&str>) -> Option<String> {
+    let from_header = content_disposition.and_then(|header| {
+        header
+            .split(';')
+            .map(str::trim)
+            .find_map(|part| part.strip_prefix("filename="))
+            .map(|name| name.trim_matches('"').to_string())
+    });
+    let from_url = || {
+        let path = url.split(['?', '#']).next()?;
+        let segment = path.rsplit('/').next()?;
+        Some(percent_decode(segment))
+    };
+
+    let filename = from_header.or_else(from_url)?;
+    let name = filename
+        .strip_suffix(".zip")
+        .or_else(|| filename.strip_suffix(".ZIP"))
+        .unwrap_or(&filename)
+        .trim();
+    let valid = !name.is_empty()
+        && !name.contains(['/', '\\', ':'])
+        && !name.starts_with('.');
+    valid.then(|| name.to_string())
+}
+
+/// Whether a file starts with the ZIP local file header, rather than being an HTML error page
+fn is_zip_file(path: &Path) -> bool {
+    let mut header = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|_| header == [0x50, 0x4B, 0x03, 0x04])
+}
+
+/// Download a zip from any direct link into the sideload directory and extract it as a sideloaded
+/// mod. The name comes from `name`, else the server's filename, else the URL.
+#[tauri::command]
+pub async fn download_from_url(
+    app_handle: tauri::AppHandle,
+    url: String,
+    name: Option<String>,
+) -> Result<Mod, String> {
+    let settings = Settings::load()?;
+    if settings.sideload_path.is_empty() {
+        return Err("Set a sideload path before installing mods from a URL".to_string());
+    }
+    let url = url.trim().to_string();
+    let downloader = ModDownloader::new();
+
+    let mod_name = match name.filter(|n| !n.trim().is_empty()) {
+        Some(name) => infer_mod_name(&name, None),
+        None => infer_mod_name(&url, downloader.content_disposition(&url).await.as_deref()),
+    }
+    .ok_or_else(|| format!("Could not work out a mod name from {}", url))?;
+
+    let sideload_dir = PathBuf::from(&settings.sideload_path);
+    let extract_dir = sideload_dir.join(&mod_name);
+    if extract_dir.exists() {
+        return Err(format!("A sideloaded mod named {} already exists", mod_name));
+    }
+    std::fs::create_dir_all(&sideload_dir)
+        .map_err(|e| format!("Failed to create sideload directory: {}", e))?;
+
+    let filename = format!("{}.zip", mod_name);
+    let zip_path = sideload_dir.join(&filename);
+    let temp_path = zip_path.with_extension("tmp");
+    info!("Installing {} from {}", mod_name, url);
+
+    if let Err(e) = downloader
+        .download_mod(app_handle.clone(), &url, &temp_path, &filename, None)
+        .await
+    {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.to_string());
+    }
+    if !is_zip_file(&temp_path) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err("Downloaded file is not a valid ZIP archive".to_string());
+    }
+    std::fs::rename(&temp_path, &zip_path).map_err(|e| e.to_string())?;
+
+    let extracted = extract_zip(app_handle, &zip_path, &extract_dir, &filename).await;
+    let _ = std::fs::remove_file(&zip_path);
+    let installed = extracted.and_then(|_| {
+        verify_mod_structure(&extract_dir)
+            .map_err(|e| format!("{} is not a valid mod: {}", mod_name, e))
+    });
+    if let Err(e) = installed {
+        let _ = std::fs::remove_dir_all(&extract_dir);
+        return Err(e);
+    }
+
+    info!("Installed {} as a sideloaded mod", mod_name);
+    read_mod_metadata(&extract_dir).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_mod_name_prefers_content_disposition() {
+        assert_eq!(
+            infer_mod_name("https://example.com/get?id=1", Some("attachment; filename=\"Cool Mod.zip\"")),
+            Some("Cool Mod".to_string())
+        );
+        assert_eq!(
+            infer_mod_name("https://example.com/files/Cool%20Mod.zip?dl=1", None),
+            Some("Cool Mod".to_string())
+        );
+        assert_eq!(infer_mod_name("https://example.com/", None), None);
+        assert_eq!(infer_mod_name("https://example.com/..", None), None);
+    }
+}
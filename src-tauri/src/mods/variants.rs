@@ -0,0 +1,65 @@
+use super::mod_utils::{get_mod_variant, get_repo_download_dir, get_repo_source, mod_key};
+use super::types::Category;
+use super::xml_cache::XmlCache;
+use crate::app_data;
+use crate::settings::Settings;
+use std::collections::BTreeMap;
+
+const VARIANTS_FILE: &str = "variants.json";
+
+/// Variant the user picked for each mod offering several, keyed by source and mod name (see mod_key)
+pub fn load_variant_choices() -> Result<BTreeMap<String, String>, String> {
+    app_data::load_json(VARIANTS_FILE)
+}
+
+/// Point every listed mod with variants at the chosen one
+pub fn select_variants(categories: &mut [Category], choices: &BTreeMap<String, String>) {
+    for mod_entry in categories.iter_mut().flat_map(|cat| cat.mods.iter_mut()) {
+        if !mod_entry.variants.is_empty() {
+            mod_entry.select_variant(choices.get(&mod_key(mod_entry.source.as_deref(), &mod_entry.name)).map(String::as_str));
+        }
+    }
+}
+
+/// Id of the variant a download URL belongs to, going by the cached listing of the repository
+pub fn variant_for_download(repo_url: &str, mod_name: &str, url: &str) -> Option<String> {
+    XmlCache::load_for_repo(repo_url)?
+        .categories
+        .into_iter()
+        .flat_map(|cat| cat.mods)
+        .find(|m| m.name == mod_name)?
+        .variant_for_url(url)
+        .map(|v| v.id.clone())
+}
+
+/// A reminder for the user when the downloaded variant of a mod isn't the one they picked since
+pub fn variant_mismatch_note(profile_name: &str, mod_name: &str) -> Option<String> {
+    let settings = Settings::load().ok()?;
+    let profile = settings.profiles.iter().find(|p| p.name == profile_name)?;
+    let repo_dir = get_repo_download_dir(&settings.profile_download_path(profile), &profile.repo_url);
+    let installed = get_mod_variant(&repo_dir.join(mod_name))?;
+    let key = mod_key(Some(&get_repo_source(&profile.repo_url)), mod_name);
+    let chosen = load_variant_choices().ok()?.remove(&key)?;
+    let mods_file = XmlCache::load_for_repo(&profile.repo_url)?;
+    let listed = mods_file.categories.iter().flat_map(|cat| cat.mods.iter()).find(|m| m.name == mod_name)?;
+    let label = |id: &str| {
+        listed.variants.iter().find(|v| v.id == id).and_then(|v| v.name.clone()).unwrap_or_else(|| id.to_string())
+    };
+    (installed != chosen && listed.variants.iter().any(|v| v.id == chosen)).then(|| {
+        format!(
+            "Enabled the downloaded {} variant of {}, update the mod to switch to {}",
+            label(&installed),
+            mod_name,
+            label(&chosen)
+        )
+    })
+}
+
+/// Pick the variant to download for a mod. The local copy keeps its variant until the new
+/// choice is downloaded as an update
+#[tauri::command]
+pub async fn set_mod_variant(mod_name: String, source: Option<String>, variant: String) -> Result<(), String> {
+    let mut choices = load_variant_choices()?;
+    choices.insert(mod_key(source.as_deref(), &mod_name), variant);
+    app_data::save_json(VARIANTS_FILE, &choices)
+}
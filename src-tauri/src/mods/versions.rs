@@ -0,0 +1,86 @@
+use std::cmp::Ordering;
+
+/// Parse a semantic version, allowing a leading "v"
+fn parse_semver(version: &str) -> Option<semver::Version> {
+    semver::Version::parse(version.trim().trim_start_matches(['v', 'V'])).ok()
+}
+
+/// Compare version strings segment by segment, numerically where both segments are numbers,
+/// so 1.10 sorts after 1.9. A word segment where the other version goes on with a number or
+/// ends marks a pre-release, so 1.0-beta sorts before 1.0 and 1.0.1
+fn compare_segments(a: &str, b: &str) -> Ordering {
+    let segments = |v: &str| -> Vec<String> {
+        v.trim()
+            .trim_start_matches(['v', 'V'])
+            .split(['.', '-', '_'])
+            .map(str::to_string)
+            .collect()
+    };
+    let (a, b) = (segments(a), segments(b));
+    for (x, y) in a.iter().zip(&b) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            _ => x.to_lowercase().cmp(&y.to_lowercase()),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    // 1.0 and 1.0.0 are the same version, 1.0.1 is newer and 1.0-beta older
+    let longer_order = |rest: &[String]| match rest.iter().find(|s| s.parse::<u64>() != Ok(0)) {
+        None => Ordering::Equal,
+        Some(s) if s.parse::<u64>().is_err() => Ordering::Less,
+        Some(_) => Ordering::Greater,
+    };
+    match a.len().cmp(&b.len()) {
+        Ordering::Greater => longer_order(&a[b.len()..]),
+        Ordering::Less => longer_order(&b[a.len()..]).reverse(),
+        Ordering::Equal => Ordering::Equal,
+    }
+}
+
+/// Order two mod versions: by semver precedence when both are semantic versions, otherwise
+/// segment by segment
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (parse_semver(a), parse_semver(b)) {
+        (Some(a), Some(b)) => a.cmp_precedence(&b),
+        _ => compare_segments(a, b),
+    }
+}
+
+/// The minimum app version a repository asks for, if this build is older than it
+pub fn required_app_update(min_app_version: Option<&str>) -> Option<String> {
+    let min_app_version = min_app_version?.trim();
+    (compare_versions(env!("CARGO_PKG_VERSION"), min_app_version) == Ordering::Less)
+        .then(|| min_app_version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v2.0", "2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0", "1.0.1"), Ordering::Less);
+        // Semver pre-releases come before the release
+        assert_eq!(compare_versions("1.0.0-beta", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-beta", "1.0-alpha"), Ordering::Greater);
+        // Pre-release words outside semver too
+        assert_eq!(compare_versions("1.0-beta", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0", "1.0.0-rc.1"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0-beta", "1.0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_required_app_update() {
+        assert_eq!(required_app_update(None), None);
+        assert_eq!(required_app_update(Some(env!("CARGO_PKG_VERSION"))), None);
+        assert_eq!(required_app_update(Some("0.0.1")), None);
+        assert_eq!(required_app_update(Some(" 999.0 ")), Some("999.0".to_string()));
+    }
+}
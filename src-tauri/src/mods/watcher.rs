@@ -0,0 +1,119 @@
+use super::installed::sync_installed_mods;
+use super::mod_utils::{list_mod_dirs, list_source_dirs, SIDELOAD_SOURCE};
+use crate::settings::Settings;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+/// How often the download and sideload directories are rescanned
+const WATCH_INTERVAL: Duration = Duration::from_secs(3);
+
+/// A mod directory found by the watcher
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WatchedMod {
+    pub name: String,
+    /// Repo-hash directory name, or "sideload"
+    pub source: String,
+}
+
+/// Payload of the `mods-changed` event
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ModsChanged {
+    pub added: Vec<WatchedMod>,
+    pub removed: Vec<WatchedMod>,
+    /// Mods whose VERSION.txt changed
+    pub modified: Vec<WatchedMod>,
+}
+
+impl ModsChanged {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Every mod directory with the modification time of its VERSION.txt. ENABLED files aren't
+/// looked at, enabling and disabling from the app isn't a change worth reporting.
+fn snapshot(settings: &Settings) -> BTreeMap<WatchedMod, Option<SystemTime>> {
+    let mut sources: Vec<(String, PathBuf)> = list_source_dirs(settings)
+        .into_iter()
+        .filter(|(_, dir)| dir.extension().is_none_or(|ext| ext != "updating"))
+        .collect();
+    if !settings.sideload_path.is_empty() {
+        sources.push((SIDELOAD_SOURCE.to_string(), PathBuf::from(&settings.sideload_path)));
+    }
+
+    let mut mods = BTreeMap::new();
+    for (source, dir) in sources {
+        for mod_dir in list_mod_dirs(&dir) {
+            let modified = mod_dir.join("VERSION.txt").metadata().and_then(|m| m.modified()).ok();
+            let name = mod_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+            mods.insert(WatchedMod { name, source: source.clone() }, modified);
+        }
+    }
+    mods
+}
+
+fn diff(
+    previous: &BTreeMap<WatchedMod, Option<SystemTime>>,
+    current: &BTreeMap<WatchedMod, Option<SystemTime>>,
+) -> ModsChanged {
+    let mut changes = ModsChanged::default();
+    for (watched, modified) in current {
+        match previous.get(watched) {
+            None => changes.added.push(watched.clone()),
+            Some(before) if before != modified => changes.modified.push(watched.clone()),
+            _ => {}
+        }
+    }
+    changes.removed = previous
+        .keys()
+        .filter(|watched| !current.contains_key(*watched))
+        .cloned()
+        .collect();
+    changes
+}
+
+/// Poll the download and sideload directories and emit `mods-changed` when mod folders appear,
+/// disappear or get a new VERSION.txt, including changes made outside the app
+pub fn start_download_watcher(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut previous = None;
+        loop {
+            // Reloaded every time, the download and sideload paths can change while running
+            if let Ok(settings) = Settings::load() {
+                let current = tokio::task::spawn_blocking(move || snapshot(&settings))
+                    .await
+                    .unwrap_or_default();
+                let changes = previous.as_ref().map(|previous| diff(previous, &current));
+                // The state database follows the folders, filled on the first pass
+                if changes.as_ref().is_none_or(|changes| !changes.is_empty()) {
+                    let synced = tokio::task::spawn_blocking(|| {
+                        Settings::load().and_then(|settings| sync_installed_mods(&settings))
+                    })
+                    .await;
+                    if let Ok(Err(e)) = synced {
+                        warn!("Failed to record installed mods: {}", e);
+                    }
+                }
+                if let Some(changes) = changes {
+                    if !changes.is_empty() {
+                        info!(
+                            "Mod folders changed: {} added, {} removed, {} modified",
+                            changes.added.len(),
+                            changes.removed.len(),
+                            changes.modified.len()
+                        );
+                        if let Err(e) = app_handle.emit("mods-changed", &changes) {
+                            error!("Failed to emit mods-changed event: {}", e);
+                        }
+                    }
+                }
+                previous = Some(current);
+            }
+            tokio::time::sleep(WATCH_INTERVAL).await;
+        }
+    });
+}
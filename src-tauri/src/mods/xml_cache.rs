@@ -1,9 +1,17 @@
+use std::collections::{BTreeSet, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use directories::ProjectDirs;
 use super::types::{ModError, ModsFile};
-use super::parser::ModParser;
+use super::includes::{merge_cached_includes, resolve_include_url};
+use super::parsed_cache::parse_repo_file;
+use super::repo_history::{history_dir, keep_replaced_copy};
+use super::cache_limit::enforce_cache_limit;
+use crate::settings::{app_language, Settings};
 use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+use tracing::{error, info, warn};
 
 /// Handler for caching and loading XML files
 pub struct XmlCache;
@@ -14,7 +22,7 @@ impl XmlCache {
         let proj_dirs = ProjectDirs::from("com", "borderzone", "bzmm")?;
         let cache_dir = proj_dirs.cache_dir().join("xml_cache");
         if let Err(e) = fs::create_dir_all(&cache_dir) {
-            eprintln!("Failed to create XML cache directory: {}", e);
+            error!("Failed to create XML cache directory: {}", e);
             return None;
         }
         Some(cache_dir)
@@ -32,26 +40,73 @@ impl XmlCache {
         format!("repo_{}.xml", hash)
     }
 
-    /// Save XML content to cache
-    pub fn save_xml(url: &str, xml_content: &str) -> Result<PathBuf, ModError> {
+    /// Where a repository file is written while it downloads, before it is known to parse.
+    /// Every download gets its own file, so fetches of the same URL (from another window or
+    /// another instance) never write into each other's file
+    pub fn partial_path(url: &str) -> Result<PathBuf, ModError> {
+        static NEXT_PARTIAL: AtomicU64 = AtomicU64::new(0);
+
         let cache_dir = Self::get_cache_dir()
             .ok_or_else(|| ModError::IoError(io::Error::new(
                 io::ErrorKind::NotFound, 
                 "Could not find or create cache directory"
             )))?;
-        
-        let filename = Self::generate_cache_filename(url);
-        let file_path = cache_dir.join(&filename);
-        
-        fs::write(&file_path, xml_content)
+        let file_name = format!(
+            "{}.{}-{}.part",
+            partial_prefix(url),
+            std::process::id(),
+            NEXT_PARTIAL.fetch_add(1, Ordering::Relaxed)
+        );
+        Ok(cache_dir.join(file_name))
+    }
+
+    /// Partial downloads of a repository file left in the cache directory
+    fn partial_files(url: &str) -> Vec<PathBuf> {
+        let Some(cache_dir) = Self::get_cache_dir() else {
+            return Vec::new();
+        };
+        let prefix = format!("{}.", partial_prefix(url));
+        fs::read_dir(cache_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".part"))
+            })
+            .collect()
+    }
+
+    /// Make a downloaded repository file that parsed the cached copy for its URL
+    pub fn commit_partial(url: &str, partial_path: &Path) -> Result<PathBuf, ModError> {
+        // Renaming over the cached copy is atomic, the last download to parse wins
+        let file_path = partial_path.with_file_name(Self::generate_cache_filename(url));
+        if let Err(e) = keep_replaced_copy(&file_path, partial_path) {
+            warn!("Failed to keep the previous copy of {}: {}", url, e);
+        }
+        fs::rename(partial_path, &file_path)
             .map_err(ModError::IoError)?;
         
-        println!("Saved XML cache to {}", file_path.display());
+        info!("Saved XML cache to {}", file_path.display());
+        enforce_cache_limit();
         Ok(file_path)
     }
 
-    /// Load XML content from cache
-    pub fn load_xml(path: &Path) -> Result<ModsFile, ModError> {
+    /// Load a cached repository listing, along with the cached files it includes
+    pub fn load_xml(path: &Path, repo_url: &str) -> Result<ModsFile, ModError> {
+        let mut mods_file = Self::parse_cached(path)?;
+        merge_cached_includes(&mut mods_file, repo_url);
+        Ok(mods_file)
+    }
+
+    /// A single cached repository file, without resolving its includes
+    pub fn load_cached_file(url: &str) -> Option<ModsFile> {
+        Self::parse_cached(&Self::get_cache_path(url)?).ok()
+    }
+
+    fn parse_cached(path: &Path) -> Result<ModsFile, ModError> {
         if !path.exists() {
             return Err(ModError::IoError(io::Error::new(
                 io::ErrorKind::NotFound,
@@ -59,10 +114,42 @@ impl XmlCache {
             )));
         }
         
-        let xml_content = fs::read_to_string(path)
-            .map_err(ModError::IoError)?;
-        
-        ModParser::parse_mod_list(&xml_content)
+        parse_repo_file(path, &app_language())
+    }
+
+    /// Cached listing of a repository, for lookups that shouldn't wait on the network
+    pub fn load_for_repo(repo_url: &str) -> Option<ModsFile> {
+        let repo_url = repo_url.trim_end_matches('/');
+        let path = Self::get_cache_path(repo_url)?;
+        Self::load_xml(&path, repo_url).ok()
+    }
+
+    /// When a cached repository file was written, seconds since the Unix epoch
+    pub fn fetched_at(path: &Path) -> Option<u64> {
+        let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+        Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+    }
+
+    /// Cache files of a repository: its own and those of the files it includes, found by following
+    /// the includes of the cached copies
+    pub(super) fn repo_cache_files(repo_url: &str) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![repo_url.to_string()];
+        while let Some(url) = pending.pop() {
+            if !visited.insert(url.clone()) {
+                continue;
+            }
+            let Some(path) = Self::get_cache_path(&url) else {
+                continue;
+            };
+            if let Some(cached) = Self::load_cached_file(&url) {
+                pending.extend(cached.includes.iter().map(|include| resolve_include_url(&url, &include.url)));
+            }
+            files.extend(Self::partial_files(&url));
+            files.push(path);
+        }
+        files
     }
 
     /// Get the cache path for a repo URL
@@ -73,30 +160,109 @@ impl XmlCache {
     }
 }
 
-/// Add cache path to settings
+/// Start of the partial download file names of a repository file
+fn partial_prefix(url: &str) -> String {
+    XmlCache::generate_cache_filename(url).trim_end_matches(".xml").to_string()
+}
+
+/// Record the cached XML of a repository in settings, under its URL
 pub fn update_cache_path_in_settings(
     settings: &mut crate::settings::Settings, 
     url: &str, 
     cache_path: &Path
 ) -> Result<(), String> {
-    // Convert cache_path to string
     let cache_path_str = cache_path.to_string_lossy().to_string();
+    let url = url.trim_end_matches('/');
     
-    // Find index for this URL
-    let index = settings.profiles.iter().position(|p| p.repo_url == url);
-    
-    if let Some(index) = index {
-        // Ensure the cached_xml_paths vector has enough elements
-        while settings.cached_xml_paths.len() <= index {
-            settings.cached_xml_paths.push(String::new());
-        }
-        
-        // Update the cache path
-        settings.cached_xml_paths[index] = cache_path_str;
-        
-        // Save settings
+    // Settings are only written when the path changes, which is rarely
+    if settings.cached_xml_paths.get(url) != Some(&cache_path_str) {
+        settings.cached_xml_paths.insert(url.to_string(), cache_path_str);
         settings.save()?;
     }
     
     Ok(())
 }
+
+/// Remove the cached files of repositories no profile uses any more, after a profile was deleted
+/// or pointed at another repository, along with the files they include and their earlier copies.
+/// Drops their settings entries without saving. Returns how many files were removed
+pub fn remove_unused_repo_caches(settings: &mut Settings) -> usize {
+    let in_use: HashSet<PathBuf> = settings
+        .profiles
+        .iter()
+        .flat_map(|profile| XmlCache::repo_cache_files(profile.repo_url.trim_end_matches('/')))
+        .collect();
+    let unused: Vec<String> = settings
+        .cached_xml_paths
+        .iter()
+        .filter(|(url, path)| {
+            !in_use.contains(Path::new(path.as_str()))
+                && XmlCache::get_cache_path(url).is_none_or(|path| !in_use.contains(&path))
+        })
+        .map(|(url, _)| url.clone())
+        .collect();
+
+    let mut removed = 0;
+    for url in unused {
+        let mut files = XmlCache::repo_cache_files(&url);
+        files.extend(settings.cached_xml_paths.remove(&url).filter(|path| !path.is_empty()).map(PathBuf::from));
+        for file in files.iter().filter(|file| !in_use.contains(*file)) {
+            match fs::remove_file(file) {
+                Ok(()) => removed += 1,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => warn!("Failed to remove {}: {}", file.display(), e),
+            }
+            let history = history_dir(file);
+            if history.is_dir() {
+                if let Err(e) = fs::remove_dir_all(&history) {
+                    warn!("Failed to remove {}: {}", history.display(), e);
+                }
+            }
+        }
+        info!("Removed the cache of {}, no profile uses it any more", url);
+    }
+    removed
+}
+
+/// Remove cached repository listings and their settings entries, so the next load fetches them
+/// afresh, such as after a maintainer fixed a broken file. Without a URL every repository's cache
+/// goes. Returns how many files were removed
+#[tauri::command]
+pub async fn clear_xml_cache(repo_url: Option<String>) -> Result<usize, String> {
+    let mut settings = Settings::load()?;
+    let mut files = BTreeSet::new();
+    let recorded: Vec<String> = match &repo_url {
+        Some(url) => {
+            let url = url.trim_end_matches('/');
+            files.extend(XmlCache::repo_cache_files(url));
+            settings.cached_xml_paths.remove(url).into_iter().collect()
+        }
+        None => {
+            let cache_dir = XmlCache::get_cache_dir().ok_or_else(|| "Could not determine cache path".to_string())?;
+            let entries = fs::read_dir(&cache_dir).map_err(|e| format!("Failed to read {}: {}", cache_dir.display(), e))?;
+            files.extend(entries.flatten().map(|entry| entry.path()).filter(|path| path.is_file()));
+            // Parsed copies of every repository go too
+            let parsed = cache_dir.join("parsed");
+            if parsed.is_dir() {
+                let entries = fs::read_dir(&parsed).map_err(|e| format!("Failed to read {}: {}", parsed.display(), e))?;
+                files.extend(entries.flatten().map(|entry| entry.path()));
+            }
+            std::mem::take(&mut settings.cached_xml_paths).into_values().collect()
+        }
+    };
+    if !recorded.is_empty() {
+        files.extend(recorded.into_iter().filter(|path| !path.is_empty()).map(PathBuf::from));
+        settings.save()?;
+    }
+
+    let mut removed = 0;
+    for file in files {
+        match fs::remove_file(&file) {
+            Ok(()) => removed += 1,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to remove {}: {}", file.display(), e)),
+        }
+    }
+    info!("Cleared {} cached repository files", removed);
+    Ok(removed)
+}
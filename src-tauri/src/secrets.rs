@@ -0,0 +1,46 @@
+use keyring::Entry;
+
+/// Service the app's secrets are filed under in the OS keyring
+const KEYRING_SERVICE: &str = "com.borderzone.bzmm";
+
+/// Settings values starting with this name a keyring entry instead of holding the secret itself
+const REFERENCE_PREFIX: &str = "keyring:";
+
+/// Whether a settings value refers to a keyring entry
+pub fn is_reference(value: &str) -> bool {
+    value.starts_with(REFERENCE_PREFIX)
+}
+
+fn entry(name: &str) -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, name).map_err(|e| format!("Failed to open keyring entry {}: {}", name, e))
+}
+
+/// Put a secret in the OS keyring under `name`, returning the reference to keep in settings
+pub fn store_secret(name: &str, secret: &str) -> Result<String, String> {
+    entry(name)?
+        .set_password(secret)
+        .map_err(|e| format!("Failed to store {} in the keyring: {}", name, e))?;
+    Ok(format!("{}{}", REFERENCE_PREFIX, name))
+}
+
+/// The secret a settings value stands for: read from the keyring for a reference, otherwise
+/// the value itself as settings from before the keyring hold it
+pub fn resolve_secret(value: &str) -> Result<String, String> {
+    match value.strip_prefix(REFERENCE_PREFIX) {
+        Some(name) => entry(name)?
+            .get_password()
+            .map_err(|e| format!("Failed to read {} from the keyring: {}", name, e)),
+        None => Ok(value.to_string()),
+    }
+}
+
+/// Remove the keyring entry a reference points at. Plain values and missing entries are left be
+pub fn delete_secret(value: &str) -> Result<(), String> {
+    let Some(name) = value.strip_prefix(REFERENCE_PREFIX) else {
+        return Ok(());
+    };
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove {} from the keyring: {}", name, e)),
+    }
+}
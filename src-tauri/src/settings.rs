@@ -1,13 +1,85 @@
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use crate::mods::dcs_version::{dcs_dir_variant, detect_dcs_variant, profile_dcs_variant};
+use crate::mods::mod_utils::get_repo_download_dir;
+use crate::mods::profile_rename::rename_profile;
+use crate::mods::download_queue::get_queue;
+use crate::mods::throttle::get_throttle;
+use crate::mods::cache_limit::enforce_cache_limit;
+use crate::mods::xml_cache::remove_unused_repo_caches;
+use crate::logging::{level_set_by_env, parse_filter, set_log_filter};
+use crate::secrets;
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// How many earlier copies of the settings file are kept, `settings.json.1` being the newest
+const SETTINGS_BACKUPS: usize = 3;
+
+/// App handle for telling the frontend about restored settings, set once the app is up
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// Warning about settings restored from a backup that the frontend hasn't picked up yet
+static RESTORE_WARNING: Mutex<Option<String>> = Mutex::new(None);
+
+/// Set once this session has tried moving plain-text secrets into the keyring
+static SECRETS_MIGRATED: AtomicBool = AtomicBool::new(false);
+
+/// Keyring entry of the manual proxy's password
+const PROXY_PASSWORD_SECRET: &str = "proxy-password";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
     pub name: String,
     pub dcs_path: String,
     pub repo_url: String,
+    /// DCS installation directory, for mods that don't go into Saved Games
+    #[serde(default)]
+    pub install_path: String,
+    /// Where this profile's mods are downloaded instead of the global download path, empty to
+    /// use the global one
+    #[serde(default)]
+    pub download_path: String,
+    /// Which DCS this profile is for, detected from its paths when not set
+    #[serde(default)]
+    pub dcs_variant: Option<DcsVariant>,
+    /// Script run after one of this profile's mods is downloaded, empty for none
+    #[serde(default)]
+    pub hook_script: String,
+    /// Also run `hook_script` after a mod is enabled for this profile
+    #[serde(default)]
+    pub hook_on_enable: bool,
+    /// Enable mods downloaded for this profile once they are extracted
+    #[serde(default)]
+    pub auto_enable: bool,
+    /// Leave DCS lua (and ini) files as they are: mods patching them install without the patches
+    #[serde(default)]
+    pub disable_lua_patching: bool,
+}
+
+/// Release branch or edition of DCS a profile points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DcsVariant {
+    Stable,
+    OpenBeta,
+    DedicatedServer,
+}
+
+impl DcsVariant {
+    /// How the variant is written in messages
+    pub fn label(self) -> &'static str {
+        match self {
+            DcsVariant::Stable => "Stable",
+            DcsVariant::OpenBeta => "OpenBeta",
+            DcsVariant::DedicatedServer => "Dedicated Server",
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,21 +89,305 @@ pub enum DarkMode {
     Dark,
 }
 
+/// Which proxy HTTP requests go through
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ProxyMode {
+    /// The system's proxy configuration, as reqwest finds it
+    #[default]
+    System,
+    Manual,
+    None,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProxySettings {
+    #[serde(default)]
+    pub mode: ProxyMode,
+    /// Proxy URL such as "http://proxy.local:3128", used in manual mode
+    #[serde(default)]
+    pub url: String,
+    /// Credentials for the manual proxy, empty when it needs none
+    #[serde(default)]
+    pub username: String,
+    /// Reference to the password in the OS keyring, or the password itself where no keyring
+    /// is available
+    #[serde(default)]
+    pub password: String,
+}
+
+impl ProxySettings {
+    /// Configure an HTTP client to go through this proxy
+    pub fn apply(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, String> {
+        match self.mode {
+            ProxyMode::System => Ok(builder),
+            ProxyMode::None => Ok(builder.no_proxy()),
+            ProxyMode::Manual => {
+                let url = self.url.trim();
+                if url.is_empty() {
+                    return Err("A manual proxy needs a URL".to_string());
+                }
+                let mut proxy = reqwest::Proxy::all(url)
+                    .map_err(|e| format!("Invalid proxy URL {}: {}", url, e))?;
+                if !self.username.is_empty() {
+                    let password = secrets::resolve_secret(&self.password)?;
+                    proxy = proxy.basic_auth(&self.username, &password);
+                }
+                Ok(builder.proxy(proxy))
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Settings {
     pub dark_mode: DarkMode,
     pub download_path: String,
     #[serde(default)]
     pub sideload_path: String,
+    /// Where downloads are written and extracted before they move into the download path, such
+    /// as a fast drive for a download path on a large slow one. Empty to work in the download path
+    #[serde(default)]
+    pub staging_path: String,
     pub profiles: Vec<Profile>,
+    /// Name of the profile last selected. The app opens on it and commands given no profile use it
+    #[serde(default)]
+    pub active_profile: String,
+    /// Cached repository XML by repository URL
+    #[serde(default)]
+    pub cached_xml_paths: BTreeMap<String, String>,
+    /// Hide to the tray instead of exiting when the window is closed
+    #[serde(default)]
+    pub close_to_tray: bool,
+    /// Region whose mirrors are tried first when a mod lists several, empty for no preference
+    #[serde(default)]
+    pub mirror_region: String,
+    /// Language tag for repository texts such as "de" or "fr-CA", empty to follow the system
+    #[serde(default)]
+    pub language: String,
+    /// How many downloads the queue runs at once
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
+    /// Cap on the combined speed of mod downloads in kilobytes per second, 0 for no limit
+    #[serde(default)]
+    pub max_download_speed_kbps: u64,
+    #[serde(default)]
+    pub proxy: ProxySettings,
+    /// Minutes between background checks of the repositories for mod updates, 0 to only check
+    /// when the mods page loads them
+    #[serde(default)]
+    pub auto_refresh_minutes: u64,
+    /// Also write `ENABLED-<profile>.txt` into mod folders, for older versions and other tools.
+    /// Which mods are enabled is kept in the app data directory either way
+    #[serde(default = "default_enabled_markers")]
+    pub enabled_markers: bool,
+    /// Hours after which a cached repository listing is flagged as stale, 0 to never flag it
+    #[serde(default = "default_cache_ttl_hours")]
+    pub cache_ttl_hours: u64,
+    /// Size the cache directory is pruned down to, in megabytes, 0 for no limit
+    #[serde(default = "default_cache_size_mb")]
+    pub cache_size_mb: u64,
+    /// Log filter directives such as `debug` or `info,bzmm::mods::downloader=trace`, empty for
+    /// the default level
     #[serde(default)]
-    pub cached_xml_paths: Vec<String>,
+    pub log_level: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+fn default_enabled_markers() -> bool {
+    true
+}
+
+fn default_cache_ttl_hours() -> u64 {
+    24
+}
+
+fn default_cache_size_mb() -> u64 {
+    200
+}
+
+/// Shortest allowed `auto_refresh_minutes` other than 0, to go easy on repository servers
+pub const MIN_AUTO_REFRESH_MINUTES: u64 = 5;
+
+/// Allowed values of `max_concurrent_downloads`, more mostly splits the same bandwidth further
+pub const MAX_CONCURRENT_DOWNLOADS_RANGE: RangeInclusive<usize> = 1..=8;
+
+/// Largest allowed `cache_size_mb` (1 TB), any more is as good as no limit
+pub const MAX_CACHE_SIZE_MB: u64 = 1024 * 1024;
+
+/// Largest allowed `cache_ttl_hours` (a year), any more is as good as never
+pub const MAX_CACHE_TTL_HOURS: u64 = 365 * 24;
+
+fn default_max_concurrent_downloads() -> usize {
+    2
+}
+
+/// Language of the user's system as a tag like "de-DE"
+#[cfg(windows)]
+fn system_language() -> Option<String> {
+    use windows_sys::Win32::Globalization::GetUserDefaultLocaleName;
+
+    let mut buffer = [0u16; 85];
+    let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+    (len > 1).then(|| String::from_utf16_lossy(&buffer[..len as usize - 1]))
+}
+
+/// Language of the user's system as a tag like "de-DE", from the POSIX locale variables
+#[cfg(not(windows))]
+fn system_language() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|locale| locale.split(['.', '@']).next().unwrap_or_default().replace('_', "-"))
+        .find(|locale| !locale.is_empty() && locale != "C" && locale != "POSIX")
+}
+
+/// Language to pick repository texts in: the `language` setting, else the system's. Doesn't
+/// create the settings file when there is none yet
+pub fn app_language() -> String {
+    let configured = Settings::get_settings_path()
+        .filter(|path| path.exists())
+        .and_then(|_| Settings::load().ok())
+        .map(|settings| settings.language)
+        .unwrap_or_default();
+    if configured.trim().is_empty() {
+        system_language().unwrap_or_default()
+    } else {
+        configured.trim().to_string()
+    }
+}
+
+/// Changes to the app-wide settings, fields left out stay as they are. Profiles and the XML
+/// cache paths have commands of their own
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SettingsUpdate {
-    pub key: String,
-    pub value: String,
+    pub dark_mode: Option<DarkMode>,
+    pub download_path: Option<String>,
+    pub sideload_path: Option<String>,
+    pub staging_path: Option<String>,
+    pub close_to_tray: Option<bool>,
+    pub mirror_region: Option<String>,
+    pub language: Option<String>,
+    pub max_concurrent_downloads: Option<usize>,
+    pub max_download_speed_kbps: Option<u64>,
+    pub proxy: Option<ProxySettings>,
+    pub auto_refresh_minutes: Option<u64>,
+    pub enabled_markers: Option<bool>,
+    pub cache_ttl_hours: Option<u64>,
+    pub cache_size_mb: Option<u64>,
+    pub log_level: Option<String>,
+}
+
+impl SettingsUpdate {
+    /// Check every field given, before anything is changed
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if let Some(limit) = self.max_concurrent_downloads {
+            if !MAX_CONCURRENT_DOWNLOADS_RANGE.contains(&limit) {
+                return Err(format!(
+                    "Concurrent downloads must be between {} and {}",
+                    MAX_CONCURRENT_DOWNLOADS_RANGE.start(),
+                    MAX_CONCURRENT_DOWNLOADS_RANGE.end()
+                ));
+            }
+        }
+        if let Some(minutes) = self.auto_refresh_minutes {
+            if minutes != 0 && minutes < MIN_AUTO_REFRESH_MINUTES {
+                return Err(format!(
+                    "Auto-refresh interval must be 0 or at least {} minutes",
+                    MIN_AUTO_REFRESH_MINUTES
+                ));
+            }
+        }
+        if let Some(hours) = self.cache_ttl_hours {
+            if hours > MAX_CACHE_TTL_HOURS {
+                return Err(format!("Cache expiry must be at most {} hours, or 0 for never", MAX_CACHE_TTL_HOURS));
+            }
+        }
+        if let Some(size) = self.cache_size_mb {
+            if size > MAX_CACHE_SIZE_MB {
+                return Err(format!("Cache size must be at most {} MB, or 0 for no limit", MAX_CACHE_SIZE_MB));
+            }
+        }
+        if let Some(level) = &self.log_level {
+            parse_filter(level)?;
+        }
+        // A manual proxy reqwest can't use would only fail once something is downloaded
+        if let Some(proxy) = &self.proxy {
+            let _ = proxy.apply(reqwest::Client::builder())?;
+        }
+        Ok(())
+    }
+
+    fn apply(self, settings: &mut Settings) {
+        if let Some(dark_mode) = self.dark_mode {
+            settings.dark_mode = dark_mode;
+        }
+        if let Some(download_path) = self.download_path {
+            settings.download_path = download_path;
+        }
+        if let Some(sideload_path) = self.sideload_path {
+            settings.sideload_path = sideload_path;
+        }
+        if let Some(staging_path) = self.staging_path {
+            settings.staging_path = staging_path.trim().to_string();
+        }
+        if let Some(close_to_tray) = self.close_to_tray {
+            settings.close_to_tray = close_to_tray;
+        }
+        if let Some(mirror_region) = self.mirror_region {
+            settings.mirror_region = mirror_region.trim().to_string();
+        }
+        if let Some(language) = self.language {
+            settings.language = language.trim().to_string();
+        }
+        if let Some(limit) = self.max_concurrent_downloads {
+            settings.max_concurrent_downloads = limit;
+        }
+        if let Some(limit) = self.max_download_speed_kbps {
+            settings.max_download_speed_kbps = limit;
+        }
+        if let Some(proxy) = self.proxy {
+            settings.proxy = proxy;
+        }
+        if let Some(minutes) = self.auto_refresh_minutes {
+            settings.auto_refresh_minutes = minutes;
+        }
+        if let Some(enabled_markers) = self.enabled_markers {
+            settings.enabled_markers = enabled_markers;
+        }
+        if let Some(hours) = self.cache_ttl_hours {
+            settings.cache_ttl_hours = hours;
+        }
+        if let Some(size_mb) = self.cache_size_mb {
+            settings.cache_size_mb = size_mb;
+        }
+        if let Some(level) = self.log_level {
+            settings.log_level = level.trim().to_string();
+        }
+    }
+}
+
+/// What kind of DCS folder a profile's DCS path is
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum DcsPathKind {
+    SavedGames,
+    Install,
+}
+
+/// Outcome of checking a DCS path, with guidance for the user when it is the wrong folder
+#[derive(Debug, Serialize)]
+pub struct DcsPathCheck {
+    pub valid: bool,
+    pub kind: Option<DcsPathKind>,
+    /// Variant the folder's name or updater config gives away
+    pub variant: Option<DcsVariant>,
+    pub message: Option<String>,
+}
+
+/// The profile the app was last on
+#[derive(Debug, Serialize)]
+pub struct ActiveProfile {
+    pub index: usize,
+    pub profile: Profile,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,8 +401,21 @@ impl Default for Settings {
             dark_mode: DarkMode::System,
             download_path: "".to_string(),
             sideload_path: "".to_string(),
+            staging_path: "".to_string(),
             profiles: vec![],
-            cached_xml_paths: vec![],
+            active_profile: "".to_string(),
+            cached_xml_paths: BTreeMap::new(),
+            close_to_tray: false,
+            mirror_region: "".to_string(),
+            language: "".to_string(),
+            max_concurrent_downloads: default_max_concurrent_downloads(),
+            max_download_speed_kbps: 0,
+            proxy: ProxySettings::default(),
+            auto_refresh_minutes: 0,
+            enabled_markers: default_enabled_markers(),
+            cache_ttl_hours: default_cache_ttl_hours(),
+            cache_size_mb: default_cache_size_mb(),
+            log_level: "".to_string(),
         }
     }
 }
@@ -56,21 +425,59 @@ impl Settings {
         let proj_dirs = ProjectDirs::from("com", "borderzone", "bzmm")?;
         let config_dir = proj_dirs.config_dir();
         if let Err(e) = fs::create_dir_all(config_dir) {
-            eprintln!("Failed to create config directory: {}", e);
+            error!("Failed to create config directory: {}", e);
             return None;
         }
         Some(config_dir.join("settings.json"))
     }
 
     pub fn load() -> Result<Self, String> {
+        let mut settings = Self::load_file()?;
+        if !SECRETS_MIGRATED.swap(true, Ordering::Relaxed) && settings.move_secrets_to_keyring() {
+            match settings.save() {
+                Ok(()) => info!("Moved the proxy password from settings into the system keyring"),
+                Err(e) => warn!("Failed to save settings after moving secrets to the keyring: {}", e),
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Move secrets still held in plain text into the OS keyring, leaving references in their
+    /// place. Returns whether any moved, without a usable keyring they stay where they are
+    fn move_secrets_to_keyring(&mut self) -> bool {
+        let password = &self.proxy.password;
+        if password.is_empty() || secrets::is_reference(password) {
+            return false;
+        }
+        match secrets::store_secret(PROXY_PASSWORD_SECRET, password) {
+            Ok(reference) => {
+                self.proxy.password = reference;
+                true
+            }
+            Err(e) => {
+                info!("Keeping the proxy password in settings: {}", e);
+                false
+            }
+        }
+    }
+
+    fn load_file() -> Result<Self, String> {
         let path = Self::get_settings_path()
             .ok_or_else(|| "Could not determine settings path".to_string())?;
 
         if path.exists() {
-            let content = fs::read_to_string(&path)
-                .map_err(|e| format!("Failed to read settings file: {}", e))?;
-
-            serde_json::from_str(&content).map_err(|e| format!("Failed to parse settings: {}", e))
+            match Self::read_file(&path) {
+                Ok(settings) => Ok(settings),
+                Err(e) => {
+                    let (settings, backup) = Self::restore_backup(&path).ok_or(e.clone())?;
+                    report_restore(format!(
+                        "Your settings file could not be read ({}). The last good copy from {} was restored.",
+                        e,
+                        backup.display()
+                    ));
+                    Ok(settings)
+                }
+            }
         } else {
             let settings = Settings::default();
             settings.save()?;
@@ -78,6 +485,89 @@ impl Settings {
         }
     }
 
+    /// Index of the active profile, the first one when none was picked or it is gone
+    pub fn active_profile_index(&self) -> Option<usize> {
+        self.profiles
+            .iter()
+            .position(|p| p.name == self.active_profile)
+            .or_else(|| (!self.profiles.is_empty()).then_some(0))
+    }
+
+    /// Name of the profile a command acts on: the one given, otherwise the active one
+    pub fn profile_or_active(&self, profile_name: Option<String>) -> Result<String, String> {
+        match profile_name {
+            Some(name) => Ok(name),
+            None => self
+                .active_profile_index()
+                .map(|index| self.profiles[index].name.clone())
+                .ok_or_else(|| "No profile to use, create one first".to_string()),
+        }
+    }
+
+    /// Where a profile's mods are downloaded, its own download path when it overrides the global one
+    pub fn profile_download_path(&self, profile: &Profile) -> PathBuf {
+        match profile.download_path.trim() {
+            "" => PathBuf::from(&self.download_path),
+            own => PathBuf::from(own),
+        }
+    }
+
+    /// Where mods from a repository are downloaded for a profile: that profile's download path
+    /// when it uses the repository, else the one of the first profile using it or the global one
+    pub fn repo_download_path(&self, repo_url: &str, profile_name: Option<&str>) -> PathBuf {
+        let repo_url = repo_url.trim_end_matches('/');
+        let uses_repo = |p: &&Profile| p.repo_url.trim_end_matches('/') == repo_url;
+        self.profiles
+            .iter()
+            .filter(uses_repo)
+            .find(|p| Some(p.name.as_str()) == profile_name)
+            .or_else(|| self.profiles.iter().find(uses_repo))
+            .map(|p| self.profile_download_path(p))
+            .unwrap_or_else(|| PathBuf::from(&self.download_path))
+    }
+
+    /// Profiles that download the same repository to the same place and so share one pool of
+    /// downloads, like Stable and OpenBeta installs of the same repository. Each group is ordered
+    /// by DCS variant, profiles sharing with no other are left out
+    pub fn linked_profiles(&self) -> Vec<Vec<usize>> {
+        let mut groups: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+        for (index, profile) in self.profiles.iter().enumerate() {
+            if profile.repo_url.trim().is_empty() {
+                continue;
+            }
+            let pool = get_repo_download_dir(
+                &self.profile_download_path(profile),
+                profile.repo_url.trim_end_matches('/'),
+            );
+            groups.entry(pool).or_default().push(index);
+        }
+        groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .map(|mut group| {
+                // Profiles of an unknown variant last
+                group.sort_by_key(|&index| {
+                    profile_dcs_variant(&self.profiles[index]).map_or(u8::MAX, |variant| variant as u8)
+                });
+                group
+            })
+            .collect()
+    }
+
+    /// Every directory mods are downloaded to, the global one first
+    pub fn download_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![PathBuf::from(&self.download_path)];
+        for profile in &self.profiles {
+            let path = self.profile_download_path(profile);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Save the settings, keeping the previous file as the newest backup. The file is written
+    /// next to the old one and renamed over it, so a crash mid-save can't leave it half written
     pub fn save(&self) -> Result<(), String> {
         let path = Self::get_settings_path()
             .ok_or_else(|| "Could not determine settings path".to_string())?;
@@ -85,8 +575,187 @@ impl Settings {
         let content = serde_json::to_string_pretty(self)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-        fs::write(&path, content).map_err(|e| format!("Failed to write settings file: {}", e))
+        // A corrupted file is no use as a backup and would push out a good one
+        if Self::read_file(&path).is_ok() {
+            rotate_backups(&path);
+        }
+        write_atomically(&path, content.as_bytes()).map_err(|e| format!("Failed to write settings file: {}", e))
+    }
+
+    fn read_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read settings file: {}", e))?;
+        let mut value: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse settings: {}", e))?;
+        migrate_cached_xml_paths(&mut value);
+        serde_json::from_value(value).map_err(|e| format!("Failed to parse settings: {}", e))
     }
+
+    /// Put the newest readable backup back in place of a corrupted settings file, keeping the
+    /// corrupted one aside as `settings.json.corrupt`
+    fn restore_backup(path: &Path) -> Option<(Self, PathBuf)> {
+        let (settings, backup) = (1..=SETTINGS_BACKUPS)
+            .map(|n| backup_path(path, n))
+            .find_map(|backup| Self::read_file(&backup).ok().map(|settings| (settings, backup)))?;
+        if let Err(e) = fs::rename(path, path.with_extension("json.corrupt")) {
+            error!("Failed to keep the corrupted settings file aside: {}", e);
+        }
+        if let Err(e) = fs::copy(&backup, path) {
+            error!("Failed to restore settings from {}: {}", backup.display(), e);
+        }
+        Some((settings, backup))
+    }
+}
+
+/// Older settings kept cached XML paths in a list lined up with the profiles, which went out of
+/// step when profiles were deleted. Key them by the profiles' repository URLs instead, the
+/// converted form is written with the next save
+fn migrate_cached_xml_paths(value: &mut serde_json::Value) {
+    let Some(serde_json::Value::Array(paths)) = value.get("cached_xml_paths") else {
+        return;
+    };
+    let profiles = value.get("profiles").and_then(|p| p.as_array()).cloned().unwrap_or_default();
+    let by_url: serde_json::Map<String, serde_json::Value> = paths
+        .iter()
+        .zip(&profiles)
+        .filter_map(|(path, profile)| {
+            let path = path.as_str().filter(|path| !path.is_empty())?;
+            let url = profile.get("repo_url")?.as_str()?.trim_end_matches('/');
+            Some((url.to_string(), serde_json::Value::from(path)))
+        })
+        .collect();
+    value["cached_xml_paths"] = serde_json::Value::Object(by_url);
+}
+
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    path.with_extension(format!("json.{}", n))
+}
+
+/// Shift the backups one place older, dropping the oldest, and copy the current file to the newest
+fn rotate_backups(path: &Path) {
+    for n in (1..SETTINGS_BACKUPS).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            if let Err(e) = fs::rename(&from, backup_path(path, n + 1)) {
+                error!("Failed to rotate settings backup {}: {}", from.display(), e);
+            }
+        }
+    }
+    if let Err(e) = fs::copy(path, backup_path(path, 1)) {
+        error!("Failed to back up settings: {}", e);
+    }
+}
+
+/// Write a file through a temporary one renamed over it once its contents are on disk
+fn write_atomically(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let temp_path = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&temp_path, path)
+}
+
+/// Keep the app handle for `settings-restored` events, called during setup
+pub fn register_app_handle(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Tell the user their settings were restored from a backup, now through a `settings-restored`
+/// event and later through `take_settings_warning` in case the window wasn't listening yet
+fn report_restore(message: String) {
+    error!("{}", message);
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit("settings-restored", &message);
+    }
+    *RESTORE_WARNING.lock().unwrap() = Some(message);
+}
+
+/// The warning about settings restored from a backup, if there is one the user hasn't seen
+#[tauri::command]
+pub async fn take_settings_warning() -> Result<Option<String>, String> {
+    Ok(RESTORE_WARNING.lock().unwrap().take())
+}
+
+/// Recognize a DCS folder by what DCS keeps in it: the executable or CoreMods for an install,
+/// Config, Logs or Missions for Saved Games. Installs have a Config folder too, so they go first
+fn dcs_dir_kind(dir: &Path) -> Option<DcsPathKind> {
+    if ["bin/DCS.exe", "bin-mt/DCS.exe"].iter().any(|exe| dir.join(exe).is_file()) || dir.join("CoreMods").is_dir() {
+        return Some(DcsPathKind::Install);
+    }
+    if ["Config", "Logs", "Missions"].iter().any(|sub| dir.join(sub).is_dir()) {
+        return Some(DcsPathKind::SavedGames);
+    }
+    None
+}
+
+/// Check that a folder looks like a DCS Saved Games or install directory. An empty path passes,
+/// profiles start out without one
+pub fn check_dcs_path(path: &str) -> DcsPathCheck {
+    let invalid = |message: String| DcsPathCheck {
+        valid: false,
+        kind: None,
+        variant: None,
+        message: Some(message),
+    };
+    if path.trim().is_empty() {
+        return DcsPathCheck {
+            valid: true,
+            kind: None,
+            variant: None,
+            message: None,
+        };
+    }
+    let dir = Path::new(path.trim());
+    if !dir.is_dir() {
+        return invalid(format!("The folder {} doesn't exist.", dir.display()));
+    }
+    if let Some(kind) = dcs_dir_kind(dir) {
+        return DcsPathCheck {
+            valid: true,
+            kind: Some(kind),
+            variant: dcs_dir_variant(dir),
+            message: None,
+        };
+    }
+
+    // The Saved Games folder itself, rather than the DCS folder in it
+    let variants: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("DCS") && dcs_dir_kind(&dir.join(name)).is_some())
+        .collect();
+    if !variants.is_empty() {
+        return invalid(format!(
+            "This is the folder containing your DCS folders. Pick the one inside it instead: {}.",
+            variants.join(" or ")
+        ));
+    }
+    // A folder inside a DCS folder, such as Mods or Config
+    if let Some(parent) = dir.ancestors().skip(1).find(|ancestor| dcs_dir_kind(ancestor).is_some()) {
+        return invalid(format!(
+            "This folder is inside a DCS folder. Pick {} itself.",
+            parent.display()
+        ));
+    }
+    invalid(
+        "This doesn't look like a DCS folder. Pick your DCS Saved Games folder, usually \
+         Saved Games\\DCS or Saved Games\\DCS.openbeta in your user folder, or the DCS install folder."
+            .to_string(),
+    )
+}
+
+/// Check a DCS path before it is saved, for the profile settings dialog
+#[tauri::command]
+pub async fn validate_dcs_path(path: String) -> Result<DcsPathCheck, String> {
+    Ok(check_dcs_path(&path))
+}
+
+/// Groups of profile indices sharing their downloads, see `Settings::linked_profiles`
+#[tauri::command]
+pub async fn get_linked_profiles() -> Result<Vec<Vec<usize>>, String> {
+    Ok(Settings::load()?.linked_profiles())
 }
 
 #[tauri::command]
@@ -94,6 +763,32 @@ pub async fn get_settings() -> Result<Settings, String> {
     Settings::load()
 }
 
+#[tauri::command]
+pub async fn get_active_profile() -> Result<Option<ActiveProfile>, String> {
+    let settings = Settings::load()?;
+    Ok(settings.active_profile_index().map(|index| ActiveProfile {
+        index,
+        profile: settings.profiles[index].clone(),
+    }))
+}
+
+/// Remember the profile picked for the next start, saving only when it changed
+#[tauri::command]
+pub async fn set_active_profile(index: usize) -> Result<(), String> {
+    let mut settings = Settings::load()?;
+    let name = settings
+        .profiles
+        .get(index)
+        .ok_or_else(|| "Profile index out of bounds".to_string())?
+        .name
+        .clone();
+    if settings.active_profile != name {
+        settings.active_profile = name;
+        settings.save()?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_app_version() -> Result<AppVersion, String> {
     Ok(AppVersion {
@@ -101,28 +796,75 @@ pub async fn get_app_version() -> Result<AppVersion, String> {
     })
 }
 
+/// Change any of the app-wide settings at once. Nothing is saved unless every field given is valid
 #[tauri::command]
 pub async fn update_settings(update: SettingsUpdate) -> Result<Settings, String> {
+    update.validate()?;
     let mut settings = Settings::load()?;
 
-    match update.key.as_str() {
-        "download_path" => settings.download_path = update.value,
-        "sideload_path" => settings.sideload_path = update.value,
-        _ => return Err("Invalid settings key".to_string()),
+    // Running downloads pick these up without waiting for a restart
+    let max_concurrent = update.max_concurrent_downloads;
+    let max_speed = update.max_download_speed_kbps;
+    let cache_size = update.cache_size_mb;
+    let previous_log_level = settings.log_level.clone();
+    let previous_password = settings.proxy.password.clone();
+    update.apply(&mut settings);
+    // A password typed in goes to the keyring before settings are written
+    settings.move_secrets_to_keyring();
+    settings.save()?;
+    if settings.proxy.password.is_empty() {
+        if let Err(e) = secrets::delete_secret(&previous_password) {
+            info!("{}", e);
+        }
     }
 
-    settings.save()?;
+    if let Some(limit) = max_concurrent {
+        get_queue().set_max_concurrent(limit);
+    }
+    if let Some(limit) = max_speed {
+        get_throttle().set_limit_kbps(limit);
+    }
+    if cache_size.is_some() {
+        enforce_cache_limit();
+    }
+    // The form sends the level with every save, only a new one changes what is logged
+    if settings.log_level != previous_log_level && !level_set_by_env() {
+        if let Err(e) = set_log_filter(&settings.log_level) {
+            warn!("{}", e);
+        }
+    }
     Ok(settings)
 }
 
 #[tauri::command]
 pub async fn update_profile(index: usize, profile: Profile) -> Result<Settings, String> {
+    let current = Settings::load()?.profiles.get(index).cloned();
+    // A DCS folder that was accepted once can be renamed or unplugged since, saving other
+    // changes to the profile shouldn't depend on it
+    if current.as_ref().is_none_or(|current| current.dcs_path != profile.dcs_path) {
+        let check = check_dcs_path(&profile.dcs_path);
+        if !check.valid {
+            return Err(check.message.unwrap_or_else(|| "Invalid DCS path".to_string()));
+        }
+    }
+    let mut profile = profile;
+    if profile.dcs_variant.is_none() {
+        profile.dcs_variant = detect_dcs_variant(&profile);
+    }
+    // A new name goes through rename_profile, or the profile's mods would look disabled
+    if current.is_some_and(|current| current.name != profile.name) {
+        rename_profile(index, profile.name.clone()).await?;
+    }
     let mut settings = Settings::load()?;
 
     if index >= settings.profiles.len() {
         settings.profiles.push(profile);
     } else {
-        settings.profiles[index] = profile;
+        let previous = std::mem::replace(&mut settings.profiles[index], profile);
+        // The old repository's cache only lingers otherwise
+        if previous.repo_url.trim_end_matches('/') != settings.profiles[index].repo_url.trim_end_matches('/') {
+            remove_unused_repo_caches(&mut settings);
+        }
     }
 
     settings.save()?;
@@ -137,7 +879,169 @@ pub async fn delete_profile(index: usize) -> Result<Settings, String> {
         return Err("Profile index out of bounds".to_string());
     }
 
-    settings.profiles.remove(index);
+    let removed = settings.profiles.remove(index);
+    if settings.active_profile == removed.name {
+        settings.active_profile.clear();
+    }
+    remove_unused_repo_caches(&mut settings);
+    settings.save()?;
+    Ok(settings)
+}
+
+/// Duplicate a profile, for pairs like Stable and OpenBeta that share a repository. Without a
+/// name the copy is called "<name> (copy)", without `dcs_path` it keeps the original's. Returns
+/// the settings with the copy appended
+#[tauri::command]
+pub async fn clone_profile(
+    index: usize,
+    name: Option<String>,
+    dcs_path: Option<String>,
+) -> Result<Settings, String> {
+    let mut settings = Settings::load()?;
+
+    let mut profile = settings
+        .profiles
+        .get(index)
+        .cloned()
+        .ok_or_else(|| "Profile index out of bounds".to_string())?;
+
+    let name = match name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty()) {
+        Some(name) => name,
+        None => unique_copy_name(&settings.profiles, &profile.name),
+    };
+    // Enabled mods are recorded per profile name, two profiles can't share one
+    if settings.profiles.iter().any(|p| p.name == name) {
+        return Err(format!("A profile named '{}' already exists", name));
+    }
+    profile.name = name;
+    if let Some(dcs_path) = dcs_path {
+        profile.dcs_path = dcs_path;
+        // A copy for another DCS folder is usually for the other variant
+        profile.dcs_variant = detect_dcs_variant(&profile);
+    }
+
+    settings.profiles.push(profile);
     settings.save()?;
     Ok(settings)
 }
+
+/// "<name> (copy)", numbered when that is taken too
+fn unique_copy_name(profiles: &[Profile], name: &str) -> String {
+    let taken = |candidate: &str| profiles.iter().any(|p| p.name == candidate);
+    let mut candidate = format!("{} (copy)", name);
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{} (copy {})", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_restore_backup_after_corruption() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let settings = Settings {
+            download_path: "D:/Mods".to_string(),
+            ..Settings::default()
+        };
+        write_atomically(&path, serde_json::to_string(&settings).unwrap().as_bytes()).unwrap();
+        rotate_backups(&path);
+
+        // Cut off by a power loss
+        fs::write(&path, "{\"dark_mode\": \"Sys").unwrap();
+        assert!(Settings::read_file(&path).is_err());
+
+        let (restored, backup) = Settings::restore_backup(&path).unwrap();
+        assert_eq!(restored.download_path, "D:/Mods");
+        assert_eq!(backup, dir.path().join("settings.json.1"));
+        assert_eq!(Settings::read_file(&path).unwrap().download_path, "D:/Mods");
+        assert!(dir.path().join("settings.json.corrupt").exists());
+    }
+
+    #[test]
+    fn test_settings_update_validation() {
+        let update: SettingsUpdate =
+            serde_json::from_str(r#"{"dark_mode": "Dark", "max_concurrent_downloads": 4}"#).unwrap();
+        assert!(update.validate().is_ok());
+        let mut settings = Settings::default();
+        update.apply(&mut settings);
+        assert!(matches!(settings.dark_mode, DarkMode::Dark));
+        assert_eq!(settings.max_concurrent_downloads, 4);
+        assert_eq!(settings.download_path, "");
+
+        let too_many: SettingsUpdate = serde_json::from_str(r#"{"max_concurrent_downloads": 20}"#).unwrap();
+        assert!(too_many.validate().is_err());
+        assert!(serde_json::from_str::<SettingsUpdate>(r#"{"profiles": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_check_dcs_path() {
+        let dir = tempdir().unwrap();
+        let saved_games = dir.path().join("Saved Games");
+        fs::create_dir_all(saved_games.join("DCS.openbeta/Config")).unwrap();
+        fs::create_dir_all(saved_games.join("DCS.openbeta/Mods/aircraft")).unwrap();
+        let install = dir.path().join("Eagle Dynamics/DCS World");
+        fs::create_dir_all(install.join("bin")).unwrap();
+        fs::write(install.join("bin/DCS.exe"), "").unwrap();
+
+        let path = |dir: &Path| dir.to_string_lossy().to_string();
+        assert_eq!(check_dcs_path(&path(&saved_games.join("DCS.openbeta"))).kind, Some(DcsPathKind::SavedGames));
+        assert_eq!(check_dcs_path(&path(&install)).kind, Some(DcsPathKind::Install));
+        assert!(check_dcs_path("").valid);
+
+        let parent = check_dcs_path(&path(&saved_games));
+        assert!(!parent.valid && parent.message.unwrap().contains("DCS.openbeta"));
+        let nested = check_dcs_path(&path(&saved_games.join("DCS.openbeta/Mods/aircraft")));
+        assert!(!nested.valid && nested.message.unwrap().contains("inside a DCS folder"));
+        assert!(!check_dcs_path(&path(&dir.path().join("missing"))).valid);
+    }
+
+    #[test]
+    fn test_repo_download_path_per_profile() {
+        let profile = |name: &str, download_path: &str| {
+            serde_json::from_value::<Profile>(serde_json::json!({
+                "name": name,
+                "dcs_path": "",
+                "repo_url": "https://a.example/mods.xml",
+                "download_path": download_path,
+            }))
+            .unwrap()
+        };
+        let settings = Settings {
+            download_path: "/mods".to_string(),
+            profiles: vec![profile("Stable", "/stable"), profile("OpenBeta", "/openbeta")],
+            ..Settings::default()
+        };
+        let url = "https://a.example/mods.xml/";
+        assert_eq!(settings.repo_download_path(url, Some("OpenBeta")), PathBuf::from("/openbeta"));
+        assert_eq!(settings.repo_download_path(url, None), PathBuf::from("/stable"));
+        assert_eq!(settings.repo_download_path(url, Some("Other")), PathBuf::from("/stable"));
+        assert_eq!(settings.repo_download_path("https://b.example/mods.xml", None), PathBuf::from("/mods"));
+    }
+
+    #[test]
+    fn test_migrate_cached_xml_paths_by_index() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let profile = |name: &str, url: &str| serde_json::json!({"name": name, "dcs_path": "", "repo_url": url});
+        let legacy = serde_json::json!({
+            "dark_mode": "System",
+            "download_path": "",
+            "profiles": [profile("Stable", "https://a.example/mods.xml/"), profile("Beta", "https://b.example/mods.xml")],
+            "cached_xml_paths": ["", "/cache/b.xml"],
+        });
+        fs::write(&path, legacy.to_string()).unwrap();
+
+        let settings = Settings::read_file(&path).unwrap();
+        assert_eq!(
+            settings.cached_xml_paths,
+            BTreeMap::from([("https://b.example/mods.xml".to_string(), "/cache/b.xml".to_string())])
+        );
+    }
+}
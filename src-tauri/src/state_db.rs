@@ -0,0 +1,127 @@
+use crate::app_data;
+use crate::mods::enable_state::import_legacy_state;
+use rusqlite::Connection;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const STATE_DB_FILE: &str = "state.db";
+
+/// Schema changes in order, the database's `user_version` counts the ones applied
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE enabled_mods (
+        profile TEXT NOT NULL,
+        source TEXT NOT NULL,
+        name TEXT NOT NULL,
+        enabled_at INTEGER NOT NULL,
+        PRIMARY KEY (profile, source, name)
+    );
+    CREATE TABLE installed_mods (
+        path TEXT PRIMARY KEY,
+        source TEXT NOT NULL,
+        name TEXT NOT NULL,
+        repo_url TEXT,
+        version TEXT,
+        variant TEXT
+    );
+    CREATE INDEX installed_mods_repo ON installed_mods (repo_url, name);
+    CREATE TABLE repo_mods (
+        repo_url TEXT NOT NULL,
+        name TEXT NOT NULL,
+        version TEXT NOT NULL,
+        checked_at INTEGER NOT NULL,
+        PRIMARY KEY (repo_url, name)
+    );
+    CREATE TABLE downloads (
+        id INTEGER PRIMARY KEY,
+        repo_url TEXT NOT NULL,
+        mod_name TEXT NOT NULL,
+        version TEXT,
+        variant TEXT,
+        size INTEGER,
+        status TEXT NOT NULL,
+        error TEXT,
+        started_at INTEGER NOT NULL,
+        finished_at INTEGER NOT NULL
+    );",
+    // Archive each downloaded mod was extracted from, to find identical downloads
+    "CREATE TABLE mod_archives (
+        path TEXT PRIMARY KEY,
+        archive_hash TEXT NOT NULL
+    );
+    CREATE INDEX mod_archives_hash ON mod_archives (archive_hash);",
+];
+
+/// The database connection, opened on first use
+static DB: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// Seconds since the Unix epoch, how times are stored
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or_default()
+}
+
+/// Bring the schema up to date, returning whether the database was new. A database from a
+/// newer version of the app is used as it is
+pub fn migrate(conn: &Connection) -> rusqlite::Result<bool> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version >= MIGRATIONS.len() {
+        return Ok(false);
+    }
+    for migration in &MIGRATIONS[version..] {
+        conn.execute_batch(migration)?;
+    }
+    conn.pragma_update(None, "user_version", MIGRATIONS.len())?;
+    Ok(version == 0)
+}
+
+/// Open the database, filling a new one with the state earlier versions kept in files
+fn open() -> Result<Connection, String> {
+    let path = app_data::get_app_data_dir()
+        .ok_or_else(|| "Could not determine app data path".to_string())?
+        .join(STATE_DB_FILE);
+    let mut conn = Connection::open(&path).map_err(|e| format!("Failed to open {}: {}", STATE_DB_FILE, e))?;
+    // Background tasks and commands can take turns on the file
+    conn.busy_timeout(Duration::from_secs(5)).map_err(|e| e.to_string())?;
+
+    let setup = (|| {
+        let tx = conn.transaction()?;
+        if migrate(&tx)? {
+            import_legacy_state(&tx)?;
+        }
+        tx.commit()
+    })();
+    setup.map_err(|e| format!("Failed to set up {}: {}", STATE_DB_FILE, e))?;
+    Ok(conn)
+}
+
+/// Run queries against the local state database
+pub fn with_db<T>(query: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut db = DB.lock().unwrap_or_else(|e| e.into_inner());
+    let conn = match db.as_mut() {
+        Some(conn) => conn,
+        None => db.insert(open()?),
+    };
+    query(conn).map_err(|e| format!("Local state database error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(migrate(&conn).unwrap());
+        assert!(!migrate(&conn).unwrap());
+
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        conn.execute(
+            "INSERT INTO enabled_mods (profile, source, name, enabled_at) VALUES ('Stable', 'a1b2c3', 'ModA', 1)",
+            [],
+        )
+        .unwrap();
+    }
+}
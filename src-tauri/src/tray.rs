@@ -0,0 +1,103 @@
+use crate::mods::download_queue::{get_queue, QueueStatus};
+use crate::settings::Settings;
+use std::time::Duration;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Window, WindowEvent};
+
+const TRAY_ID: &str = "main";
+const STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// One-line summary of the download queue for the tray menu and tooltip
+fn status_label(status: &QueueStatus) -> String {
+    let running = match status.active.as_slice() {
+        [] => "No downloads running".to_string(),
+        [single] => format!("Downloading {}", single.trim_end_matches(".zip")),
+        active => format!("Downloading {} mods", active.len()),
+    };
+    match (status.queued, status.paused) {
+        (0, false) => running,
+        (queued, false) => format!("{}, {} queued", running, queued),
+        (queued, true) => format!("{}, {} queued (paused)", running, queued),
+    }
+}
+
+/// Bring the main window back from the tray
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Create the tray icon with the download status and quick actions, and keep the status current
+pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
+    let status_item = MenuItem::with_id(app, "status", "No downloads running", false, None::<&str>)?;
+    let pause_item = MenuItem::with_id(app, "pause", "Pause downloads", true, None::<&str>)?;
+    let open_item = MenuItem::with_id(app, "open", "Open BZMM", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status_item,
+            &PredefinedMenuItem::separator(app)?,
+            &open_item,
+            &pause_item,
+            &PredefinedMenuItem::separator(app)?,
+            &quit_item,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip("BorderZone Mod Manager")
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "open" => show_main_window(app),
+            "pause" => {
+                let queue = get_queue();
+                queue.set_paused(app, !queue.is_paused());
+            }
+            "quit" => app.exit(0),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let status = get_queue().status().await;
+            let label = status_label(&status);
+            let _ = status_item.set_text(&label);
+            let _ = pause_item.set_text(if status.paused { "Resume downloads" } else { "Pause downloads" });
+            if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                let _ = tray.set_tooltip(Some(format!("BorderZone Mod Manager - {}", label)));
+            }
+            tokio::time::sleep(STATUS_REFRESH_INTERVAL).await;
+        }
+    });
+    Ok(())
+}
+
+/// Hide the window instead of closing it when close to tray is enabled, so downloads keep running
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+    if let WindowEvent::CloseRequested { api, .. } = event {
+        if Settings::load().is_ok_and(|settings| settings.close_to_tray) {
+            api.prevent_close();
+            let _ = window.hide();
+        }
+    }
+}
@@ -0,0 +1,57 @@
+import React from 'react';
+import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert"
+import { Megaphone } from "lucide-react";
+import { open } from '@tauri-apps/plugin-shell';
+import type { RepoHeader } from '../types/types';
+
+interface AnnouncementBannerProps {
+  header: RepoHeader;
+  onDismiss?: () => void;
+  className?: string;
+}
+
+// Message of the day from the repository maintainer
+const AnnouncementBanner: React.FC<AnnouncementBannerProps> = ({
+  header,
+  onDismiss,
+  className = ""
+}) => {
+  return (
+    <Alert className={`mb-4 border-blue-500/50 ${className}`}>
+      <Megaphone className="h-4 w-4" />
+      <AlertTitle className="flex justify-between items-center">
+        {header.name ?? 'Repository announcement'}
+        {onDismiss && (
+          <button
+            onClick={onDismiss}
+            className="text-sm text-muted-foreground hover:text-foreground"
+          >
+            Dismiss
+          </button>
+        )}
+      </AlertTitle>
+      <AlertDescription className="mt-2 whitespace-pre-line">
+        {header.motd}
+        {(header.maintainer || header.supportUrl) && (
+          <div className="mt-2 text-xs text-muted-foreground">
+            {header.maintainer && `Maintained by ${header.maintainer}`}
+            {header.supportUrl && (
+              <>
+                {header.maintainer && ' · '}
+                <button
+                  type="button"
+                  className="underline hover:text-foreground"
+                  onClick={() => open(header.supportUrl!)}
+                >
+                  Support
+                </button>
+              </>
+            )}
+          </div>
+        )}
+      </AlertDescription>
+    </Alert>
+  );
+};
+
+export default AnnouncementBanner;
@@ -5,6 +5,7 @@ import { ChevronRight, Download, RotateCw } from 'lucide-react';
 import { Mod } from '../types/types';
 import { useCategoryActions } from './hooks/useCategoryActions';
 import { Tooltip, TooltipContent, TooltipProvider, TooltipTrigger } from './ui/tooltip';
+import { CATEGORY_PATH_SEPARATOR, isInCategory } from '../lib/utils';
 
 interface CategoryListProps {
   categories: string[];
@@ -30,7 +31,7 @@ const CategoryList: React.FC<CategoryListProps> = ({
   const getCategoryMods = (categoryName: string): Mod[] => {
     return categoryName === 'All' 
       ? mods 
-      : mods.filter(m => m.category === categoryName);
+      : mods.filter(m => isInCategory(m.category, categoryName));
   };
 
   const handleDownloadCategory = (e: React.MouseEvent, category: string) => {
@@ -77,6 +78,9 @@ const CategoryList: React.FC<CategoryListProps> = ({
         {categories.map(category => {
           const downloadableMods = getDownloadableMods(category);
           const updatableMods = getUpdatableMods(category);
+          // Subcategories are indented under their parent and show only their own name
+          const path = category.split(CATEGORY_PATH_SEPARATOR);
+          const description = mods.find(m => m.category === category)?.categoryDescription;
           
           return (
             <Button
@@ -86,9 +90,11 @@ const CategoryList: React.FC<CategoryListProps> = ({
                 selectedCategory === category ? 'bg-muted' : ''
               }`}
               onClick={() => setSelectedCategory(category)}
+              style={{ paddingLeft: `${path.length}rem` }}
+              title={description}
             >
               <ChevronRight className="h-4 w-4 mr-2" />
-              <span className="flex-1 text-left">{category}</span>
+              <span className="flex-1 text-left">{path[path.length - 1]}</span>
               <span className="flex items-center gap-2">
                 {category !== 'Sideloaded' && category !== 'Deprecated' && downloadableMods.length > 0 && (
                   <TooltipProvider>
@@ -141,7 +147,7 @@ const CategoryList: React.FC<CategoryListProps> = ({
                 <span className="text-muted-foreground text-sm">
                   {category === 'All'
                     ? mods.length
-                    : mods.filter(m => m.category === category).length}
+                    : getCategoryMods(category).length}
                 </span>
               </span>
             </Button>
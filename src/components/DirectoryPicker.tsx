@@ -5,12 +5,16 @@ import { Input } from './ui/input';
 interface DirectoryPickerProps {
   value: string;
   onBrowse: () => void;
+  onClear?: () => void;
+  placeholder?: string;
   id?: string;
 }
 
 const DirectoryPicker: React.FC<DirectoryPickerProps> = ({ 
   value, 
   onBrowse, 
+  onClear,
+  placeholder,
   id 
 }) => {
   return (
@@ -19,6 +23,7 @@ const DirectoryPicker: React.FC<DirectoryPickerProps> = ({
         id={id}
         value={value}
         readOnly
+        placeholder={placeholder}
         className="flex-1 text-foreground focus:ring-0 cursor-text overflow-x-auto"
       />
       <Button 
@@ -28,6 +33,15 @@ const DirectoryPicker: React.FC<DirectoryPickerProps> = ({
       >
         Browse
       </Button>
+      {onClear && value && (
+        <Button
+          type="button"
+          variant="ghost"
+          onClick={onClear}
+        >
+          Clear
+        </Button>
+      )}
     </div>
   );
 };
@@ -0,0 +1,156 @@
+import React, { useState, useEffect, useRef, useMemo } from 'react';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+  DialogTrigger,
+} from './ui/dialog';
+import { Button } from './ui/button';
+import { Input } from './ui/input';
+import { Switch } from './ui/switch';
+import { Label } from './ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from './ui/select';
+import { ScrollText } from 'lucide-react';
+import { useLogs } from './hooks/useLogs';
+import { LogEntry } from '../types/types';
+
+const LEVEL_CLASSES: Record<LogEntry['level'], string> = {
+  ERROR: 'text-destructive',
+  WARN: 'text-yellow-600 dark:text-yellow-400',
+  INFO: 'text-muted-foreground',
+  DEBUG: 'text-muted-foreground',
+  TRACE: 'text-muted-foreground',
+};
+
+// Levels the panel can switch to for the session, "settings" goes back to the saved one
+const SESSION_LEVELS = [
+  { value: 'settings', label: 'Level from settings' },
+  { value: 'error', label: 'Errors' },
+  { value: 'warn', label: 'Warnings' },
+  { value: 'info', label: 'Info' },
+  { value: 'debug', label: 'Debug' },
+  { value: 'trace', label: 'Trace' },
+];
+
+// One line per entry, the way the log files read
+const formatEntry = (entry: LogEntry) => {
+  const time = new Date(entry.timestamp).toLocaleTimeString();
+  const spans = entry.spans.length > 0 ? ` ${entry.spans.join(':')}` : '';
+  return `${time} ${entry.level.padEnd(5)} ${entry.target}${spans}: ${entry.message}`;
+};
+
+const LogsDialog: React.FC = () => {
+  const [isOpen, setIsOpen] = useState(false);
+  const [problemsOnly, setProblemsOnly] = useState(false);
+  const [search, setSearch] = useState('');
+  const [copied, setCopied] = useState(false);
+  const [sessionLevel, setSessionLevel] = useState('settings');
+  const { entries, error, clearView, logLevel, changeLogLevel } = useLogs(isOpen);
+  const bottomRef = useRef<HTMLDivElement>(null);
+
+  const shown = useMemo(() => {
+    const query = search.trim().toLowerCase();
+    return entries.filter(entry =>
+      (!problemsOnly || entry.level === 'ERROR' || entry.level === 'WARN') &&
+      (!query || formatEntry(entry).toLowerCase().includes(query))
+    );
+  }, [entries, problemsOnly, search]);
+
+  // Follow new entries as they come in
+  useEffect(() => {
+    bottomRef.current?.scrollIntoView({ block: 'end' });
+  }, [shown.length]);
+
+  const onSessionLevelChange = (level: string) => {
+    setSessionLevel(level);
+    changeLogLevel(level === 'settings' ? '' : level);
+  };
+
+  const copyShown = async () => {
+    try {
+      await navigator.clipboard.writeText(shown.map(formatEntry).join('\n'));
+      setCopied(true);
+      setTimeout(() => setCopied(false), 2000);
+    } catch (err) {
+      console.error('Failed to copy logs:', err);
+    }
+  };
+
+  return (
+    <Dialog open={isOpen} onOpenChange={setIsOpen}>
+      <DialogTrigger asChild>
+        <Button variant="ghost" size="icon" className="h-8 w-8" title="Show logs">
+          <ScrollText className="h-4 w-4" />
+        </Button>
+      </DialogTrigger>
+      <DialogContent className="w-[900px] max-w-[90vw]">
+        <DialogHeader>
+          <DialogTitle>Logs</DialogTitle>
+          <DialogDescription>
+            What the app is doing, as it happens. Copy these when asking someone for help.
+          </DialogDescription>
+        </DialogHeader>
+        <div className="flex items-center gap-4">
+          <Input
+            value={search}
+            onChange={(e) => setSearch(e.target.value)}
+            placeholder="Filter logs"
+            className="h-8"
+          />
+          <div className="flex items-center gap-2 shrink-0">
+            <Switch id="logs-problems-only" checked={problemsOnly} onCheckedChange={setProblemsOnly} />
+            <Label htmlFor="logs-problems-only">Errors and warnings only</Label>
+          </div>
+        </div>
+        {error && <p className="text-sm text-destructive">{error}</p>}
+        <div className="h-[400px] overflow-auto rounded-md border bg-muted/30 p-2 font-mono text-xs">
+          {shown.length === 0 ? (
+            <p className="text-muted-foreground">No log entries</p>
+          ) : (
+            shown.map((entry, index) => (
+              <div key={`${entry.timestamp}-${index}`} className={`whitespace-pre-wrap break-all ${LEVEL_CLASSES[entry.level]}`}>
+                {formatEntry(entry)}
+              </div>
+            ))
+          )}
+          <div ref={bottomRef} />
+        </div>
+        <div className="flex items-center justify-between gap-2">
+          <div className="flex items-center gap-2">
+            <Select value={sessionLevel} onValueChange={onSessionLevelChange}>
+              <SelectTrigger className="h-8 w-[180px] text-foreground" title="Log level until the app closes">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {SESSION_LEVELS.map(level => (
+                  <SelectItem key={level.value} value={level.value} className="text-foreground">
+                    {level.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            {logLevel && <span className="text-xs text-muted-foreground font-mono">{logLevel}</span>}
+          </div>
+          <div className="flex gap-2">
+            <Button variant="secondary" size="sm" onClick={clearView}>
+              Clear View
+            </Button>
+            <Button size="sm" onClick={copyShown} disabled={shown.length === 0}>
+              {copied ? 'Copied' : 'Copy'}
+            </Button>
+          </div>
+        </div>
+      </DialogContent>
+    </Dialog>
+  );
+};
+
+export default LogsDialog;
@@ -14,6 +14,8 @@ interface ModCardProps {
   onUpdate: (modId: number) => void;
   onToggle: (modId: number) => void;
   onDelete?: (modId: number) => void;
+  onMigrate?: (modId: number) => void;
+  onSelectVariant?: (modId: number, variant: string) => void;
   className?: string;
 }
 
@@ -24,6 +26,8 @@ const ModCard: React.FC<ModCardProps> = ({
   onUpdate, 
   onToggle,
   onDelete,
+  onMigrate,
+  onSelectVariant,
   className = ""
 }) => {
   const [isExpanded, setIsExpanded] = useState(false);
@@ -76,6 +80,18 @@ const ModCard: React.FC<ModCardProps> = ({
     }
   };
 
+  const handleMigrate = () => {
+    if (onMigrate) {
+      onMigrate(mod.id);
+    }
+  };
+
+  const handleSelectVariant = (variant: string) => {
+    if (onSelectVariant) {
+      onSelectVariant(mod.id, variant);
+    }
+  };
+
   const handleCancel = () => {
     console.log('Cancelling download for mod:', mod.name);
     const filename = `${mod.name}.zip`;
@@ -116,6 +132,8 @@ const ModCard: React.FC<ModCardProps> = ({
           onDownload={handleDownload}
           onToggle={handleToggle}
           onDelete={onDelete ? handleDelete : undefined}
+          onMigrate={onMigrate ? handleMigrate : undefined}
+          onSelectVariant={onSelectVariant ? handleSelectVariant : undefined}
           onCancel={handleCancel}
           onExpandClick={() => setIsExpanded(!isExpanded)}
           isDownloading={isDownloading}
@@ -136,7 +154,7 @@ const ModCard: React.FC<ModCardProps> = ({
         />
       )}
       {isExpanded && (
-        <ModCardDescription description={mod.description} />
+        <ModCardDescription description={mod.description} descriptionHtml={mod.descriptionHtml} />
       )}
     </div>
   );
@@ -7,6 +7,7 @@ import {
   ArrowUpCircle,
   Download,
   Trash2,
+  ArrowRightLeft,
   X
 } from 'lucide-react';
 import {
@@ -25,6 +26,13 @@ import {
   AlertDialogHeader,
   AlertDialogTitle,
 } from "./ui/alert-dialog";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "./ui/select";
 import type { Mod, Profile } from '../types/types';
 
 interface ModCardActionsProps {
@@ -38,6 +46,8 @@ interface ModCardActionsProps {
   onDownload: () => void;
   onToggle: () => void;
   onDelete?: () => void;
+  onMigrate?: () => void;
+  onSelectVariant?: (variant: string) => void;
   onCancel?: () => void;
   onExpandClick: () => void;
 }
@@ -53,10 +63,14 @@ const ModCardActions: React.FC<ModCardActionsProps> = ({
   onDownload,
   onToggle,
   onDelete,
+  onMigrate,
+  onSelectVariant,
   onCancel,
   onExpandClick,
 }) => {
   const [showDeleteDialog, setShowDeleteDialog] = useState(false);
+  const [showInstallNotesDialog, setShowInstallNotesDialog] = useState(false);
+  const [showMigrateDialog, setShowMigrateDialog] = useState(false);
   const dcsPathSet = profile.dcs_path && profile.dcs_path.length > 0;
 
   // const getButtonStatus = () => {
@@ -69,6 +83,12 @@ const ModCardActions: React.FC<ModCardActionsProps> = ({
   // const buttonStatus = getButtonStatus();
   const showStatusButton = isDownloading || isExtracting || isQueued;
 
+  // The downloaded copy stays the variant it was until the chosen one is downloaded over it
+  const variants = mod.variants ?? [];
+  const variantLabel = (id?: string) => variants.find(v => v.id === id)?.name ?? id;
+  const switchesVariant = mod.isDownloaded && !!mod.installedVariant && !!mod.variant
+    && mod.variant !== mod.installedVariant;
+
   const handleDelete = () => {
     setShowDeleteDialog(false);
     if (onDelete) {
@@ -76,9 +96,46 @@ const ModCardActions: React.FC<ModCardActionsProps> = ({
     }
   };
 
+  // Mods with installation caveats are only enabled once the user has read them
+  const handleToggle = () => {
+    if (!mod.isEnabled && mod.installNotes) {
+      setShowInstallNotesDialog(true);
+    } else {
+      onToggle();
+    }
+  };
+
+  const handleConfirmEnable = () => {
+    setShowInstallNotesDialog(false);
+    onToggle();
+  };
+
+  const handleMigrate = () => {
+    setShowMigrateDialog(false);
+    if (onMigrate) {
+      onMigrate();
+    }
+  };
+
   return (
     <>
       <div className="flex items-center gap-2">
+        {/* Pick which variant downloads and updates fetch */}
+        {variants.length > 1 && onSelectVariant && !showStatusButton && (
+          <Select value={mod.variant} onValueChange={onSelectVariant}>
+            <SelectTrigger className="h-8 w-[140px] text-xs">
+              <SelectValue placeholder="Variant" />
+            </SelectTrigger>
+            <SelectContent>
+              {variants.map(variant => (
+                <SelectItem key={variant.id} value={variant.id}>
+                  {variant.name ?? variant.id}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        )}
+
         {/* Show download button if not downloaded or cancel button if processing */}
         {(!mod.isDownloaded && mod.url) ? (
           <Button
@@ -111,6 +168,18 @@ const ModCardActions: React.FC<ModCardActionsProps> = ({
           </Button>
         )}
 
+        {/* Download the chosen variant over the installed one */}
+        {switchesVariant && !mod.newVersion && !showStatusButton && (
+          <Button
+            size="sm"
+            variant="outline"
+            onClick={onUpdate}
+          >
+            <ArrowRightLeft className="h-4 w-4 mr-1" />
+            Switch to {variantLabel(mod.variant)}
+          </Button>
+        )}
+
         {/* Show cancel button during extraction when no other buttons are shown */}
         {isExtracting && !(!mod.isDownloaded && mod.url) && !(mod.isDownloaded && mod.newVersion) && (
           <Button
@@ -123,6 +192,18 @@ const ModCardActions: React.FC<ModCardActionsProps> = ({
           </Button>
         )}
 
+        {/* Offer the replacement the repository names for a deprecated mod */}
+        {mod.isDownloaded && mod.replacedBy && onMigrate && !isExtracting && !isDownloading && !isQueued && (
+          <Button
+            size="sm"
+            variant="outline"
+            onClick={() => setShowMigrateDialog(true)}
+          >
+            <ArrowRightLeft className="h-4 w-4 mr-1" />
+            Migrate
+          </Button>
+        )}
+
         {/* Show enable/disable toggle if mod is downloaded and not currently being processed */}
         {mod.isDownloaded && !isExtracting && !isDownloading && !isQueued && (
           <div className="flex items-center gap-2">
@@ -135,7 +216,7 @@ const ModCardActions: React.FC<ModCardActionsProps> = ({
             </span>
             <Switch
               checked={mod.isEnabled ?? false}
-              onCheckedChange={onToggle}
+              onCheckedChange={handleToggle}
               disabled={!dcsPathSet || isExtracting || isDownloading || isQueued}
             />
           </div>
@@ -179,6 +260,46 @@ const ModCardActions: React.FC<ModCardActionsProps> = ({
         </Button>
       </div>
 
+      {/* Installation notes confirmation dialog */}
+      <AlertDialog open={showInstallNotesDialog} onOpenChange={setShowInstallNotesDialog}>
+        <AlertDialogContent>
+          <AlertDialogHeader>
+            <AlertDialogTitle className="text-foreground">Before enabling {mod.name}</AlertDialogTitle>
+            <AlertDialogDescription className="text-muted-foreground whitespace-pre-wrap">
+              {mod.installNotes}
+            </AlertDialogDescription>
+          </AlertDialogHeader>
+          <AlertDialogFooter>
+            <AlertDialogCancel className="bg-background text-foreground hover:bg-muted">Cancel</AlertDialogCancel>
+            <AlertDialogAction onClick={handleConfirmEnable}>
+              Enable
+            </AlertDialogAction>
+          </AlertDialogFooter>
+        </AlertDialogContent>
+      </AlertDialog>
+
+      {/* Migrate to replacement confirmation dialog */}
+      <AlertDialog open={showMigrateDialog} onOpenChange={setShowMigrateDialog}>
+        <AlertDialogContent>
+          <AlertDialogHeader>
+            <AlertDialogTitle className="text-foreground">Migrate to {mod.replacedBy}</AlertDialogTitle>
+            <AlertDialogDescription className="text-muted-foreground">
+              {mod.name} is deprecated and replaced by {mod.replacedBy}.
+              {mod.isEnabled
+                ? ` ${mod.name} will be disabled and ${mod.replacedBy} enabled in its place, after downloading it if needed.`
+                : ` ${mod.replacedBy} will be downloaded if needed.`}
+              {' '}The old copy stays on disk until you delete it.
+            </AlertDialogDescription>
+          </AlertDialogHeader>
+          <AlertDialogFooter>
+            <AlertDialogCancel className="bg-background text-foreground hover:bg-muted">Cancel</AlertDialogCancel>
+            <AlertDialogAction onClick={handleMigrate}>
+              Migrate
+            </AlertDialogAction>
+          </AlertDialogFooter>
+        </AlertDialogContent>
+      </AlertDialog>
+
       {/* Delete confirmation dialog */}
       <AlertDialog open={showDeleteDialog} onOpenChange={setShowDeleteDialog}>
         <AlertDialogContent>
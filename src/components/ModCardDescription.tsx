@@ -1,10 +1,33 @@
 import React from 'react';
+import { open } from '@tauri-apps/plugin-shell';
 
 interface ModCardDescriptionProps {
   description: string;
+  descriptionHtml?: string;  // Sanitized by the backend, safe to insert
 }
 
-const ModCardDescription: React.FC<ModCardDescriptionProps> = ({ description }) => {
+const ModCardDescription: React.FC<ModCardDescriptionProps> = ({ description, descriptionHtml }) => {
+  // Links open in the browser instead of navigating the app window
+  const handleClick = (e: React.MouseEvent<HTMLDivElement>) => {
+    const link = (e.target as HTMLElement).closest('a');
+    if (link) {
+      e.preventDefault();
+      open(link.href);
+    }
+  };
+
+  if (descriptionHtml) {
+    return (
+      <div className="mt-2 pt-2 border-t border-border">
+        <div
+          className="text-sm bg-muted text-muted-foreground rounded p-2 space-y-2 [&_a]:underline [&_code]:font-mono [&_pre]:font-mono [&_pre]:whitespace-pre-wrap [&_ul]:list-disc [&_ol]:list-decimal [&_ul]:pl-5 [&_ol]:pl-5 [&_h1]:font-semibold [&_h2]:font-semibold [&_h3]:font-semibold"
+          onClick={handleClick}
+          dangerouslySetInnerHTML={{ __html: descriptionHtml }}
+        />
+      </div>
+    );
+  }
+
   return (
     <div className="mt-2 pt-2 border-t border-border">
       <div className="font-mono text-sm whitespace-pre-wrap bg-muted text-muted-foreground rounded p-2">
@@ -2,6 +2,8 @@ import React from 'react';
 import type { Mod } from '../types/types';
 import { AlertTriangle } from 'lucide-react';
 import { Tooltip, TooltipContent, TooltipProvider, TooltipTrigger } from "./ui/tooltip";
+import { formatBytes } from "../lib/utils";
+import { open } from '@tauri-apps/plugin-shell';
 
 interface ModCardHeaderProps {
   mod: Mod;
@@ -14,11 +16,53 @@ const ModCardHeader: React.FC<ModCardHeaderProps> = ({ mod }) => {
     <div className="flex-1">
       <div className="flex items-center gap-2">
         <h3 className="text-lg font-semibold">
-          {mod.name}
+          {mod.displayName ?? mod.name}
         </h3>
         <span className="text-sm text-muted-foreground">
           v{mod.version}
         </span>
+        {mod.installedVariant && (
+          <span className="text-xs bg-muted text-muted-foreground px-1.5 py-0.5 rounded">
+            {mod.variants?.find(v => v.id === mod.installedVariant)?.name ?? mod.installedVariant}
+          </span>
+        )}
+        {mod.size !== undefined && (
+          <span className="text-xs text-muted-foreground">
+            {formatBytes(mod.size)}
+          </span>
+        )}
+        {mod.enablingLock && (
+          <TooltipProvider>
+            <Tooltip>
+              <TooltipTrigger asChild>
+                <span className="text-xs font-semibold bg-yellow-500/20 text-yellow-700 dark:text-yellow-400 px-1.5 py-0.5 rounded">
+                  {mod.enablingLock.stale ? 'INTERRUPTED' : 'ENABLING'}
+                </span>
+              </TooltipTrigger>
+              <TooltipContent>
+                <p>
+                  {mod.enablingLock.stale
+                    ? 'Enabling this mod was interrupted, unlock it to roll back what was linked'
+                    : 'This mod is being enabled'}
+                </p>
+              </TooltipContent>
+            </Tooltip>
+          </TooltipProvider>
+        )}
+        {mod.dcsIncompatible && (
+          <TooltipProvider>
+            <Tooltip>
+              <TooltipTrigger asChild>
+                <span className="text-xs font-semibold bg-yellow-500/20 text-yellow-700 dark:text-yellow-400 px-1.5 py-0.5 rounded">
+                  DCS {mod.dcsVersion}
+                </span>
+              </TooltipTrigger>
+              <TooltipContent>
+                <p>Made for DCS {mod.dcsVersion}, it may not work with this profile's DCS installation</p>
+              </TooltipContent>
+            </Tooltip>
+          </TooltipProvider>
+        )}
         {isDeprecated && (
           <div className="flex items-center gap-1">
             <TooltipProvider>
@@ -27,7 +71,11 @@ const ModCardHeader: React.FC<ModCardHeaderProps> = ({ mod }) => {
                   <AlertTriangle className="h-4 w-4 text-yellow-500" />
                 </TooltipTrigger>
                 <TooltipContent>
-                  <p>This mod is deprecated (no longer in the repository)</p>
+                  <p>
+                    {mod.replacedBy
+                      ? `This mod is deprecated and replaced by ${mod.replacedBy}`
+                      : 'This mod is deprecated (no longer in the repository)'}
+                  </p>
                 </TooltipContent>
               </Tooltip>
             </TooltipProvider>
@@ -38,6 +86,35 @@ const ModCardHeader: React.FC<ModCardHeaderProps> = ({ mod }) => {
       <p className="text-sm text-muted-foreground">
         {mod.shortDescription}
       </p>
+      {mod.tags && mod.tags.length > 0 && (
+        <div className="flex flex-wrap gap-1 mt-1">
+          {mod.tags.map(tag => (
+            <span key={tag} className="text-xs bg-muted text-muted-foreground px-1.5 py-0.5 rounded">
+              {tag}
+            </span>
+          ))}
+        </div>
+      )}
+      {(mod.author || mod.license || mod.homepageUrl) && (
+        <p className="text-xs text-muted-foreground">
+          {[mod.author && `by ${mod.author}`, mod.license].filter(Boolean).join(' · ')}
+          {mod.homepageUrl && (
+            <>
+              {(mod.author || mod.license) && ' · '}
+              <button
+                type="button"
+                className="underline hover:text-foreground"
+                onClick={(e) => {
+                  e.stopPropagation();
+                  open(mod.homepageUrl!);
+                }}
+              >
+                Homepage
+              </button>
+            </>
+          )}
+        </p>
+      )}
     </div>
   );
 };
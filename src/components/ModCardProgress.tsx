@@ -1,6 +1,6 @@
 import React from 'react';
 import { Progress } from './ui/progress';
-import { cn } from "../lib/utils";
+import { cn, formatBytes } from "../lib/utils";
 
 interface ModCardProgressProps {
   modFileName?: string;
@@ -13,15 +13,6 @@ interface ModCardProgressProps {
   onComplete?: () => void;
 }
 
-// Helper function to format bytes to KB, MB, GB
-const formatBytes = (bytes: number): string => {
-  if (bytes === 0) return '0 B';
-  
-  const sizes = ['B', 'KB', 'MB', 'GB', 'TB'];
-  const i = Math.floor(Math.log(bytes) / Math.log(1024));
-  return `${(bytes / Math.pow(1024, i)).toFixed(2)} ${sizes[i]}`;
-};
-
 // Helper function to format download speed
 const formatSpeed = (bytesPerSecond: number): string => {
   if (bytesPerSecond === 0) return '';
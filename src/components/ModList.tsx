@@ -11,6 +11,8 @@ interface ModListProps {
   onUpdate: (modId: number) => void;
   onToggle: (modId: number) => void;
   onDelete?: (modId: number) => void;
+  onMigrate?: (modId: number) => void;
+  onSelectVariant?: (modId: number, variant: string) => void;
   className?: string;
 }
 
@@ -22,6 +24,8 @@ const ModList: React.FC<ModListProps> = ({
   onUpdate,
   onToggle,
   onDelete,
+  onMigrate,
+  onSelectVariant,
   className = "",
 }) => {
   if (mods.length === 0) return null;
@@ -39,6 +43,8 @@ const ModList: React.FC<ModListProps> = ({
             onUpdate={onUpdate}
             onToggle={onToggle}
             onDelete={onDelete}
+            onMigrate={onMigrate}
+            onSelectVariant={onSelectVariant}
             className={styles.modCard}
           />
         ))}
@@ -1,4 +1,5 @@
 import React, { useState, useEffect, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import ModManagerContent from './ModManagerContent';
 import ModManagerSidebar from './ModManagerSidebar';
 import ModManagerWrapper from './ModManagerWrapper';
@@ -10,6 +11,7 @@ import { useProfiles } from './hooks/useProfiles';
 import CriticalErrorBanner from './CriticalErrorBanner';
 import SettingsDialog from './SettingsDialog';
 import ProfileSettingsDialog from './ProfileSettingsDialog';
+import type { SelfCheckItem, SelfCheckReport } from '../types/types';
 
 const ModManager: React.FC = () => {
   const [searchQuery, setSearchQuery] = useState<string>("");
@@ -18,6 +20,7 @@ const ModManager: React.FC = () => {
   const settingsDialogRef = useRef<HTMLButtonElement>(null);
   const [newProfileIndex, setNewProfileIndex] = useState<number | null>(null);
   const [profileSettingsOpen, setProfileSettingsOpen] = useState(false);
+  const [selfCheckProblems, setSelfCheckProblems] = useState<SelfCheckItem[]>([]);
   
   // Get download state
   const downloadState = useDownloadState();
@@ -36,6 +39,8 @@ const ModManager: React.FC = () => {
     currentProfileIndex,
     setCurrentProfileIndex,
     modsError,
+    repoHeader,
+    parseWarnings,
   } = useModData(setGlobalError);
   
   const { 
@@ -48,6 +53,13 @@ const ModManager: React.FC = () => {
     loadSettings
   );
 
+  // Check paths, symlinks and repositories once at startup, so problems show before a download fails
+  useEffect(() => {
+    invoke<SelfCheckReport>('run_self_check')
+      .then(report => setSelfCheckProblems(report.checks.filter(item => item.status !== 'ok')))
+      .catch(error => console.error('Self-check failed:', error));
+  }, []);
+
   // Debug log for error state
   useEffect(() => {
     if (globalError) {
@@ -73,7 +85,9 @@ const ModManager: React.FC = () => {
     handleDownload, 
     handleUpdate, 
     handleToggle,
-    handleDelete
+    handleDelete,
+    handleMigrate,
+    handleSelectVariant
   } = useModActions(
     mods,
     setMods,
@@ -155,6 +169,26 @@ const ModManager: React.FC = () => {
     }
   };
 
+  const onMigrate = async (modId: number) => {
+    const mod = mods.find(m => m.id === modId);
+    try {
+      await handleMigrate(modId);
+      setGlobalError(null);
+    } catch (err) {
+      setGlobalError(formatDetailedError('Migrate', mod?.name, err));
+    }
+  };
+
+  const onSelectVariant = async (modId: number, variant: string) => {
+    const mod = mods.find(m => m.id === modId);
+    try {
+      await handleSelectVariant(modId, variant);
+      setGlobalError(null);
+    } catch (err) {
+      setGlobalError(formatDetailedError('Select variant', mod?.name, err));
+    }
+  };
+
   // Show critical error banner when there's a settings error
   if (!settings && globalError) {
     return (
@@ -231,12 +265,18 @@ const ModManager: React.FC = () => {
             selectedCategory={selectedCategory}
             filteredMods={filteredMods}
             error={showError ? modsError : null}
+            repoHeader={repoHeader}
+            parseWarnings={parseWarnings}
+            selfCheckProblems={selfCheckProblems}
+            onDismissSelfCheck={() => setSelfCheckProblems([])}
             loading={loading}
             onRefresh={() => loadMods(currentProfileIndex)}
             onDownload={onDownload}
             onUpdate={onUpdate}
             onToggle={onToggle}
             onDelete={onDelete}
+            onMigrate={onMigrate}
+            onSelectVariant={onSelectVariant}
             onAddProfile={createAndConfigureProfile}
             onOpenSettings={openSettingsDialog}
           />
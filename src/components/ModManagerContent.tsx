@@ -1,10 +1,13 @@
-import React from 'react';
+import React, { useState } from 'react';
 import ModList from './ModList';
 import ModManagerHeader from './ModManagerHeader';
 import LoadingState from './LoadingStates';
 import ErrorBanner from './ErrorBanner';
+import AnnouncementBanner from './AnnouncementBanner';
+import ParseWarningsBanner from './ParseWarningsBanner';
+import SelfCheckBanner from './SelfCheckBanner';
 import SetupWarning from './SetupWarning';
-import type { Mod, Profile, Settings } from '../types/types';
+import type { Mod, ParseWarning, Profile, RepoHeader, SelfCheckItem, Settings } from '../types/types';
 
 interface ModManagerContentProps {
   profile: Profile;
@@ -13,12 +16,18 @@ interface ModManagerContentProps {
   selectedCategory: string;
   filteredMods: Mod[];
   error: string | null;
+  repoHeader?: RepoHeader | null;
+  parseWarnings?: ParseWarning[];
+  selfCheckProblems?: SelfCheckItem[];
+  onDismissSelfCheck?: () => void;
   loading: boolean;
   onRefresh: () => void;
   onDownload: (modId: number) => void;
   onUpdate: (modId: number) => void;
   onToggle: (modId: number) => void;
   onDelete?: (modId: number) => void;
+  onMigrate?: (modId: number) => void;
+  onSelectVariant?: (modId: number, variant: string) => void;
   onAddProfile: () => Promise<number>;
   onOpenSettings: () => void;
 }
@@ -30,15 +39,24 @@ const ModManagerContent: React.FC<ModManagerContentProps> = ({
   selectedCategory,
   filteredMods,
   error,
+  repoHeader,
+  parseWarnings = [],
+  selfCheckProblems = [],
+  onDismissSelfCheck = () => {},
   loading,
   onRefresh,
   onDownload,
   onUpdate,
   onToggle,
   onDelete,
+  onMigrate,
+  onSelectVariant,
   onAddProfile,
   onOpenSettings,
 }) => {
+  // A dismissed announcement comes back when the maintainer posts a new one
+  const [dismissedMotd, setDismissedMotd] = useState<string | null>(null);
+  const showAnnouncement = !!repoHeader?.motd && repoHeader.motd !== dismissedMotd;
   const activeMods = filteredMods.filter(mod => mod.isEnabled);
   const inactiveMods = filteredMods.filter(mod => !mod.isEnabled);
 
@@ -70,6 +88,25 @@ const ModManagerContent: React.FC<ModManagerContentProps> = ({
           </div>
         )}
 
+        {!loading && showAnnouncement && repoHeader && (
+          <AnnouncementBanner
+            header={repoHeader}
+            onDismiss={() => setDismissedMotd(repoHeader.motd)}
+          />
+        )}
+
+        {selfCheckProblems.length > 0 && (
+          <SelfCheckBanner
+            problems={selfCheckProblems}
+            onOpenSettings={onOpenSettings}
+            onDismiss={onDismissSelfCheck}
+          />
+        )}
+
+        {!loading && parseWarnings.length > 0 && (
+          <ParseWarningsBanner warnings={parseWarnings} />
+        )}
+
         {!loading && settings.profiles.length === 0 && (
           <SetupWarning
             title="No Profile Configured"
@@ -99,6 +136,8 @@ const ModManagerContent: React.FC<ModManagerContentProps> = ({
                 onUpdate={onUpdate}
                 onToggle={onToggle}
                 onDelete={onDelete}
+                onMigrate={onMigrate}
+                onSelectVariant={onSelectVariant}
                 className="mb-4"
               />
             )}
@@ -111,6 +150,8 @@ const ModManagerContent: React.FC<ModManagerContentProps> = ({
                 onUpdate={onUpdate}
                 onToggle={onToggle}
                 onDelete={onDelete}
+                onMigrate={onMigrate}
+                onSelectVariant={onSelectVariant}
               />
             )}
           </>
@@ -2,6 +2,7 @@ import React from 'react';
 import { Button } from './ui/button';
 import { RefreshCw } from 'lucide-react';
 import SettingsDialog from './SettingsDialog';
+import LogsDialog from './LogsDialog';
 
 interface ModManagerHeaderProps {
   profileName: string;
@@ -30,6 +31,7 @@ const ModManagerHeader: React.FC<ModManagerHeaderProps> = ({
         >
           <RefreshCw className="h-4 w-4" />
         </Button>
+        <LogsDialog />
         <SettingsDialog onSaved={onRefresh} />
       </div>
     </div>
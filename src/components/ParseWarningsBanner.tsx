@@ -0,0 +1,48 @@
+import React, { useState } from 'react';
+import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert"
+import { AlertCircle } from "lucide-react";
+import type { ParseWarning } from '../types/types';
+
+interface ParseWarningsBannerProps {
+  warnings: ParseWarning[];
+  className?: string;
+}
+
+// Repository entries that couldn't be read and are missing from the list
+const ParseWarningsBanner: React.FC<ParseWarningsBannerProps> = ({
+  warnings,
+  className = ""
+}) => {
+  const [expanded, setExpanded] = useState(false);
+
+  return (
+    <Alert className={`mb-4 border-amber-500/50 ${className}`}>
+      <AlertCircle className="h-4 w-4 text-amber-500" />
+      <AlertTitle className="flex justify-between items-center">
+        {warnings.length === 1
+          ? '1 repository entry could not be read'
+          : `${warnings.length} repository entries could not be read`}
+        <button
+          onClick={() => setExpanded(!expanded)}
+          className="text-sm text-muted-foreground hover:text-foreground"
+        >
+          {expanded ? 'Hide details' : 'Details'}
+        </button>
+      </AlertTitle>
+      <AlertDescription className="mt-2">
+        These mods are left out of the list. Please let the repository maintainer know.
+        {expanded && (
+          <ul className="mt-2 space-y-1 text-xs font-mono">
+            {warnings.map((warning, index) => (
+              <li key={index}>
+                Line {warning.line}: {warning.element} {warning.reason}
+              </li>
+            ))}
+          </ul>
+        )}
+      </AlertDescription>
+    </Alert>
+  );
+};
+
+export default ParseWarningsBanner;
@@ -1,6 +1,7 @@
 import React, { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { Button } from './ui/button';
-import { Plus } from 'lucide-react';
+import { Copy, Plus } from 'lucide-react';
 import type { Profile } from '../types/types';
 import ProfileListItem from './ProfileListItem';
 import DeleteProfileDialog from './DeleteProfileDialog';
@@ -20,8 +21,10 @@ const ProfileList: React.FC<ProfileListProps> = ({
   refreshSettings,
 }) => {
   const [newProfileIndex, setNewProfileIndex] = useState<number | null>(null);
+  // Groups of profile indices that share one pool of downloads
+  const [linkedGroups, setLinkedGroups] = useState<number[][]>([]);
 
-  const { addNewProfile, deleteProfile, updateProfile } = useProfiles(
+  const { addNewProfile, cloneProfile, deleteProfile, updateProfile } = useProfiles(
     profiles,
     currentProfileIndex,
     setCurrentProfileIndex,
@@ -35,12 +38,33 @@ const ProfileList: React.FC<ProfileListProps> = ({
     }
   }, [profiles.length]);
 
+  useEffect(() => {
+    invoke<number[][]>('get_linked_profiles')
+      .then(setLinkedGroups)
+      .catch(error => console.error('Failed to load linked profiles:', error));
+  }, [profiles]);
+
+  const linkedProfileNames = (index: number) =>
+    (linkedGroups.find(group => group.includes(index)) ?? [])
+      .filter(other => other !== index && other < profiles.length)
+      .map(other => profiles[other].name);
+
   const handleNewProfile = async () => {
     const index = await addNewProfile();
     // Set the new profile index after the profile has been created and loaded
     setNewProfileIndex(index);
   };
 
+  const handleCloneProfile = async () => {
+    try {
+      const index = await cloneProfile();
+      // Open the copy's settings straight away, it usually needs its own name or DCS path
+      setNewProfileIndex(index);
+    } catch (error) {
+      console.error('Failed to copy profile:', error);
+    }
+  };
+
   const handleSettingsDialogOpenChange = (open: boolean) => {
     if (!open) {
       setNewProfileIndex(null);
@@ -67,6 +91,7 @@ const ProfileList: React.FC<ProfileListProps> = ({
             onSelect={setCurrentProfileIndex}
             onUpdateProfile={updateProfile}
             isNewProfile={index === newProfileIndex}
+            linkedProfiles={linkedProfileNames(index)}
             onSettingsDialogOpenChange={handleSettingsDialogOpenChange}
           />
         ))}
@@ -77,6 +102,11 @@ const ProfileList: React.FC<ProfileListProps> = ({
           <Plus className="h-4 w-4 mr-1" />
           New
         </Button>
+        {profiles.length > 0 && currentProfile && (
+          <Button variant="outline" onClick={handleCloneProfile} title="Copy profile">
+            <Copy className="h-4 w-4" />
+          </Button>
+        )}
         {profiles.length > 0 && currentProfile && (
           <DeleteProfileDialog
             profileName={currentProfile.name}
@@ -1,7 +1,8 @@
 import React from 'react';
 import { Button } from './ui/button';
+import { Link2 } from 'lucide-react';
 import ProfileSettingsDialog from './ProfileSettingsDialog';
-import type { Profile } from '../types/types';
+import { DCS_VARIANT_LABELS, type Profile } from '../types/types';
 
 interface ProfileListItemProps {
   profile: Profile;
@@ -10,6 +11,7 @@ interface ProfileListItemProps {
   onSelect: (index: number) => void;
   onUpdateProfile: (profile: Profile) => void;
   isNewProfile?: boolean;
+  linkedProfiles?: string[];  // Other profiles sharing this one's downloads
   onSettingsDialogOpenChange?: (open: boolean) => void;
 }
 
@@ -20,6 +22,7 @@ const ProfileListItem: React.FC<ProfileListItemProps> = ({
   onSelect,
   onUpdateProfile,
   isNewProfile,
+  linkedProfiles = [],
   onSettingsDialogOpenChange,
 }) => {
   return (
@@ -34,7 +37,17 @@ const ProfileListItem: React.FC<ProfileListItemProps> = ({
         onClick={() => onSelect(index)}
       >
         {profile.name}
+        {profile.dcs_variant && (
+          <span className="ml-2 text-xs text-muted-foreground">
+            {DCS_VARIANT_LABELS[profile.dcs_variant]}
+          </span>
+        )}
       </Button>
+      {linkedProfiles.length > 0 && (
+        <span title={`Shares downloads with ${linkedProfiles.join(', ')}`}>
+          <Link2 className="h-4 w-4 text-muted-foreground" />
+        </span>
+      )}
       <ProfileSettingsDialog
         profile={profile}
         onUpdateProfile={onUpdateProfile}
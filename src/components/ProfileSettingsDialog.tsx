@@ -10,8 +10,16 @@ import {
 } from './ui/dialog';
 import { Button } from './ui/button';
 import { Input } from './ui/input';
+import { Switch } from './ui/switch';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from './ui/select';
 import { Settings } from 'lucide-react';
-import { Profile } from '../types/types';
+import { DCS_VARIANT_LABELS, DcsVariant, Profile } from '../types/types';
 import FormField from './FormField';
 import DirectoryPicker from './DirectoryPicker';
 import { useProfileSettings } from './hooks/useProfileSettings';
@@ -34,7 +42,10 @@ const ProfileSettingsDialog: React.FC<ProfileSettingsDialogProps> = ({
   const { 
     settings, 
     setSettings, 
+    dcsPathError,
     handleDcsDirectoryChange,
+    handleDownloadDirectoryChange,
+    handleHookScriptChange,
     handleSave,
     handleCancel 
   } = useProfileSettings(profile, onUpdateProfile);
@@ -49,7 +60,13 @@ const ProfileSettingsDialog: React.FC<ProfileSettingsDialogProps> = ({
     setSettings({
       name: profile.name,
       dcs_path: profile.dcs_path,
-      repo_url: profile.repo_url
+      repo_url: profile.repo_url,
+      download_path: profile.download_path || '',
+      dcs_variant: profile.dcs_variant ?? null,
+      hook_script: profile.hook_script || '',
+      hook_on_enable: profile.hook_on_enable ?? false,
+      auto_enable: profile.auto_enable ?? false,
+      disable_lua_patching: profile.disable_lua_patching ?? false
     });
   }, [profile]);
 
@@ -120,13 +137,101 @@ const ProfileSettingsDialog: React.FC<ProfileSettingsDialogProps> = ({
               value={settings.dcs_path}
               onBrowse={handleDcsDirectoryChange}
             />
+            {dcsPathError && (
+              <p className="mt-1 text-sm text-destructive">{dcsPathError}</p>
+            )}
+          </FormField>
+          <FormField 
+            label="DCS Variant" 
+            htmlFor="dcs-variant"
+            tooltip="Which DCS this profile is for. Mods made for another variant are flagged, and profiles sharing downloads are listed together. Detected from the DCS path when left on automatic."
+          >
+            <Select
+              value={settings.dcs_variant ?? 'auto'}
+              onValueChange={value => setSettings(prev => ({
+                ...prev,
+                dcs_variant: value === 'auto' ? null : value as DcsVariant,
+              }))}
+            >
+              <SelectTrigger id="dcs-variant" className="w-[180px] text-foreground">
+                <SelectValue placeholder="Select variant" />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="auto" className="text-foreground">Automatic</SelectItem>
+                {(Object.keys(DCS_VARIANT_LABELS) as DcsVariant[]).map(variant => (
+                  <SelectItem key={variant} value={variant} className="text-foreground">
+                    {DCS_VARIANT_LABELS[variant]}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </FormField>
+          <FormField 
+            label="Download Path" 
+            htmlFor="profile-download-path"
+            tooltip="Where this profile's mods are downloaded, for example another drive for OpenBeta. Leave empty to use the download path from the app settings."
+          >
+            <DirectoryPicker
+              id="profile-download-path"
+              value={settings.download_path}
+              placeholder="Same as app settings"
+              onBrowse={handleDownloadDirectoryChange}
+              onClear={() => setSettings(prev => ({ ...prev, download_path: '' }))}
+            />
+          </FormField>
+          <FormField 
+            label="Enable After Download" 
+            htmlFor="auto-enable"
+            tooltip="Enable mods for this profile as soon as their download is extracted, so they are ready to fly without a second step."
+          >
+            <Switch
+              id="auto-enable"
+              checked={settings.auto_enable}
+              onCheckedChange={checked => setSettings(prev => ({ ...prev, auto_enable: checked }))}
+            />
+          </FormField>
+          <FormField 
+            label="No Lua Patching" 
+            htmlFor="disable-lua-patching"
+            tooltip="Never modify DCS lua files, for example to pass integrity checks. Mods that patch them are installed without those changes, and you are told which files were left out. Applies to mods enabled from now on."
+          >
+            <Switch
+              id="disable-lua-patching"
+              checked={settings.disable_lua_patching}
+              onCheckedChange={checked => setSettings(prev => ({ ...prev, disable_lua_patching: checked }))}
+            />
+          </FormField>
+          <FormField 
+            label="Hook Script" 
+            htmlFor="hook-script"
+            tooltip="Script run after one of this profile's mods is downloaded, for example to back it up or send a notification. It gets the event (installed or enabled), the mod name, the mod folder and the profile name as arguments."
+          >
+            <DirectoryPicker
+              id="hook-script"
+              value={settings.hook_script}
+              placeholder="No script"
+              onBrowse={handleHookScriptChange}
+              onClear={() => setSettings(prev => ({ ...prev, hook_script: '' }))}
+            />
+          </FormField>
+          <FormField 
+            label="Run Hook on Enable" 
+            htmlFor="hook-on-enable"
+            tooltip="Also run the hook script each time a mod is enabled for this profile."
+          >
+            <Switch
+              id="hook-on-enable"
+              checked={settings.hook_on_enable}
+              disabled={!settings.hook_script}
+              onCheckedChange={checked => setSettings(prev => ({ ...prev, hook_on_enable: checked }))}
+            />
           </FormField>
         </div>
         <DialogFooter>
           <Button type="button" variant="secondary" onClick={onCancel}>
             Cancel
           </Button>
-          <Button type="submit" onClick={onSave}>
+          <Button type="submit" onClick={onSave} disabled={!!dcsPathError}>
             Save
           </Button>
         </DialogFooter>
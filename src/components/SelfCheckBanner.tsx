@@ -0,0 +1,68 @@
+import React, { useState } from 'react';
+import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert"
+import { AlertCircle, X } from "lucide-react";
+import type { SelfCheckItem } from '../types/types';
+
+interface SelfCheckBannerProps {
+  problems: SelfCheckItem[];
+  onOpenSettings: () => void;
+  onDismiss: () => void;
+  className?: string;
+}
+
+// Problems the startup self-check found, with what to do about each
+const SelfCheckBanner: React.FC<SelfCheckBannerProps> = ({
+  problems,
+  onOpenSettings,
+  onDismiss,
+  className = ""
+}) => {
+  const [expanded, setExpanded] = useState(false);
+  const hasErrors = problems.some(problem => problem.status === 'error');
+  const color = hasErrors ? 'text-destructive' : 'text-amber-500';
+
+  return (
+    <Alert className={`mb-4 ${hasErrors ? 'border-destructive/50' : 'border-amber-500/50'} ${className}`}>
+      <AlertCircle className={`h-4 w-4 ${color}`} />
+      <AlertTitle className="flex justify-between items-center">
+        {problems.length === 1
+          ? '1 problem found at startup'
+          : `${problems.length} problems found at startup`}
+        <div className="flex items-center gap-3">
+          <button
+            onClick={() => setExpanded(!expanded)}
+            className="text-sm text-muted-foreground hover:text-foreground"
+          >
+            {expanded ? 'Hide details' : 'Details'}
+          </button>
+          <button onClick={onDismiss} className="text-muted-foreground hover:text-foreground">
+            <X className="h-4 w-4" />
+          </button>
+        </div>
+      </AlertTitle>
+      <AlertDescription className="mt-2">
+        {hasErrors
+          ? 'Some mods may not download or enable until these are fixed.'
+          : 'The app works, but something may go wrong later.'}{' '}
+        <button onClick={onOpenSettings} className="underline hover:text-foreground">
+          Open settings
+        </button>
+        {expanded && (
+          <ul className="mt-2 space-y-2 text-xs">
+            {problems.map((problem, index) => (
+              <li key={index}>
+                <div className={problem.status === 'error' ? 'text-destructive' : undefined}>
+                  {problem.profile && <span className="font-medium">{problem.profile}: </span>}
+                  {problem.message}
+                </div>
+                {problem.hint && <div className="opacity-70">{problem.hint}</div>}
+              </li>
+            ))}
+          </ul>
+        )}
+      </AlertDescription>
+    </Alert>
+  );
+};
+
+export default SelfCheckBanner;
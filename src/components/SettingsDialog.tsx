@@ -27,6 +27,24 @@ const SettingsDialog = React.forwardRef<HTMLButtonElement, SettingsDialogProps>(
     loadSettings,
     handleDownloadPathChange,
     handleSideloadPathChange,
+    handleStagingPathChange,
+    handleStagingPathClear,
+    handleCloseToTrayChange,
+    handleMirrorRegionChange,
+    handleLanguageChange,
+    handleMaxConcurrentDownloadsChange,
+    handleMaxDownloadSpeedChange,
+    handleProxyChange,
+    handleAutoRefreshChange,
+    handleEnabledMarkersChange,
+    handleCacheTtlChange,
+    handleCacheSizeChange,
+    cacheUsageMessage,
+    handleClearXmlCache,
+    xmlCacheMessage,
+    handlePurgeAssetCache,
+    assetCacheMessage,
+    handleLogLevelChange,
     handleSave,
     handleCancel
   } = useSettings(onSaved);
@@ -71,6 +89,36 @@ const SettingsDialog = React.forwardRef<HTMLButtonElement, SettingsDialogProps>(
             sideloadPath={settings.sideload_path}
             onDownloadPathChange={handleDownloadPathChange}
             onSideloadPathChange={handleSideloadPathChange}
+            stagingPath={settings.staging_path}
+            onStagingPathChange={handleStagingPathChange}
+            onStagingPathClear={handleStagingPathClear}
+            closeToTray={settings.close_to_tray}
+            onCloseToTrayChange={handleCloseToTrayChange}
+            mirrorRegion={settings.mirror_region}
+            onMirrorRegionChange={handleMirrorRegionChange}
+            language={settings.language}
+            onLanguageChange={handleLanguageChange}
+            maxConcurrentDownloads={settings.max_concurrent_downloads}
+            onMaxConcurrentDownloadsChange={handleMaxConcurrentDownloadsChange}
+            maxDownloadSpeedKbps={settings.max_download_speed_kbps}
+            onMaxDownloadSpeedChange={handleMaxDownloadSpeedChange}
+            proxy={settings.proxy}
+            onProxyChange={handleProxyChange}
+            autoRefreshMinutes={settings.auto_refresh_minutes}
+            onAutoRefreshChange={handleAutoRefreshChange}
+            enabledMarkers={settings.enabled_markers}
+            onEnabledMarkersChange={handleEnabledMarkersChange}
+            cacheTtlHours={settings.cache_ttl_hours}
+            onCacheTtlChange={handleCacheTtlChange}
+            cacheSizeMb={settings.cache_size_mb}
+            onCacheSizeChange={handleCacheSizeChange}
+            cacheUsageMessage={cacheUsageMessage}
+            onClearXmlCache={handleClearXmlCache}
+            xmlCacheMessage={xmlCacheMessage}
+            onPurgeAssetCache={handlePurgeAssetCache}
+            assetCacheMessage={assetCacheMessage}
+            logLevel={settings.log_level}
+            onLogLevelChange={handleLogLevelChange}
           />
         </div>
         <div className="flex flex-col-reverse sm:flex-row sm:justify-between sm:items-center">
@@ -2,12 +2,63 @@ import React from 'react';
 import { ThemeToggle } from './theme/theme-toggle';
 import FormField from './FormField';
 import DirectoryPicker from './DirectoryPicker';
+import { Switch } from './ui/switch';
+import { Button } from './ui/button';
+import { Input } from './ui/input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from './ui/select';
+import { KEYRING_REFERENCE_PREFIX, ProxySettings } from '../types/types';
+
+// Background update check intervals offered, in minutes
+const AUTO_REFRESH_OPTIONS = [
+  { minutes: 0, label: 'Manual only' },
+  { minutes: 15, label: 'Every 15 minutes' },
+  { minutes: 30, label: 'Every 30 minutes' },
+  { minutes: 60, label: 'Every hour' },
+  { minutes: 180, label: 'Every 3 hours' },
+  { minutes: 720, label: 'Every 12 hours' },
+];
 
 interface SettingsFormProps {
   downloadPath: string;
   sideloadPath: string;
   onDownloadPathChange: () => void;
   onSideloadPathChange: () => void;
+  stagingPath: string;
+  onStagingPathChange: () => void;
+  onStagingPathClear: () => void;
+  closeToTray: boolean;
+  onCloseToTrayChange: (closeToTray: boolean) => void;
+  mirrorRegion: string;
+  onMirrorRegionChange: (mirrorRegion: string) => void;
+  language: string;
+  onLanguageChange: (language: string) => void;
+  maxConcurrentDownloads: number;
+  onMaxConcurrentDownloadsChange: (maxConcurrentDownloads: number) => void;
+  maxDownloadSpeedKbps: number;
+  onMaxDownloadSpeedChange: (maxDownloadSpeedKbps: number) => void;
+  proxy: ProxySettings;
+  onProxyChange: (proxy: Partial<ProxySettings>) => void;
+  autoRefreshMinutes: number;
+  onAutoRefreshChange: (autoRefreshMinutes: number) => void;
+  enabledMarkers: boolean;
+  onEnabledMarkersChange: (enabledMarkers: boolean) => void;
+  cacheTtlHours: number;
+  onCacheTtlChange: (cacheTtlHours: number) => void;
+  cacheSizeMb: number;
+  onCacheSizeChange: (cacheSizeMb: number) => void;
+  cacheUsageMessage: string | null;
+  onClearXmlCache: () => void;
+  xmlCacheMessage: string | null;
+  onPurgeAssetCache: () => void;
+  assetCacheMessage: string | null;
+  logLevel: string;
+  onLogLevelChange: (logLevel: string) => void;
 }
 
 const SettingsForm: React.FC<SettingsFormProps> = ({
@@ -15,7 +66,40 @@ const SettingsForm: React.FC<SettingsFormProps> = ({
   sideloadPath,
   onDownloadPathChange,
   onSideloadPathChange,
+  stagingPath,
+  onStagingPathChange,
+  onStagingPathClear,
+  closeToTray,
+  onCloseToTrayChange,
+  mirrorRegion,
+  onMirrorRegionChange,
+  language,
+  onLanguageChange,
+  maxConcurrentDownloads,
+  onMaxConcurrentDownloadsChange,
+  maxDownloadSpeedKbps,
+  onMaxDownloadSpeedChange,
+  proxy,
+  onProxyChange,
+  autoRefreshMinutes,
+  onAutoRefreshChange,
+  enabledMarkers,
+  onEnabledMarkersChange,
+  cacheTtlHours,
+  onCacheTtlChange,
+  cacheSizeMb,
+  onCacheSizeChange,
+  cacheUsageMessage,
+  onClearXmlCache,
+  xmlCacheMessage,
+  onPurgeAssetCache,
+  assetCacheMessage,
+  logLevel,
+  onLogLevelChange,
 }) => {
+  // The saved password isn't shown, typing replaces it
+  const passwordSaved = proxy.password.startsWith(KEYRING_REFERENCE_PREFIX);
+
   return (
     <div className="grid gap-4 py-4">
       <FormField 
@@ -49,6 +133,277 @@ const SettingsForm: React.FC<SettingsFormProps> = ({
           onBrowse={onSideloadPathChange}
         />
       </FormField>
+
+      <FormField 
+        label="Staging Path" 
+        htmlFor="staging-path"
+        tooltip="Directory where downloads are saved and unpacked before being moved into the download path. A fast local drive helps when the download path is on a network share. Leave empty to work in the download path."
+      >
+        <DirectoryPicker
+          id="staging-path"
+          value={stagingPath}
+          placeholder="Same as download path"
+          onBrowse={onStagingPathChange}
+          onClear={onStagingPathClear}
+        />
+      </FormField>
+
+      <FormField 
+        label="Close to Tray" 
+        htmlFor="close-to-tray"
+        tooltip="Keep running in the system tray when the window is closed, so downloads can finish in the background."
+      >
+        <Switch
+          id="close-to-tray"
+          checked={closeToTray}
+          onCheckedChange={onCloseToTrayChange}
+        />
+      </FormField>
+
+      <FormField 
+        label="Mirror Region" 
+        htmlFor="mirror-region"
+        tooltip="Region code of the download mirrors to try first, when a repository offers several. Leave empty to follow the repository's order."
+      >
+        <Input
+          id="mirror-region"
+          value={mirrorRegion}
+          placeholder="e.g. EU"
+          onChange={e => onMirrorRegionChange(e.target.value)}
+          className="text-foreground"
+        />
+      </FormField>
+
+      <FormField 
+        label="Language" 
+        htmlFor="language"
+        tooltip="Language code for mod names and descriptions, when the repository translates them. Leave empty to follow the system language. Takes effect the next time mods are loaded."
+      >
+        <Input
+          id="language"
+          value={language}
+          placeholder="e.g. de or fr-CA"
+          onChange={e => onLanguageChange(e.target.value)}
+          className="text-foreground"
+        />
+      </FormField>
+
+      <FormField 
+        label="Parallel Downloads" 
+        htmlFor="max-concurrent-downloads"
+        tooltip="How many mods download at the same time, from 1 to 8. Changes apply to the download queue right away."
+      >
+        <Input
+          id="max-concurrent-downloads"
+          type="number"
+          min={1}
+          max={8}
+          value={maxConcurrentDownloads}
+          onChange={e => onMaxConcurrentDownloadsChange(Math.min(8, Math.max(1, Number(e.target.value) || 1)))}
+          className="text-foreground w-24"
+        />
+      </FormField>
+
+      <FormField 
+        label="Speed Limit (KB/s)" 
+        htmlFor="max-download-speed"
+        tooltip="Combined download speed of all mods in kilobytes per second, 0 for no limit. Running downloads follow changes right away."
+      >
+        <Input
+          id="max-download-speed"
+          type="number"
+          min={0}
+          value={maxDownloadSpeedKbps}
+          onChange={e => onMaxDownloadSpeedChange(Math.max(0, Math.floor(Number(e.target.value) || 0)))}
+          className="text-foreground w-24"
+        />
+      </FormField>
+
+      <FormField 
+        label="Check for Updates" 
+        htmlFor="auto-refresh"
+        tooltip="How often the repositories are checked for mod updates in the background. With Manual only, updates show up when the mods are reloaded."
+      >
+        <Select
+          value={String(autoRefreshMinutes)}
+          onValueChange={minutes => onAutoRefreshChange(Number(minutes))}
+        >
+          <SelectTrigger id="auto-refresh" className="w-[180px] text-foreground">
+            <SelectValue placeholder="Select interval" />
+          </SelectTrigger>
+          <SelectContent>
+            {AUTO_REFRESH_OPTIONS.some(option => option.minutes === autoRefreshMinutes) || (
+              <SelectItem value={String(autoRefreshMinutes)} className="text-foreground">
+                Every {autoRefreshMinutes} minutes
+              </SelectItem>
+            )}
+            {AUTO_REFRESH_OPTIONS.map(option => (
+              <SelectItem key={option.minutes} value={String(option.minutes)} className="text-foreground">
+                {option.label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </FormField>
+
+      <FormField 
+        label="Cache Expiry (hours)" 
+        htmlFor="cache-ttl"
+        tooltip="When the repository can't be reached, a cached mod list older than this is marked as out of date. 0 never marks it."
+      >
+        <Input
+          id="cache-ttl"
+          type="number"
+          min={0}
+          max={8760}
+          value={cacheTtlHours}
+          onChange={e => onCacheTtlChange(Math.max(0, Math.floor(Number(e.target.value) || 0)))}
+          className="text-foreground w-24"
+        />
+      </FormField>
+
+      <FormField 
+        label="Cache Size (MB)" 
+        htmlFor="cache-size"
+        tooltip="Largest size of the cache of mod lists, their earlier copies, changelogs and images. The files used least recently are removed beyond it, the mod lists of your profiles are always kept. 0 for no limit."
+      >
+        <div className="flex items-center gap-3">
+          <Input
+            id="cache-size"
+            type="number"
+            min={0}
+            max={1048576}
+            value={cacheSizeMb}
+            onChange={e => onCacheSizeChange(Math.max(0, Math.floor(Number(e.target.value) || 0)))}
+            className="text-foreground w-24"
+          />
+          {cacheUsageMessage && (
+            <span className="text-sm text-muted-foreground">{cacheUsageMessage}</span>
+          )}
+        </div>
+      </FormField>
+
+      <FormField 
+        label="Repository Cache" 
+        htmlFor="clear-xml-cache"
+        tooltip="Remove the cached mod lists of all repositories, so they are downloaded again the next time mods load. Useful when a repository was fixed but the app still shows the old list."
+      >
+        <div className="flex items-center gap-3">
+          <Button id="clear-xml-cache" type="button" variant="secondary" onClick={onClearXmlCache}>
+            Clear Cache
+          </Button>
+          {xmlCacheMessage && (
+            <span className="text-sm text-muted-foreground">{xmlCacheMessage}</span>
+          )}
+        </div>
+      </FormField>
+
+      <FormField 
+        label="Asset Cache" 
+        htmlFor="purge-asset-cache"
+        tooltip="Changelogs, icons and screenshots are kept on disk within the cache size. Clearing it frees the space, they are downloaded again when needed."
+      >
+        <div className="flex items-center gap-3">
+          <Button id="purge-asset-cache" type="button" variant="secondary" onClick={onPurgeAssetCache}>
+            Clear Cache
+          </Button>
+          {assetCacheMessage && (
+            <span className="text-sm text-muted-foreground">{assetCacheMessage}</span>
+          )}
+        </div>
+      </FormField>
+
+      <FormField 
+        label="Marker Files" 
+        htmlFor="enabled-markers"
+        tooltip="Also write an ENABLED file into each enabled mod's folder, for older versions of the app and other tools. The app itself keeps track of enabled mods either way."
+      >
+        <Switch
+          id="enabled-markers"
+          checked={enabledMarkers}
+          onCheckedChange={onEnabledMarkersChange}
+        />
+      </FormField>
+
+      <FormField 
+        label="Log Level" 
+        htmlFor="log-level"
+        tooltip="How much the app writes to its logs: error, warn, info, debug or trace, optionally per module like info,bzmm::mods::downloader=debug. Leave empty for info. Applies as soon as you save. The Logs panel can also raise it until the app closes."
+      >
+        <Input
+          id="log-level"
+          value={logLevel}
+          placeholder="info"
+          onChange={e => onLogLevelChange(e.target.value)}
+          className="text-foreground"
+        />
+      </FormField>
+
+      <FormField 
+        label="Proxy" 
+        htmlFor="proxy-mode"
+        tooltip="How the app reaches repositories and downloads: through the system's proxy configuration, a proxy you enter, or directly."
+      >
+        <Select
+          value={proxy.mode}
+          onValueChange={mode => onProxyChange({ mode: mode as ProxySettings['mode'] })}
+        >
+          <SelectTrigger id="proxy-mode" className="w-[180px] text-foreground">
+            <SelectValue placeholder="Select proxy" />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value="System" className="text-foreground">System</SelectItem>
+            <SelectItem value="Manual" className="text-foreground">Manual</SelectItem>
+            <SelectItem value="None" className="text-foreground">None</SelectItem>
+          </SelectContent>
+        </Select>
+      </FormField>
+
+      {proxy.mode === 'Manual' && (
+        <>
+          <FormField 
+            label="Proxy URL" 
+            htmlFor="proxy-url"
+            tooltip="Address of the proxy, including the scheme and port."
+          >
+            <Input
+              id="proxy-url"
+              value={proxy.url}
+              placeholder="e.g. http://proxy.local:3128"
+              onChange={e => onProxyChange({ url: e.target.value })}
+              className="text-foreground"
+            />
+          </FormField>
+
+          <FormField 
+            label="Proxy Username" 
+            htmlFor="proxy-username"
+            tooltip="Leave empty when the proxy doesn't ask for credentials."
+          >
+            <Input
+              id="proxy-username"
+              value={proxy.username}
+              onChange={e => onProxyChange({ username: e.target.value })}
+              className="text-foreground"
+            />
+          </FormField>
+
+          <FormField 
+            label="Proxy Password" 
+            htmlFor="proxy-password"
+            tooltip="Stored in the system keyring, the settings file only refers to it. Type a new one to replace it."
+          >
+            <Input
+              id="proxy-password"
+              type="password"
+              value={passwordSaved ? '' : proxy.password}
+              placeholder={passwordSaved ? 'Saved in the system keyring' : undefined}
+              onChange={e => onProxyChange({ password: e.target.value })}
+              className="text-foreground"
+            />
+          </FormField>
+        </>
+      )}
     </div>
   );
 };
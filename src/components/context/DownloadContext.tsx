@@ -170,7 +170,8 @@ function downloadReducer(state: DownloadState, action: Action): DownloadState {
 interface DownloadContextType {
   state: DownloadState;
   dispatch: React.Dispatch<Action>;
-  startDownload: (modName: string, url: string, repoUrl: string) => void;
+  // Without `enable` the profile's auto-enable setting decides whether the mod gets enabled
+  startDownload: (modName: string, url: string, repoUrl: string, size?: number, enable?: boolean, profileName?: string) => void;
   cancelDownload: (modName: string) => void;
 }
 
@@ -180,7 +181,7 @@ export function DownloadProvider({ children }: { children: React.ReactNode }) {
   const [state, dispatch] = useReducer(downloadReducer, initialState);
   const modUrlMap = React.useRef(new Map<string, ModDownloadInfo>());
 
-  const startDownload = useCallback(async (modName: string, url: string, repoUrl: string) => {
+  const startDownload = useCallback(async (modName: string, url: string, repoUrl: string, size?: number, enable?: boolean, profileName?: string) => {
     console.log(`Attempting to queue download for ${modName} from ${url}`);
 
     // Check if already downloading or queued
@@ -200,7 +201,10 @@ export function DownloadProvider({ children }: { children: React.ReactNode }) {
       await invoke('queue_download', {
         url,
         filename: modName,
-        repoUrl
+        repoUrl,
+        size: size ?? null,
+        enable: enable ?? null,
+        profileName: profileName ?? null
       });
       console.log(`Successfully queued download for ${modName}`);
     } catch (error) {
@@ -12,7 +12,7 @@ export function useCategoryActions(repoUrl: string) {
     modsToDownload.forEach(mod => {
       if (mod.url) {
         const filename = `${mod.name}.zip`;
-        startDownload(filename, mod.url, repoUrl);
+        startDownload(filename, mod.url, repoUrl, mod.size);
       }
     });
 
@@ -28,7 +28,7 @@ export function useCategoryActions(repoUrl: string) {
     modsToUpdate.forEach(mod => {
       if (mod.url) {
         const filename = `${mod.name}.zip`;
-        startDownload(filename, mod.url, repoUrl);
+        startDownload(filename, mod.url, repoUrl, mod.size);
       }
     });
 
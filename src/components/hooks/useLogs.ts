@@ -0,0 +1,55 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { LogEntry } from '../../types/types';
+
+// Entries the panel holds, matching what the backend keeps in memory
+const MAX_ENTRIES = 1000;
+
+// Recent backend log entries, followed live through log-event while the panel is open
+export function useLogs(active: boolean) {
+  const [entries, setEntries] = useState<LogEntry[]>([]);
+  const [error, setError] = useState<string | null>(null);
+  const [logLevel, setLogLevel] = useState('');
+
+  const loadLogs = useCallback(async () => {
+    try {
+      setEntries(await invoke<LogEntry[]>('get_recent_logs', { limit: MAX_ENTRIES }));
+      setLogLevel(await invoke<string>('get_log_level'));
+      setError(null);
+    } catch (err) {
+      setError(`Failed to load logs: ${err}`);
+      console.error(err);
+    }
+  }, []);
+
+  useEffect(() => {
+    if (!active) {
+      return;
+    }
+    loadLogs();
+
+    const unlisten = listen<LogEntry>('log-event', (event) => {
+      setEntries(prev => [...prev.slice(-(MAX_ENTRIES - 1)), event.payload]);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [active, loadLogs]);
+
+  const clearView = useCallback(() => setEntries([]), []);
+
+  // Until the app closes, an empty level goes back to the one in the settings
+  const changeLogLevel = useCallback(async (level: string) => {
+    try {
+      setLogLevel(await invoke<string>('set_log_level', { level }));
+      setError(null);
+    } catch (err) {
+      setError(`Failed to change the log level: ${err}`);
+      console.error(err);
+    }
+  }, []);
+
+  return { entries, error, clearView, logLevel, changeLogLevel };
+}
@@ -1,7 +1,7 @@
 import { useEffect } from 'react';
-import type { Mod } from '../../types/types';
+import type { EnabledModEntry, Mod } from '../../types/types';
 import { invoke } from '@tauri-apps/api/core';
-import { Settings } from '../../types/types';
+import { Settings, enabledKey } from '../../types/types';
 import { listen } from '@tauri-apps/api/event';
 import { useDownloadContext } from '../context/DownloadContext';
 
@@ -50,7 +50,8 @@ export function useModActions(
     };
   }, []);
 
-  const handleDownload = async (modId: number) => {
+  // `enable` overrides the profile's auto-enable setting for this download
+  const handleDownload = async (modId: number, enable?: boolean) => {
     const mod = mods.find(m => m.id === modId);
     if (!mod) return;
 
@@ -71,7 +72,7 @@ export function useModActions(
       const repoUrl = currentProfile.repo_url || ""; // Use empty string as fallback
       
       // Start the download directly
-      startDownload(filename, mod.url, repoUrl);
+      startDownload(filename, mod.url, repoUrl, mod.size, enable, currentProfile.name);
       
       // We'll update the download state when we receive events from backend
     } catch (error) {
@@ -104,7 +105,7 @@ export function useModActions(
       // Start the download for the update directly
       console.log(`Starting update for ${mod.name} from URL: ${mod.url}`);
       const repoUrl = currentProfile.repo_url || ""; // Use empty string as fallback
-      startDownload(filename, mod.url, repoUrl);
+      startDownload(filename, mod.url, repoUrl, mod.size);
 
       // Listen for download completion to refresh the mod list
       const unlistenComplete = await listen('download-complete', async () => {
@@ -140,12 +141,14 @@ export function useModActions(
       if (mod.isEnabled) {
         await invoke('disable_mod', { 
           modName: mod.name,
-          profileName: currentProfile.name
+          profileName: currentProfile.name,
+          source: mod.source
         });
       } else {
         await invoke('enable_mod', { 
           modName: mod.name,
-          profileName: currentProfile.name
+          profileName: currentProfile.name,
+          source: mod.source
         });
       }
       
@@ -170,7 +173,8 @@ export function useModActions(
     try {
       await invoke('delete_mod', { 
         modName: mod.name,
-        profileName: currentProfile.name
+        profileName: currentProfile.name,
+        source: mod.source
       });
       
       // Update downloaded mods set immediately for quick UI feedback
@@ -199,9 +203,10 @@ export function useModActions(
         }));
         
         // Get enabled mods
-        const enabledMods = new Set(await invoke<string[]>('get_enabled_mods', { 
+        const enabledEntries = await invoke<EnabledModEntry[]>('get_enabled_mods', { 
           profileName: currentProfile.name 
-        }));
+        });
+        const enabledMods = new Set(enabledEntries.map(entry => enabledKey(entry.source, entry.name)));
         
         // Transform mods with updated state
         const transformedMods = modsResult.categories.flatMap((category: any, categoryIndex: number) => {
@@ -212,16 +217,44 @@ export function useModActions(
           return category.mods.map((mod: any, modIndex: number) => ({
             id: categoryIndex * 1000 + modIndex,
             name: mod.name,
+            displayName: mod.displayName || undefined,
             category: category.name || 'Uncategorized',
             version: mod.version || '0.0.0',
             newVersion: mod.newVersion || undefined,
+            downgradeVersion: mod.downgradeVersion || undefined,
             url: mod.url || null,
             filename: mod.name ? `${mod.name}.zip` : null,
             shortDescription: mod.description?.split('\n')[0] || '',
             description: mod.description || '',
+            descriptionHtml: mod.descriptionHtml || undefined,
+            changelogUrl: mod.changelogUrl || undefined,
+            iconUrl: mod.iconUrl || undefined,
+            screenshotUrl: mod.screenshotUrl || undefined,
+            author: mod.author || undefined,
+            homepageUrl: mod.homepageUrl || undefined,
+            license: mod.license || undefined,
+            size: mod.size ?? undefined,
+            mirrors: mod.mirrors || [],
+            dcsVersion: mod.dcsVersion || undefined,
+            dcsIncompatible: mod.dcsIncompatible || false,
+            deprecated: mod.deprecated || false,
+            replacedBy: mod.replacedBy || undefined,
+            tags: mod.tags || [],
+            installNotes: mod.installNotes || undefined,
             isDownloaded: downloadedModsSet.has(mod.name) || downloadedModsSet.has(mod.name + '.zip'),
-            isEnabled: enabledMods.has(mod.name),
+            isEnabled: enabledMods.has(enabledKey(mod.source, mod.name)),
             sort_order: category.sort_order || 0,
+            categoryDescription: category.description || undefined,
+            origin: mod.origin || undefined,
+            source: mod.source || undefined,
+            isFavorite: mod.favorite || false,
+            note: mod.note || undefined,
+            sinceLastVisit: mod.sinceLastVisit || undefined,
+            requires: mod.requires || [],
+            variants: mod.variants || [],
+            variant: mod.variant || undefined,
+            installedVariant: mod.installedVariant || undefined,
+            enablingLock: mod.enablingLock || undefined,
           }));
         });
         
@@ -234,11 +267,59 @@ export function useModActions(
     }
   };
 
+  const handleMigrate = async (modId: number) => {
+    const mod = mods.find(m => m.id === modId);
+    if (!mod || !settings) return;
+
+    const currentProfile = settings.profiles[currentProfileIndex];
+    if (!currentProfile) {
+      throw new Error('No profile selected');
+    }
+
+    try {
+      // Disables the deprecated mod and enables or queues its replacement
+      await invoke('migrate_deprecated_mod', {
+        profileName: currentProfile.name,
+        modName: mod.name
+      });
+
+      if (loadMods) {
+        await loadMods(currentProfileIndex);
+      }
+    } catch (error) {
+      console.error('Failed to migrate mod:', error);
+      throw error;
+    }
+  };
+
+  const handleSelectVariant = async (modId: number, variant: string) => {
+    const mod = mods.find(m => m.id === modId);
+    if (!mod) return;
+
+    try {
+      // Only remembered here, a downloaded mod switches once it is updated
+      await invoke('set_mod_variant', {
+        modName: mod.name,
+        source: mod.source,
+        variant
+      });
+
+      if (loadMods) {
+        await loadMods(currentProfileIndex);
+      }
+    } catch (error) {
+      console.error('Failed to select mod variant:', error);
+      throw error;
+    }
+  };
+
   return {
     handleDownload,
     handleUpdate,
     handleToggle,
-    handleDelete
+    handleDelete,
+    handleMigrate,
+    handleSelectVariant
   };
 }
 
@@ -1,12 +1,59 @@
-import { useState, useEffect } from 'react';
+import { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import type { Mod, Settings } from '../../types/types';
+import { listen } from '@tauri-apps/api/event';
+import type { ActiveProfile, EnabledModEntry, Mod, ParseWarning, RepoHeader, Settings } from '../../types/types';
+import { enabledKey } from '../../types/types';
 
 const LOADING_DELAY = 500; // ms before showing loading state
 
+interface ModsSummary {
+  total: number;
+  downloaded: number;
+  enabled: number;
+  updatable: number;
+  deprecated: number;
+}
+
 interface ModsResult {
   categories: any[];
   error: string | null;
+  summary: ModsSummary;
+  app_update_required: string | null;  // BZMM version the repository needs when this one is older
+  header: RepoHeader | null;
+  warnings?: ParseWarning[];  // Entries left out because they couldn't be parsed
+  fetched_at: number | null;  // Seconds since the epoch the listing was fetched from the repository
+  from_cache: boolean;
+  cache_age_secs: number | null;
+  stale: boolean;  // Cached and older than the cache expiry setting
+}
+
+// "3 days ago", "5 hours ago", ...
+function formatAge(seconds: number): string {
+  const units: [number, string][] = [[86400, 'day'], [3600, 'hour'], [60, 'minute']];
+  for (const [size, unit] of units) {
+    const count = Math.floor(seconds / size);
+    if (count >= 1) {
+      return `${count} ${unit}${count === 1 ? '' : 's'} ago`;
+    }
+  }
+  return 'just now';
+}
+
+// An outdated app matters more than a connection problem, the listing may be missing mods
+function listingError(result: ModsResult): string | null {
+  if (result.app_update_required) {
+    return `This repository requires BZMM ${result.app_update_required} or newer. Please update the app.`;
+  }
+  // Say how old the cached list is rather than just that the repository couldn't be reached
+  if (result.from_cache && result.cache_age_secs !== null) {
+    if (result.error) {
+      return `The repository couldn't be reached, showing the mod list from ${formatAge(result.cache_age_secs)}.`;
+    }
+    if (result.stale) {
+      return `The mod list is from ${formatAge(result.cache_age_secs)} and may be out of date.`;
+    }
+  }
+  return result.error;
 }
 
 function formatErrorMessage(error: unknown): string {
@@ -27,10 +74,14 @@ export function useModData(setGlobalError: (error: string | null) => void) {
   const [showLoading, setShowLoading] = useState(false);
   const [downloadedMods, setDownloadedMods] = useState<Set<string>>(new Set());
   const [currentProfileIndex, setCurrentProfileIndex] = useState<number>(0);
+  const activeProfileRestored = useRef(false);
   const [modsError, setModsError] = useState<string | null>(null);
+  const [repoHeader, setRepoHeader] = useState<RepoHeader | null>(null);
+  const [parseWarnings, setParseWarnings] = useState<ParseWarning[]>([]);
 
   const transformMods = async (categories: any[], downloadedModsSet: Set<string>, profileName: string) => {
-    const enabledMods = new Set(await invoke<string[]>('get_enabled_mods', { profileName }));
+    const enabledEntries = await invoke<EnabledModEntry[]>('get_enabled_mods', { profileName });
+    const enabledMods = new Set(enabledEntries.map(entry => enabledKey(entry.source, entry.name)));
     
     return categories.flatMap((category: any, categoryIndex: number) => {
       if (!category?.mods || !Array.isArray(category.mods)) {
@@ -43,16 +94,44 @@ export function useModData(setGlobalError: (error: string | null) => void) {
         const transformedMod = {
           id: categoryIndex * 1000 + modIndex,
           name: mod.name,
+          displayName: mod.displayName || undefined,
           category: category.name || 'Uncategorized',
           version: mod.version || '0.0.0',
           newVersion: mod.newVersion || undefined,
+          downgradeVersion: mod.downgradeVersion || undefined,
           url: mod.url || null,
           filename: mod.name ? `${mod.name}.zip` : null,
           shortDescription: mod.description?.split('\n')[0] || '',
           description: mod.description || '',
+          descriptionHtml: mod.descriptionHtml || undefined,
+          changelogUrl: mod.changelogUrl || undefined,
+          iconUrl: mod.iconUrl || undefined,
+          screenshotUrl: mod.screenshotUrl || undefined,
+          author: mod.author || undefined,
+          homepageUrl: mod.homepageUrl || undefined,
+          license: mod.license || undefined,
+          size: mod.size ?? undefined,
+          mirrors: mod.mirrors || [],
+          dcsVersion: mod.dcsVersion || undefined,
+          dcsIncompatible: mod.dcsIncompatible || false,
+          deprecated: mod.deprecated || false,
+          replacedBy: mod.replacedBy || undefined,
+          tags: mod.tags || [],
+          installNotes: mod.installNotes || undefined,
           isDownloaded: downloadedModsSet.has(mod.name) || downloadedModsSet.has(mod.name + '.zip'),
-          isEnabled: enabledMods.has(mod.name),
+          isEnabled: enabledMods.has(enabledKey(mod.source, mod.name)),
           sort_order: category.sort_order || 0,
+          categoryDescription: category.description || undefined,
+          origin: mod.origin || undefined,
+          source: mod.source || undefined,
+          isFavorite: mod.favorite || false,
+          note: mod.note || undefined,
+          sinceLastVisit: mod.sinceLastVisit || undefined,
+          requires: mod.requires || [],
+          variants: mod.variants || [],
+          variant: mod.variant || undefined,
+          installedVariant: mod.installedVariant || undefined,
+          enablingLock: mod.enablingLock || undefined,
         };
         return transformedMod;
       });
@@ -62,8 +141,18 @@ export function useModData(setGlobalError: (error: string | null) => void) {
   const loadSettings = async () => {
     try {
       const settingsData = await invoke<Settings>('get_settings');
+      // Open on the profile used last time, before the first listing loads
+      if (!activeProfileRestored.current) {
+        activeProfileRestored.current = true;
+        const active = await invoke<ActiveProfile | null>('get_active_profile');
+        if (active) {
+          setCurrentProfileIndex(active.index);
+        }
+      }
       setSettings(settingsData);
-      setGlobalError(null);
+      // A corrupted settings file is replaced by its last good backup, which may be a little older
+      const restoreWarning = await invoke<string | null>('take_settings_warning');
+      setGlobalError(restoreWarning);
     } catch (error) {
       const formattedError = formatErrorMessage(error);
       setGlobalError(formattedError);
@@ -71,9 +160,17 @@ export function useModData(setGlobalError: (error: string | null) => void) {
     }
   };
 
+  // Remembered so the app reopens on the same profile
+  const selectProfile = (index: number) => {
+    setCurrentProfileIndex(index);
+    invoke('set_active_profile', { index }).catch(error => {
+      console.error('Failed to save the active profile:', error);
+    });
+  };
+
   const loadMods = async (profileIndex?: number) => {
     if (profileIndex !== undefined) {
-      setCurrentProfileIndex(profileIndex);
+      selectProfile(profileIndex);
     }
     
     try {
@@ -103,14 +200,14 @@ export function useModData(setGlobalError: (error: string | null) => void) {
       setMods(transformedMods);
       setDownloadedMods(downloadedModsSet);
       
-      if (modsResult.error) {
-        setModsError(modsResult.error);
-      } else {
-        setModsError(null);
-      }
+      setModsError(listingError(modsResult));
+      setRepoHeader(modsResult.header ?? null);
+      setParseWarnings(modsResult.warnings ?? []);
     } catch (error) {
       console.error('Critical error loading mods:', error);
       setMods([]);
+      setRepoHeader(null);
+      setParseWarnings([]);
       setDownloadedMods(new Set());
       setModsError(formatErrorMessage(error));
     } finally {
@@ -140,6 +237,77 @@ export function useModData(setGlobalError: (error: string | null) => void) {
     }
   }, [settings, currentProfileIndex]);
 
+  // get_mods answers from cache first and sends the fresh list once the repository responds
+  useEffect(() => {
+    const unlisten = listen('mods-refreshed', async (event) => {
+      const payload = event.payload as { profile_index: number; result: ModsResult };
+      const currentProfile = settings?.profiles[currentProfileIndex];
+      if (!currentProfile || payload.profile_index !== currentProfileIndex) {
+        return;
+      }
+
+      const transformedMods = await transformMods(payload.result.categories, downloadedMods, currentProfile.name);
+      setMods(transformedMods);
+      setModsError(listingError(payload.result));
+      setRepoHeader(payload.result.header ?? null);
+      setParseWarnings(payload.result.warnings ?? []);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [settings, currentProfileIndex, downloadedMods]);
+
+  // Settings restored from a backup while the app is running
+  useEffect(() => {
+    const unlisten = listen<string>('settings-restored', (event) => {
+      setGlobalError(event.payload);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Mod folders added, removed or changed outside the app
+  useEffect(() => {
+    const unlisten = listen('mods-changed', () => {
+      if (settings) {
+        loadMods();
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [settings, currentProfileIndex]);
+
+  // A mod enabled by itself once its download finished
+  useEffect(() => {
+    const unlisten = listen<{ mod_name: string; profile_name: string }>('mod-enabled', (event) => {
+      if (settings && event.payload.profile_name === settings.profiles[currentProfileIndex]?.name) {
+        loadMods();
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [settings, currentProfileIndex]);
+
+  // Background update check found something for the profile on screen
+  useEffect(() => {
+    const unlisten = listen<{ profile_index: number; updates: unknown[] }>('mod-updates-checked', (event) => {
+      if (settings && event.payload.profile_index === currentProfileIndex && event.payload.updates.length > 0) {
+        loadMods();
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [settings, currentProfileIndex]);
+
   return {
     mods,
     setMods,
@@ -150,8 +318,10 @@ export function useModData(setGlobalError: (error: string | null) => void) {
     loadSettings,
     loadMods,
     currentProfileIndex,
-    setCurrentProfileIndex,
-    modsError
+    setCurrentProfileIndex: selectProfile,
+    modsError,
+    repoHeader,
+    parseWarnings
   };
 }
 
@@ -1,5 +1,6 @@
 import { useMemo } from 'react';
 import type { Mod } from '../../types/types';
+import { CATEGORY_PATH_SEPARATOR, isInCategory } from '../../lib/utils';
 
 export function useModFiltering(
   mods: Mod[],
@@ -12,10 +13,15 @@ export function useModFiltering(
     categoryMap.set("All", -1); // Ensure "All" is always first
 
     mods.forEach(mod => {
-      // We get the sort_order from any mod in the category since they should all have the same value
-      if (!categoryMap.has(mod.category)) {
-        categoryMap.set(mod.category, mod.sort_order || 0);
-      }
+      // We get the sort_order from any mod in the category since they should all have the same value.
+      // Parents of nested categories are listed too, even when they hold no mods of their own
+      const path = mod.category.split(CATEGORY_PATH_SEPARATOR);
+      path.forEach((_, depth) => {
+        const category = path.slice(0, depth + 1).join(CATEGORY_PATH_SEPARATOR);
+        if (!categoryMap.has(category)) {
+          categoryMap.set(category, mod.sort_order || 0);
+        }
+      });
     });
 
     // Convert to array and sort by sort_order
@@ -27,8 +33,8 @@ export function useModFiltering(
   const filteredMods = useMemo(() => 
     mods
       .filter(mod => {
-        const matchesSearch = mod.name.toLowerCase().includes(searchQuery.toLowerCase());
-        const matchesCategory = selectedCategory === "All" || mod.category === selectedCategory;
+        const matchesSearch = (mod.displayName ?? mod.name).toLowerCase().includes(searchQuery.toLowerCase());
+        const matchesCategory = selectedCategory === "All" || isInCategory(mod.category, selectedCategory);
         return matchesSearch && matchesCategory;
       })
       .sort((a, b) => {
@@ -46,7 +52,7 @@ export function useModFiltering(
         }
 
         // Finally sort alphabetically by name
-        return a.name.localeCompare(b.name);
+        return (a.displayName ?? a.name).localeCompare(b.displayName ?? b.name);
       }),
     [mods, searchQuery, selectedCategory]
   );
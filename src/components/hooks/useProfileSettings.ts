@@ -1,11 +1,18 @@
 import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
-import type { Profile } from '../../types/types';
+import type { DcsPathCheck, DcsVariant, Profile } from '../../types/types';
 
 interface ProfileSettingsData {
   name: string;
   dcs_path: string;
   repo_url: string;
+  download_path: string;
+  dcs_variant: DcsVariant | null;
+  hook_script: string;
+  hook_on_enable: boolean;
+  auto_enable: boolean;
+  disable_lua_patching: boolean;
 }
 
 export function useProfileSettings(
@@ -16,13 +23,27 @@ export function useProfileSettings(
     name: profile.name,
     dcs_path: profile.dcs_path || '',
     repo_url: profile.repo_url || 'https://repo.borderzone.ca/BZ_Saved_Mods.xml',
+    download_path: profile.download_path || '',
+    dcs_variant: profile.dcs_variant ?? null,
+    hook_script: profile.hook_script || '',
+    hook_on_enable: profile.hook_on_enable ?? false,
+    auto_enable: profile.auto_enable ?? false,
+    disable_lua_patching: profile.disable_lua_patching ?? false,
   });
+  // Guidance when the picked DCS folder isn't a Saved Games or install folder
+  const [dcsPathError, setDcsPathError] = useState<string | null>(null);
 
   useEffect(() => {
     setSettings({
       name: profile.name,
       dcs_path: profile.dcs_path || '',
       repo_url: profile.repo_url || 'https://repo.borderzone.ca/BZ_Saved_Mods.xml',
+      download_path: profile.download_path || '',
+      dcs_variant: profile.dcs_variant ?? null,
+      hook_script: profile.hook_script || '',
+      hook_on_enable: profile.hook_on_enable ?? false,
+      auto_enable: profile.auto_enable ?? false,
+      disable_lua_patching: profile.disable_lua_patching ?? false,
     });
   }, [profile]);
 
@@ -35,33 +56,85 @@ export function useProfileSettings(
 
       if (selected) {
         setSettings(prev => ({ ...prev, dcs_path: selected as string }));
+        const check = await invoke<DcsPathCheck>('validate_dcs_path', { path: selected });
+        setDcsPathError(check.valid ? null : check.message);
+        // A new folder usually means another variant, go with what its name says
+        if (check.variant) {
+          setSettings(prev => ({ ...prev, dcs_variant: check.variant }));
+        }
       }
     } catch (error) {
       console.error('Failed to select directory:', error);
     }
   };
 
+  const handleDownloadDirectoryChange = async () => {
+    try {
+      const selected = await open({
+        directory: true,
+        multiple: false,
+      });
+
+      if (selected) {
+        setSettings(prev => ({ ...prev, download_path: selected as string }));
+      }
+    } catch (error) {
+      console.error('Failed to select directory:', error);
+    }
+  };
+
+  const handleHookScriptChange = async () => {
+    try {
+      const selected = await open({
+        directory: false,
+        multiple: false,
+      });
+
+      if (selected) {
+        setSettings(prev => ({ ...prev, hook_script: selected as string }));
+      }
+    } catch (error) {
+      console.error('Failed to select hook script:', error);
+    }
+  };
+
   const handleSave = async () => {
     onUpdateProfile({
       ...profile,
       name: settings.name,
       dcs_path: settings.dcs_path,
       repo_url: settings.repo_url,
+      download_path: settings.download_path,
+      dcs_variant: settings.dcs_variant,
+      hook_script: settings.hook_script,
+      hook_on_enable: settings.hook_on_enable,
+      auto_enable: settings.auto_enable,
+      disable_lua_patching: settings.disable_lua_patching,
     });
   };
 
   const handleCancel = () => {
+    setDcsPathError(null);
     setSettings({
       name: profile.name,
       dcs_path: profile.dcs_path || '',
       repo_url: profile.repo_url || 'https://repo.borderzone.ca/BZ_Saved.xml',
+      download_path: profile.download_path || '',
+      dcs_variant: profile.dcs_variant ?? null,
+      hook_script: profile.hook_script || '',
+      hook_on_enable: profile.hook_on_enable ?? false,
+      auto_enable: profile.auto_enable ?? false,
+      disable_lua_patching: profile.disable_lua_patching ?? false,
     });
   };
 
   return {
     settings,
     setSettings,
+    dcsPathError,
     handleDcsDirectoryChange,
+    handleDownloadDirectoryChange,
+    handleHookScriptChange,
     handleSave,
     handleCancel
   };
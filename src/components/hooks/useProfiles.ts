@@ -1,5 +1,29 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Profile } from '../../types/types';
+import { ask } from '@tauri-apps/plugin-dialog';
+import type { OrphanCleanupReport, Profile } from '../../types/types';
+import { formatBytes } from '../../lib/utils';
+
+// Downloads of a repository no profile uses any more stay on disk until the user agrees to delete them
+async function offerUnusedRepoCleanup() {
+  try {
+    const report = await invoke<OrphanCleanupReport>('cleanup_orphaned_files', { delete: false });
+    const unused = report.files.filter(file => file.kind === 'unused_repo_dir');
+    if (unused.length === 0) {
+      return;
+    }
+    // Deleting removes every leftover found, so the other ones are mentioned too
+    const others = unused.length < report.files.length ? ', along with other download leftovers,' : '';
+    const confirmed = await ask(
+      `Mods downloaded from repositories no profile uses any more${others} take ${formatBytes(report.total_bytes)}. Delete them?`,
+      { title: 'Unused Downloads', kind: 'warning' }
+    );
+    if (confirmed) {
+      await invoke('cleanup_orphaned_files', { delete: true });
+    }
+  } catch (error) {
+    console.error('Failed to clean up unused downloads:', error);
+  }
+}
 
 export function useProfiles(
   profiles: Profile[],
@@ -28,6 +52,17 @@ export function useProfiles(
     return newProfileIndex;
   };
 
+  // Copies the current profile with the same repository and DCS path, to adjust afterwards
+  const cloneProfile = async () => {
+    await invoke('clone_profile', {
+      index: currentProfileIndex
+    });
+    await refreshSettings();
+    const cloneIndex = profiles.length;
+    setCurrentProfileIndex(cloneIndex);
+    return cloneIndex;
+  };
+
   const deleteProfile = async () => {
     try {
       await invoke('delete_profile', {
@@ -39,21 +74,27 @@ export function useProfiles(
       if (profiles.length > 1) {
         setCurrentProfileIndex(0);
       }
+      await offerUnusedRepoCleanup();
     } catch (error) {
       console.error('Failed to delete profile:', error);
     }
   };
 
   const updateProfile = async (updatedProfile: Profile) => {
+    const previousRepoUrl = profiles[currentProfileIndex]?.repo_url;
     await invoke('update_profile', {
       index: currentProfileIndex,
       profile: updatedProfile
     });
     await refreshSettings();
+    if (previousRepoUrl !== undefined && previousRepoUrl !== updatedProfile.repo_url) {
+      await offerUnusedRepoCleanup();
+    }
   };
 
   return {
     addNewProfile,
+    cloneProfile,
     deleteProfile,
     updateProfile
   };
@@ -1,26 +1,69 @@
 import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
+import { AssetCacheUsage, CacheUsage, ProxySettings, SettingsUpdate } from '../../types/types';
+import { formatBytes } from '../../lib/utils';
 
 interface SettingsData {
   download_path: string;
   sideload_path: string;
+  staging_path: string;
+  close_to_tray: boolean;
+  mirror_region: string;
+  language: string;
+  max_concurrent_downloads: number;
+  max_download_speed_kbps: number;
+  proxy: ProxySettings;
+  auto_refresh_minutes: number;
+  enabled_markers: boolean;
+  cache_ttl_hours: number;
+  cache_size_mb: number;
+  log_level: string;
 }
 
 const DEFAULT_SETTINGS: SettingsData = {
   download_path: '',
   sideload_path: '',
+  staging_path: '',
+  close_to_tray: false,
+  mirror_region: '',
+  language: '',
+  max_concurrent_downloads: 2,
+  max_download_speed_kbps: 0,
+  proxy: { mode: 'System', url: '', username: '', password: '' },
+  auto_refresh_minutes: 0,
+  enabled_markers: true,
+  cache_ttl_hours: 24,
+  cache_size_mb: 200,
+  log_level: '',
 };
 
 export function useSettings(onSaved?: () => void) {
   const [settings, setSettings] = useState<SettingsData>(DEFAULT_SETTINGS);
   const [isLoading, setIsLoading] = useState(false);
+  const [xmlCacheMessage, setXmlCacheMessage] = useState<string | null>(null);
+  const [assetCacheMessage, setAssetCacheMessage] = useState<string | null>(null);
+  const [cacheUsageMessage, setCacheUsageMessage] = useState<string | null>(null);
+
+  const describeAssetCache = (usage: AssetCacheUsage) => `${formatBytes(usage.bytes)} cached`;
+
+  const loadCacheUsage = async () => {
+    try {
+      const usage = await invoke<CacheUsage>('get_cache_usage');
+      setCacheUsageMessage(`${formatBytes(usage.bytes)} used`);
+    } catch (error) {
+      console.error('Failed to get cache usage:', error);
+    }
+  };
 
   const loadSettings = useCallback(async () => {
     try {
       setIsLoading(true);
       const savedSettings = await invoke<SettingsData>('get_settings');
       setSettings(savedSettings);
+      const usage = await invoke<AssetCacheUsage>('get_asset_cache_usage');
+      setAssetCacheMessage(describeAssetCache(usage));
+      await loadCacheUsage();
     } catch (error) {
       console.error('Failed to load settings:', error);
       setSettings(DEFAULT_SETTINGS);
@@ -29,7 +72,7 @@ export function useSettings(onSaved?: () => void) {
     }
   }, []);
 
-  const handleSettingsPathChange = async (key: 'download_path' | 'sideload_path') => {
+  const handleSettingsPathChange = async (key: 'download_path' | 'sideload_path' | 'staging_path') => {
     try {
       const selected = await open({
         directory: true,
@@ -46,18 +89,96 @@ export function useSettings(onSaved?: () => void) {
 
   const handleDownloadPathChange = () => handleSettingsPathChange('download_path');
   const handleSideloadPathChange = () => handleSettingsPathChange('sideload_path');
+  const handleStagingPathChange = () => handleSettingsPathChange('staging_path');
+  const handleStagingPathClear = () => setSettings(prev => ({ ...prev, staging_path: '' }));
+
+  const handleCloseToTrayChange = (closeToTray: boolean) => {
+    setSettings(prev => ({ ...prev, close_to_tray: closeToTray }));
+  };
+
+  const handleMirrorRegionChange = (mirrorRegion: string) => {
+    setSettings(prev => ({ ...prev, mirror_region: mirrorRegion }));
+  };
+
+  const handleLanguageChange = (language: string) => {
+    setSettings(prev => ({ ...prev, language }));
+  };
+
+  const handleMaxConcurrentDownloadsChange = (maxConcurrentDownloads: number) => {
+    setSettings(prev => ({ ...prev, max_concurrent_downloads: maxConcurrentDownloads }));
+  };
+
+  const handleMaxDownloadSpeedChange = (maxDownloadSpeedKbps: number) => {
+    setSettings(prev => ({ ...prev, max_download_speed_kbps: maxDownloadSpeedKbps }));
+  };
+
+  const handleProxyChange = (proxy: Partial<ProxySettings>) => {
+    setSettings(prev => ({ ...prev, proxy: { ...prev.proxy, ...proxy } }));
+  };
+
+  const handleAutoRefreshChange = (autoRefreshMinutes: number) => {
+    setSettings(prev => ({ ...prev, auto_refresh_minutes: autoRefreshMinutes }));
+  };
+
+  const handleEnabledMarkersChange = (enabledMarkers: boolean) => {
+    setSettings(prev => ({ ...prev, enabled_markers: enabledMarkers }));
+  };
+
+  const handleCacheTtlChange = (cacheTtlHours: number) => {
+    setSettings(prev => ({ ...prev, cache_ttl_hours: cacheTtlHours }));
+  };
+
+  const handleCacheSizeChange = (cacheSizeMb: number) => {
+    setSettings(prev => ({ ...prev, cache_size_mb: cacheSizeMb }));
+  };
+
+  const handleLogLevelChange = (logLevel: string) => {
+    setSettings(prev => ({ ...prev, log_level: logLevel }));
+  };
+
+  // Cached repository lists of every profile, they are fetched again the next time mods load
+  const handleClearXmlCache = async () => {
+    try {
+      const removed = await invoke<number>('clear_xml_cache', { repoUrl: null });
+      setXmlCacheMessage(removed === 1 ? '1 cached file removed' : `${removed} cached files removed`);
+      await loadCacheUsage();
+    } catch (error) {
+      console.error('Failed to clear XML cache:', error);
+      setXmlCacheMessage(`Failed to clear the cache: ${error}`);
+    }
+  };
+
+  const handlePurgeAssetCache = async () => {
+    try {
+      const usage = await invoke<AssetCacheUsage>('purge_asset_cache', { kind: null });
+      setAssetCacheMessage(describeAssetCache(usage));
+      await loadCacheUsage();
+    } catch (error) {
+      console.error('Failed to purge asset cache:', error);
+      setAssetCacheMessage(`Failed to clear the cache: ${error}`);
+    }
+  };
 
   const handleSave = async () => {
     try {
-      // Save download_path
-      await invoke('update_settings', { 
-        update: { key: 'download_path', value: settings.download_path }
-      });
-      
-      // Save sideload_path
-      await invoke('update_settings', { 
-        update: { key: 'sideload_path', value: settings.sideload_path }
-      });
+      // One update for every field, nothing is saved if any of them is invalid
+      const update: SettingsUpdate = {
+        download_path: settings.download_path,
+        sideload_path: settings.sideload_path,
+        staging_path: settings.staging_path,
+        close_to_tray: settings.close_to_tray,
+        mirror_region: settings.mirror_region,
+        language: settings.language,
+        max_concurrent_downloads: settings.max_concurrent_downloads,
+        max_download_speed_kbps: settings.max_download_speed_kbps,
+        proxy: settings.proxy,
+        auto_refresh_minutes: settings.auto_refresh_minutes,
+        enabled_markers: settings.enabled_markers,
+        cache_ttl_hours: settings.cache_ttl_hours,
+        cache_size_mb: settings.cache_size_mb,
+        log_level: settings.log_level,
+      };
+      await invoke('update_settings', { update });
       
       onSaved?.();
     } catch (error) {
@@ -75,6 +196,24 @@ export function useSettings(onSaved?: () => void) {
     loadSettings,
     handleDownloadPathChange,
     handleSideloadPathChange,
+    handleStagingPathChange,
+    handleStagingPathClear,
+    handleCloseToTrayChange,
+    handleMirrorRegionChange,
+    handleLanguageChange,
+    handleMaxConcurrentDownloadsChange,
+    handleMaxDownloadSpeedChange,
+    handleProxyChange,
+    handleAutoRefreshChange,
+    handleEnabledMarkersChange,
+    handleCacheTtlChange,
+    handleCacheSizeChange,
+    cacheUsageMessage,
+    handleClearXmlCache,
+    xmlCacheMessage,
+    handlePurgeAssetCache,
+    assetCacheMessage,
+    handleLogLevelChange,
     handleSave,
     handleCancel
   };
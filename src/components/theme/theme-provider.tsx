@@ -1,7 +1,15 @@
 import { createContext, useContext, useEffect, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
 
 type Theme = "dark" | "light" | "system"
 
+// How the backend's dark_mode setting spells each theme
+const DARK_MODES: Record<Theme, string> = {
+  dark: "Dark",
+  light: "Light",
+  system: "System",
+}
+
 type ThemeProviderProps = {
   children: React.ReactNode
   defaultTheme?: Theme
@@ -53,6 +61,8 @@ export function ThemeProvider({
     setTheme: (theme: Theme) => {
       localStorage.setItem(storageKey, theme)
       setTheme(theme)
+      invoke("update_settings", { update: { dark_mode: DARK_MODES[theme] } })
+        .catch(error => console.error("Failed to save theme:", error))
     },
   }
 
@@ -4,3 +4,20 @@ import { twMerge } from "tailwind-merge"
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs))
 }
+
+// Format a byte count as B, KB, MB, GB or TB
+export function formatBytes(bytes: number): string {
+  if (bytes === 0) return '0 B';
+
+  const sizes = ['B', 'KB', 'MB', 'GB', 'TB'];
+  const i = Math.floor(Math.log(bytes) / Math.log(1024));
+  return `${(bytes / Math.pow(1024, i)).toFixed(2)} ${sizes[i]}`;
+}
+
+// Nested repository categories are named by their path, e.g. "Aircraft / Liveries"
+export const CATEGORY_PATH_SEPARATOR = ' / ';
+
+// Whether a mod's category is the given category or nested somewhere inside it
+export function isInCategory(modCategory: string, category: string): boolean {
+  return modCategory === category || modCategory.startsWith(category + CATEGORY_PATH_SEPARATOR);
+}
@@ -1,27 +1,214 @@
 export interface Mod {
   id: number;
-  name: string;
+  name: string;  // Stable id from the repository, or its name when it gives none
+  displayName?: string;  // Repository name when it differs from the id
   category: string;
+  categoryDescription?: string;
   version: string;
   url: string | null;  // Changed to allow null
   filename: string | null;  // Changed to allow null
   newVersion?: string;
+  downgradeVersion?: string;  // Older repo version when the local copy is newer
   shortDescription: string;
   description: string;
+  descriptionHtml?: string;  // Description Markdown rendered and sanitized by the backend
+  changelogUrl?: string;
+  iconUrl?: string;
+  screenshotUrl?: string;
+  author?: string;
+  homepageUrl?: string;
+  license?: string;
+  size?: number;  // Download size in bytes from the repository
+  dcsVersion?: string;  // DCS versions the mod is made for, e.g. "2.9" or "2.8-2.9"
+  dcsIncompatible?: boolean;  // The profile's DCS installation is outside dcsVersion
+  deprecated?: boolean;  // Marked by the repository, or no longer listed in it
+  replacedBy?: string;  // Mod the repository offers in place of this deprecated one
+  mirrors?: ModMirror[];  // Extra download locations, tried when the main url fails
+  tags?: string[];
+  installNotes?: string;  // Caveats to confirm before enabling
   isDownloaded: boolean;
   isEnabled?: boolean;
   sort_order: number;
+  origin?: string;  // Included repository file that listed the mod
+  source?: string;  // Repo-hash directory or "sideload" the local copy lives in
+  isFavorite?: boolean;
+  note?: string;
+  sinceLastVisit?: 'added' | 'updated';  // Repo change since the previous session
+  requires?: ModRequirement[];  // Other mods this one needs, enabled or downloaded along with it
+  variants?: ModVariant[];  // Alternative downloads, e.g. 4K or 2K textures
+  variant?: string;  // Id of the variant url points at
+  installedVariant?: string;  // Id of the variant that was downloaded
+  enablingLock?: EnablingLock;  // Set while an enable runs for the profile, or after one was interrupted
+}
+
+export interface EnablingLock {
+  mod_name: string;
+  pid?: number;
+  age_secs: number;
+  stale: boolean;  // The enable can't still be running, force_unlock_mod clears it
+}
+
+export interface ModVariant {
+  id: string;
+  name?: string;
+  url: string;
+  size?: number;
+  description?: string;
+}
+
+export interface ModMirror {
+  url: string;
+  region?: string;
+  priority?: number;
+}
+
+export interface ModRequirement {
+  name: string;
+  minVersion?: string;
+}
+
+// Repository name, maintainer and announcement from its <header>
+export interface RepoHeader {
+  name: string | null;
+  maintainer: string | null;
+  supportUrl: string | null;
+  motd: string;  // Message of the day, empty when there is none
+}
+
+// A repository entry left out of the listing because it couldn't be parsed
+export interface ParseWarning {
+  element: string;
+  line: number;
+  reason: string;
+}
+
+// One finding of the startup self-check
+export interface SelfCheckItem {
+  check: string;  // e.g. 'download_path', 'symlinks', 'repository'
+  profile: string | null;  // Set for checks made for one profile
+  status: 'ok' | 'warning' | 'error';
+  message: string;
+  hint: string | null;  // What to do about it
+}
+
+export interface SelfCheckReport {
+  ok: boolean;  // No errors, warnings don't count
+  checks: SelfCheckItem[];
+}
+
+// Disk space taken by cached changelogs, icons and screenshots
+export interface AssetCacheUsage {
+  files: number;
+  bytes: number;
+  bytes_by_kind: Partial<Record<'changelog' | 'icon' | 'screenshot', number>>;
+}
+
+// Disk space taken by the whole cache, pruned down to the size limit in the settings
+export interface CacheUsage {
+  files: number;
+  bytes: number;
+  limit_bytes: number;  // 0 when not limited
+  xml_bytes: number;
+  history_bytes: number;  // Earlier copies of repository files
+  asset_bytes: number;
+}
+
+// A backend log event, from get_recent_logs or a log-event
+export interface LogEntry {
+  timestamp: number;  // Milliseconds since the Unix epoch
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
+  target: string;  // Module that logged it, like bzmm::mods::downloader
+  spans: string[];  // Outermost first, like download{file=a.zip}
+  message: string;
 }
 
+// A leftover in the download path found by cleanup_orphaned_files
+export interface OrphanedFile {
+  path: string;
+  kind: 'zip_without_folder' | 'temp_file' | 'empty_repo_dir' | 'unused_repo_dir';
+  bytes: number;
+}
+
+export interface OrphanCleanupReport {
+  files: OrphanedFile[];
+  total_bytes: number;
+  deleted: boolean;  // Only reported when false
+}
+
+export interface EnabledModEntry {
+  name: string;
+  version: string | null;
+  variant: string | null;  // Downloaded variant, for mods offering several
+  source: string;  // Repo-hash directory or "sideload"
+  repo_url: string | null;
+  enabled_at: number | null;  // Seconds since the Unix epoch
+}
+
+// Enabled state is per source, the same name can be downloaded and sideloaded
+export const enabledKey = (source: string | undefined, name: string) => `${source ?? ''}/${name}`;
+
+export type DcsVariant = 'Stable' | 'OpenBeta' | 'DedicatedServer';
+
+export const DCS_VARIANT_LABELS: Record<DcsVariant, string> = {
+  Stable: 'Stable',
+  OpenBeta: 'OpenBeta',
+  DedicatedServer: 'Dedicated Server',
+};
+
 export interface Profile {
   name: string;
   dcs_path: string;  // Changed from mod_path
   repo_url: string;
+  install_path?: string;  // DCS installation directory
+  download_path?: string;  // Overrides the global download path, empty to use it
+  dcs_variant?: DcsVariant | null;  // Detected from the paths when not set
+  hook_script?: string;  // Run after a mod is downloaded, empty for none
+  hook_on_enable?: boolean;  // Also run the hook script after a mod is enabled
+  auto_enable?: boolean;  // Enable mods once their download is extracted
+  disable_lua_patching?: boolean;  // Leave DCS lua files untouched, patching mods install partially
+}
+
+export interface DcsPathCheck {
+  valid: boolean;
+  kind: 'SavedGames' | 'Install' | null;
+  variant: DcsVariant | null;  // What the folder name gives away
+  message: string | null;  // What to pick instead when the folder is wrong
+}
+
+export interface ProxySettings {
+  mode: 'System' | 'Manual' | 'None';
+  url: string;  // Used in manual mode
+  username: string;
+  password: string;  // A keyring reference once saved, see KEYRING_REFERENCE_PREFIX
 }
 
+// Secrets saved in the system keyring come back as a reference starting with this
+export const KEYRING_REFERENCE_PREFIX = 'keyring:';
+
 export interface Settings {
   dark_mode: 'System' | 'Light' | 'Dark';
   download_path: string;
   sideload_path: string;
+  staging_path: string;
   profiles: Profile[];
+  active_profile?: string;  // Name of the profile the app opens on, see set_active_profile
+  close_to_tray?: boolean;
+  mirror_region?: string;  // Region whose download mirrors are tried first
+  max_concurrent_downloads?: number;  // Downloads the queue runs at once, 1 to 8
+  max_download_speed_kbps?: number;  // Combined download speed cap in KB/s, 0 for none
+  proxy?: ProxySettings;
+  auto_refresh_minutes?: number;  // Background update checks, 0 for manual only
+  enabled_markers?: boolean;  // Also write ENABLED-<profile>.txt into mod folders
+  cache_ttl_hours?: number;  // Cached listings older than this are flagged as stale, 0 never
+  cache_size_mb?: number;  // The cache is pruned down to this, 0 for no limit
+  log_level?: string;  // Log filter such as debug or info,bzmm::mods::downloader=trace, empty for info
+  language?: string;  // Language tag for repository texts, empty to follow the system
+}
+
+// Fields to change with update_settings, the others stay as they are
+export type SettingsUpdate = Partial<Omit<Settings, 'profiles' | 'active_profile'>>;
+
+export interface ActiveProfile {
+  index: number;
+  profile: Profile;
 }
\ No newline at end of file